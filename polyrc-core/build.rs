@@ -0,0 +1,19 @@
+use std::process::Command;
+
+fn main() {
+    // Shown by `polyrc env`; "unknown" outside a git checkout (e.g. crates.io builds)
+    let sha = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_SHA={}", sha.trim());
+    for git_file in ["../.git/HEAD", "../.git/refs"] {
+        if std::path::Path::new(git_file).exists() {
+            println!("cargo:rerun-if-changed={git_file}");
+        }
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,26 @@
+//! Convert one tool's rules to another using polyrc as a library.
+//!
+//!     cargo run --example convert -- cursor claude ./my-project ./out
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use polyrc_core::formats::Format;
+use polyrc_core::ConvertOptions;
+
+fn main() -> polyrc_core::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [from, to, input, output] = args.as_slice() else {
+        eprintln!("usage: convert <from> <to> <input-dir> <output-dir>");
+        std::process::exit(2);
+    };
+
+    let from = Format::from_str(from)?;
+    let to = Format::from_str(to)?;
+    let report = polyrc_core::convert(&from, &to, &PathBuf::from(input), &PathBuf::from(output), &ConvertOptions::default())?;
+
+    for rule in &report.rules {
+        println!("{}", rule.name.as_deref().unwrap_or("<unnamed>"));
+    }
+    println!("{} rule(s) converted from {} to {}", report.rules.len(), from.name(), to.name());
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! `polyrc budget` — how much context each format's output would take.
+//!
+//! The output is rendered the way `pull-format` would write it (see
+//! [`crate::lock::plan`]) and measured in characters, with a rough token
+//! estimate. Windsurf's hard limits always apply; any format can carry a soft
+//! limit in `[formats.<name>] budget_chars`.
+
+use std::path::Path;
+use serde::Serialize;
+use crate::error::Result;
+use crate::formats::options::{FormatOptions, BUDGET_CHARS};
+use crate::formats::Format;
+use crate::ir::Rule;
+use crate::writer::WriteOptions;
+
+/// Characters per token used by [`estimate_tokens`].
+pub const CHARS_PER_TOKEN: usize = 4;
+/// Rules listed in [`FormatBudget::top_rules`].
+pub const TOP_RULES: usize = 5;
+
+/// Rough token count for `chars` characters of English prose or code.
+pub fn estimate_tokens(chars: usize) -> usize {
+    chars.div_ceil(CHARS_PER_TOKEN)
+}
+
+/// One format's output measured against its limits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FormatBudget {
+    pub format: String,
+    pub chars: usize,
+    pub tokens: usize,
+    /// Limit on all files combined: `budget_chars`, else the format's own.
+    pub limit_chars: Option<usize>,
+    /// Limit on each file (Windsurf only).
+    pub file_limit_chars: Option<usize>,
+    /// Over either limit.
+    pub over_limit: bool,
+    pub files: Vec<FileBudget>,
+    /// The largest rules, biggest first — the ones to trim.
+    pub top_rules: Vec<RuleBudget>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileBudget {
+    /// `/`-separated, relative to the output root.
+    pub path: String,
+    pub chars: usize,
+    pub tokens: usize,
+    pub over_limit: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleBudget {
+    pub rule: String,
+    pub chars: usize,
+    pub tokens: usize,
+}
+
+/// Measure `rules` as `fmt` would write them. `options` supplies `budget_chars`.
+pub fn measure(fmt: &Format, rules: &[Rule], options: &FormatOptions) -> Result<FormatBudget> {
+    let capabilities = fmt.capabilities();
+    let file_limit = capabilities.file_char_limit;
+    let limit = options.get::<usize>(BUDGET_CHARS).or(capabilities.total_char_limit);
+
+    let files: Vec<FileBudget> = crate::lock::plan(fmt, rules, Path::new(""), &WriteOptions::default())?
+        .into_iter()
+        .map(|(path, rendered)| {
+            let chars = String::from_utf8_lossy(&rendered.content).chars().count();
+            FileBudget { path, chars, tokens: estimate_tokens(chars), over_limit: file_limit.is_some_and(|l| chars > l) }
+        })
+        .collect();
+    let chars: usize = files.iter().map(|f| f.chars).sum();
+
+    let mut top_rules: Vec<RuleBudget> = rules
+        .iter()
+        .map(|r| {
+            let chars = r.content.trim().chars().count();
+            RuleBudget { rule: r.name.clone().unwrap_or_else(|| r.filename_stem()), chars, tokens: estimate_tokens(chars) }
+        })
+        .collect();
+    top_rules.sort_by(|a, b| b.chars.cmp(&a.chars).then_with(|| a.rule.cmp(&b.rule)));
+    top_rules.truncate(TOP_RULES);
+
+    Ok(FormatBudget {
+        format: fmt.name().to_string(),
+        chars,
+        tokens: estimate_tokens(chars),
+        limit_chars: limit,
+        file_limit_chars: file_limit,
+        over_limit: limit.is_some_and(|l| chars > l) || files.iter().any(|f| f.over_limit),
+        files,
+        top_rules,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::windsurf;
+
+    fn rule(name: &str, chars: usize) -> Rule {
+        Rule { name: Some(name.into()), content: "x".repeat(chars), ..Default::default() }
+    }
+
+    #[test]
+    fn windsurf_limits_apply_per_file_and_in_total() {
+        let rules = [rule("small", 99), rule("big", 7_000)];
+        let budget = measure(&Format::Windsurf, &rules, &FormatOptions::default()).unwrap();
+        assert_eq!(budget.chars, 7_101);
+        assert_eq!(budget.tokens, 1_776);
+        assert_eq!(budget.limit_chars, Some(windsurf::TOTAL_CHAR_LIMIT));
+        assert!(budget.over_limit);
+        let over: Vec<&str> = budget.files.iter().filter(|f| f.over_limit).map(|f| f.path.as_str()).collect();
+        assert_eq!(over, vec![".windsurf/rules/big.md"]);
+        assert_eq!(budget.top_rules[0].rule, "big");
+    }
+
+    #[test]
+    fn budget_chars_sets_a_soft_limit() {
+        let rules = [rule("a", 500), rule("b", 800)];
+        assert!(!measure(&Format::Gemini, &rules, &FormatOptions::default()).unwrap().over_limit);
+
+        let mut options = FormatOptions::default();
+        options.set(BUDGET_CHARS, toml::Value::Integer(1_000));
+        let budget = measure(&Format::Gemini, &rules, &options).unwrap();
+        assert_eq!(budget.limit_chars, Some(1_000));
+        assert!(budget.over_limit);
+        assert_eq!(budget.top_rules.iter().map(|r| r.rule.as_str()).collect::<Vec<_>>(), vec!["b", "a"]);
+    }
+}
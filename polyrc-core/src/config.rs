@@ -0,0 +1,490 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use crate::error::{PolyrcError, Result};
+use crate::formats::options::{self, OptionsMap};
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Config {
+    #[serde(default)]
+    pub store: StoreConfig,
+
+    /// Preferred editor command (e.g. "code", "zed", "vim").
+    /// Falls back to $EDITOR env var, then OS default, when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferred_editor: Option<String>,
+
+    /// Per-file size limit in bytes for input and store files.
+    /// `--max-file-size` overrides it; defaults to 4 MiB when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u64>,
+
+    /// `lossy` (default) or `skip` for input files that aren't valid UTF-8.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invalid_utf8: Option<crate::parser::InvalidUtf8Policy>,
+
+    #[serde(default)]
+    pub write: WriteConfig,
+
+    #[serde(default)]
+    pub copilot: CopilotConfig,
+
+    #[serde(default)]
+    pub claude: ClaudeConfig,
+
+    #[serde(default)]
+    pub sync: SyncConfig,
+
+    #[serde(default)]
+    pub log: LogConfig,
+
+    /// `[formats.<name>]` — per-format options, checked against each format's
+    /// declared options (see [`Config::format_options`]).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub formats: BTreeMap<String, toml::Table>,
+
+    /// `[user_dirs]` — format name → user config dir, replacing the built-in
+    /// location (see [`Config::user_dirs`]). `~` and `$VAR` are expanded.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub user_dirs: BTreeMap<String, String>,
+
+    /// `[plugins.<name>]` — formats backed by an external command (see
+    /// [`crate::formats::plugin`]).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub plugins: BTreeMap<String, PluginConfig>,
+}
+
+/// `[plugins.<name>]` — one plugin format.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PluginConfig {
+    /// Executable, optionally followed by arguments; run as
+    /// `<command> parse <dir>` and `<command> write <dir>`. Not run through a shell.
+    pub command: String,
+    /// Shown by `polyrc supported-formats`; defaults to the command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Directory passed to the plugin for user-scope rules.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_dir: Option<String>,
+}
+
+/// `[sync]` — `polyrc sync` behaviour.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct SyncConfig {
+    /// Exit 0 when the remote is unreachable and sync works offline. Defaults
+    /// to true; `false` behaves like `sync --strict-network`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offline_ok: Option<bool>,
+
+    /// Remind to sync once the last sync is this many days old. Defaults to 7;
+    /// 0 disables the reminder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_after_days: Option<u64>,
+
+    /// Merge a local and a remote rule with different ids but the same name
+    /// and project as one rule. Defaults to true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_by_name: Option<bool>,
+
+    /// Days to keep the tombstones of deleted rules, so other machines learn
+    /// of the deletion on their next sync. Defaults to 90; 0 keeps them forever.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tombstone_retention_days: Option<u64>,
+}
+
+/// `[log]` — debug log file.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct LogConfig {
+    /// Write debug logs to this file (`~` is expanded). Same as `--debug` with
+    /// the file moved; `$POLYRC_LOG_FILE` overrides it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+/// `[claude]` — Claude Code parsing options. Superseded by `[formats.claude]`.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ClaudeConfig {
+    /// Store `settings.json`'s `permissions` as a separate, normalized
+    /// `settings-permissions` rule instead of inside the `settings` blob.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_permissions: Option<bool>,
+}
+
+/// `[copilot]` — GitHub Copilot output options. Superseded by `[formats.copilot]`.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct CopilotConfig {
+    /// Write each always rule to `.github/instructions/<name>.instructions.md`
+    /// with `applyTo: "**"` instead of one combined `copilot-instructions.md`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split_always: Option<bool>,
+}
+
+/// `[write]` — how rules are rendered into format files.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct WriteConfig {
+    /// Derive a description from content for AI-decided rules that lack one,
+    /// where the target format needs it. Defaults to true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derive_descriptions: Option<bool>,
+
+    /// `downgrade` (default), `skip` or `error` for rules whose activation the
+    /// target format can't express.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_unsupported_activation: Option<crate::writer::ActivationPolicy>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct StoreConfig {
+    /// Path to the local store git repo. Defaults to ~/polyrc/store.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Store format version — set on `polyrc init`, absent if not yet initialised.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// RFC3339 timestamp of store creation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+
+    /// Optional git remote URL for sync.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_url: Option<String>,
+
+    /// Keep a generated README.md at the store root. Defaults to true.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generate_readme: Option<bool>,
+
+    /// Number of `polyrc store backup` bundles kept in `~/polyrc/backups`. Defaults to 10.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_retention: Option<usize>,
+}
+
+impl Config {
+    /// Load config from ~/.polyrc/config.toml.
+    /// Returns a default config if the file does not exist.
+    pub fn load() -> Result<Self> {
+        let path = config_file_path();
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let raw = std::fs::read_to_string(&path).map_err(|e| PolyrcError::Io {
+            path: path.clone(),
+            source: e,
+        })?;
+        toml::from_str(&raw).map_err(|e| PolyrcError::TomlParse { path, source: e })
+    }
+
+    /// Save config to ~/.polyrc/config.toml.
+    pub fn save(&self) -> Result<()> {
+        let path = config_file_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| PolyrcError::Io {
+                path: parent.to_path_buf(),
+                source: e,
+            })?;
+        }
+        let content = toml::to_string_pretty(self).map_err(|e| PolyrcError::ConfigError {
+            msg: format!("failed to serialize config: {e}"),
+        })?;
+        std::fs::write(&path, content).map_err(|e| PolyrcError::Io { path, source: e })
+    }
+
+    /// The store path set in config (tilde-expanded), if any.
+    ///
+    /// Use `store::resolve_store_path` to apply the full precedence order.
+    pub fn configured_store_path(&self) -> Option<PathBuf> {
+        self.store.path.as_deref().map(expand_tilde)
+    }
+
+
+    /// Every configured per-format option, validated and keyed by canonical
+    /// format name. `[formats.<name>]` wins over the older `[copilot]` and
+    /// `[claude]` sections.
+    pub fn format_options(&self) -> Result<OptionsMap> {
+        let mut out = OptionsMap::new();
+        let legacy = [
+            ("copilot", crate::formats::copilot::SPLIT_ALWAYS, self.copilot.split_always),
+            ("claude", crate::formats::claude::STRUCTURED_PERMISSIONS, self.claude.structured_permissions),
+        ];
+        for (format, key, value) in legacy {
+            if let Some(value) = value {
+                out.entry(format.to_string()).or_default().set(key, toml::Value::Boolean(value));
+            }
+        }
+        for (format, table) in &self.formats {
+            for (key, value) in table {
+                let fmt = options::validate(format, key, value).map_err(|e| PolyrcError::ConfigError {
+                    msg: format!("[formats.{format}]: {e}"),
+                })?;
+                out.entry(fmt.name().to_string()).or_default().set(key.clone(), value.clone());
+            }
+        }
+        Ok(out)
+    }
+
+    /// One per-format option, e.g. `config.format_option::<bool>("copilot", "split_always")`.
+    /// Errors for formats or keys that don't exist, `None` when unset.
+    pub fn format_option<T: DeserializeOwned>(&self, format: &str, key: &str) -> Result<Option<T>> {
+        let fmt = <crate::formats::Format as std::str::FromStr>::from_str(format)?;
+        if !fmt.options().iter().any(|s| s.key == key) {
+            return Err(PolyrcError::ConfigError { msg: format!("unknown option '{key}' for format '{}'", fmt.name()) });
+        }
+        Ok(self.format_options()?.get(fmt.name()).and_then(|o| o.get(key)))
+    }
+
+    /// `[user_dirs]` keyed by canonical format name, with paths expanded.
+    /// Errors for unknown formats and for formats without a user config dir.
+    pub fn user_dirs(&self) -> Result<BTreeMap<String, PathBuf>> {
+        let mut out = BTreeMap::new();
+        for (format, dir) in &self.user_dirs {
+            let fmt = <crate::formats::Format as std::str::FromStr>::from_str(format)?;
+            if fmt.default_user_dir().is_none() {
+                return Err(PolyrcError::ConfigError {
+                    msg: format!("[user_dirs]: {} has no local user config dir to override", fmt.name()),
+                });
+            }
+            out.insert(fmt.name().to_string(), expand_path(dir));
+        }
+        Ok(out)
+    }
+
+    /// The editor command to open files with: `preferred_editor`, then `$EDITOR`
+    /// (passed in as `env`), then `notepad` on Windows and `vi` elsewhere.
+    pub fn editor(&self, env: Option<&str>) -> String {
+        self.preferred_editor
+            .as_deref()
+            .or(env)
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .unwrap_or(if cfg!(windows) { "notepad" } else { "vi" })
+            .to_string()
+    }
+
+    /// Whether mutating store operations regenerate the store README.
+    pub fn generate_readme(&self) -> bool {
+        self.store.generate_readme.unwrap_or(true)
+    }
+
+    /// How many timestamped store backups to keep.
+    pub fn backup_retention(&self) -> usize {
+        self.store.backup_retention.unwrap_or(crate::store::DEFAULT_BACKUP_RETENTION)
+    }
+
+    /// Days without a sync before commands remind you to run it (0 = never).
+    pub fn sync_stale_after_days(&self) -> u64 {
+        self.sync.stale_after_days.unwrap_or(7)
+    }
+
+    /// Where to write debug logs: `$POLYRC_LOG_FILE` (passed in as `env`), then
+    /// `[log] file`, then the default location when `debug` (`--debug`) is set.
+    /// `None` leaves logging off.
+    pub fn log_file(&self, debug: bool, env: Option<&str>) -> Option<PathBuf> {
+        env.filter(|p| !p.is_empty())
+            .or(self.log.file.as_deref())
+            .map(expand_tilde)
+            .or_else(|| debug.then(crate::logfile::default_log_path))
+    }
+
+    /// Whether `sync` exits 0 after working offline.
+    pub fn sync_offline_ok(&self) -> bool {
+        self.sync.offline_ok.unwrap_or(true)
+    }
+
+    /// How `sync` pairs rules changed on both sides.
+    pub fn sync_merge_options(&self) -> crate::store::MergeOptions {
+        crate::store::MergeOptions { match_by_name: self.sync.match_by_name.unwrap_or(true) }
+    }
+
+    /// Days `sync` keeps tombstones of deleted rules (0 = forever).
+    pub fn sync_tombstone_retention_days(&self) -> u64 {
+        self.sync.tombstone_retention_days.unwrap_or(crate::store::DEFAULT_TOMBSTONE_RETENTION_DAYS)
+    }
+
+    /// Returns true if the store has been initialised (version is set).
+    pub fn store_initialized(&self) -> bool {
+        self.store.version.is_some()
+    }
+
+    /// Mark the store as initialised with version + timestamp, and optionally set remote URL.
+    pub fn init_store_config(&mut self, remote_url: Option<&str>) {
+        self.store.version = Some("1".to_string());
+        self.store.created_at = Some(chrono::Utc::now().to_rfc3339());
+        if let Some(url) = remote_url {
+            self.store.remote_url = Some(url.to_string());
+        }
+    }
+}
+
+/// Root directory for all polyrc data and config: ~/polyrc/
+pub fn polyrc_dir() -> PathBuf {
+    home_dir().join("polyrc")
+}
+
+/// `~/polyrc/config.toml`.
+pub fn config_file_path() -> PathBuf {
+    polyrc_dir().join("config.toml")
+}
+
+pub fn default_store_path() -> PathBuf {
+    polyrc_dir().join("store")
+}
+
+/// Resolve the user's home directory.
+///
+/// Uses `dirs::home_dir()` (which honours `HOME` on Unix and the profile folder
+/// on Windows), then falls back to `USERPROFILE` on Windows or `HOME` elsewhere,
+/// and finally to the current directory.
+pub fn home_dir() -> PathBuf {
+    let fallback_var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    dirs::home_dir()
+        .filter(|h| !h.as_os_str().is_empty())
+        .or_else(|| {
+            std::env::var(fallback_var)
+                .ok()
+                .filter(|h| !h.is_empty())
+                .map(PathBuf::from)
+        })
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Expand a leading `~`, `~/` or `~\\` to the user's home directory.
+pub(crate) fn expand_tilde(p: &str) -> PathBuf {
+    expand_tilde_with(p, &home_dir())
+}
+
+/// Expand `$VAR` / `${VAR}` from the environment, then a leading `~`.
+/// Unset variables are left as written.
+pub fn expand_path(p: &str) -> PathBuf {
+    expand_tilde(&expand_env_with(p, |name| std::env::var(name).ok()))
+}
+
+fn expand_env_with(p: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(p.len());
+    let mut rest = p;
+    while let Some(at) = rest.find('$') {
+        out.push_str(&rest[..at]);
+        let after = &rest[at + 1..];
+        let (name, len) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+        match lookup(name).filter(|_| !name.is_empty()) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[at..at + 1 + len]),
+        }
+        rest = &after[len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn expand_tilde_with(p: &str, home: &std::path::Path) -> PathBuf {
+    if p == "~" {
+        return home.to_path_buf();
+    }
+    match p.strip_prefix("~/").or_else(|| p.strip_prefix("~\\")) {
+        Some(rest) => rest
+            .split(['/', '\\'])
+            .filter(|seg| !seg.is_empty())
+            .fold(home.to_path_buf(), |acc, seg| acc.join(seg)),
+        None => PathBuf::from(p),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn expand_tilde_forward_slash() {
+        let home = Path::new("/home/me");
+        assert_eq!(expand_tilde_with("~/polyrc/store", home), home.join("polyrc").join("store"));
+    }
+
+    #[test]
+    fn expand_tilde_backslash() {
+        let home = Path::new("/home/me");
+        assert_eq!(expand_tilde_with("~\\polyrc\\store", home), home.join("polyrc").join("store"));
+    }
+
+    #[test]
+    fn expand_tilde_bare_and_absolute() {
+        let home = Path::new("/home/me");
+        assert_eq!(expand_tilde_with("~", home), home.to_path_buf());
+        assert_eq!(expand_tilde_with("/srv/store", home), PathBuf::from("/srv/store"));
+        assert_eq!(expand_tilde_with("~other/x", home), PathBuf::from("~other/x"));
+    }
+
+    #[test]
+    fn expand_env_vars() {
+        let lookup = |name: &str| match name {
+            "XDG_DATA_HOME" => Some("/home/me/.local/share".to_string()),
+            "APP" => Some("windsurf".to_string()),
+            _ => None,
+        };
+        assert_eq!(expand_env_with("$XDG_DATA_HOME/codeium", lookup), "/home/me/.local/share/codeium");
+        assert_eq!(expand_env_with("~/.var/${APP}-data/memories", lookup), "~/.var/windsurf-data/memories");
+        assert_eq!(expand_env_with("$UNSET/x and ${ALSO_UNSET}", lookup), "$UNSET/x and ${ALSO_UNSET}");
+        assert_eq!(expand_env_with("cost: $5 and $ alone, ${open", lookup), "cost: $5 and $ alone, ${open");
+    }
+
+    #[test]
+    fn user_dirs_are_validated_and_expanded() {
+        let config: Config = toml::from_str("[user_dirs]\nWindsurf = \"/opt/memories\"\ngemini-cli = \"~/g\"\n").unwrap();
+        let dirs = config.user_dirs().unwrap();
+        assert_eq!(dirs["windsurf"], PathBuf::from("/opt/memories"));
+        assert_eq!(dirs["gemini"], home_dir().join("g"));
+
+        let config: Config = toml::from_str("[user_dirs]\ncursor = \"/x\"\n").unwrap();
+        assert!(config.user_dirs().unwrap_err().to_string().contains("cursor has no local user config dir"));
+        let config: Config = toml::from_str("[user_dirs]\nnotepad = \"/x\"\n").unwrap();
+        assert!(config.user_dirs().is_err());
+    }
+
+    #[test]
+    fn log_file_precedence() {
+        let config: Config = toml::from_str("[log]\nfile = \"/var/log/polyrc.log\"\n").unwrap();
+        assert_eq!(config.log_file(false, Some("/tmp/env.log")), Some(PathBuf::from("/tmp/env.log")));
+        assert_eq!(config.log_file(false, None), Some(PathBuf::from("/var/log/polyrc.log")));
+        assert_eq!(Config::default().log_file(true, None), Some(crate::logfile::default_log_path()));
+        assert_eq!(Config::default().log_file(false, Some("")), None);
+    }
+
+    #[test]
+    fn format_options_override_legacy_sections() {
+        let config: Config = toml::from_str(
+            "[copilot]\nsplit_always = true\n[claude]\nstructured_permissions = true\n\
+             [formats.github-copilot]\nsplit_always = false\n",
+        )
+        .unwrap();
+        assert_eq!(config.format_option::<bool>("copilot", "split_always").unwrap(), Some(false));
+        assert_eq!(config.format_option::<bool>("claude", "structured_permissions").unwrap(), Some(true));
+        assert!(config.format_option::<bool>("cursor", "legacy").is_err());
+    }
+
+    #[test]
+    fn unknown_format_options_are_rejected() {
+        let config: Config = toml::from_str("[formats.copilot]\nsplt_always = true\n").unwrap();
+        let err = config.format_options().unwrap_err().to_string();
+        assert!(err.contains("[formats.copilot]: Config error: unknown option 'splt_always'"), "{err}");
+
+        let config: Config = toml::from_str("[formats.notepad]\nx = 1\n").unwrap();
+        assert!(config.format_options().is_err());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn home_dir_is_absolute_on_windows() {
+        assert!(home_dir().is_absolute());
+    }
+}
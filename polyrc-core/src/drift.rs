@@ -0,0 +1,425 @@
+//! Compare a format's files on disk against the rules in the store.
+//!
+//! Store rules are rendered through the format's writer into a scratch directory
+//! and parsed back, so both sides go through the same (possibly lossy) parser
+//! before comparison. Nothing in the store or the working tree is modified.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::error::{PolyrcError, Result};
+use crate::formats::Format;
+use crate::ir::Rule;
+
+/// How one rule differs between the store and the local files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum DriftKind {
+    /// In the store but not on disk.
+    MissingLocally,
+    /// On disk but not in the store.
+    MissingInStore,
+    /// Present on both sides with different fields.
+    Modified {
+        fields: Vec<String>,
+        /// First differing content line, when content differs.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        first_difference: Option<LineDiff>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineDiff {
+    /// 1-based line number within the rule content (surrounding blank lines ignored).
+    pub line: usize,
+    pub store: Option<String>,
+    pub local: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Drift {
+    pub rule: String,
+    #[serde(flatten)]
+    pub kind: DriftKind,
+}
+
+/// Compare `store_rules` rendered as `fmt` with what `fmt` parses from `local_dir`.
+/// Results are sorted by rule name.
+pub fn diff_format(fmt: &Format, store_rules: &[Rule], local_dir: &Path) -> Result<Vec<Drift>> {
+    let (store, local) = sides(fmt, store_rules, local_dir)?;
+    Ok(diff_rules(&store, &local))
+}
+
+/// Both sides [`diff_format`] compares, keyed by rule name: `store_rules`
+/// rendered as `fmt`, and what `fmt` parses from `local_dir`.
+pub fn sides(fmt: &Format, store_rules: &[Rule], local_dir: &Path) -> Result<(BTreeMap<String, Rule>, BTreeMap<String, Rule>)> {
+    let rendered = render(fmt, store_rules)?;
+    let local = fmt.parser().parse(local_dir)?;
+    Ok((keyed(rendered), keyed(local)))
+}
+
+/// Round-trip `rules` through `fmt`'s writer and parser in a scratch directory.
+pub(crate) fn render(fmt: &Format, rules: &[Rule]) -> Result<Vec<Rule>> {
+    if rules.is_empty() {
+        return Ok(vec![]);
+    }
+    let scratch = std::env::temp_dir().join(format!("polyrc-check-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&scratch).map_err(|e| PolyrcError::Io { path: scratch.clone(), source: e })?;
+    let result = fmt.writer().write(rules, &scratch).and_then(|_| fmt.parser().parse(&scratch));
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
+
+pub(crate) fn keyed(rules: Vec<Rule>) -> BTreeMap<String, Rule> {
+    rules
+        .into_iter()
+        .map(|r| (r.name.clone().unwrap_or_else(|| r.filename_stem()), r))
+        .collect()
+}
+
+/// How the rules in `store` and `local` differ, sorted by rule name.
+pub fn diff_rules(store: &BTreeMap<String, Rule>, local: &BTreeMap<String, Rule>) -> Vec<Drift> {
+    let mut names: Vec<&String> = store.keys().chain(local.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let kind = match (store.get(name), local.get(name)) {
+                (Some(_), None) => DriftKind::MissingLocally,
+                (None, Some(_)) => DriftKind::MissingInStore,
+                (Some(s), Some(l)) => compare(s, l)?,
+                (None, None) => return None,
+            };
+            Some(Drift { rule: name.clone(), kind })
+        })
+        .collect()
+}
+
+fn compare(store: &Rule, local: &Rule) -> Option<DriftKind> {
+    let mut fields = vec![];
+    if store.scope != local.scope {
+        fields.push("scope".to_string());
+    }
+    if store.activation != local.activation {
+        fields.push("activation".to_string());
+    }
+    if store.globs != local.globs {
+        fields.push("globs".to_string());
+    }
+    if store.description != local.description {
+        fields.push("description".to_string());
+    }
+    if store.frontmatter.as_deref().map(str::trim) != local.frontmatter.as_deref().map(str::trim) {
+        fields.push("frontmatter".to_string());
+    }
+    let first_difference = first_line_diff(store.content.trim(), local.content.trim());
+    if first_difference.is_some() {
+        fields.push("content".to_string());
+    }
+    (!fields.is_empty()).then_some(DriftKind::Modified { fields, first_difference })
+}
+
+fn first_line_diff(store: &str, local: &str) -> Option<LineDiff> {
+    if store == local {
+        return None;
+    }
+    let (mut s, mut l) = (store.lines(), local.lines());
+    let mut line = 1;
+    loop {
+        match (s.next(), l.next()) {
+            (a, b) if a == b && a.is_some() => line += 1,
+            (a, b) => {
+                return Some(LineDiff { line, store: a.map(str::to_string), local: b.map(str::to_string) });
+            }
+        }
+    }
+}
+
+/// Where one rule stands between the local files and the store, for `polyrc status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncState {
+    InSync,
+    /// Differs, and the local file was modified after the store rule was updated.
+    LocalNewer,
+    /// Differs, and the store rule was updated last.
+    StoreNewer,
+    OnlyLocal,
+    OnlyStore,
+}
+
+impl SyncState {
+    pub const ALL: [SyncState; 5] = [Self::InSync, Self::LocalNewer, Self::StoreNewer, Self::OnlyLocal, Self::OnlyStore];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::InSync => "in sync",
+            Self::LocalNewer => "local newer",
+            Self::StoreNewer => "store newer",
+            Self::OnlyLocal => "only local",
+            Self::OnlyStore => "only store",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleState {
+    pub rule: String,
+    pub state: SyncState,
+}
+
+/// Where a whole format stands: the side its differing rules lean to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatState {
+    InSync,
+    LocalNewer,
+    StoreNewer,
+    /// Some rules are newer locally and others in the store.
+    Diverged,
+    /// None of the format's rules are in the store.
+    NotInStore,
+}
+
+impl FormatState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::InSync => "in sync",
+            Self::LocalNewer => "local newer",
+            Self::StoreNewer => "store newer",
+            Self::Diverged => "diverged",
+            Self::NotInStore => "not in store",
+        }
+    }
+
+    pub fn of(rules: &[RuleState]) -> Self {
+        let has = |states: &[SyncState]| rules.iter().any(|r| states.contains(&r.state));
+        let (local, store) = (has(&[SyncState::LocalNewer, SyncState::OnlyLocal]), has(&[SyncState::StoreNewer, SyncState::OnlyStore]));
+        match (local, store) {
+            _ if !rules.is_empty() && rules.iter().all(|r| r.state == SyncState::OnlyLocal) => Self::NotInStore,
+            (true, true) => Self::Diverged,
+            (true, false) => Self::LocalNewer,
+            (false, true) => Self::StoreNewer,
+            (false, false) => Self::InSync,
+        }
+    }
+}
+
+/// Every rule on either side of [`diff_format`] with its [`SyncState`], sorted
+/// by name. Rules are matched by name and compared by content and metadata;
+/// when they differ, the local file's modification time is weighed against the
+/// store rule's `updated_at`, and a side without a timestamp counts as older.
+pub fn sync_states(fmt: &Format, store_rules: &[Rule], local_dir: &Path) -> Result<Vec<RuleState>> {
+    let (store, local) = sides(fmt, store_rules, local_dir)?;
+    let mut states: BTreeMap<String, SyncState> =
+        store.keys().chain(local.keys()).map(|name| (name.clone(), SyncState::InSync)).collect();
+    let updated = keyed(store_rules.to_vec());
+    let (modified, newest) = local_times(fmt, local_dir);
+    for drift in diff_rules(&store, &local) {
+        let state = match drift.kind {
+            DriftKind::MissingLocally => SyncState::OnlyStore,
+            DriftKind::MissingInStore => SyncState::OnlyLocal,
+            DriftKind::Modified { .. } => {
+                let store_time = updated
+                    .get(&drift.rule)
+                    .and_then(|r| r.updated_at.as_deref())
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .map(|t| t.with_timezone(&Utc));
+                // A single-file format has no file per rule; its newest file stands in
+                let local_time = modified.get(&drift.rule).copied().or(newest);
+                if local_time > store_time { SyncState::LocalNewer } else { SyncState::StoreNewer }
+            }
+        };
+        states.insert(drift.rule, state);
+    }
+    Ok(states.into_iter().map(|(rule, state)| RuleState { rule, state }).collect())
+}
+
+/// Modification times of `fmt`'s files under `local_dir`, keyed by file name
+/// without its rule-directory suffix, and the newest of them.
+fn local_times(fmt: &Format, local_dir: &Path) -> (HashMap<String, DateTime<Utc>>, Option<DateTime<Utc>>) {
+    let (mut times, mut newest) = (HashMap::new(), None);
+    for path in fmt.project_paths() {
+        for entry in walkdir::WalkDir::new(local_dir.join(path)).into_iter().flatten() {
+            let Some(time) = entry.metadata().ok().and_then(|m| m.modified().ok()).map(DateTime::<Utc>::from) else {
+                continue;
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            newest = newest.max(Some(time));
+            let name = entry.file_name().to_string_lossy();
+            if let Some(stem) = fmt.rule_dirs().iter().find_map(|dir| name.strip_suffix(dir.suffix)) {
+                times.insert(stem.to_string(), time);
+            }
+        }
+    }
+    (times, newest)
+}
+
+/// Lines of unchanged context around each hunk of [`unified_diff`].
+const CONTEXT: usize = 3;
+
+/// `old` → `new` as a unified diff under `---`/`+++` headers naming them, or an
+/// empty string when they match. Surrounding blank lines are ignored, as in
+/// [`LineDiff`].
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let (old, new): (Vec<&str>, Vec<&str>) = (old.trim().lines().collect(), new.trim().lines().collect());
+    if old == new {
+        return String::new();
+    }
+    let ops = line_ops(&old, &new);
+    let changed: Vec<usize> = ops.iter().enumerate().filter(|(_, (tag, _))| *tag != ' ').map(|(i, _)| i).collect();
+    let count = |ops: &[(char, &str)], skip: char| ops.iter().filter(|(tag, _)| *tag != skip).count();
+
+    let mut out = format!("--- {old_label}\n+++ {new_label}\n");
+    let mut i = 0;
+    while i < changed.len() {
+        let start = changed[i].saturating_sub(CONTEXT);
+        // Changes closer than twice the context share a hunk
+        while i + 1 < changed.len() && changed[i + 1] - changed[i] <= 2 * CONTEXT + 1 {
+            i += 1;
+        }
+        let end = (changed[i] + CONTEXT + 1).min(ops.len());
+        let hunk = &ops[start..end];
+        let (old_len, new_len) = (count(hunk, '+'), count(hunk, '-'));
+        // An empty range starts at the line before it
+        let old_start = count(&ops[..start], '+') + usize::from(old_len > 0);
+        let new_start = count(&ops[..start], '-') + usize::from(new_len > 0);
+        out.push_str(&format!("@@ -{old_start},{old_len} +{new_start},{new_len} @@\n"));
+        for (tag, line) in hunk {
+            out.push_str(&format!("{tag}{line}\n"));
+        }
+        i += 1;
+    }
+    out
+}
+
+/// The edit script from `old` to `new` along their longest common subsequence:
+/// `' '` keeps a line, `'-'` removes one and `'+'` adds one.
+fn line_ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(char, &'a str)> {
+    // lcs[i][j]: length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j, mut ops) = (0, 0, vec![]);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((' ', old[i]));
+            (i, j) = (i + 1, j + 1);
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', old[i]));
+            i += 1;
+        } else {
+            ops.push(('+', new[j]));
+            j += 1;
+        }
+    }
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Activation, Scope};
+
+    fn rule(name: &str, content: &str) -> Rule {
+        Rule {
+            name: Some(name.into()),
+            content: content.into(),
+            scope: Scope::Project,
+            activation: Activation::Always,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn clean_when_local_matches_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = vec![rule("style", "Use tabs.\n"), rule("tests", "Write tests.")];
+        Format::Cursor.writer().write(&rules, dir.path()).unwrap();
+        assert!(diff_format(&Format::Cursor, &rules, dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reports_missing_and_modified_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = vec![rule("style", "Use tabs.\nWrap at 80."), rule("extra", "local only")];
+        Format::Cursor.writer().write(&local, dir.path()).unwrap();
+
+        let store = vec![rule("style", "Use tabs.\nWrap at 100."), rule("gone", "store only")];
+        let drift = diff_format(&Format::Cursor, &store, dir.path()).unwrap();
+        assert_eq!(drift.len(), 3);
+        assert_eq!(drift[0], Drift { rule: "extra".into(), kind: DriftKind::MissingInStore });
+        assert_eq!(drift[1], Drift { rule: "gone".into(), kind: DriftKind::MissingLocally });
+        match &drift[2].kind {
+            DriftKind::Modified { fields, first_difference: Some(d) } => {
+                assert_eq!(fields, &vec!["content".to_string()]);
+                assert_eq!(d.line, 2);
+                assert_eq!(d.store.as_deref(), Some("Wrap at 100."));
+                assert_eq!(d.local.as_deref(), Some("Wrap at 80."));
+            }
+            other => panic!("expected Modified, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reports_changed_frontmatter() {
+        let store = Rule { frontmatter: Some("allowed-tools: Bash(git:*)\n".into()), ..rule("commit", "Commit.") };
+        let local = Rule { frontmatter: Some("allowed-tools: Bash\n".into()), ..rule("commit", "Commit.") };
+        assert!(!store.same_content(&local));
+        assert_eq!(
+            compare(&store, &local),
+            Some(DriftKind::Modified { fields: vec!["frontmatter".to_string()], first_difference: None })
+        );
+    }
+
+    #[test]
+    fn unified_diff_groups_changes_into_hunks() {
+        let old = (1..=12).map(|n| format!("line {n}")).collect::<Vec<_>>().join("\n");
+        let new = old.replace("line 2", "line two").replace("line 12", "") + "\nline 13";
+        assert_eq!(unified_diff(&old, &old, "a", "b"), "");
+        assert_eq!(
+            unified_diff(&old, &new, "store/style", "local/style"),
+            "--- store/style\n+++ local/style\n\
+             @@ -1,5 +1,5 @@\n line 1\n-line 2\n+line two\n line 3\n line 4\n line 5\n\
+             @@ -9,4 +9,5 @@\n line 9\n line 10\n line 11\n-line 12\n+\n+line 13\n"
+        );
+        assert_eq!(unified_diff("", "Added.", "a", "b"), "--- a\n+++ b\n@@ -0,0 +1,1 @@\n+Added.\n");
+    }
+
+    #[test]
+    fn sync_states_use_timestamps_to_break_ties() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = vec![rule("style", "Wrap at 80."), rule("tests", "Test it."), rule("extra", "local only")];
+        Format::Cursor.writer().write(&local, dir.path()).unwrap();
+
+        let mut store = vec![rule("style", "Wrap at 100."), rule("tests", "Test it."), rule("gone", "store only")];
+        let state = |store: &[Rule]| sync_states(&Format::Cursor, store, dir.path()).unwrap();
+        let states = state(&store);
+        let names: Vec<(&str, SyncState)> = states.iter().map(|r| (r.rule.as_str(), r.state)).collect();
+        // The store rule has no updated_at, so the local file wins
+        assert_eq!(
+            names,
+            vec![
+                ("extra", SyncState::OnlyLocal),
+                ("gone", SyncState::OnlyStore),
+                ("style", SyncState::LocalNewer),
+                ("tests", SyncState::InSync),
+            ]
+        );
+        assert_eq!(FormatState::of(&states), FormatState::Diverged);
+
+        store[0].updated_at = Some((Utc::now() + chrono::Duration::hours(1)).to_rfc3339());
+        assert_eq!(state(&store)[2].state, SyncState::StoreNewer);
+        assert_eq!(FormatState::of(&state(&[])), FormatState::NotInStore);
+        let states = [RuleState { rule: "style".into(), state: SyncState::StoreNewer }, states[3].clone()];
+        assert_eq!(FormatState::of(&states), FormatState::StoreNewer);
+    }
+}
@@ -0,0 +1,218 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, PolyrcError>;
+
+#[derive(Debug, Error)]
+pub enum PolyrcError {
+    #[error("IO error reading {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("YAML parse error in {path}: {source}")]
+    YamlParse {
+        path: PathBuf,
+        #[source]
+        source: serde_yml::Error,
+    },
+
+    #[error(
+        "Unknown format: '{name}'.{} Use `polyrc supported-formats` to see valid formats.",
+        crate::suggest::did_you_mean(suggestions)
+    )]
+    UnknownFormat { name: String, suggestions: Vec<String> },
+
+    #[error(
+        "{format} can't express the activation of {}; nothing written (use --unsupported-activation downgrade or skip)",
+        rules.join(", ")
+    )]
+    UnsupportedActivation { format: String, rules: Vec<String> },
+
+    #[error(
+        "--strict: writing {format} would lose:\n{}\nnothing written",
+        losses.iter().map(|l| format!("  - {l}")).collect::<Vec<_>>().join("\n")
+    )]
+    LossyConversion { format: String, losses: Vec<String> },
+
+    #[error("Cannot write to {path}: {reason}")]
+    WriteFailure { path: PathBuf, reason: String },
+
+    #[error(
+        "Rule '{name}' exists in several projects: {}. Pass --project <name> or --user to choose.",
+        candidates.join(", ")
+    )]
+    AmbiguousRule { name: String, candidates: Vec<String> },
+
+    #[error("Invalid rule name '{name}': {reason}")]
+    InvalidName { name: String, reason: String },
+
+    #[error("Invalid search pattern '{pattern}': {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+
+    #[error(
+        "Rule '{name}' not found {}.{}{}",
+        namespace.as_deref().map(|ns| format!("in project '{ns}'")).unwrap_or_else(|| "in any project".to_string()),
+        crate::suggest::did_you_mean(&crate::suggest::suggest(name, available)),
+        list_available("rules", available)
+    )]
+    RuleNotFound { name: String, namespace: Option<String>, available: Vec<String> },
+
+    #[error(
+        "Project '{name}' not found.{}{}",
+        crate::suggest::did_you_mean(&crate::suggest::suggest(name, available)),
+        list_available("projects", available)
+    )]
+    ProjectNotFound { name: String, available: Vec<String> },
+
+    #[error("{path} is {size} bytes, over the {limit}-byte limit (raise it with --max-file-size)")]
+    FileTooLarge { path: PathBuf, size: u64, limit: u64 },
+
+    #[error("Failed to fetch {url}: {reason}")]
+    Fetch { url: String, reason: String },
+
+    #[error("Store not found. Run `polyrc init` first.")]
+    StoreNotFound,
+
+    #[error("Git error: {msg}")]
+    GitError { msg: String },
+
+    #[error("Plugin '{name}' failed: {reason}")]
+    Plugin { name: String, reason: String },
+
+    #[error("Config error: {msg}")]
+    ConfigError { msg: String },
+
+    #[error("TOML parse error in {path}: {source}")]
+    TomlParse {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+}
+
+/// Process exit code for errors without a more specific one.
+pub const EXIT_ERROR: i32 = 1;
+/// Exit code for bad input: unknown formats, invalid names, ambiguous rules.
+pub const EXIT_USAGE: i32 = 2;
+/// Exit code when no store is set up.
+pub const EXIT_STORE_NOT_FOUND: i32 = 3;
+/// Exit code when a named rule or project doesn't exist.
+pub const EXIT_NOT_FOUND: i32 = 4;
+/// Exit code for unreadable or invalid configuration.
+pub const EXIT_CONFIG: i32 = 5;
+/// Exit code for `polyrc check` and `polyrc diff` when local files and the store disagree.
+pub const EXIT_DRIFT: i32 = 6;
+/// Exit code for `polyrc check` when the comparison itself fails.
+pub const EXIT_CHECK_ERROR: i32 = 7;
+/// Exit code for `polyrc sync --strict-network` when the remote is unreachable
+/// (7 was already [`EXIT_CHECK_ERROR`]).
+pub const EXIT_OFFLINE: i32 = 8;
+/// Exit code for `polyrc budget --fail-over-limit` when a format is over a limit.
+pub const EXIT_OVER_BUDGET: i32 = 9;
+/// Exit code when a conversion would lose something: `--strict`, `polyrc verify`.
+pub const EXIT_LOSSY: i32 = 10;
+
+impl PolyrcError {
+    /// Stable snake_case name of the variant, used as `kind` in JSON error output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Io { .. } => "io",
+            Self::YamlParse { .. } => "yaml_parse",
+            Self::UnknownFormat { .. } => "unknown_format",
+            Self::UnsupportedActivation { .. } => "unsupported_activation",
+            Self::LossyConversion { .. } => "lossy_conversion",
+            Self::WriteFailure { .. } => "write_failure",
+            Self::AmbiguousRule { .. } => "ambiguous_rule",
+            Self::InvalidName { .. } => "invalid_name",
+            Self::InvalidPattern { .. } => "invalid_pattern",
+            Self::RuleNotFound { .. } => "rule_not_found",
+            Self::ProjectNotFound { .. } => "project_not_found",
+            Self::FileTooLarge { .. } => "file_too_large",
+            Self::Fetch { .. } => "fetch",
+            Self::StoreNotFound => "store_not_found",
+            Self::GitError { .. } => "git",
+            Self::Plugin { .. } => "plugin",
+            Self::ConfigError { .. } => "config",
+            Self::TomlParse { .. } => "toml_parse",
+        }
+    }
+
+    /// Process exit code for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::UnknownFormat { .. }
+            | Self::InvalidName { .. }
+            | Self::InvalidPattern { .. }
+            | Self::AmbiguousRule { .. } => EXIT_USAGE,
+            Self::StoreNotFound => EXIT_STORE_NOT_FOUND,
+            Self::RuleNotFound { .. } | Self::ProjectNotFound { .. } => EXIT_NOT_FOUND,
+            Self::ConfigError { .. } | Self::TomlParse { .. } => EXIT_CONFIG,
+            Self::LossyConversion { .. } => EXIT_LOSSY,
+            _ => EXIT_ERROR,
+        }
+    }
+}
+
+/// How many names `list_available` shows before summarising the rest.
+const MAX_LISTED: usize = 10;
+
+/// Render `" Available rules: a, b, … (N more)"`, or `" No rules exist."` when empty.
+fn list_available(kind: &str, names: &[String]) -> String {
+    if names.is_empty() {
+        return format!(" No {kind} exist.");
+    }
+    let shown = names.iter().take(MAX_LISTED).cloned().collect::<Vec<_>>().join(", ");
+    match names.len().saturating_sub(MAX_LISTED) {
+        0 => format!(" Available {kind}: {shown}."),
+        more => format!(" Available {kind}: {shown}, … ({more} more)."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(n: &[&str]) -> Vec<String> {
+        n.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn rule_not_found_lists_available() {
+        let err = PolyrcError::RuleNotFound {
+            name: "rust-stye".into(),
+            namespace: Some("myApp".into()),
+            available: names(&["rust-style", "tone"]),
+        };
+        let msg = err.to_string();
+        assert!(msg.contains("in project 'myApp'"), "{msg}");
+        assert!(msg.contains("Did you mean 'rust-style'?"), "{msg}");
+        assert!(msg.contains("Available rules: rust-style, tone."), "{msg}");
+    }
+
+    #[test]
+    fn project_not_found_truncates_list() {
+        let available: Vec<String> = (0..13).map(|i| format!("p{i:02}")).collect();
+        let msg = PolyrcError::ProjectNotFound { name: "zzz".into(), available }.to_string();
+        assert!(msg.contains("p00, p01"), "{msg}");
+        assert!(msg.contains("p09, … (3 more)"), "{msg}");
+        assert!(!msg.contains("p10"), "{msg}");
+    }
+
+    #[test]
+    fn kinds_and_exit_codes_are_stable() {
+        assert_eq!((PolyrcError::StoreNotFound.kind(), PolyrcError::StoreNotFound.exit_code()), ("store_not_found", 3));
+        let missing = PolyrcError::ProjectNotFound { name: "x".into(), available: vec![] };
+        assert_eq!((missing.kind(), missing.exit_code()), ("project_not_found", 4));
+        let git = PolyrcError::GitError { msg: "boom".into() };
+        assert_eq!((git.kind(), git.exit_code()), ("git", EXIT_ERROR));
+    }
+
+    #[test]
+    fn not_found_with_nothing_available() {
+        let msg = PolyrcError::ProjectNotFound { name: "x".into(), available: vec![] }.to_string();
+        assert!(msg.ends_with("No projects exist."), "{msg}");
+    }
+}
@@ -0,0 +1,162 @@
+//! Download rule text from a URL (`push-rule --from-url`).
+
+use std::io::Read;
+use crate::error::{PolyrcError, Result};
+
+/// A downloaded rule body and the URL it finally came from (after redirects).
+#[derive(Debug, Clone)]
+pub struct Fetched {
+    pub url: String,
+    pub content: String,
+}
+
+/// Rewrite GitHub page URLs to their raw equivalents:
+///
+/// - `github.com/<o>/<r>/blob/<ref>/<path>` → `raw.githubusercontent.com/<o>/<r>/<ref>/<path>`
+/// - `gist.github.com/<user>/<id>` → `gist.githubusercontent.com/<user>/<id>/raw`
+///
+/// Anything else (including raw URLs) is returned unchanged.
+pub fn normalize_url(url: &str) -> String {
+    if let Some(rest) = url.strip_prefix("https://github.com/") {
+        let parts: Vec<&str> = rest.splitn(4, '/').collect();
+        if let [owner, repo, "blob", tail] = parts.as_slice() {
+            return format!("https://raw.githubusercontent.com/{owner}/{repo}/{tail}");
+        }
+    }
+    if let Some(rest) = url.strip_prefix("https://gist.github.com/") {
+        let rest = rest.trim_end_matches('/');
+        if rest.split('/').count() == 2 {
+            return format!("https://gist.githubusercontent.com/{rest}/raw");
+        }
+    }
+    url.to_string()
+}
+
+/// Rule name suggested by a URL: the last path segment without its extension.
+pub fn name_from_url(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next()?;
+    let path = path.split_once("://").map_or(path, |(_, rest)| rest.split_once('/').map_or("", |(_, p)| p));
+    let segment = path.rsplit('/').find(|s| !s.is_empty())?;
+    let stem = segment.rsplit_once('.').map_or(segment, |(stem, _)| stem);
+    (!stem.is_empty()).then(|| stem.to_string())
+}
+
+/// Download `url` as text. Rejects non-HTTP(S) URLs, non-2xx responses,
+/// non-text content types and bodies over `max_bytes`.
+pub fn fetch_text(url: &str, max_bytes: u64) -> Result<Fetched> {
+    let fail = |reason: String| PolyrcError::Fetch { url: url.to_string(), reason };
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(fail("only http(s) URLs are supported".to_string()));
+    }
+
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(format!("polyrc/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| fail(e.to_string()))?;
+    let resp = client.get(normalize_url(url)).send().map_err(|e| fail(e.to_string()))?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(fail(format!("server returned {status}")));
+    }
+    if let Some(ct) = resp.headers().get(reqwest::header::CONTENT_TYPE)
+        && let Ok(ct) = ct.to_str()
+        && !is_text_content_type(ct)
+    {
+        return Err(fail(format!("content type '{ct}' is not text")));
+    }
+    if let Some(len) = resp.content_length()
+        && len > max_bytes
+    {
+        return Err(fail(format!("{len} bytes exceeds the {max_bytes}-byte limit")));
+    }
+
+    let final_url = resp.url().to_string();
+    let mut body = Vec::new();
+    // Content-Length may be absent or wrong; never read more than the cap + 1
+    resp.take(max_bytes + 1).read_to_end(&mut body).map_err(|e| fail(e.to_string()))?;
+    if body.len() as u64 > max_bytes {
+        return Err(fail(format!("body exceeds the {max_bytes}-byte limit")));
+    }
+    let content = String::from_utf8(body).map_err(|_| fail("body is not valid UTF-8".to_string()))?;
+    Ok(Fetched { url: final_url, content })
+}
+
+fn is_text_content_type(ct: &str) -> bool {
+    let mime = ct.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    mime.starts_with("text/")
+        || matches!(
+            mime.as_str(),
+            "application/json" | "application/yaml" | "application/x-yaml" | "application/toml" | "application/xml"
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    /// Serve one canned HTTP response on a local port; returns the base URL.
+    fn serve_once(status: &str, content_type: &str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn fetches_text() {
+        let base = serve_once("200 OK", "text/plain; charset=utf-8", "Prefer small functions.\n");
+        let fetched = fetch_text(&format!("{base}/rules/style.md"), 1024).unwrap();
+        assert_eq!(fetched.content, "Prefer small functions.\n");
+        assert!(fetched.url.ends_with("/rules/style.md"));
+    }
+
+    #[test]
+    fn rejects_404() {
+        let base = serve_once("404 Not Found", "text/plain", "nope");
+        let err = fetch_text(&format!("{base}/missing.md"), 1024).unwrap_err();
+        assert!(err.to_string().contains("404"), "{err}");
+    }
+
+    #[test]
+    fn rejects_oversized_body() {
+        let base = serve_once("200 OK", "text/plain", "0123456789abcdef");
+        let err = fetch_text(&format!("{base}/big.md"), 8).unwrap_err();
+        assert!(err.to_string().contains("limit"), "{err}");
+    }
+
+    #[test]
+    fn rejects_binary_content_type() {
+        let base = serve_once("200 OK", "application/octet-stream", "\u{1}\u{2}");
+        assert!(fetch_text(&format!("{base}/blob.bin"), 1024).is_err());
+    }
+
+    #[test]
+    fn rewrites_github_page_urls() {
+        assert_eq!(
+            normalize_url("https://github.com/o/r/blob/main/docs/style.md"),
+            "https://raw.githubusercontent.com/o/r/main/docs/style.md"
+        );
+        assert_eq!(normalize_url("https://gist.github.com/me/abc123"), "https://gist.githubusercontent.com/me/abc123/raw");
+        let raw = "https://raw.githubusercontent.com/o/r/main/x.md";
+        assert_eq!(normalize_url(raw), raw);
+    }
+
+    #[test]
+    fn names_from_last_segment() {
+        assert_eq!(name_from_url("https://example.com/a/rust-style.md?x=1").as_deref(), Some("rust-style"));
+        assert_eq!(name_from_url("https://example.com/a/b/").as_deref(), Some("b"));
+        assert_eq!(name_from_url("https://example.com/"), None);
+    }
+}
@@ -0,0 +1,101 @@
+//! `AGENTS.md`, the plain-markdown instructions file read by Codex, Cursor,
+//! Zed and others.
+//!
+//! The root file is one always-on rule. An `AGENTS.md` further down applies to
+//! its own subtree, so it is parsed as a path-scoped glob rule for `<dir>/**`,
+//! the same shape `--recursive` gives package rules (see [`crate::monorepo`]).
+//! Writing reverses that: such rules go back into their directory's
+//! `AGENTS.md`, and everything else into the root file.
+
+use std::path::Path;
+use crate::error::Result;
+use crate::formats::single_file;
+use crate::ir::Rule;
+use crate::monorepo;
+use crate::parser::Parser;
+use crate::writer::{WriteOptions, Writer};
+
+/// The instructions file, in the project root and any subdirectory.
+pub const AGENTS_FILE: &str = "AGENTS.md";
+
+/// Name the parser gives the whole of the root `AGENTS.md`.
+pub const AGENTS_RULE_NAME: &str = "agents";
+
+pub struct AgentsMdParser;
+pub struct AgentsMdWriter;
+
+impl Parser for AgentsMdParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        let mut rules: Vec<Rule> = parse_file(path)?.into_iter().collect();
+        for dir in monorepo::package_dirs(path, usize::MAX) {
+            if let Some(rule) = parse_file(&path.join(&dir))? {
+                rules.push(monorepo::scope_to_package(rule, &dir));
+            }
+        }
+        Ok(rules)
+    }
+}
+
+/// The `AGENTS.md` directly in `dir` as a project rule, if it has any content.
+fn parse_file(dir: &Path) -> Result<Option<Rule>> {
+    single_file::parse(&dir.join(AGENTS_FILE), AGENTS_RULE_NAME)
+}
+
+impl Writer for AgentsMdWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        self.write_with(rules, target, &WriteOptions::default())
+    }
+
+    fn write_with(&self, rules: &[Rule], target: &Path, options: &WriteOptions) -> Result<()> {
+        if rules.is_empty() {
+            return Ok(());
+        }
+        // An explicit output file gets everything
+        if let Some(file) = &options.output_file {
+            return single_file::write(file, rules);
+        }
+        let (root, nested) = monorepo::split(Vec::from(rules));
+        if !root.is_empty() {
+            single_file::write(&target.join(AGENTS_FILE), &root)?;
+        }
+        for (dir, rules) in nested {
+            single_file::write(&target.join(dir).join(AGENTS_FILE), &rules)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::formats::Format;
+    use crate::ir::Scope;
+
+    #[test]
+    fn nested_files_are_path_rules_and_are_written_back() {
+        let repo = tempfile::tempdir().unwrap();
+        fs::create_dir_all(repo.path().join("services/api")).unwrap();
+        fs::create_dir_all(repo.path().join("node_modules/dep")).unwrap();
+        fs::write(repo.path().join(AGENTS_FILE), "Run make test.\n").unwrap();
+        fs::write(repo.path().join("services/api").join(AGENTS_FILE), "Use sqlx.\n").unwrap();
+        fs::write(repo.path().join("node_modules/dep").join(AGENTS_FILE), "Not ours.\n").unwrap();
+
+        let rules = AgentsMdParser.parse(repo.path()).unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!((rules[0].name.as_deref(), &rules[0].scope), (Some(AGENTS_RULE_NAME), &Scope::Project));
+        let api = &rules[1];
+        assert_eq!((api.name.as_deref(), &api.scope), (Some("services-api-agents"), &Scope::Path));
+        assert_eq!(api.globs, Some(vec!["services/api/**".to_string()]));
+        assert!(Format::AgentsMd.expresses(api));
+        let ts = Rule { globs: Some(vec!["**/*.ts".into()]), ..api.clone() };
+        assert!(!Format::AgentsMd.expresses(&ts));
+
+        let out = tempfile::tempdir().unwrap();
+        let style = Rule { name: Some("style".into()), content: "Use tabs.".into(), ..Default::default() };
+        AgentsMdWriter.write(&[rules[0].clone(), style, api.clone()], out.path()).unwrap();
+        let root = fs::read_to_string(out.path().join(AGENTS_FILE)).unwrap();
+        assert_eq!(root, "## agents\n\nRun make test.\n\n## style\n\nUse tabs.\n");
+        assert_eq!(fs::read_to_string(out.path().join("services/api").join(AGENTS_FILE)).unwrap(), "Use sqlx.\n");
+    }
+}
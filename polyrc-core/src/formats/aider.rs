@@ -0,0 +1,87 @@
+//! Aider reads project conventions from `CONVENTIONS.md`, once it is listed
+//! under `read:` in `.aider.conf.yml` (or passed with `--read`).
+//!
+//! Several rules are written as `## <name>` sections, like `GEMINI.md`, and
+//! a file made only of such sections is parsed back into one rule per section.
+//! A rule whose own content has `## ` headers therefore comes back split.
+
+use std::path::Path;
+use crate::error::Result;
+use crate::formats::single_file;
+use crate::ir::Rule;
+use crate::parser::Parser;
+use crate::writer::{WriteOptions, Writer};
+
+/// The conventions file, relative to the project root.
+pub const CONVENTIONS_FILE: &str = "CONVENTIONS.md";
+
+/// Name the parser gives the whole of `CONVENTIONS.md` when it isn't split.
+pub const AIDER_RULE_NAME: &str = "aider";
+
+pub struct AiderParser;
+pub struct AiderWriter;
+
+impl Parser for AiderParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        let Some(whole) = single_file::parse(&path.join(CONVENTIONS_FILE), AIDER_RULE_NAME)? else {
+            return Ok(vec![]);
+        };
+        let Some(sections) = crate::formats::sections::split_joined(&whole.content) else {
+            return Ok(vec![whole]);
+        };
+        let rules = sections
+            .into_iter()
+            .map(|(name, content)| Rule { name: Some(name), content, ..whole.clone() })
+            .collect();
+        Ok(rules)
+    }
+}
+
+impl Writer for AiderWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        self.write_with(rules, target, &WriteOptions::default())
+    }
+
+    fn write_with(&self, rules: &[Rule], target: &Path, options: &WriteOptions) -> Result<()> {
+        if rules.is_empty() {
+            return Ok(());
+        }
+        single_file::write(&options.output_file.clone().unwrap_or_else(|| target.join(CONVENTIONS_FILE)), rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::ir::{Activation, Scope};
+
+    fn rule(name: &str, content: &str) -> Rule {
+        Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn rule_names_survive_a_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = vec![rule("claude", "Run cargo test."), rule("style", "Use tabs.")];
+        AiderWriter.write(&rules, dir.path()).unwrap();
+        let written = fs::read_to_string(dir.path().join(CONVENTIONS_FILE)).unwrap();
+        assert_eq!(written, "## claude\n\nRun cargo test.\n\n## style\n\nUse tabs.\n");
+
+        let parsed = AiderParser.parse(dir.path()).unwrap();
+        let names: Vec<_> = parsed.iter().map(|r| (r.name.as_deref().unwrap(), r.content.as_str())).collect();
+        assert_eq!(names, [("claude", "Run cargo test."), ("style", "Use tabs.")]);
+        assert!(parsed.iter().all(|r| r.scope == Scope::Project && r.activation == Activation::Always));
+    }
+
+    #[test]
+    fn hand_written_file_is_one_rule() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = "# Conventions\n\n## Style\n\nUse tabs.\n\n## Tests\n\nAlways add one.\n";
+        fs::write(dir.path().join(CONVENTIONS_FILE), text).unwrap();
+        let parsed = AiderParser.parse(dir.path()).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name.as_deref(), Some(AIDER_RULE_NAME));
+        assert_eq!(parsed[0].content, text.trim_end());
+    }
+}
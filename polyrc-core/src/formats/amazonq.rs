@@ -0,0 +1,90 @@
+//! Amazon Q Developer: project rules in `.amazonq/rules/*.md`, each loaded
+//! into every chat in the project. There is no user-level rules directory.
+
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+use crate::error::{PolyrcError, Result};
+use crate::ir::{split_order_prefix, unique_output_stems, Activation, Rule, Scope};
+use crate::parser::{read_source_file, Parser};
+use crate::writer::Writer;
+
+/// Rules directory, relative to the project root.
+pub const RULES_DIR: &str = ".amazonq/rules";
+
+pub struct AmazonQParser;
+pub struct AmazonQWriter;
+
+fn rules_dir(path: &Path) -> std::path::PathBuf {
+    path.join(".amazonq").join("rules")
+}
+
+impl Parser for AmazonQParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        let dir = rules_dir(path);
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut rules = vec![];
+        for entry in WalkDir::new(&dir).min_depth(1).max_depth(1).sort_by_file_name() {
+            let entry = entry.map_err(|e| PolyrcError::Io {
+                path: dir.clone(),
+                source: e.into(),
+            })?;
+            let p = entry.path();
+            if p.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            let Some(content) = read_source_file(p)? else { continue };
+            let (order, name) = split_order_prefix(p.file_stem().and_then(|s| s.to_str()).unwrap_or("rule"));
+            rules.push(Rule {
+                scope: Scope::Project,
+                activation: Activation::Always,
+                name: Some(name.to_string()),
+                content: content.trim_end().to_string(),
+                order,
+                ..Default::default()
+            });
+        }
+        Ok(rules)
+    }
+}
+
+impl Writer for AmazonQWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        let dir = rules_dir(target);
+        fs::create_dir_all(&dir).map_err(|e| PolyrcError::Io {
+            path: dir.clone(),
+            source: e,
+        })?;
+        for (rule, stem) in rules.iter().zip(unique_output_stems(rules)) {
+            let file = dir.join(format!("{}.md", stem));
+            let content = rule.content.trim_end().to_string() + "\n";
+            fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_round_trip_through_the_rules_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = vec![
+            Rule { name: Some("style".into()), content: "Use tabs.".into(), ..Default::default() },
+            Rule { name: Some("api".into()), content: "Return JSON.".into(), order: Some(1), ..Default::default() },
+        ];
+        AmazonQWriter.write(&rules, dir.path()).unwrap();
+        let rules_dir = dir.path().join(".amazonq").join("rules");
+        assert_eq!(fs::read_to_string(rules_dir.join("style.md")).unwrap(), "Use tabs.\n");
+        assert!(rules_dir.join("01-api.md").exists());
+
+        let parsed = AmazonQParser.parse(dir.path()).unwrap();
+        let names: Vec<_> = parsed.iter().map(|r| (r.name.as_deref().unwrap(), r.order)).collect();
+        assert_eq!(names, [("api", Some(1)), ("style", None)]);
+        assert!(parsed.iter().all(|r| r.scope == Scope::Project && r.activation == Activation::Always));
+    }
+}
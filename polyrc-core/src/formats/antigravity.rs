@@ -2,8 +2,9 @@ use std::fs;
 use std::path::Path;
 use walkdir::WalkDir;
 use crate::error::{PolyrcError, Result};
-use crate::ir::{Activation, Rule, Scope};
-use crate::parser::Parser;
+use crate::formats::trigger;
+use crate::ir::{split_order_prefix, unique_output_stems, Rule, Scope};
+use crate::parser::{read_source_file, Parser};
 use crate::writer::Writer;
 
 pub struct AntigravityParser;
@@ -11,11 +12,11 @@ pub struct AntigravityWriter;
 
 /// Returns the rules directory, checking both legacy (.agents) and current (.agent) paths.
 fn rules_dir(path: &Path) -> Option<std::path::PathBuf> {
-    let current = path.join(".agent/rules");
+    let current = path.join(".agent").join("rules");
     if current.exists() {
         return Some(current);
     }
-    let legacy = path.join(".agents/rules");
+    let legacy = path.join(".agents").join("rules");
     if legacy.exists() {
         return Some(legacy);
     }
@@ -49,16 +50,17 @@ fn parse_rules_dir(dir: &std::path::PathBuf, scope: Scope) -> Result<Vec<Rule>>
         if p.extension().and_then(|e| e.to_str()) != Some("md") {
             continue;
         }
-        let content = fs::read_to_string(p).map_err(|e| PolyrcError::Io {
-            path: p.to_path_buf(),
-            source: e,
-        })?;
-        let name = p.file_stem().and_then(|s| s.to_str()).unwrap_or("rule").to_string();
+        let Some(raw) = read_source_file(p)? else { continue };
+        let parsed = trigger::parse(p, &raw)?;
+        let (order, name) = split_order_prefix(p.file_stem().and_then(|s| s.to_str()).unwrap_or("rule"));
         rules.push(Rule {
             scope: scope.clone(),
-            activation: Activation::Always,
-            name: Some(name),
-            content: content.trim_end().to_string(),
+            activation: parsed.activation,
+            globs: parsed.globs,
+            name: Some(name.to_string()),
+            description: parsed.description,
+            content: parsed.body.trim_end().to_string(),
+            order,
             ..Default::default()
         });
     }
@@ -72,16 +74,16 @@ impl Writer for AntigravityWriter {
         let rules_dir = if is_user {
             target.join("rules")
         } else {
-            target.join(".agent/rules")
+            target.join(".agent").join("rules")
         };
         fs::create_dir_all(&rules_dir).map_err(|e| PolyrcError::Io {
             path: rules_dir.clone(),
             source: e,
         })?;
-        for rule in rules {
-            let filename = format!("{}.md", rule.filename_stem());
+        for (rule, stem) in rules.iter().zip(unique_output_stems(rules)) {
+            let filename = format!("{}.md", stem);
             let file = rules_dir.join(&filename);
-            let content = rule.content.trim_end().to_string() + "\n";
+            let content = trigger::render(rule, &file)?;
             fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
         }
         Ok(())
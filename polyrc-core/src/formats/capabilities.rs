@@ -0,0 +1,145 @@
+//! What each format can express — `polyrc supported-formats --matrix`.
+//!
+//! [`Format::capabilities`] is the single table: the activation column is what
+//! [`crate::ops::apply_activation_policy`] checks before writing, and the size
+//! limits are what `polyrc budget` measures against.
+
+use serde::Serialize;
+use crate::formats::Format;
+use crate::ir::Activation;
+
+/// IR features a format's files can carry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Capabilities {
+    /// Has a local user-level config polyrc can read and write.
+    pub user_scope: bool,
+    pub project_scope: bool,
+    /// Activations the writer can express; others are loaded as always-on.
+    pub activations: &'static [Activation],
+    /// Keeps a rule's `description`.
+    pub descriptions: bool,
+    /// Writes one file per rule rather than a single combined file.
+    pub multi_file: bool,
+    /// Character limit across all rules, if the tool enforces one.
+    pub total_char_limit: Option<usize>,
+    /// Character limit per rule file, if the tool enforces one.
+    pub file_char_limit: Option<usize>,
+}
+
+impl Capabilities {
+    pub fn supports(&self, activation: &Activation) -> bool {
+        self.activations.contains(activation)
+    }
+}
+
+/// One row of the matrix.
+#[derive(Debug, Clone)]
+pub struct MatrixRow {
+    pub format: &'static str,
+    pub capabilities: Capabilities,
+}
+
+/// The matrix for every format, in [`Format::all`] order.
+pub fn matrix() -> Vec<MatrixRow> {
+    Format::all().iter().map(|fmt| MatrixRow { format: fmt.name(), capabilities: fmt.capabilities() }).collect()
+}
+
+/// Render [`matrix`] as a text table.
+pub fn render_matrix(rows: &[MatrixRow]) -> String {
+    const COLUMNS: [&str; 8] = ["USER", "PROJECT", "GLOB", "ON-DEMAND", "AI-DECIDES", "DESCRIPTION", "MULTI-FILE", "SIZE LIMIT"];
+    let mark = |yes: bool| if yes { "yes" } else { "-" };
+    let mut out = format!("{:<12}", "FORMAT");
+    for col in COLUMNS {
+        out.push_str(&format!("  {col:<11}"));
+    }
+    out = out.trim_end().to_string() + "\n";
+    for row in rows {
+        let c = &row.capabilities;
+        let limit = match (c.total_char_limit, c.file_char_limit) {
+            (Some(total), Some(file)) => format!("{total} total, {file}/file"),
+            (Some(total), None) => format!("{total} total"),
+            (None, Some(file)) => format!("{file}/file"),
+            (None, None) => "-".to_string(),
+        };
+        let cells = [
+            mark(c.user_scope),
+            mark(c.project_scope),
+            mark(c.supports(&Activation::Glob)),
+            mark(c.supports(&Activation::OnDemand)),
+            mark(c.supports(&Activation::AiDecides)),
+            mark(c.descriptions),
+            mark(c.multi_file),
+            &limit,
+        ];
+        let mut line = format!("{:<12}", row.format);
+        for cell in cells {
+            line.push_str(&format!("  {cell:<11}"));
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fails to compile when a variant is added without a match arm here, and
+    /// fails at run time when it is missing from `Format::built_in()`.
+    #[test]
+    fn every_format_has_a_row() {
+        let listed = |fmt: Format| match fmt {
+            Format::Cursor
+            | Format::Windsurf
+            | Format::Copilot
+            | Format::Claude
+            | Format::Gemini
+            | Format::Antigravity
+            | Format::Aider
+            | Format::AgentsMd
+            | Format::AmazonQ
+            | Format::Zed
+            | Format::Warp
+            | Format::OpenHands
+            | Format::Kiro
+            | Format::Qodo => Format::built_in().contains(&fmt),
+            Format::Custom(_) => true,
+        };
+        let every = [
+            Format::Cursor,
+            Format::Windsurf,
+            Format::Copilot,
+            Format::Claude,
+            Format::Gemini,
+            Format::Antigravity,
+            Format::Aider,
+            Format::AgentsMd,
+            Format::AmazonQ,
+            Format::Zed,
+            Format::Warp,
+            Format::OpenHands,
+            Format::Kiro,
+            Format::Qodo,
+        ];
+        for fmt in every {
+            assert!(listed(fmt.clone()), "{} missing from Format::built_in()", fmt.name());
+        }
+        let rows = matrix();
+        assert_eq!(rows.len(), Format::all().len());
+        for (row, fmt) in rows.iter().zip(Format::all()) {
+            assert!(row.capabilities.supports(&Activation::Always), "{}", fmt.name());
+            assert_eq!(row.capabilities.user_scope, fmt.user_input_dir().is_some(), "{}", fmt.name());
+            assert_eq!(row.capabilities.multi_file, !fmt.rule_dirs().is_empty(), "{}", fmt.name());
+        }
+    }
+
+    #[test]
+    fn table_renders_each_capability() {
+        let table = render_matrix(&matrix());
+        let cursor = table.lines().find(|l| l.starts_with("cursor")).unwrap();
+        assert_eq!(cursor.split_whitespace().collect::<Vec<_>>(), ["cursor", "-", "yes", "yes", "yes", "yes", "yes", "yes", "-"]);
+        let windsurf = table.lines().find(|l| l.starts_with("windsurf")).unwrap();
+        assert!(windsurf.ends_with("12000 total, 6000/file"), "{windsurf}");
+    }
+}
@@ -0,0 +1,956 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde_json::Value;
+use serde_yml::Mapping;
+use walkdir::WalkDir;
+use crate::error::{PolyrcError, Result};
+use crate::formats::copilot::split_frontmatter;
+use crate::formats::options::FormatOptions;
+use crate::ir::{nested_output_stem, split_order_prefix, uniquify, Activation, Rule, Scope};
+use crate::parser::{read_source_file, Parser};
+use crate::writer::Writer;
+
+/// `structured_permissions` splits `permissions` out of `settings.json` into its
+/// own rule (see [`PERMISSIONS_RULE_NAME`]) instead of keeping the file as one blob.
+/// `resolve_imports` turns `@path` import lines in `CLAUDE.md` into rules of their own.
+pub struct ClaudeParser {
+    pub structured_permissions: bool,
+    pub resolve_imports: bool,
+}
+#[derive(Default)]
+pub struct ClaudeWriter {
+    /// Replace `settings.json` with the stored settings instead of merging them
+    /// into the local file.
+    pub overwrite_settings: bool,
+}
+
+/// Rule holding all of `settings.json` as a fenced JSON block.
+const SETTINGS_RULE_NAME: &str = "settings";
+/// Name the parser gives the whole of `CLAUDE.md`.
+pub const CLAUDE_RULE_NAME: &str = "claude";
+/// Rule holding only `settings.json`'s `permissions` object, normalized.
+pub const PERMISSIONS_RULE_NAME: &str = "settings-permissions";
+/// `[formats.claude]` option backing [`ClaudeParser::structured_permissions`].
+pub const STRUCTURED_PERMISSIONS: &str = "structured_permissions";
+/// `[formats.claude]` option backing [`ClaudeParser::resolve_imports`]; on unless set to false.
+pub const RESOLVE_IMPORTS: &str = "resolve_imports";
+/// `[formats.claude]` option backing [`ClaudeWriter::overwrite_settings`].
+pub const OVERWRITE_SETTINGS: &str = "overwrite_settings";
+/// Import hops followed from `CLAUDE.md`, as in Claude Code itself.
+const MAX_IMPORT_DEPTH: usize = 5;
+
+impl ClaudeParser {
+    pub fn new(options: &FormatOptions) -> Self {
+        Self {
+            structured_permissions: options.flag(STRUCTURED_PERMISSIONS),
+            resolve_imports: options.get(RESOLVE_IMPORTS).unwrap_or(true),
+        }
+    }
+}
+
+impl ClaudeWriter {
+    pub fn new(options: &FormatOptions) -> Self {
+        Self { overwrite_settings: options.flag(OVERWRITE_SETTINGS) }
+    }
+}
+
+impl Default for ClaudeParser {
+    fn default() -> Self {
+        Self::new(&FormatOptions::default())
+    }
+}
+
+/// Joins directory levels in names of rules nested under `rules/` or `agents/`.
+const RULE_PATH_SEP: char = '/';
+/// Joins directory levels in slash-command names (`git/commit.md` → `git:commit`).
+const COMMAND_PATH_SEP: char = ':';
+/// Subagent directory; its rules carry a `source_path` under it so the writer
+/// can tell them from skills, which are ai-decides too.
+const AGENTS_DIR: &str = "agents";
+/// The file holding a skill, inside its own directory under `skills/`.
+const SKILL_FILE: &str = "SKILL.md";
+
+impl Parser for ClaudeParser {
+    /// Parse Claude Code config from `path`.
+    ///
+    /// Two layouts are supported:
+    ///
+    /// **Project layout** — `path` is a project root (e.g. `/home/user/myapp`):
+    /// - `{path}/CLAUDE.md`                    always-on, project scope
+    /// - `{path}/.claude/settings.json`        always-on, project scope (JSON → fenced block)
+    /// - `{path}/.claude/rules/**/*.md`        always-on, project scope
+    /// - `{path}/.claude/commands/**/*.md`     on-demand (slash commands), project scope
+    /// - `{path}/.claude/skills/*/SKILL.md`   ai-decides (skill descriptions), project scope
+    /// - `{path}/.claude/agents/*.md`          ai-decides, project scope
+    ///
+    /// **User layout** — `path` is `~/.claude` (detected by dir name ending in `.claude`):
+    /// - `{path}/settings.json`                always-on, user scope (JSON → fenced block)
+    /// - `{path}/CLAUDE.md`                    always-on, user scope
+    /// - `{path}/rules/**/*.md`                always-on, user scope
+    /// - `{path}/commands/**/*.md`             on-demand (slash commands), user scope
+    /// - `{path}/skills/*/SKILL.md`           ai-decides, user scope
+    /// - `{path}/agents/*.md`                  ai-decides, user scope
+    ///
+    /// A command's frontmatter `description` becomes the rule's; its other keys
+    /// (`allowed-tools`, `argument-hint`, …) are kept in `frontmatter` for the writer.
+    ///
+    /// Files in subdirectories are named by their relative path: `rules/git/commit.md`
+    /// becomes `git/commit`, and `commands/git/commit.md` becomes `git:commit`
+    /// (Claude's namespaced slash command).
+    ///
+    /// With `structured_permissions`, `settings.json`'s `permissions` object becomes
+    /// a separate `settings-permissions` rule with sorted keys and sorted,
+    /// deduplicated lists, and the `settings` rule holds the remaining keys.
+    ///
+    /// With `resolve_imports`, a `CLAUDE.md` line like `@docs/style.md` (relative to
+    /// the file, or `~/`) becomes an always-on rule named after the imported file,
+    /// following nested imports up to five deep. Lines naming no file are kept.
+    ///
+    /// Note: `~/.claude.json` (auth, sessions, caches) is intentionally skipped — it is
+    /// internal Claude Code state, not portable user configuration.
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        // Detect whether path IS the .claude config directory (user root) or a project root.
+        let is_user_root = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n == ".claude")
+            .unwrap_or(false);
+
+        let scope = if is_user_root { Scope::User } else { Scope::Project };
+
+        // Helper: path to the .claude subdirectory (only used in project layout)
+        let dot_claude = path.join(".claude");
+
+        let (settings_file, rules_dir, commands_dir, skills_dir, agents_dir) = if is_user_root {
+            (
+                path.join("settings.json"),
+                path.join("rules"),
+                path.join("commands"),
+                path.join("skills"),
+                path.join(AGENTS_DIR),
+            )
+        } else {
+            (
+                dot_claude.join("settings.json"),
+                dot_claude.join("rules"),
+                dot_claude.join("commands"),
+                dot_claude.join("skills"),
+                dot_claude.join(AGENTS_DIR),
+            )
+        };
+
+        let mut rules = vec![];
+
+        // ── settings.json ─────────────────────────────────────────────────────
+        if settings_file.exists() {
+            let json = read_source_file(&settings_file)?.unwrap_or_default();
+            if !json.trim().is_empty() {
+                let settings_rule = |name: &str, json: &str| Rule {
+                    scope: scope.clone(),
+                    activation: Activation::Always,
+                    name: Some(name.to_string()),
+                    content: format!("```json\n{}\n```", json.trim_end()),
+                    ..Default::default()
+                };
+                match self.structured_permissions.then(|| split_permissions(&json)).flatten() {
+                    Some((rest, permissions)) => {
+                        if let Some(rest) = rest {
+                            rules.push(settings_rule(SETTINGS_RULE_NAME, &rest));
+                        }
+                        rules.push(settings_rule(PERMISSIONS_RULE_NAME, &permissions));
+                    }
+                    None => rules.push(settings_rule(SETTINGS_RULE_NAME, &json)),
+                }
+            }
+        }
+
+        // ── CLAUDE.md ────────────────────────────────────────────────────────
+        let main_file = path.join("CLAUDE.md");
+        if main_file.exists() {
+            let mut content = read_source_file(&main_file)?.unwrap_or_default();
+            let mut imported = vec![];
+            if self.resolve_imports {
+                let mut seen = HashSet::from([canonical(&main_file)]);
+                content = resolve_imports(&main_file, &content, 0, &mut seen, &scope, &mut imported)?;
+            }
+            if !content.trim().is_empty() {
+                rules.push(Rule {
+                    scope: scope.clone(),
+                    activation: Activation::Always,
+                    name: Some(CLAUDE_RULE_NAME.to_string()),
+                    content: content.trim_end().to_string(),
+                    ..Default::default()
+                });
+            }
+            rules.append(&mut imported);
+        }
+
+        // ── rules/*.md — always-on ────────────────────────────────────────────
+        parse_md_dir(&rules_dir, scope.clone(), Activation::Always, RULE_PATH_SEP, &mut rules)?;
+
+        // ── commands/*.md — on-demand (slash commands) ────────────────────────
+        let first_command = rules.len();
+        parse_md_dir(&commands_dir, scope.clone(), Activation::OnDemand, COMMAND_PATH_SEP, &mut rules)?;
+        for rule in &mut rules[first_command..] {
+            split_md_frontmatter(rule, false);
+        }
+
+        // ── skills/*/SKILL.md — ai-decides ───────────────────────────────────
+        parse_skill_dir(&skills_dir, scope.clone(), &mut rules)?;
+
+        // ── agents/*.md — ai-decides ──────────────────────────────────────────
+        let first_agent = rules.len();
+        parse_md_dir(&agents_dir, scope.clone(), Activation::AiDecides, RULE_PATH_SEP, &mut rules)?;
+        for rule in &mut rules[first_agent..] {
+            rule.source_path = Some(format!("{AGENTS_DIR}/{}.md", rule.name.as_deref().unwrap_or_default()));
+        }
+
+        Ok(rules)
+    }
+}
+
+/// Move the import lines of `content`, read from `file` at import depth `depth`,
+/// out into rules named after the imported files (nested imports included), and
+/// return the rest. Files already in `seen` are dropped, which breaks cycles.
+/// Imports inside code fences, past [`MAX_IMPORT_DEPTH`] or naming no file stay as written.
+fn resolve_imports(
+    file: &Path,
+    content: &str,
+    depth: usize,
+    seen: &mut HashSet<PathBuf>,
+    scope: &Scope,
+    rules: &mut Vec<Rule>,
+) -> Result<String> {
+    let base = file.parent().unwrap_or(Path::new("."));
+    let mut kept = String::with_capacity(content.len());
+    let mut in_fence = false;
+    for line in content.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let target = (!in_fence && depth < MAX_IMPORT_DEPTH).then(|| import_target(base, line)).flatten();
+        let Some(target) = target.filter(|t| t.is_file()) else {
+            kept.push_str(line);
+            continue;
+        };
+        if !seen.insert(canonical(&target)) {
+            continue;
+        }
+        let raw = read_source_file(&target)?.unwrap_or_default();
+        let mut nested = vec![];
+        let body = resolve_imports(&target, &raw, depth + 1, seen, scope, &mut nested)?;
+        if !body.trim().is_empty() {
+            rules.push(Rule {
+                scope: scope.clone(),
+                activation: Activation::Always,
+                name: target.file_stem().map(|s| s.to_string_lossy().into_owned()),
+                content: body.trim_end().to_string(),
+                ..Default::default()
+            });
+        }
+        rules.append(&mut nested);
+    }
+    Ok(kept)
+}
+
+/// The file an `@path` line imports, resolved against `base` (or home for `@~/`).
+fn import_target(base: &Path, line: &str) -> Option<PathBuf> {
+    let path = line.trim().strip_prefix('@').filter(|p| !p.is_empty() && !p.contains(char::is_whitespace))?;
+    Some(match path.strip_prefix("~/") {
+        Some(rest) => crate::config::home_dir().join(rest),
+        None => base.join(path),
+    })
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Read all `*.md` files under `dir` (recursively), push as rules with the given
+/// scope/activation. Subdirectory names are joined to the file stem with `sep`.
+fn parse_md_dir(
+    dir: &Path,
+    scope: Scope,
+    activation: Activation,
+    sep: char,
+    rules: &mut Vec<Rule>,
+) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in WalkDir::new(dir).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| PolyrcError::Io {
+            path: dir.to_path_buf(),
+            source: e.into(),
+        })?;
+        let p = entry.path();
+        if p.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(content) = read_source_file(p)? else { continue };
+        if content.trim().is_empty() {
+            continue;
+        }
+        let (order, stem) = split_order_prefix(p.file_stem().and_then(|s| s.to_str()).unwrap_or("rule"));
+        let mut name: Vec<String> = p
+            .parent()
+            .and_then(|d| d.strip_prefix(dir).ok())
+            .map(|rel| rel.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect())
+            .unwrap_or_default();
+        name.push(stem.to_string());
+        rules.push(Rule {
+            scope: scope.clone(),
+            activation: activation.clone(),
+            name: Some(name.join(&sep.to_string())),
+            content: content.trim_end().to_string(),
+            order,
+            ..Default::default()
+        });
+    }
+    Ok(())
+}
+
+/// Move `rule`'s frontmatter out of its content: `description` (and `name`, if
+/// `named`) into the rule's fields and the other keys, as written, into
+/// [`Rule::frontmatter`]. Frontmatter that isn't a YAML mapping is left in the content.
+fn split_md_frontmatter(rule: &mut Rule, named: bool) {
+    let (Some(fm), body) = split_frontmatter(&rule.content) else { return };
+    let Ok(keys) = serde_yml::from_str::<Mapping>(fm) else { return };
+    let body = body.trim_start_matches(['\r', '\n']).to_string();
+    let mut taken = vec![];
+    let mut take = |key: &'static str| {
+        let value = keys.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        if value.is_some() {
+            taken.push(key);
+        }
+        value
+    };
+    if named {
+        rule.name = take("name").or(rule.name.take());
+    }
+    rule.description = take("description");
+    let rest = without_keys(fm, &taken);
+    rule.frontmatter = (!rest.trim().is_empty()).then_some(rest);
+    rule.content = body;
+}
+
+/// `fm` without the top-level `keys` and their continuation lines. The other
+/// lines are kept verbatim: re-serializing them could change their meaning
+/// (`argument-hint: [msg]` is a YAML list).
+fn without_keys(fm: &str, keys: &[&str]) -> String {
+    let mut kept = String::new();
+    let mut dropping = false;
+    for line in fm.lines() {
+        let starts_key = !line.starts_with([' ', '\t', '-', '#']) && !line.trim().is_empty();
+        if starts_key {
+            dropping = keys.iter().any(|k| line.strip_prefix(k).is_some_and(|rest| rest.trim_start().starts_with(':')));
+        }
+        if !dropping {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+    kept
+}
+
+/// A command file: `description` and [`Rule::frontmatter`] as frontmatter, then the content.
+fn render_command(rule: &Rule, file: &Path) -> Result<String> {
+    render_md(rule, None, file)
+}
+
+/// `rule`'s content under frontmatter holding `name`, its description and
+/// [`Rule::frontmatter`]; plain content when there is none of those.
+fn render_md(rule: &Rule, name: Option<&str>, file: &Path) -> Result<String> {
+    let content = rule.content.trim_end().to_string() + "\n";
+    if name.is_none() && rule.description.is_none() && rule.frontmatter.is_none() {
+        return Ok(content);
+    }
+    let mut keys = Mapping::new();
+    if let Some(name) = name {
+        keys.insert("name".into(), name.into());
+    }
+    if let Some(description) = &rule.description {
+        keys.insert("description".into(), description.as_str().into());
+    }
+    let mut fm = String::new();
+    if !keys.is_empty() {
+        fm = serde_yml::to_string(&keys).map_err(|e| PolyrcError::YamlParse { path: file.to_path_buf(), source: e })?;
+    }
+    if let Some(rest) = &rule.frontmatter {
+        fm.push_str(rest.trim_end());
+        fm.push('\n');
+    }
+    Ok(format!("---\n{fm}---\n\n{content}"))
+}
+
+/// A rule file holding just the content.
+fn render_plain(rule: &Rule, _file: &Path) -> Result<String> {
+    Ok(rule.content.trim_end().to_string() + "\n")
+}
+
+/// Read `skills/*/SKILL.md` — each skill is a subdirectory, named by its frontmatter
+/// `name` or else the subdirectory name. The other files in the subdirectory are
+/// listed in [`Rule::resources`].
+fn parse_skill_dir(dir: &Path, scope: Scope, rules: &mut Vec<Rule>) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in WalkDir::new(dir).min_depth(1).max_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| PolyrcError::Io {
+            path: dir.to_path_buf(),
+            source: e.into(),
+        })?;
+        let subdir = entry.path();
+        if !subdir.is_dir() {
+            continue;
+        }
+        let skill_file = subdir.join(SKILL_FILE);
+        if !skill_file.exists() {
+            continue;
+        }
+        let Some(content) = read_source_file(&skill_file)? else { continue };
+        if content.trim().is_empty() {
+            continue;
+        }
+        let mut rule = Rule {
+            scope: scope.clone(),
+            activation: Activation::AiDecides,
+            content: content.trim_end().to_string(),
+            ..Default::default()
+        };
+        split_md_frontmatter(&mut rule, true);
+        let dir_name = subdir.file_name().and_then(|s| s.to_str()).unwrap_or("skill");
+        rule.name = rule.name.or_else(|| Some(dir_name.to_string()));
+        let resources = skill_resources(subdir)?;
+        rule.resources = (!resources.is_empty()).then_some(resources);
+        rules.push(rule);
+    }
+    Ok(())
+}
+
+/// Every file under a skill directory but its `SKILL.md`, as sorted `/`-separated relative paths.
+fn skill_resources(subdir: &Path) -> Result<Vec<String>> {
+    let mut resources = vec![];
+    for entry in WalkDir::new(subdir).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| PolyrcError::Io { path: subdir.to_path_buf(), source: e.into() })?;
+        let Ok(rel) = entry.path().strip_prefix(subdir) else { continue };
+        if !entry.file_type().is_file() || rel == Path::new(SKILL_FILE) {
+            continue;
+        }
+        resources.push(rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"));
+    }
+    Ok(resources)
+}
+
+impl Writer for ClaudeWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        if rules.is_empty() {
+            return Ok(());
+        }
+
+        let dot_claude = target.join(".claude");
+
+        // Partition: settings rules (written as JSON) vs markdown rules.
+        let (settings_rules, md_rules): (Vec<&Rule>, Vec<&Rule>) = rules
+            .iter()
+            .partition(|r| r.name.as_deref() == Some(SETTINGS_RULE_NAME));
+        let (permission_rules, md_rules): (Vec<&Rule>, Vec<&Rule>) = md_rules
+            .into_iter()
+            .partition(|r| r.name.as_deref() == Some(PERMISSIONS_RULE_NAME));
+
+        // ── settings.json ────────────────────────────────────────────────────
+        for rule in settings_rules {
+            // Strip the ```json ... ``` fence added by the parser.
+            let json = strip_json_fence(&rule.content);
+            fs::create_dir_all(&dot_claude).map_err(|e| PolyrcError::Io {
+                path: dot_claude.clone(),
+                source: e,
+            })?;
+            write_settings(&dot_claude.join("settings.json"), json, self.overwrite_settings)?;
+        }
+        // Merged in after the settings rule so it wins over any `permissions` there
+        for rule in permission_rules {
+            fs::create_dir_all(&dot_claude).map_err(|e| PolyrcError::Io {
+                path: dot_claude.clone(),
+                source: e,
+            })?;
+            merge_permissions(&dot_claude.join("settings.json"), strip_json_fence(&rule.content), self.overwrite_settings)?;
+        }
+
+        // ── slash commands ───────────────────────────────────────────────────
+        let (command_rules, md_rules): (Vec<&Rule>, Vec<&Rule>) =
+            md_rules.into_iter().partition(|r| r.activation == Activation::OnDemand);
+        write_md_tree(&dot_claude.join("commands"), &command_rules, COMMAND_PATH_SEP, render_command)?;
+
+        // ── skills and agents ────────────────────────────────────────────────
+        let (ai_rules, md_rules): (Vec<&Rule>, Vec<&Rule>) =
+            md_rules.into_iter().partition(|r| r.activation == Activation::AiDecides);
+        let (agent_rules, skill_rules): (Vec<&Rule>, Vec<&Rule>) = ai_rules.into_iter().partition(|r| is_agent(r));
+        write_md_tree(&dot_claude.join(AGENTS_DIR), &agent_rules, RULE_PATH_SEP, render_plain)?;
+        write_skills(&dot_claude.join("skills"), &skill_rules)?;
+
+        // ── markdown rules ───────────────────────────────────────────────────
+        if md_rules.len() == 1 {
+            // Single md rule → CLAUDE.md
+            let file = target.join("CLAUDE.md");
+            crate::formats::create_parent_dir(&file)?;
+            let content = md_rules[0].content.trim_end().to_string() + "\n";
+            fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        } else if md_rules.len() > 1 {
+            // Multiple md rules → .claude/rules/**/*.md
+            write_md_tree(&dot_claude.join("rules"), &md_rules, RULE_PATH_SEP, render_plain)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Write each rule to `<dir>/<path>.md`, where `path` comes from the rule name
+/// split on `sep` (so `git:commit` with `sep = ':'` lands in `git/commit.md`).
+/// Each level is sanitized like [`Rule::filename_stem`]; `render` gives the file content.
+fn write_md_tree(
+    dir: &Path,
+    rules: &[&Rule],
+    sep: char,
+    render: fn(&Rule, &Path) -> Result<String>,
+) -> Result<()> {
+    if rules.is_empty() {
+        return Ok(());
+    }
+    let stems = rules.iter().map(|r| nested_output_stem(r, sep)).collect();
+    for (rule, stem) in rules.iter().zip(uniquify(rules, stems)) {
+        let file = dir.join(format!("{}.md", stem));
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).map_err(|e| PolyrcError::Io { path: parent.to_path_buf(), source: e })?;
+        }
+        let content = render(rule, &file)?;
+        fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+    }
+    Ok(())
+}
+
+/// Write each rule to `<dir>/<stem>/SKILL.md`, with `name` and `description`
+/// frontmatter. Resource files the skill listed are not in the store; the ones
+/// missing from the skill directory are reported as a [notice](crate::notice).
+fn write_skills(dir: &Path, rules: &[&Rule]) -> Result<()> {
+    let stems = rules.iter().map(|r| r.filename_stem()).collect();
+    for (rule, stem) in rules.iter().zip(uniquify(rules, stems)) {
+        let skill_dir = dir.join(&stem);
+        let file = skill_dir.join(SKILL_FILE);
+        crate::formats::create_parent_dir(&file)?;
+        // Skills stored before frontmatter was split out still carry their own
+        let content = match split_frontmatter(&rule.content) {
+            (Some(_), _) => render_plain(rule, &file)?,
+            (None, _) => render_md(rule, Some(&stem), &file)?,
+        };
+        fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        let missing: Vec<&str> = rule
+            .resources
+            .iter()
+            .flatten()
+            .filter(|r| !skill_dir.join(r).exists())
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            crate::notice::warn(format!("skill '{stem}': resource file(s) not copied: {}", missing.join(", ")));
+        }
+    }
+    Ok(())
+}
+
+/// Whether an ai-decides rule was read from `agents/` rather than `skills/`.
+fn is_agent(rule: &Rule) -> bool {
+    rule.source_path.as_deref().is_some_and(|p| p.starts_with(&format!("{AGENTS_DIR}/")))
+}
+
+/// Split `settings.json` text into (the other keys, the `permissions` object),
+/// both pretty-printed; permissions are [normalized](normalize_permissions).
+/// `None` when the text isn't a JSON object with `permissions`, so the caller
+/// falls back to keeping it whole. The first part is `None` if nothing else is set.
+fn split_permissions(json: &str) -> Option<(Option<String>, String)> {
+    let mut settings = match serde_json::from_str::<Value>(json) {
+        Ok(Value::Object(map)) => map,
+        Ok(_) => return None,
+        Err(e) => {
+            crate::notice::warn(format!("settings.json is not valid JSON ({e}); keeping it as one rule"));
+            return None;
+        }
+    };
+    let permissions = normalize_permissions(settings.remove("permissions")?);
+    let pretty = |v: &Value| serde_json::to_string_pretty(v).expect("JSON value serializes");
+    let rest = (!settings.is_empty()).then(|| pretty(&Value::Object(settings)));
+    Some((rest, pretty(&permissions)))
+}
+
+/// Sort and deduplicate every list of strings in a `permissions` object
+/// (`allow`, `deny`, `ask`, `additionalDirectories`, ...). Object keys are
+/// already kept sorted by `serde_json`.
+fn normalize_permissions(mut permissions: Value) -> Value {
+    if let Value::Object(map) = &mut permissions {
+        for value in map.values_mut() {
+            if let Value::Array(items) = value
+                && items.iter().all(Value::is_string)
+            {
+                items.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+                items.dedup();
+            }
+        }
+    }
+    permissions
+}
+
+/// Set `permissions` in the settings file at `file` from the normalized `json`,
+/// [merged](merge_json) into the local permissions unless `overwrite`, keeping
+/// the file's other keys.
+fn merge_permissions(file: &Path, json: &str, overwrite: bool) -> Result<()> {
+    let permissions: Value = serde_json::from_str(json).map_err(|e| invalid_json(file, PERMISSIONS_RULE_NAME, e))?;
+    let mut settings = read_settings(file)?.unwrap_or_default();
+    let permissions = match settings.remove("permissions") {
+        Some(mut local) if !overwrite => {
+            merge_json(&mut local, normalize_permissions(permissions));
+            local
+        }
+        _ => permissions,
+    };
+    settings.insert("permissions".to_string(), normalize_permissions(permissions));
+    let out = serde_json::to_string_pretty(&Value::Object(settings)).expect("JSON value serializes") + "\n";
+    fs::write(file, out).map_err(|e| PolyrcError::Io { path: file.to_path_buf(), source: e })
+}
+
+/// Write the stored settings `json` to `file`. Unless `overwrite`, they are
+/// [merged](merge_json) into the file already there, so keys only the local
+/// file has (say a machine's `env`) survive.
+fn write_settings(file: &Path, json: &str, overwrite: bool) -> Result<()> {
+    let verbatim = json.trim_end().to_string() + "\n";
+    let out = match read_settings(file).map(|local| local.filter(|_| !overwrite))? {
+        None => verbatim,
+        Some(local) => {
+            let stored: Value = serde_json::from_str(json).map_err(|e| invalid_json(file, SETTINGS_RULE_NAME, e))?;
+            let mut merged = Value::Object(local);
+            merge_json(&mut merged, stored.clone());
+            // Keep the stored formatting when the local file added nothing
+            if merged == stored {
+                verbatim
+            } else {
+                serde_json::to_string_pretty(&merged).expect("JSON value serializes") + "\n"
+            }
+        }
+    };
+    fs::write(file, out).map_err(|e| PolyrcError::Io { path: file.to_path_buf(), source: e })
+}
+
+/// The JSON object in the settings file at `file`, or `None` if there is no file.
+fn read_settings(file: &Path) -> Result<Option<serde_json::Map<String, Value>>> {
+    if !file.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(file).map_err(|e| PolyrcError::Io { path: file.to_path_buf(), source: e })?;
+    match serde_json::from_str::<Value>(&raw).map_err(|e| invalid_json(file, "settings.json", e))? {
+        Value::Object(map) => Ok(Some(map)),
+        _ => Err(PolyrcError::WriteFailure {
+            path: file.to_path_buf(),
+            reason: "settings.json is not a JSON object".to_string(),
+        }),
+    }
+}
+
+fn invalid_json(file: &Path, what: &str, e: serde_json::Error) -> PolyrcError {
+    PolyrcError::WriteFailure { path: file.to_path_buf(), reason: format!("{what} is not valid JSON: {e}") }
+}
+
+/// Deep-merge `stored` into `local`: objects key by key, lists as the stored
+/// entries followed by the local ones they lack, anything else replaced.
+fn merge_json(local: &mut Value, stored: Value) {
+    match (local, stored) {
+        (Value::Object(local), Value::Object(stored)) => {
+            for (key, value) in stored {
+                match local.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        local.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(local), Value::Array(mut stored)) => {
+            let local_only: Vec<Value> = local.drain(..).filter(|v| !stored.contains(v)).collect();
+            stored.extend(local_only);
+            *local = stored;
+        }
+        (local, stored) => *local = stored,
+    }
+}
+
+/// Strip a leading/trailing ```json ... ``` fence if present, otherwise return as-is.
+fn strip_json_fence(s: &str) -> &str {
+    let s = s.trim();
+    if let Some(inner) = s.strip_prefix("```json\n").or_else(|| s.strip_prefix("```json\r\n"))
+        && let Some(body) = inner.strip_suffix("\r\n```").or_else(|| inner.strip_suffix("\n```"))
+    {
+        return body;
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_json_fence_lf() {
+        assert_eq!(strip_json_fence("```json\n{\"a\": 1}\n```"), "{\"a\": 1}");
+    }
+
+    #[test]
+    fn strip_json_fence_crlf() {
+        assert_eq!(strip_json_fence("```json\r\n{\"a\": 1}\r\n```\r\n"), "{\"a\": 1}");
+    }
+
+    #[test]
+    fn strip_json_fence_passthrough() {
+        assert_eq!(strip_json_fence("{\"a\": 1}"), "{\"a\": 1}");
+    }
+
+    #[test]
+    fn nested_rules_and_commands_round_trip() {
+        let src = tempfile::tempdir().unwrap();
+        let dot = src.path().join(".claude");
+        for (rel, body) in [
+            ("rules/general.md", "General."),
+            ("rules/lang/rust/errors.md", "Use anyhow."),
+            ("commands/review.md", "Review the diff."),
+            ("commands/git/pr/open.md", "Open a PR."),
+            ("skills/lint/SKILL.md", "---\nname: lint\n---\nRun clippy."),
+            ("agents/reviewer.md", "---\nname: reviewer\n---\nReview carefully."),
+        ] {
+            let file = dot.join(rel);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, body).unwrap();
+        }
+
+        let rules = ClaudeParser::default().parse(src.path()).unwrap();
+        let mut names: Vec<(String, Activation)> =
+            rules.iter().map(|r| (r.name.clone().unwrap(), r.activation.clone())).collect();
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            names,
+            vec![
+                ("general".to_string(), Activation::Always),
+                ("git:pr:open".to_string(), Activation::OnDemand),
+                ("lang/rust/errors".to_string(), Activation::Always),
+                ("lint".to_string(), Activation::AiDecides),
+                ("review".to_string(), Activation::OnDemand),
+                ("reviewer".to_string(), Activation::AiDecides),
+            ]
+        );
+
+        let out = tempfile::tempdir().unwrap();
+        ClaudeWriter::default().write(&rules, out.path()).unwrap();
+        let out_dot = out.path().join(".claude");
+        assert_eq!(fs::read_to_string(out_dot.join("rules/lang/rust/errors.md")).unwrap(), "Use anyhow.\n");
+        assert_eq!(fs::read_to_string(out_dot.join("commands/git/pr/open.md")).unwrap(), "Open a PR.\n");
+        assert!(out_dot.join("commands/review.md").exists());
+        assert!(out_dot.join("skills/lint/SKILL.md").exists());
+        assert!(out_dot.join("agents/reviewer.md").exists());
+
+        let mut again = ClaudeParser::default().parse(out.path()).unwrap();
+        let mut original = rules;
+        for set in [&mut again, &mut original] {
+            set.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        assert_eq!(again, original);
+    }
+
+    #[test]
+    fn nested_names_cannot_escape_the_rules_dir() {
+        let rule = Rule { name: Some("../../etc/Passwd".into()), ..Default::default() };
+        assert_eq!(nested_output_stem(&rule, RULE_PATH_SEP), "etc/passwd");
+    }
+
+    const SETTINGS: &str = r#"{
+  "model": "opus",
+  "permissions": {
+    "deny": ["Read(./.env)"],
+    "allow": ["Bash(npm test:*)", "Bash(git diff:*)", "Bash(npm test:*)"],
+    "defaultMode": "acceptEdits"
+  }
+}"#;
+
+    #[test]
+    fn imports_become_rules_of_their_own() {
+        let dir = tempfile::tempdir().unwrap();
+        for (rel, body) in [
+            ("CLAUDE.md", "Intro.\n@docs/style.md\n@missing.md\n```\n@docs/style.md\n```\n"),
+            ("docs/style.md", "Style.\n@../CLAUDE.md\n@deep/1.md\n"),
+            ("docs/deep/1.md", "One.\n@2.md\n"),
+            ("docs/deep/2.md", "Two.\n@3.md\n"),
+            ("docs/deep/3.md", "Three.\n@4.md\n"),
+            ("docs/deep/4.md", "Four.\n@5.md\n"),
+            ("docs/deep/5.md", "Five."),
+        ] {
+            let file = dir.path().join(rel);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, body).unwrap();
+        }
+
+        let rules = ClaudeParser::default().parse(dir.path()).unwrap();
+        let found: Vec<(&str, &str)> = rules.iter().map(|r| (r.name.as_deref().unwrap(), r.content.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("claude", "Intro.\n@missing.md\n```\n@docs/style.md\n```"),
+                ("style", "Style."),
+                ("1", "One."),
+                ("2", "Two."),
+                ("3", "Three."),
+                ("4", "Four.\n@5.md"),
+            ]
+        );
+
+        let literal = ClaudeParser { resolve_imports: false, ..Default::default() }.parse(dir.path()).unwrap();
+        assert_eq!(literal.len(), 1);
+        assert!(literal[0].content.contains("@docs/style.md\n@missing.md"));
+    }
+
+    #[test]
+    fn command_frontmatter_round_trips() {
+        let src = tempfile::tempdir().unwrap();
+        let file = src.path().join(".claude/commands/commit.md");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(
+            &file,
+            "---\nallowed-tools: Bash(git add:*), Bash(git commit:*)\ndescription: >-\n  Commit staged\n  work\nargument-hint: [message]\n---\n\nWrite a commit.\n",
+        )
+        .unwrap();
+
+        let rules = ClaudeParser::default().parse(src.path()).unwrap();
+        assert_eq!(rules[0].description.as_deref(), Some("Commit staged work"));
+        assert_eq!(rules[0].content, "Write a commit.");
+        assert_eq!(
+            rules[0].frontmatter.as_deref(),
+            Some("allowed-tools: Bash(git add:*), Bash(git commit:*)\nargument-hint: [message]\n")
+        );
+
+        let out = tempfile::tempdir().unwrap();
+        ClaudeWriter::default().write(&rules, out.path()).unwrap();
+        let written = fs::read_to_string(out.path().join(".claude/commands/commit.md")).unwrap();
+        assert!(written.contains("\nargument-hint: [message]\n"));
+        assert_eq!(ClaudeParser::default().parse(out.path()).unwrap(), rules);
+    }
+
+    #[test]
+    fn skill_frontmatter_and_resources_are_read() {
+        let src = tempfile::tempdir().unwrap();
+        let skill = src.path().join(".claude/skills/pdf");
+        fs::create_dir_all(skill.join("scripts")).unwrap();
+        fs::write(skill.join("SKILL.md"), "---\nname: pdf-tools\ndescription: Fill PDF forms\nlicense: MIT\n---\n\nRun scripts/run.py.\n").unwrap();
+        fs::write(skill.join("scripts/run.py"), "print('hi')\n").unwrap();
+        fs::write(skill.join("reference.md"), "Fields.\n").unwrap();
+
+        let rules = ClaudeParser::default().parse(src.path()).unwrap();
+        assert_eq!(rules[0].name.as_deref(), Some("pdf-tools"));
+        assert_eq!(rules[0].description.as_deref(), Some("Fill PDF forms"));
+        assert_eq!(rules[0].frontmatter.as_deref(), Some("license: MIT\n"));
+        assert_eq!(rules[0].content, "Run scripts/run.py.");
+        assert_eq!(rules[0].resources, Some(vec!["reference.md".to_string(), "scripts/run.py".to_string()]));
+
+        // Resources aren't copied, so they are only listed in a warning
+        let out = tempfile::tempdir().unwrap();
+        ClaudeWriter::default().write(&rules, out.path()).unwrap();
+        let again = ClaudeParser::default().parse(out.path()).unwrap();
+        assert_eq!(again, vec![Rule { resources: None, ..rules[0].clone() }]);
+        assert!(!out.path().join(".claude/skills/pdf-tools/scripts").exists());
+    }
+
+    fn structured(dir: &Path) -> Vec<Rule> {
+        let dot = dir.join(".claude");
+        fs::create_dir_all(&dot).unwrap();
+        fs::write(dot.join("settings.json"), SETTINGS).unwrap();
+        ClaudeParser { structured_permissions: true, ..Default::default() }.parse(dir).unwrap()
+    }
+
+    #[test]
+    fn structured_mode_extracts_sorted_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = structured(dir.path());
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].name.as_deref(), Some(SETTINGS_RULE_NAME));
+        assert_eq!(rules[0].content, "```json\n{\n  \"model\": \"opus\"\n}\n```");
+        assert_eq!(rules[1].name.as_deref(), Some(PERMISSIONS_RULE_NAME));
+        assert_eq!(
+            strip_json_fence(&rules[1].content),
+            r#"{
+  "allow": [
+    "Bash(git diff:*)",
+    "Bash(npm test:*)"
+  ],
+  "defaultMode": "acceptEdits",
+  "deny": [
+    "Read(./.env)"
+  ]
+}"#
+        );
+
+        // Off by default: settings.json stays one blob
+        let whole = ClaudeParser { structured_permissions: false, ..Default::default() }.parse(dir.path()).unwrap();
+        assert_eq!(whole.len(), 1);
+        assert!(whole[0].content.contains("permissions"));
+    }
+
+    #[test]
+    fn permissions_merge_back_keeping_other_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = structured(dir.path());
+        let permissions: Vec<Rule> = rules.into_iter().filter(|r| r.name.as_deref() == Some(PERMISSIONS_RULE_NAME)).collect();
+
+        let out = tempfile::tempdir().unwrap();
+        let file = out.path().join(".claude").join("settings.json");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, r#"{"theme": "dark", "permissions": {"allow": ["Old"]}}"#).unwrap();
+        ClaudeWriter { overwrite_settings: true }.write(&permissions, out.path()).unwrap();
+
+        let written: Value = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(written["theme"], "dark");
+        assert_eq!(written["permissions"]["allow"], serde_json::json!(["Bash(git diff:*)", "Bash(npm test:*)"]));
+        assert_eq!(written["permissions"]["deny"], serde_json::json!(["Read(./.env)"]));
+
+        // Parsing the merged file gives back the same permissions rule
+        let again = ClaudeParser { structured_permissions: true, ..Default::default() }.parse(out.path()).unwrap();
+        assert_eq!(again[1].content, permissions[0].content);
+    }
+
+    #[test]
+    fn settings_merge_into_the_local_file() {
+        let src = tempfile::tempdir().unwrap();
+        fs::create_dir_all(src.path().join(".claude")).unwrap();
+        fs::write(src.path().join(".claude/settings.json"), SETTINGS).unwrap();
+        let rules = ClaudeParser::default().parse(src.path()).unwrap();
+
+        let out = tempfile::tempdir().unwrap();
+        let file = out.path().join(".claude").join("settings.json");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        let local = r#"{"model": "haiku", "env": {"PATH": "/opt/bin"}, "permissions": {"allow": ["Bash(make:*)"]}}"#;
+        fs::write(&file, local).unwrap();
+        ClaudeWriter::default().write(&rules, out.path()).unwrap();
+        let written: Value = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(written["model"], "opus");
+        assert_eq!(written["env"]["PATH"], "/opt/bin");
+        assert_eq!(
+            written["permissions"]["allow"],
+            serde_json::json!(["Bash(npm test:*)", "Bash(git diff:*)", "Bash(npm test:*)", "Bash(make:*)"])
+        );
+
+        // Nothing local to keep: the stored text is written as is
+        ClaudeWriter::default().write(&rules, src.path()).unwrap();
+        assert_eq!(fs::read_to_string(src.path().join(".claude/settings.json")).unwrap(), format!("{SETTINGS}\n"));
+
+        fs::write(&file, local).unwrap();
+        ClaudeWriter { overwrite_settings: true }.write(&rules, out.path()).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), format!("{SETTINGS}\n"));
+
+        fs::write(&file, "{ not json").unwrap();
+        let err = ClaudeWriter::default().write(&rules, out.path()).unwrap_err().to_string();
+        assert!(err.contains(&file.display().to_string()) && err.contains("settings.json is not valid JSON"), "{err}");
+    }
+}
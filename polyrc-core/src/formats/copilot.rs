@@ -0,0 +1,446 @@
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+use crate::error::{PolyrcError, Result};
+use crate::formats::options::FormatOptions;
+use crate::ir::{split_order_prefix, unique_output_stems, Activation, Rule, Scope};
+use crate::parser::{read_source_file, Parser};
+use crate::writer::{WriteOptions, Writer};
+
+pub struct CopilotParser;
+
+#[derive(Default)]
+pub struct CopilotWriter {
+    /// Write each always rule to its own `.instructions.md` with `applyTo: "**"`
+    /// instead of concatenating them into `copilot-instructions.md`. A rule named
+    /// `copilot-instructions` still goes to that file.
+    pub split_always: bool,
+}
+
+/// `applyTo` pattern matching every file; such instructions are always-on.
+const APPLY_TO_ALL: &str = "**";
+/// Rule name that maps to `.github/copilot-instructions.md`.
+pub const MAIN_RULE_NAME: &str = "copilot-instructions";
+/// `[formats.copilot]` option backing [`CopilotWriter::split_always`].
+pub const SPLIT_ALWAYS: &str = "split_always";
+/// Reusable prompts, run on demand from the chat (`/name`).
+pub const PROMPTS_DIR: &str = ".github/prompts";
+pub const PROMPT_SUFFIX: &str = ".prompt.md";
+
+impl CopilotWriter {
+    pub fn new(options: &FormatOptions) -> Self {
+        Self { split_always: options.flag(SPLIT_ALWAYS) }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct CopilotFrontmatter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(rename = "applyTo", skip_serializing_if = "Option::is_none")]
+    apply_to: Option<String>,
+}
+
+/// Split YAML frontmatter from markdown content.
+/// Returns `(Option<frontmatter_str>, body_str)`.
+///
+/// Both `\n` and `\r\n` line endings are accepted for the opening and closing
+/// `---` fences; the returned body never starts with a stray `\r`.
+pub fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return (None, content);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end_matches(['\r', '\n']) == "---" {
+            let fm = rest[..offset].trim_end_matches(['\r', '\n']);
+            let body = &rest[offset + line.len()..];
+            // An empty `---\n---` block carries no metadata
+            return ((!fm.trim().is_empty()).then_some(fm), body);
+        }
+        offset += line.len();
+    }
+    (None, content)
+}
+
+impl Parser for CopilotParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        let mut rules = vec![];
+
+        // Project-wide instructions
+        let main_file = path.join(".github").join("copilot-instructions.md");
+        if main_file.exists() {
+            let content = read_source_file(&main_file)?.unwrap_or_default();
+            if !content.trim().is_empty() {
+                rules.push(Rule {
+                    scope: Scope::Project,
+                    activation: Activation::Always,
+                    globs: None,
+                    name: Some(MAIN_RULE_NAME.to_string()),
+                    description: None,
+                    content: content.trim_end().to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        // Path-scoped instructions
+        let instructions_dir = path.join(".github").join("instructions");
+        if instructions_dir.exists() {
+            for entry in WalkDir::new(&instructions_dir)
+                .min_depth(1)
+                .max_depth(1)
+                .sort_by_file_name()
+            {
+                let entry = entry.map_err(|e| PolyrcError::Io {
+                    path: instructions_dir.clone(),
+                    source: e.into(),
+                })?;
+                let p = entry.path();
+                let fname = p.file_name().and_then(|f| f.to_str()).unwrap_or("");
+                let Some(stem) = fname.strip_suffix(".instructions.md") else { continue };
+                if let Some(rule) = parse_instructions_file(p, stem)? {
+                    rules.push(rule);
+                }
+            }
+        }
+
+        // Prompt files — on-demand
+        let prompts_dir = path.join(PROMPTS_DIR);
+        if prompts_dir.exists() {
+            for entry in WalkDir::new(&prompts_dir).min_depth(1).max_depth(1).sort_by_file_name() {
+                let entry = entry.map_err(|e| PolyrcError::Io {
+                    path: prompts_dir.clone(),
+                    source: e.into(),
+                })?;
+                let p = entry.path();
+                let fname = p.file_name().and_then(|f| f.to_str()).unwrap_or("");
+                let Some(stem) = fname.strip_suffix(PROMPT_SUFFIX) else { continue };
+                if let Some(rule) = parse_prompt_file(p, stem)? {
+                    rules.push(rule);
+                }
+            }
+        }
+
+        Ok(rules)
+    }
+}
+
+/// The frontmatter of `raw` and the body after it.
+fn read_frontmatter<'a>(p: &Path, raw: &'a str) -> Result<(CopilotFrontmatter, &'a str)> {
+    let (fm_str, body) = split_frontmatter(raw);
+    let fm = fm_str
+        .map(|s| {
+            serde_yml::from_str(s).map_err(|e| PolyrcError::YamlParse {
+                path: p.to_path_buf(),
+                source: e,
+            })
+        })
+        .transpose()?
+        .unwrap_or_default();
+    Ok((fm, body))
+}
+
+/// One file with `applyTo` frontmatter as a rule named after `stem` unless the
+/// frontmatter names it. Also used by custom formats with `frontmatter = "copilot"`.
+pub(crate) fn parse_instructions_file(p: &Path, stem: &str) -> Result<Option<Rule>> {
+    let Some(raw) = read_source_file(p)? else { return Ok(None) };
+    let (fm, body) = read_frontmatter(p, &raw)?;
+
+    let (order, stem) = split_order_prefix(stem);
+    let name = fm.name.unwrap_or_else(|| stem.to_string());
+
+    // `applyTo: "**"` matches everything, i.e. an always-on rule. Without
+    // `applyTo`, a description lets the agent pick the file when it's relevant.
+    let (scope, activation, globs) = match fm.apply_to {
+        Some(apply_to) if apply_to != APPLY_TO_ALL => (Scope::Path, Activation::Glob, Some(vec![apply_to])),
+        Some(_) => (Scope::Project, Activation::Always, None),
+        None if fm.description.is_some() => (Scope::Project, Activation::AiDecides, None),
+        None => (Scope::Path, Activation::Always, None),
+    };
+
+    Ok(Some(Rule {
+        scope,
+        activation,
+        globs,
+        name: Some(name),
+        description: fm.description,
+        content: body.trim_end().to_string(),
+        order,
+        ..Default::default()
+    }))
+}
+
+/// A `.prompt.md` file as an on-demand rule named after `stem`.
+fn parse_prompt_file(p: &Path, stem: &str) -> Result<Option<Rule>> {
+    let Some(raw) = read_source_file(p)? else { return Ok(None) };
+    let (fm, body) = read_frontmatter(p, &raw)?;
+    let (order, stem) = split_order_prefix(stem);
+    Ok(Some(Rule {
+        scope: Scope::Project,
+        activation: Activation::OnDemand,
+        name: Some(stem.to_string()),
+        description: fm.description,
+        content: body.trim_end().to_string(),
+        order,
+        ..Default::default()
+    }))
+}
+
+/// `rule` as a file with `applyTo` frontmatter, to be written into `dir`.
+/// AI-decided rules get none, so the agent goes by their description.
+pub(crate) fn render_instructions_file(rule: &Rule, dir: &Path) -> Result<String> {
+    let apply_to = rule.globs.as_ref().and_then(|g| g.first()).cloned().unwrap_or_else(|| APPLY_TO_ALL.to_string());
+    let fm = CopilotFrontmatter {
+        name: rule.name.clone(),
+        description: rule.description.clone(),
+        apply_to: (rule.activation != Activation::AiDecides || rule.globs.is_some()).then_some(apply_to),
+    };
+    render_with_frontmatter(&fm, rule, dir)
+}
+
+/// `rule` as a `.prompt.md` file, to be written into `dir`.
+fn render_prompt_file(rule: &Rule, dir: &Path) -> Result<String> {
+    let fm = CopilotFrontmatter { description: rule.description.clone(), ..Default::default() };
+    if fm.description.is_none() {
+        return Ok(format!("{}\n", rule.content.trim_end()));
+    }
+    render_with_frontmatter(&fm, rule, dir)
+}
+
+fn render_with_frontmatter(fm: &CopilotFrontmatter, rule: &Rule, dir: &Path) -> Result<String> {
+    let fm_str = serde_yml::to_string(fm).map_err(|e| PolyrcError::YamlParse {
+        path: dir.to_path_buf(),
+        source: e,
+    })?;
+    Ok(format!("---\n{}---\n\n{}\n", fm_str, rule.content.trim_end()))
+}
+
+impl Writer for CopilotWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        self.write_with(rules, target, &WriteOptions::default())
+    }
+
+    /// `options.output_file` replaces `.github/copilot-instructions.md`;
+    /// path-scoped instructions still go under `target`.
+    fn write_with(&self, rules: &[Rule], target: &Path, options: &WriteOptions) -> Result<()> {
+        let rules = &crate::writer::with_derived_descriptions(rules, "copilot");
+        let mut always_rules: Vec<&Rule> = vec![];
+        let mut file_rules: Vec<&Rule> = vec![];
+        let mut prompt_rules: Vec<&Rule> = vec![];
+
+        for rule in rules {
+            let is_glob = rule.activation == Activation::Glob || rule.globs.is_some();
+            let split = self.split_always && rule.name.as_deref() != Some(MAIN_RULE_NAME);
+            if rule.activation == Activation::OnDemand {
+                prompt_rules.push(rule);
+            } else if is_glob || split || rule.activation == Activation::AiDecides {
+                file_rules.push(rule);
+            } else {
+                always_rules.push(rule);
+            }
+        }
+
+        // Write project-wide instructions
+        if !always_rules.is_empty() {
+            let file = options
+                .output_file
+                .clone()
+                .unwrap_or_else(|| target.join(".github").join("copilot-instructions.md"));
+            crate::formats::create_parent_dir(&file)?;
+            let content = if always_rules.len() == 1 {
+                always_rules[0].content.trim_end().to_string() + "\n"
+            } else {
+                always_rules
+                    .iter()
+                    .map(|r| {
+                        let header = r.name.as_deref().unwrap_or("Rule");
+                        format!("## {}\n\n{}\n", header, r.content.trim_end())
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+            fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        }
+
+        // Write path-scoped (and, when splitting, always-on) instructions
+        if !file_rules.is_empty() {
+            let instructions_dir = target.join(".github").join("instructions");
+            fs::create_dir_all(&instructions_dir).map_err(|e| PolyrcError::Io {
+                path: instructions_dir.clone(),
+                source: e,
+            })?;
+            let stems = unique_output_stems(file_rules.iter().copied());
+            for (rule, stem) in file_rules.iter().zip(stems) {
+                let content = render_instructions_file(rule, &instructions_dir)?;
+                let filename = format!("{}.instructions.md", stem);
+                let file = instructions_dir.join(&filename);
+                fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+            }
+        }
+
+        // On-demand rules as prompt files
+        if !prompt_rules.is_empty() {
+            let prompts_dir = target.join(PROMPTS_DIR);
+            fs::create_dir_all(&prompts_dir).map_err(|e| PolyrcError::Io {
+                path: prompts_dir.clone(),
+                source: e,
+            })?;
+            let stems = unique_output_stems(prompt_rules.iter().copied());
+            for (rule, stem) in prompt_rules.iter().zip(stems) {
+                let content = render_prompt_file(rule, &prompts_dir)?;
+                let file = prompts_dir.join(format!("{stem}{PROMPT_SUFFIX}"));
+                fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_frontmatter_lf() {
+        let (fm, body) = split_frontmatter("---\napplyTo: \"**/*.rs\"\n---\n\nBody\n");
+        assert_eq!(fm, Some("applyTo: \"**/*.rs\""));
+        assert_eq!(body, "\nBody\n");
+    }
+
+    #[test]
+    fn split_frontmatter_crlf() {
+        let (fm, body) = split_frontmatter("---\r\napplyTo: \"**/*.rs\"\r\n---\r\n\r\nBody\r\n");
+        assert_eq!(fm, Some("applyTo: \"**/*.rs\""));
+        assert_eq!(body, "\r\nBody\r\n");
+    }
+
+    #[test]
+    fn split_frontmatter_closing_fence_at_eof() {
+        assert_eq!(split_frontmatter("---\r\nname: x\r\n---"), (Some("name: x"), ""));
+        assert_eq!(split_frontmatter("---\nname: x\n---"), (Some("name: x"), ""));
+    }
+
+    #[test]
+    fn split_frontmatter_without_fence() {
+        let raw = "# Title\r\n---\r\ntext";
+        assert_eq!(split_frontmatter(raw), (None, raw));
+    }
+
+    #[test]
+    fn parse_crlf_instructions() {
+        let dir = tempfile::tempdir().unwrap();
+        let instructions = dir.path().join(".github").join("instructions");
+        fs::create_dir_all(&instructions).unwrap();
+        fs::write(
+            instructions.join("rust.instructions.md"),
+            "---\r\ndescription: Rust style\r\napplyTo: \"**/*.rs\"\r\n---\r\n\r\nUse rustfmt.\r\n",
+        )
+        .unwrap();
+
+        let rules = CopilotParser.parse(dir.path()).unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].activation, Activation::Glob);
+        assert_eq!(rules[0].globs, Some(vec!["**/*.rs".to_string()]));
+        assert_eq!(rules[0].description.as_deref(), Some("Rust style"));
+        assert_eq!(rules[0].content.trim(), "Use rustfmt.");
+    }
+
+    fn always(name: &str, content: &str) -> Rule {
+        Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn combines_always_rules_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = vec![always("style", "Use tabs."), always("tests", "Write tests.")];
+        CopilotWriter { split_always: false }.write(&rules, dir.path()).unwrap();
+
+        let main = fs::read_to_string(dir.path().join(".github").join("copilot-instructions.md")).unwrap();
+        assert_eq!(main, "## style\n\nUse tabs.\n\n## tests\n\nWrite tests.\n");
+        assert!(!dir.path().join(".github").join("instructions").exists());
+    }
+
+    #[test]
+    fn on_demand_and_ai_decided_rules_get_their_own_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = vec![
+            always("style", "Use tabs."),
+            Rule { activation: Activation::OnDemand, description: Some("Open a PR".into()), ..always("pr", "Open a PR.") },
+            Rule { activation: Activation::AiDecides, description: Some("SQL migrations".into()), ..always("sql", "Add a down migration.") },
+        ];
+        CopilotWriter::default().write(&rules, dir.path()).unwrap();
+
+        let github = dir.path().join(".github");
+        assert_eq!(fs::read_to_string(github.join("copilot-instructions.md")).unwrap(), "Use tabs.\n");
+        assert_eq!(
+            fs::read_to_string(github.join("prompts").join("pr.prompt.md")).unwrap(),
+            "---\ndescription: Open a PR\n---\n\nOpen a PR.\n"
+        );
+        let sql = fs::read_to_string(github.join("instructions").join("sql.instructions.md")).unwrap();
+        assert!(!sql.contains("applyTo"), "{sql}");
+
+        let parsed = CopilotParser.parse(dir.path()).unwrap();
+        let activations: Vec<(&str, &Activation)> =
+            parsed.iter().map(|r| (r.name.as_deref().unwrap(), &r.activation)).collect();
+        assert_eq!(
+            activations,
+            vec![
+                (MAIN_RULE_NAME, &Activation::Always),
+                ("sql", &Activation::AiDecides),
+                ("pr", &Activation::OnDemand),
+            ]
+        );
+        assert_eq!(parsed[2].description.as_deref(), Some("Open a PR"));
+    }
+
+    #[test]
+    fn split_always_writes_one_file_per_rule() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = vec![always(MAIN_RULE_NAME, "Main."), always("style", "Use tabs."), always("tests", "Write tests.")];
+        CopilotWriter { split_always: true }.write(&rules, dir.path()).unwrap();
+
+        let github = dir.path().join(".github");
+        assert_eq!(fs::read_to_string(github.join("copilot-instructions.md")).unwrap(), "Main.\n");
+        assert_eq!(
+            fs::read_to_string(github.join("instructions").join("style.instructions.md")).unwrap(),
+            "---\nname: style\napplyTo: '**'\n---\n\nUse tabs.\n"
+        );
+
+        let mut parsed = CopilotParser.parse(dir.path()).unwrap();
+        parsed.sort_by(|a, b| a.name.cmp(&b.name));
+        let summary: Vec<_> = parsed.iter().map(|r| (r.name.clone().unwrap(), r.activation.clone(), r.globs.clone())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (MAIN_RULE_NAME.to_string(), Activation::Always, None),
+                ("style".to_string(), Activation::Always, None),
+                ("tests".to_string(), Activation::Always, None),
+            ]
+        );
+        assert_eq!(parsed[1].content.trim(), "Use tabs.");
+    }
+
+    #[test]
+    fn output_file_replaces_only_the_main_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut scoped = always("rust", "Use clippy.");
+        scoped.activation = Activation::Glob;
+        scoped.globs = Some(vec!["**/*.rs".into()]);
+        let file = dir.path().join("docs").join("AI_GUIDELINES.md");
+        let options = WriteOptions { output_file: Some(file.clone()) };
+        CopilotWriter { split_always: false }.write_with(&[always("style", "Use tabs."), scoped], dir.path(), &options).unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "Use tabs.\n");
+        assert!(!dir.path().join(".github").join("copilot-instructions.md").exists());
+        assert!(dir.path().join(".github").join("instructions").join("rust.instructions.md").exists());
+    }
+}
@@ -0,0 +1,406 @@
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+use crate::error::{PolyrcError, Result};
+use crate::formats::options::FormatOptions;
+use crate::formats::single_file;
+use crate::ir::{nested_output_stem, split_order_prefix, uniquify, Activation, Rule, Scope};
+use crate::parser::{read_source_file, Parser};
+use crate::writer::Writer;
+use crate::formats::copilot::split_frontmatter;
+
+pub struct CursorParser;
+
+#[derive(Default)]
+pub struct CursorWriter {
+    /// Write always-on rules to the root [`LEGACY_FILE`] instead of
+    /// `.cursor/rules/`, for teams still on it. Other activations need `.mdc`
+    /// frontmatter, so those rules stay in `.cursor/rules/`.
+    pub legacy_file: bool,
+}
+
+/// Cursor's original single rules file, still read by Cursor.
+pub const LEGACY_FILE: &str = ".cursorrules";
+/// Name the parser gives the whole of [`LEGACY_FILE`].
+pub const LEGACY_RULE_NAME: &str = "cursorrules";
+/// `[formats.cursor]` option backing [`CursorWriter::legacy_file`].
+pub const LEGACY_FILE_OPTION: &str = "legacy_file";
+
+impl CursorWriter {
+    pub fn new(options: &FormatOptions) -> Self {
+        Self { legacy_file: options.flag(LEGACY_FILE_OPTION) }
+    }
+}
+
+/// Cursor's `globs` field can be a single string or a YAML sequence.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StringOrVec {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl StringOrVec {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            StringOrVec::Single(s) => {
+                // A single string may be comma-separated
+                crate::globs::split_list(&s)
+            }
+            StringOrVec::Multiple(v) => v,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CursorFrontmatter {
+    description: Option<String>,
+    globs: Option<StringOrVec>,
+    always_apply: Option<bool>,
+}
+
+/// Frontmatter fields emitted as regular YAML. `globs` is written separately by
+/// [`render_frontmatter`] because Cursor expects it as a bare scalar.
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CursorFrontmatterOut {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    always_apply: Option<bool>,
+}
+
+/// Render `.mdc` frontmatter (without the `---` fences) the way Cursor's own UI
+/// writes it: `globs: *.ts,src/**` as one unquoted comma-joined value, which
+/// some Cursor versions require. Other fields are ordinary YAML.
+fn render_frontmatter(rule: &Rule, path: &Path) -> Result<String> {
+    let fm = CursorFrontmatterOut {
+        description: rule.description.clone(),
+        always_apply: if rule.activation == Activation::Always { Some(true) } else { None },
+    };
+    let mut out = if fm.description.is_none() && fm.always_apply.is_none() {
+        String::new()
+    } else {
+        serde_yml::to_string(&fm).map_err(|e| PolyrcError::YamlParse { path: path.to_path_buf(), source: e })?
+    };
+    if let Some(globs) = rule.globs.as_ref().filter(|g| !g.is_empty()) {
+        let line = globs_line(globs);
+        // Keep Cursor's field order: description, globs, alwaysApply
+        let at = if out.starts_with("alwaysApply:") { Some(0) } else { out.find("\nalwaysApply:").map(|i| i + 1) };
+        match at {
+            Some(i) => out.insert_str(i, &line),
+            None => out.push_str(&line),
+        }
+    }
+    Ok(out)
+}
+
+/// `globs: a,b` as one bare comma-joined value, read back by [`take_bare_globs`].
+/// A list starting with `{` would read back as a YAML mapping, so it is quoted.
+pub(crate) fn globs_line(globs: &[String]) -> String {
+    let joined = globs.join(",");
+    match joined.starts_with(['{', '[', '"', '\'', '|', '>']) {
+        true => format!("globs: '{}'\n", joined.replace('\'', "''")),
+        false => format!("globs: {joined}\n"),
+    }
+}
+
+/// Split a bare `globs:` value out of raw frontmatter before YAML parsing.
+///
+/// Cursor writes `globs: *.ts,src/**`, which is not valid YAML (`*` starts an
+/// alias). A top-level `globs:` line whose value isn't quoted or a flow sequence
+/// is removed and returned verbatim; quoted values and block sequences are left
+/// for the YAML parser.
+pub(crate) fn take_bare_globs(fm: &str) -> (String, Option<String>) {
+    let mut rest = String::with_capacity(fm.len());
+    let mut bare = None;
+    for line in fm.lines() {
+        if let Some(value) = line.strip_prefix("globs:") {
+            let value = value.trim();
+            if !value.is_empty() && !value.starts_with(['"', '\'', '[', '{', '|', '>']) {
+                bare = Some(value.to_string());
+                continue;
+            }
+        }
+        rest.push_str(line);
+        rest.push('\n');
+    }
+    (rest, bare)
+}
+
+impl Parser for CursorParser {
+    /// Reads `.cursor/rules/**/*.mdc`, and the legacy `.cursorrules` file as an
+    /// always-on rule named `cursorrules`. Nested files are named by their
+    /// relative path (`backend/api`).
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        let mut rules: Vec<Rule> = single_file::parse(&path.join(LEGACY_FILE), LEGACY_RULE_NAME)?.into_iter().collect();
+        let rules_dir = path.join(".cursor").join("rules");
+        if !rules_dir.exists() {
+            return Ok(rules);
+        }
+        for entry in WalkDir::new(&rules_dir).min_depth(1).sort_by_file_name() {
+            let entry = entry.map_err(|e| PolyrcError::Io {
+                path: rules_dir.clone(),
+                source: e.into(),
+            })?;
+            let p = entry.path();
+            if p.extension().and_then(|e| e.to_str()) != Some("mdc") {
+                continue;
+            }
+            let Some(mut rule) = parse_rule_file(p, p.file_stem().and_then(|s| s.to_str()).unwrap_or("rule"))? else {
+                continue;
+            };
+            let dirs = p.parent().and_then(|d| d.strip_prefix(&rules_dir).ok()).unwrap_or(Path::new(""));
+            if dirs.components().next().is_some() {
+                let name = rule.name.take().unwrap_or_default();
+                rule.name = Some(format!("{}/{name}", dirs.to_string_lossy().replace('\\', "/")));
+            }
+            rules.push(rule);
+        }
+        Ok(rules)
+    }
+}
+
+/// One file with Cursor-style frontmatter as a project rule named after
+/// `stem`. Also used by custom formats with `frontmatter = "cursor"`.
+pub(crate) fn parse_rule_file(p: &Path, stem: &str) -> Result<Option<Rule>> {
+    let Some(raw) = read_source_file(p)? else { return Ok(None) };
+
+    let (fm_str, body) = split_frontmatter(&raw);
+    let (fm_str, bare_globs) = fm_str.map(take_bare_globs).unzip();
+    let mut fm: CursorFrontmatter = fm_str
+        .map(|s| {
+            serde_yml::from_str::<Option<CursorFrontmatter>>(&s).map_err(|e| PolyrcError::YamlParse {
+                path: p.to_path_buf(),
+                source: e,
+            })
+        })
+        .transpose()?
+        .flatten()
+        .unwrap_or_default();
+    if let Some(g) = bare_globs.flatten() {
+        fm.globs = Some(StringOrVec::Single(g));
+    }
+
+    let globs: Option<Vec<String>> = fm.globs.map(|g| g.into_vec()).filter(|v| !v.is_empty());
+
+    let activation = if fm.always_apply == Some(true) {
+        Activation::Always
+    } else if globs.is_some() {
+        Activation::Glob
+    } else if fm.description.is_some() {
+        Activation::AiDecides
+    } else {
+        Activation::OnDemand
+    };
+
+    let (order, stem) = split_order_prefix(stem);
+
+    Ok(Some(Rule {
+        scope: Scope::Project,
+        activation,
+        globs,
+        name: Some(stem.to_string()),
+        description: fm.description,
+        content: body.trim_end().to_string(),
+        order,
+        ..Default::default()
+    }))
+}
+
+/// `rule` as a file with Cursor-style frontmatter, to be written into `dir`.
+pub(crate) fn render_rule_file(rule: &Rule, dir: &Path) -> Result<String> {
+    let fm_str = render_frontmatter(rule, dir)?;
+    Ok(format!("---\n{}---\n\n{}\n", fm_str, rule.content.trim_end()))
+}
+
+impl Writer for CursorWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        let (legacy, rules): (Vec<Rule>, Vec<Rule>) =
+            rules.iter().cloned().partition(|r| self.legacy_file && r.activation == Activation::Always);
+        if !legacy.is_empty() {
+            single_file::write(&target.join(LEGACY_FILE), &legacy)?;
+        }
+        if rules.is_empty() {
+            return Ok(());
+        }
+
+        let rules_dir = target.join(".cursor").join("rules");
+        fs::create_dir_all(&rules_dir).map_err(|e| PolyrcError::Io {
+            path: rules_dir.clone(),
+            source: e,
+        })?;
+
+        let rules = &crate::writer::with_derived_descriptions(&rules, "cursor");
+        let refs: Vec<&Rule> = rules.iter().collect();
+        let stems = uniquify(&refs, rules.iter().map(|r| nested_output_stem(r, '/')).collect());
+        for (rule, stem) in rules.iter().zip(stems) {
+            let content = render_rule_file(rule, &rules_dir)?;
+            let filename = format!("{}.mdc", stem);
+            let file = rules_dir.join(&filename);
+            crate::formats::create_parent_dir(&file)?;
+            fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_crlf_mdc() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules_dir = dir.path().join(".cursor").join("rules");
+        fs::create_dir_all(&rules_dir).unwrap();
+        fs::write(
+            rules_dir.join("ts.mdc"),
+            "---\r\ndescription: TypeScript\r\nglobs: \"*.ts, *.tsx\"\r\nalwaysApply: false\r\n---\r\n\r\nPrefer interfaces.\r\n",
+        )
+        .unwrap();
+
+        let rules = CursorParser.parse(dir.path()).unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].activation, Activation::Glob);
+        assert_eq!(rules[0].globs, Some(vec!["*.ts".to_string(), "*.tsx".to_string()]));
+        assert_eq!(rules[0].description.as_deref(), Some("TypeScript"));
+        assert!(!rules[0].content.contains("---"));
+        assert_eq!(rules[0].content.trim(), "Prefer interfaces.");
+    }
+
+    fn write_one(rule: Rule) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let stem = rule.filename_stem();
+        CursorWriter::default().write(&[rule], dir.path()).unwrap();
+        fs::read_to_string(dir.path().join(".cursor").join("rules").join(format!("{stem}.mdc"))).unwrap()
+    }
+
+    #[test]
+    fn writes_globs_as_bare_comma_list() {
+        let rule = Rule {
+            name: Some("ts".into()),
+            description: Some("TypeScript".into()),
+            activation: Activation::Glob,
+            globs: Some(vec!["*.ts".into(), "src/**/*.tsx".into()]),
+            content: "Prefer interfaces.".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            write_one(rule),
+            "---\ndescription: TypeScript\nglobs: *.ts,src/**/*.tsx\n---\n\nPrefer interfaces.\n"
+        );
+    }
+
+    #[test]
+    fn writes_globs_before_always_apply() {
+        let rule = Rule {
+            name: Some("all".into()),
+            activation: Activation::Always,
+            globs: Some(vec!["**/*".into()]),
+            content: "Be terse.".into(),
+            ..Default::default()
+        };
+        assert_eq!(write_one(rule), "---\nglobs: **/*\nalwaysApply: true\n---\n\nBe terse.\n");
+    }
+
+    #[test]
+    fn writes_plain_yaml_without_globs() {
+        let rule = Rule {
+            name: Some("style".into()),
+            description: Some("Style: house rules".into()),
+            activation: Activation::Always,
+            content: "Use tabs.".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            write_one(rule),
+            "---\ndescription: 'Style: house rules'\nalwaysApply: true\n---\n\nUse tabs.\n"
+        );
+    }
+
+    #[test]
+    fn ai_decided_rule_gets_derived_description() {
+        let rule = Rule {
+            name: Some("errors".into()),
+            activation: Activation::AiDecides,
+            content: "# Error handling\n\nUse anyhow.".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            write_one(rule),
+            "---\ndescription: Error handling\n---\n\n# Error handling\n\nUse anyhow.\n"
+        );
+    }
+
+    #[test]
+    fn parses_bare_quoted_and_sequence_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules_dir = dir.path().join(".cursor").join("rules");
+        fs::create_dir_all(&rules_dir).unwrap();
+        fs::write(rules_dir.join("a.mdc"), "---\nglobs: *.ts,src/**\n---\n\nA\n").unwrap();
+        fs::write(rules_dir.join("b.mdc"), "---\nglobs: \"*.ts, src/**\"\n---\n\nB\n").unwrap();
+        fs::write(rules_dir.join("c.mdc"), "---\nglobs:\n  - '*.ts'\n  - src/**\n---\n\nC\n").unwrap();
+
+        let rules = CursorParser.parse(dir.path()).unwrap();
+        assert_eq!(rules.len(), 3);
+        for rule in &rules {
+            assert_eq!(rule.globs, Some(vec!["*.ts".to_string(), "src/**".to_string()]), "{:?}", rule.name);
+            assert_eq!(rule.activation, Activation::Glob);
+        }
+    }
+
+    #[test]
+    fn brace_globs_round_trip() {
+        let globs = vec!["{app,lib}/**/*.rs".to_string(), "src/**/*.{ts,tsx}".to_string()];
+        let rule = Rule { name: Some("code".into()), activation: Activation::Glob, globs: Some(globs.clone()), content: "Lint.".into(), ..Default::default() };
+        let dir = tempfile::tempdir().unwrap();
+        CursorWriter::default().write(&[rule], dir.path()).unwrap();
+        let written = fs::read_to_string(dir.path().join(".cursor/rules/code.mdc")).unwrap();
+        assert!(written.contains("globs: '{app,lib}/**/*.rs,src/**/*.{ts,tsx}'\n"), "{written}");
+        assert_eq!(CursorParser.parse(dir.path()).unwrap()[0].globs, Some(globs));
+
+        fs::write(dir.path().join(".cursor/rules/code.mdc"), "---\nglobs: src/**/*.{ts,tsx},*.md\n---\n\nLint.\n").unwrap();
+        let bare = CursorParser.parse(dir.path()).unwrap();
+        assert_eq!(bare[0].globs, Some(vec!["src/**/*.{ts,tsx}".to_string(), "*.md".to_string()]));
+    }
+
+    #[test]
+    fn legacy_file_is_read_alongside_the_rules_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules_dir = dir.path().join(".cursor").join("rules");
+        fs::create_dir_all(&rules_dir).unwrap();
+        fs::write(dir.path().join(LEGACY_FILE), "Use tabs.\n").unwrap();
+        fs::write(rules_dir.join("ts.mdc"), "---\nglobs: *.ts\n---\n\nPrefer interfaces.\n").unwrap();
+
+        let rules = CursorParser.parse(dir.path()).unwrap();
+        let names: Vec<(&str, &Activation)> = rules.iter().map(|r| (r.name.as_deref().unwrap(), &r.activation)).collect();
+        assert_eq!(names, vec![(LEGACY_RULE_NAME, &Activation::Always), ("ts", &Activation::Glob)]);
+
+        let out = tempfile::tempdir().unwrap();
+        CursorWriter { legacy_file: true }.write(&rules, out.path()).unwrap();
+        assert_eq!(fs::read_to_string(out.path().join(LEGACY_FILE)).unwrap(), "Use tabs.\n");
+        let mdc: Vec<_> = fs::read_dir(out.path().join(".cursor").join("rules")).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(mdc, vec!["ts.mdc"]);
+        assert_eq!(CursorParser.parse(out.path()).unwrap()[0], rules[0]);
+    }
+
+    #[test]
+    fn nested_rules_keep_their_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = dir.path().join(".cursor").join("rules").join("backend");
+        fs::create_dir_all(&backend).unwrap();
+        fs::write(backend.join("01-api.mdc"), "---\nalwaysApply: true\n---\nReturn JSON errors.\n").unwrap();
+
+        let rules = CursorParser.parse(dir.path()).unwrap();
+        assert_eq!((rules[0].name.as_deref(), rules[0].order), (Some("backend/api"), Some(1)));
+
+        let out = tempfile::tempdir().unwrap();
+        CursorWriter::default().write(&rules, out.path()).unwrap();
+        assert!(out.path().join(".cursor/rules/backend/01-api.mdc").is_file());
+        assert_eq!(CursorParser.parse(out.path()).unwrap()[0].name.as_deref(), Some("backend/api"));
+    }
+}
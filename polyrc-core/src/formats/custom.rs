@@ -0,0 +1,386 @@
+//! User-defined formats from `~/polyrc/formats.toml`, for tools polyrc doesn't
+//! know yet:
+//!
+//! ```toml
+//! [formats.mytool]
+//! rules_glob = ".mytool/rules/*.md"   # one file per rule; or:
+//! # single_file = "MYTOOL.md"         # every rule in one file
+//! frontmatter = "cursor"              # "none" (default), "cursor" or "copilot"
+//! user_dir = "~/.mytool"              # optional; same layout, for --user
+//! ```
+//!
+//! A definition is read with a [`GenericParser`] and written with a
+//! [`GenericWriter`]. Formats whose files polyrc can't read at all are plugins
+//! instead (see [`super::plugin`]). Once [`register`]ed, either is a
+//! [`Format::Custom`] like any other: `Format::from_str`, `Format::all()` and
+//! the CLI's `--format` all see it.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+use crate::error::{PolyrcError, Result};
+use crate::formats::{copilot, cursor, plugin, single_file, Format, RuleDir};
+use crate::ir::{unique_output_stems, Activation, Rule, Scope};
+use crate::parser::{read_source_file, Parser};
+use crate::writer::{WriteOptions, Writer};
+
+/// The definitions file, in [`crate::config::polyrc_dir`].
+pub const FORMATS_FILE: &str = "formats.toml";
+
+/// Frontmatter a custom format's rule files carry, borrowed from a built-in format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontmatterStyle {
+    /// Plain markdown; every rule is always-on.
+    #[default]
+    None,
+    /// `description`, `globs` and `alwaysApply`, as in `.cursor/rules/*.mdc`.
+    Cursor,
+    /// `applyTo`, as in `.github/instructions/*.instructions.md`.
+    Copilot,
+}
+
+impl FrontmatterStyle {
+    fn activations(self) -> &'static [Activation] {
+        match self {
+            Self::None => &[Activation::Always],
+            Self::Cursor => &[Activation::Always, Activation::Glob, Activation::OnDemand, Activation::AiDecides],
+            Self::Copilot => &[Activation::Always, Activation::Glob, Activation::AiDecides],
+        }
+    }
+}
+
+/// One `[formats.<name>]` table as written.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FormatDef {
+    /// Project-relative `<dir>/*<suffix>` pattern; one file per rule.
+    pub rules_glob: Option<String>,
+    /// Project-relative file holding every rule.
+    pub single_file: Option<String>,
+    #[serde(default)]
+    pub frontmatter: FrontmatterStyle,
+    /// Where the tool keeps user-scope rules, laid out like a project.
+    pub user_dir: Option<String>,
+    /// Shown by `polyrc supported-formats`; defaults to the path.
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FormatsFile {
+    #[serde(default)]
+    formats: BTreeMap<String, FormatDef>,
+}
+
+/// A validated definition. Its strings are leaked by [`define`] so that
+/// [`Format`] can hand them out as `&'static` like the built-in formats do.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CustomFormat {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub frontmatter: FrontmatterStyle,
+    pub user_dir: Option<PathBuf>,
+    /// A plugin's command line; the plugin then reads and writes the files.
+    pub command: Option<&'static str>,
+    /// `[path]`: the rules directory, or the single file. Empty for plugins.
+    pub(crate) project_paths: &'static [&'static str],
+    pub(crate) rule_dirs: &'static [RuleDir],
+    pub(crate) single_files: &'static [(&'static str, &'static str)],
+}
+
+impl CustomFormat {
+    /// The rules directory, for `rules_glob` formats.
+    fn rule_dir(&self) -> Option<&RuleDir> {
+        self.rule_dirs.first()
+    }
+
+    /// A plugin format, checked like the others. See [`plugin::define`].
+    pub(crate) fn plugin(name: &str, command: &str, description: String, user_dir: Option<PathBuf>) -> Result<Self> {
+        check_name(name, &format!("[plugins.{name}] in config.toml"))?;
+        Ok(CustomFormat {
+            name: leak(name.to_string()),
+            description: leak(description),
+            frontmatter: FrontmatterStyle::None,
+            user_dir,
+            command: Some(leak(command.to_string())),
+            project_paths: &[],
+            rule_dirs: &[],
+            single_files: &[],
+        })
+    }
+
+    /// Plugins exchange whole rules, so they may keep any activation.
+    pub fn activations(&self) -> &'static [Activation] {
+        match (self.command, self.rule_dir()) {
+            (Some(_), _) => FrontmatterStyle::Cursor.activations(),
+            (None, Some(_)) => self.frontmatter.activations(),
+            (None, None) => &[Activation::Always],
+        }
+    }
+
+    pub fn keeps_descriptions(&self) -> bool {
+        self.command.is_some() || self.frontmatter != FrontmatterStyle::None
+    }
+
+    pub fn parser(&'static self) -> Box<dyn Parser> {
+        match self.command {
+            Some(_) => Box::new(plugin::PluginParser(self)),
+            None => Box::new(GenericParser(self)),
+        }
+    }
+
+    pub fn writer(&'static self) -> Box<dyn Writer> {
+        match self.command {
+            Some(_) => Box::new(plugin::PluginWriter(self)),
+            None => Box::new(GenericWriter(self)),
+        }
+    }
+}
+
+fn leak(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
+
+/// Fail unless `name` can name a new format; `section` is where it was defined.
+fn check_name(name: &str, section: &str) -> Result<()> {
+    let invalid = |msg: String| Err(PolyrcError::ConfigError { msg: format!("{section}: {msg}") });
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        return invalid("format names use lowercase letters, digits and '-'".to_string());
+    }
+    if Format::built_in().iter().any(|f| f.name() == name || f.aliases().contains(&name)) {
+        return invalid(format!("'{name}' is a built-in format"));
+    }
+    Ok(())
+}
+
+/// Check `def` and turn it into a [`CustomFormat`] named `name`.
+pub fn define(name: &str, def: FormatDef) -> Result<CustomFormat> {
+    let section = format!("[formats.{name}] in {FORMATS_FILE}");
+    check_name(name, &section)?;
+    let invalid = |msg: String| PolyrcError::ConfigError { msg: format!("{section}: {msg}") };
+    let user_dir = def.user_dir.as_deref().map(crate::config::expand_path);
+    let (path, rule_dir) = match (def.rules_glob, def.single_file) {
+        (Some(glob), None) => {
+            let (dir, suffix) = split_rules_glob(&glob)
+                .ok_or_else(|| invalid(format!("rules_glob '{glob}' must look like '<dir>/*<suffix>', e.g. '.mytool/rules/*.md'")))?;
+            let rule_dir = RuleDir { path: leak(dir.to_string()), suffix: leak(suffix.to_string()), recursive: false };
+            (glob, Some(rule_dir))
+        }
+        (None, Some(file)) => {
+            if def.frontmatter != FrontmatterStyle::None {
+                return Err(invalid("frontmatter needs rules_glob; a single file has none".to_string()));
+            }
+            (file, None)
+        }
+        (Some(_), Some(_)) => return Err(invalid("set rules_glob or single_file, not both".to_string())),
+        (None, None) => return Err(invalid("set rules_glob or single_file".to_string())),
+    };
+    let description = def.description.unwrap_or_else(|| format!("{name} ({path})"));
+    let name = leak(name.to_string());
+    Ok(match rule_dir {
+        Some(dir) => CustomFormat {
+            name,
+            description: leak(description),
+            frontmatter: def.frontmatter,
+            user_dir,
+            command: None,
+            project_paths: Box::leak(Box::new([dir.path])),
+            rule_dirs: Box::leak(Box::new([dir])),
+            single_files: &[],
+        },
+        None => {
+            let file = leak(path);
+            CustomFormat {
+                name,
+                description: leak(description),
+                frontmatter: def.frontmatter,
+                user_dir,
+                command: None,
+                project_paths: Box::leak(Box::new([file])),
+                rule_dirs: &[],
+                single_files: Box::leak(Box::new([(file, name)])),
+            }
+        }
+    })
+}
+
+/// `.mytool/rules/*.md` as `(".mytool/rules", ".md")`. Only the final
+/// component may be a pattern, and only a `*` prefix.
+fn split_rules_glob(glob: &str) -> Option<(&str, &str)> {
+    let (dir, file) = glob.trim_start_matches("./").rsplit_once('/')?;
+    let suffix = file.strip_prefix('*')?;
+    let wild = |s: &str| s.contains(['*', '?', '[', '{']);
+    (!dir.is_empty() && !suffix.is_empty() && !wild(dir) && !wild(suffix)).then_some((dir, suffix))
+}
+
+/// Every definition in `path`, in name order. A missing file defines none.
+pub fn load(path: &Path) -> Result<Vec<CustomFormat>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let raw = fs::read_to_string(path).map_err(|e| PolyrcError::Io { path: path.to_path_buf(), source: e })?;
+    let file: FormatsFile = toml::from_str(&raw).map_err(|e| PolyrcError::TomlParse { path: path.to_path_buf(), source: e })?;
+    file.formats.into_iter().map(|(name, def)| define(&name, def)).collect()
+}
+
+/// [`load`] `~/polyrc/formats.toml`, add the `[plugins]` from config.toml,
+/// and [`register`] them all.
+pub fn load_and_register() -> Result<()> {
+    let mut formats = load(&crate::config::polyrc_dir().join(FORMATS_FILE))?;
+    for (name, config) in crate::config::Config::load()?.plugins {
+        if formats.iter().any(|f| f.name == name) {
+            return Err(PolyrcError::ConfigError {
+                msg: format!("'{name}' is defined both in {FORMATS_FILE} and under [plugins] in config.toml"),
+            });
+        }
+        formats.push(plugin::define(&name, config)?);
+    }
+    register(formats);
+    Ok(())
+}
+
+/// Make `formats` known process-wide, after the built-in ones. Replaces any
+/// earlier registration; must happen before the CLI parses `--format`.
+pub fn register(formats: Vec<CustomFormat>) {
+    let all: Vec<Format> = Format::built_in()
+        .iter()
+        .cloned()
+        .chain(formats.into_iter().map(|c| Format::Custom(Box::leak(Box::new(c)))))
+        .collect();
+    *super::ALL.write().expect("formats lock") = Some(Box::leak(all.into_boxed_slice()));
+}
+
+pub struct GenericParser(pub &'static CustomFormat);
+pub struct GenericWriter(pub &'static CustomFormat);
+
+impl Parser for GenericParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        let fmt = self.0;
+        let Some(rule_dir) = fmt.rule_dir() else {
+            let (file, name) = fmt.single_files[0];
+            return Ok(single_file::parse(&path.join(file), name)?.into_iter().collect());
+        };
+        let dir = path.join(rule_dir.path);
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut rules = vec![];
+        for entry in WalkDir::new(&dir).min_depth(1).max_depth(1).sort_by_file_name() {
+            let entry = entry.map_err(|e| PolyrcError::Io {
+                path: dir.clone(),
+                source: e.into(),
+            })?;
+            let p = entry.path();
+            let fname = p.file_name().and_then(|f| f.to_str()).unwrap_or("");
+            let Some(stem) = fname.strip_suffix(rule_dir.suffix).filter(|s| !s.is_empty() && entry.file_type().is_file()) else {
+                continue;
+            };
+            let rule = match fmt.frontmatter {
+                FrontmatterStyle::Cursor => cursor::parse_rule_file(p, stem)?,
+                FrontmatterStyle::Copilot => copilot::parse_instructions_file(p, stem)?,
+                FrontmatterStyle::None => read_source_file(p)?.filter(|raw| !raw.trim().is_empty()).map(|raw| {
+                    let (order, stem) = crate::ir::split_order_prefix(stem);
+                    Rule {
+                        scope: Scope::Project,
+                        activation: Activation::Always,
+                        name: Some(stem.to_string()),
+                        content: raw.trim_end().to_string(),
+                        order,
+                        ..Default::default()
+                    }
+                }),
+            };
+            // Without the blank line that follows the frontmatter
+            rules.extend(rule.map(|r| Rule { content: r.content.trim().to_string(), ..r }));
+        }
+        Ok(rules)
+    }
+}
+
+impl Writer for GenericWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        self.write_with(rules, target, &WriteOptions::default())
+    }
+
+    fn write_with(&self, rules: &[Rule], target: &Path, options: &WriteOptions) -> Result<()> {
+        let fmt = self.0;
+        if rules.is_empty() {
+            return Ok(());
+        }
+        let Some(rule_dir) = fmt.rule_dir() else {
+            let file = options.output_file.clone().unwrap_or_else(|| target.join(fmt.single_files[0].0));
+            return single_file::write(&file, rules);
+        };
+        let dir = target.join(rule_dir.path);
+        fs::create_dir_all(&dir).map_err(|e| PolyrcError::Io {
+            path: dir.clone(),
+            source: e,
+        })?;
+        let rules = &match fmt.frontmatter {
+            FrontmatterStyle::Cursor | FrontmatterStyle::Copilot => crate::writer::with_derived_descriptions(rules, fmt.name),
+            _ => rules.to_vec(),
+        };
+        for (rule, stem) in rules.iter().zip(unique_output_stems(rules)) {
+            let content = match fmt.frontmatter {
+                FrontmatterStyle::Cursor => cursor::render_rule_file(rule, &dir)?,
+                FrontmatterStyle::Copilot => copilot::render_instructions_file(rule, &dir)?,
+                FrontmatterStyle::None => format!("{}\n", rule.content.trim_end()),
+            };
+            let file = dir.join(format!("{stem}{}", rule_dir.suffix));
+            fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn def(toml: &str) -> FormatDef {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn definitions_are_checked() {
+        let err = |name: &str, toml: &str| define(name, def(toml)).unwrap_err().to_string();
+        assert!(err("cursor", "single_file = \"X.md\"").contains("built-in"));
+        assert!(err("My Tool", "single_file = \"X.md\"").contains("lowercase"));
+        assert!(err("mytool", "rules_glob = \"rules/**/*.md\"").contains("<dir>/*<suffix>"));
+        assert!(err("mytool", "").contains("rules_glob or single_file"));
+        assert!(err("mytool", "single_file = \"X.md\"\nfrontmatter = \"cursor\"").contains("needs rules_glob"));
+        assert!(toml::from_str::<FormatDef>("rule_glob = \"x/*.md\"").is_err());
+
+        let fmt = define("mytool", def("rules_glob = \"./.mytool/rules/*.rule.md\"")).unwrap();
+        assert_eq!(fmt.rule_dirs, &[RuleDir { path: ".mytool/rules", suffix: ".rule.md", recursive: false }]);
+        assert_eq!(fmt.description, "mytool (./.mytool/rules/*.rule.md)");
+    }
+
+    #[test]
+    fn cursor_frontmatter_round_trips() {
+        let fmt: &'static CustomFormat =
+            Box::leak(Box::new(define("mytool", def("rules_glob = \".mytool/rules/*.md\"\nfrontmatter = \"cursor\"")).unwrap()));
+        let dir = tempfile::tempdir().unwrap();
+        let rules = vec![
+            Rule { name: Some("style".into()), content: "Use tabs.".into(), ..Default::default() },
+            Rule {
+                name: Some("ts".into()),
+                activation: Activation::Glob,
+                globs: Some(vec!["*.ts".into()]),
+                description: Some("TypeScript".into()),
+                content: "Prefer interfaces.".into(),
+                ..Default::default()
+            },
+        ];
+        GenericWriter(fmt).write(&rules, dir.path()).unwrap();
+        let ts = fs::read_to_string(dir.path().join(".mytool/rules/ts.md")).unwrap();
+        assert_eq!(ts, "---\ndescription: TypeScript\nglobs: *.ts\n---\n\nPrefer interfaces.\n");
+
+        let parsed = GenericParser(fmt).parse(dir.path()).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!((&parsed[0].activation, parsed[0].name.as_deref()), (&Activation::Always, Some("style")));
+        assert_eq!(parsed[1].globs, rules[1].globs);
+    }
+}
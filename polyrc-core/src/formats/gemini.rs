@@ -0,0 +1,59 @@
+use std::path::Path;
+use crate::error::Result;
+use crate::formats::single_file;
+use crate::ir::{Rule, Scope};
+use crate::parser::Parser;
+use crate::writer::{WriteOptions, Writer};
+
+/// Name the parser gives the whole of `GEMINI.md`.
+pub const GEMINI_RULE_NAME: &str = "gemini";
+
+pub struct GeminiParser;
+
+/// `overwrite` replaces a user-scope `~/.gemini/GEMINI.md` wholesale instead of
+/// merging into its `## ` sections. Project files are always rewritten.
+#[derive(Default)]
+pub struct GeminiWriter {
+    pub overwrite: bool,
+}
+
+impl Parser for GeminiParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        Ok(single_file::parse(&path.join("GEMINI.md"), GEMINI_RULE_NAME)?.into_iter().collect())
+    }
+}
+
+impl Writer for GeminiWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        self.write_with(rules, target, &WriteOptions::default())
+    }
+
+    fn write_with(&self, rules: &[Rule], target: &Path, options: &WriteOptions) -> Result<()> {
+        if rules.is_empty() {
+            return Ok(());
+        }
+        let file = options.output_file.clone().unwrap_or_else(|| target.join("GEMINI.md"));
+        if rules.iter().any(|r| r.scope == Scope::User) {
+            return crate::formats::sections::write_single_file(&file, rules, GEMINI_RULE_NAME, self.overwrite);
+        }
+        single_file::write(&file, rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn output_file_is_the_exact_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let rule = Rule { name: Some("style".into()), content: "Use tabs.".into(), ..Default::default() };
+        let file = dir.path().join("docs").join("AI_GUIDELINES.md");
+        let options = WriteOptions { output_file: Some(file.clone()) };
+        GeminiWriter::default().write_with(&[rule], dir.path(), &options).unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "Use tabs.\n");
+        assert!(!dir.path().join("GEMINI.md").exists());
+    }
+}
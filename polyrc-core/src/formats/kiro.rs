@@ -0,0 +1,182 @@
+//! Kiro steering files in `.kiro/steering/*.md`.
+//!
+//! The `inclusion` frontmatter field is the activation: `always` (also the
+//! default), `fileMatch` with a `fileMatchPattern`, or `manual` (included when
+//! referenced with `#name` in chat). All three are written back, so
+//! activations survive a round trip.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+use crate::error::{PolyrcError, Result};
+use crate::formats::copilot::split_frontmatter;
+use crate::ir::{split_order_prefix, unique_output_stems, Activation, Rule, Scope};
+use crate::parser::{read_source_file, Parser};
+use crate::writer::Writer;
+
+/// Steering directory, relative to the project root.
+pub const STEERING_DIR: &str = ".kiro/steering";
+
+pub struct KiroParser;
+pub struct KiroWriter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Inclusion {
+    Always,
+    FileMatch,
+    Manual,
+}
+
+/// `fileMatchPattern` is one glob, or a list of them.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum Patterns {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SteeringFrontmatter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inclusion: Option<Inclusion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_match_pattern: Option<Patterns>,
+}
+
+fn steering_dir(path: &Path) -> PathBuf {
+    path.join(".kiro").join("steering")
+}
+
+impl Parser for KiroParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        let dir = steering_dir(path);
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut rules = vec![];
+        for entry in WalkDir::new(&dir).min_depth(1).max_depth(1).sort_by_file_name() {
+            let entry = entry.map_err(|e| PolyrcError::Io {
+                path: dir.clone(),
+                source: e.into(),
+            })?;
+            let p = entry.path();
+            if p.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            let Some(raw) = read_source_file(p)? else { continue };
+            let (fm_str, body) = split_frontmatter(&raw);
+            let fm: SteeringFrontmatter = fm_str
+                .map(|s| {
+                    serde_yml::from_str::<Option<SteeringFrontmatter>>(s).map_err(|e| PolyrcError::YamlParse {
+                        path: p.to_path_buf(),
+                        source: e,
+                    })
+                })
+                .transpose()?
+                .flatten()
+                .unwrap_or_default();
+            let globs: Option<Vec<String>> = match fm.file_match_pattern {
+                Some(Patterns::One(g)) => Some(vec![g]),
+                Some(Patterns::Many(g)) => Some(g),
+                None => None,
+            }
+            .map(|g| g.into_iter().map(|g| g.trim().to_string()).filter(|g| !g.is_empty()).collect::<Vec<_>>())
+            .filter(|g| !g.is_empty());
+            let activation = match fm.inclusion {
+                Some(Inclusion::FileMatch) if globs.is_some() => Activation::Glob,
+                Some(Inclusion::Manual) => Activation::OnDemand,
+                // A fileMatch without a pattern matches nothing; keep it rather than drop it
+                _ => Activation::Always,
+            };
+            let (order, stem) = split_order_prefix(p.file_stem().and_then(|s| s.to_str()).unwrap_or("rule"));
+            rules.push(Rule {
+                scope: Scope::Project,
+                globs: globs.filter(|_| activation == Activation::Glob),
+                activation,
+                name: Some(stem.to_string()),
+                content: body.trim().to_string(),
+                order,
+                ..Default::default()
+            });
+        }
+        Ok(rules)
+    }
+}
+
+impl Writer for KiroWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        let dir = steering_dir(target);
+        fs::create_dir_all(&dir).map_err(|e| PolyrcError::Io {
+            path: dir.clone(),
+            source: e,
+        })?;
+        for (rule, stem) in rules.iter().zip(unique_output_stems(rules)) {
+            let file = dir.join(format!("{stem}.md"));
+            let fm_str = serde_yml::to_string(&frontmatter(rule))
+                .map_err(|e| PolyrcError::YamlParse { path: file.clone(), source: e })?;
+            let content = format!("---\n{}---\n\n{}\n", fm_str, rule.content.trim_end());
+            fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        }
+        Ok(())
+    }
+}
+
+fn frontmatter(rule: &Rule) -> SteeringFrontmatter {
+    let globs = rule.globs.clone().unwrap_or_default();
+    match rule.activation {
+        Activation::Glob if !globs.is_empty() => SteeringFrontmatter {
+            inclusion: Some(Inclusion::FileMatch),
+            file_match_pattern: Some(match <[String; 1]>::try_from(globs) {
+                Ok([one]) => Patterns::One(one),
+                Err(many) => Patterns::Many(many),
+            }),
+        },
+        Activation::OnDemand => SteeringFrontmatter { inclusion: Some(Inclusion::Manual), file_match_pattern: None },
+        _ => SteeringFrontmatter { inclusion: Some(Inclusion::Always), file_match_pattern: None },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inclusion_modes_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let rule = |name: &str, activation: Activation, globs: Option<&[&str]>| Rule {
+            name: Some(name.into()),
+            content: format!("{name} rule"),
+            activation,
+            globs: globs.map(|g| g.iter().map(|s| s.to_string()).collect()),
+            ..Default::default()
+        };
+        let rules = vec![
+            rule("api", Activation::Glob, Some(&["app/api/**/*.ts"])),
+            rule("review", Activation::OnDemand, None),
+            rule("tech", Activation::Always, None),
+            rule("ui", Activation::Glob, Some(&["*.tsx", "*.css"])),
+        ];
+        KiroWriter.write(&rules, dir.path()).unwrap();
+        let steering = steering_dir(dir.path());
+        let api = fs::read_to_string(steering.join("api.md")).unwrap();
+        assert_eq!(api, "---\ninclusion: fileMatch\nfileMatchPattern: app/api/**/*.ts\n---\n\napi rule\n");
+        assert!(fs::read_to_string(steering.join("review.md")).unwrap().starts_with("---\ninclusion: manual\n---\n"));
+
+        let parsed = KiroParser.parse(dir.path()).unwrap();
+        assert_eq!(parsed, rules.into_iter().map(|r| Rule { scope: Scope::Project, ..r }).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn missing_frontmatter_is_always_on() {
+        let dir = tempfile::tempdir().unwrap();
+        let steering = steering_dir(dir.path());
+        fs::create_dir_all(&steering).unwrap();
+        fs::write(steering.join("product.md"), "# Product\n\nA todo app.\n").unwrap();
+        fs::write(steering.join("odd.md"), "---\ninclusion: fileMatch\n---\n\nNo pattern.\n").unwrap();
+        let parsed = KiroParser.parse(dir.path()).unwrap();
+        assert!(parsed.iter().all(|r| r.activation == Activation::Always && r.globs.is_none()));
+    }
+}
@@ -0,0 +1,703 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use crate::error::{PolyrcError, Result};
+use crate::ir::{Activation, Rule};
+use crate::parser::Parser;
+use crate::writer::Writer;
+use capabilities::Capabilities;
+use options::{FormatOptions, OptionKind, OptionSpec};
+
+pub mod agents_md;
+pub mod aider;
+pub mod amazonq;
+pub mod antigravity;
+pub mod capabilities;
+pub mod claude;
+pub mod copilot;
+pub mod cursor;
+pub mod custom;
+pub mod gemini;
+pub mod kiro;
+pub mod openhands;
+pub mod options;
+pub mod plugin;
+pub mod qodo;
+pub mod sections;
+mod single_file;
+mod trigger;
+pub mod warp;
+pub mod windsurf;
+pub mod zed;
+
+pub use copilot::split_frontmatter;
+
+/// Canonical format identifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Format {
+    Cursor,
+    Windsurf,
+    Copilot,
+    Claude,
+    Gemini,
+    Antigravity,
+    Aider,
+    AgentsMd,
+    AmazonQ,
+    Zed,
+    Warp,
+    OpenHands,
+    Kiro,
+    Qodo,
+    /// A tool defined in `~/polyrc/formats.toml`. See [`custom`].
+    Custom(&'static custom::CustomFormat),
+}
+
+impl std::str::FromStr for Format {
+    type Err = PolyrcError;
+
+    /// Look up a format by name or alias, case-insensitively.
+    fn from_str(s: &str) -> Result<Self> {
+        let wanted = s.to_lowercase();
+        if let Some(fmt) = Self::all()
+            .iter()
+            .find(|f| f.name() == wanted || f.aliases().contains(&wanted.as_str()))
+        {
+            return Ok(fmt.clone());
+        }
+        let known = Self::all()
+            .iter()
+            .flat_map(|f| std::iter::once(f.name()).chain(f.aliases().iter().copied()));
+        Err(PolyrcError::UnknownFormat {
+            suggestions: crate::suggest::suggest(&wanted, known),
+            name: wanted,
+        })
+    }
+}
+
+impl Format {
+    /// Alternative names accepted by `from_str` (and the CLI) for this format.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Self::Cursor => &[],
+            Self::Windsurf => &[],
+            Self::Copilot => &["github-copilot", "ghcopilot"],
+            Self::Claude => &["claude-code"],
+            Self::Gemini => &["gemini-cli"],
+            Self::Antigravity => &["google-antigravity"],
+            Self::Aider => &["aider-chat"],
+            Self::AgentsMd => &["agents", "agents.md"],
+            Self::AmazonQ => &["amazonq", "q-developer"],
+            Self::Zed => &[],
+            Self::Warp => &[],
+            Self::OpenHands => &["openhands-microagents"],
+            Self::Kiro => &[],
+            Self::Qodo => &["codium", "qodo-gen"],
+            Self::Custom(_) => &[],
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cursor => "cursor",
+            Self::Windsurf => "windsurf",
+            Self::Copilot => "copilot",
+            Self::Claude => "claude",
+            Self::Gemini => "gemini",
+            Self::Antigravity => "antigravity",
+            Self::Aider => "aider",
+            Self::AgentsMd => "agents-md",
+            Self::AmazonQ => "amazon-q",
+            Self::Zed => "zed",
+            Self::Warp => "warp",
+            Self::OpenHands => "openhands",
+            Self::Kiro => "kiro",
+            Self::Qodo => "qodo",
+            Self::Custom(c) => c.name,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Cursor      => "Cursor (.cursor/rules/*.mdc, YAML frontmatter; legacy .cursorrules)",
+            Self::Windsurf    => "Windsurf (.windsurf/rules/*.md, trigger frontmatter)",
+            Self::Copilot     => "GitHub Copilot (.github/copilot-instructions.md + .github/instructions/ + .github/prompts/)",
+            Self::Claude      => "Claude Code (CLAUDE.md + .claude/rules/*.md)",
+            Self::Gemini      => "Gemini CLI (GEMINI.md)",
+            Self::Antigravity => "Google Antigravity (.agent/rules/*.md)",
+            Self::Aider       => "Aider (CONVENTIONS.md)",
+            Self::AgentsMd    => "AGENTS.md (Codex, Zed, …; nested per directory)",
+            Self::AmazonQ     => "Amazon Q Developer (.amazonq/rules/*.md)",
+            Self::Zed         => "Zed (.rules)",
+            Self::Warp        => "Warp (WARP.md)",
+            Self::OpenHands   => "OpenHands (.openhands/microagents/*.md, triggers frontmatter)",
+            Self::Kiro        => "Kiro (.kiro/steering/*.md, inclusion frontmatter)",
+            Self::Qodo        => "Qodo Gen, formerly Codium (best_practices.md)",
+            Self::Custom(c)   => c.description,
+        }
+    }
+
+    /// Activations the writer can express. Rules with any other activation are
+    /// loaded as always-on by the tool (see [`crate::ops::apply_activation_policy`]).
+    pub fn supported_activations(&self) -> &'static [Activation] {
+        self.capabilities().activations
+    }
+
+    /// Whether the writer keeps `rule`'s activation. AGENTS.md has no glob
+    /// rules, but a glob rule for a whole directory is written as that
+    /// directory's own AGENTS.md.
+    pub fn expresses(&self, rule: &Rule) -> bool {
+        self.supported_activations().contains(&rule.activation)
+            || (*self == Self::AgentsMd && crate::monorepo::package_of(rule).is_some())
+    }
+
+    /// Whether the writer keeps `rule`'s description. OpenHands has none, but
+    /// an on-demand microagent's triggers are kept there.
+    pub fn keeps_description(&self, rule: &Rule) -> bool {
+        self.capabilities().descriptions || (*self == Self::OpenHands && rule.activation == Activation::OnDemand)
+    }
+
+    /// What this format's files can carry. See [`capabilities`].
+    pub fn capabilities(&self) -> Capabilities {
+        let activations: &'static [Activation] = match self {
+            Self::Cursor      => &[Activation::Always, Activation::Glob, Activation::OnDemand, Activation::AiDecides],
+            Self::Windsurf    => &[Activation::Always, Activation::Glob, Activation::OnDemand, Activation::AiDecides],
+            Self::Copilot     => &[Activation::Always, Activation::Glob, Activation::OnDemand, Activation::AiDecides],
+            Self::Claude      => &[Activation::Always, Activation::OnDemand, Activation::AiDecides],
+            Self::Gemini      => &[Activation::Always],
+            Self::Antigravity => &[Activation::Always, Activation::Glob, Activation::OnDemand, Activation::AiDecides],
+            Self::Aider       => &[Activation::Always],
+            Self::AgentsMd    => &[Activation::Always],
+            Self::AmazonQ     => &[Activation::Always],
+            Self::Zed         => &[Activation::Always],
+            Self::Warp        => &[Activation::Always],
+            Self::OpenHands   => &[Activation::Always, Activation::OnDemand],
+            Self::Kiro        => &[Activation::Always, Activation::Glob, Activation::OnDemand],
+            Self::Qodo        => &[Activation::Always],
+            Self::Custom(c)   => c.activations(),
+        };
+        let (total_char_limit, file_char_limit) = match self {
+            Self::Windsurf => (Some(windsurf::TOTAL_CHAR_LIMIT), Some(windsurf::FILE_CHAR_LIMIT)),
+            _ => (None, None),
+        };
+        Capabilities {
+            user_scope: self.user_input_dir().is_some(),
+            project_scope: true,
+            activations,
+            descriptions: match self {
+                Self::Custom(c) => c.keeps_descriptions(),
+                _ => matches!(self, Self::Cursor | Self::Copilot | Self::Windsurf | Self::Antigravity),
+            },
+            multi_file: !self.rule_dirs().is_empty(),
+            total_char_limit,
+            file_char_limit,
+        }
+    }
+
+    /// Single files the format collects rules into, as paths relative to the
+    /// output directory, each with the name its parser gives the whole file.
+    pub fn single_files(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Claude   => &[("CLAUDE.md", claude::CLAUDE_RULE_NAME)],
+            Self::Gemini   => &[("GEMINI.md", gemini::GEMINI_RULE_NAME)],
+            Self::Aider    => &[(aider::CONVENTIONS_FILE, aider::AIDER_RULE_NAME)],
+            Self::AgentsMd => &[(agents_md::AGENTS_FILE, agents_md::AGENTS_RULE_NAME)],
+            Self::Zed      => &[(zed::RULES_FILE, zed::ZED_RULE_NAME)],
+            Self::Warp     => &[(warp::WARP_FILE, warp::WARP_RULE_NAME)],
+            Self::OpenHands => &[(openhands::REPO_FILE, openhands::REPO_RULE_NAME)],
+            Self::Qodo     => &[(qodo::BEST_PRACTICES_FILE, qodo::QODO_RULE_NAME)],
+            Self::Copilot  => &[(".github/copilot-instructions.md", copilot::MAIN_RULE_NAME)],
+            Self::Cursor   => &[(cursor::LEGACY_FILE, cursor::LEGACY_RULE_NAME)],
+            Self::Windsurf => &[(windsurf::GLOBAL_RULES_FILE, windsurf::GLOBAL_RULES_NAME)],
+            Self::Custom(c) => c.single_files,
+            Self::Antigravity | Self::AmazonQ | Self::Kiro => &[],
+        }
+    }
+
+    /// Whether user-scope rules are merged into the sections of an existing
+    /// single file rather than replacing it.
+    pub fn merges_user_rules(&self) -> bool {
+        matches!(self, Self::Gemini | Self::Windsurf)
+    }
+
+    /// Whether a plugin command writes this format's files (see [`plugin`]),
+    /// so they can't be rendered ahead of time.
+    pub fn is_plugin(&self) -> bool {
+        matches!(self, Self::Custom(c) if c.command.is_some())
+    }
+
+    /// Options this format reads from `[formats.<name>]` / `--format-option`.
+    pub fn options(&self) -> &'static [OptionSpec] {
+        match self {
+            Self::Copilot => &[
+                OptionSpec {
+                    key: copilot::SPLIT_ALWAYS,
+                    kind: OptionKind::Bool,
+                    description: "write each always-on rule to its own .instructions.md with applyTo \"**\"",
+                },
+                options::BUDGET_SPEC,
+            ],
+            Self::Cursor => &[
+                OptionSpec {
+                    key: cursor::LEGACY_FILE_OPTION,
+                    kind: OptionKind::Bool,
+                    description: "write always-on rules to the legacy .cursorrules file",
+                },
+                options::BUDGET_SPEC,
+            ],
+            Self::Claude => &[
+                OptionSpec {
+                    key: claude::STRUCTURED_PERMISSIONS,
+                    kind: OptionKind::Bool,
+                    description: "store settings.json permissions as a separate, normalized rule",
+                },
+                OptionSpec {
+                    key: claude::RESOLVE_IMPORTS,
+                    kind: OptionKind::Bool,
+                    description: "read @path import lines in CLAUDE.md as rules of their own (default true)",
+                },
+                OptionSpec {
+                    key: claude::OVERWRITE_SETTINGS,
+                    kind: OptionKind::Bool,
+                    description: "replace .claude/settings.json on write instead of merging into it",
+                },
+                options::BUDGET_SPEC,
+            ],
+            _ => &[options::BUDGET_SPEC],
+        }
+    }
+
+    /// Parser configured with the process-wide options (see [`options::set_configured`]).
+    pub fn parser(&self) -> Box<dyn Parser> {
+        self.parser_with(&options::configured(self))
+    }
+
+    pub fn parser_with(&self, options: &FormatOptions) -> Box<dyn Parser> {
+        match self {
+            Self::Cursor      => Box::new(cursor::CursorParser),
+            Self::Windsurf    => Box::new(windsurf::WindsurfParser),
+            Self::Copilot     => Box::new(copilot::CopilotParser),
+            Self::Claude      => Box::new(claude::ClaudeParser::new(options)),
+            Self::Gemini      => Box::new(gemini::GeminiParser),
+            Self::Antigravity => Box::new(antigravity::AntigravityParser),
+            Self::Aider       => Box::new(aider::AiderParser),
+            Self::AgentsMd    => Box::new(agents_md::AgentsMdParser),
+            Self::AmazonQ     => Box::new(amazonq::AmazonQParser),
+            Self::Zed         => Box::new(zed::ZedParser),
+            Self::Warp        => Box::new(warp::WarpParser),
+            Self::OpenHands   => Box::new(openhands::OpenHandsParser),
+            Self::Kiro        => Box::new(kiro::KiroParser),
+            Self::Qodo        => Box::new(qodo::QodoParser),
+            Self::Custom(c)   => c.parser(),
+        }
+    }
+
+    /// Writer configured with the process-wide options (see [`options::set_configured`]).
+    pub fn writer(&self) -> Box<dyn Writer> {
+        self.writer_with(&options::configured(self))
+    }
+
+    pub fn writer_with(&self, options: &FormatOptions) -> Box<dyn Writer> {
+        match self {
+            Self::Cursor      => Box::new(cursor::CursorWriter::new(options)),
+            Self::Windsurf    => Box::new(windsurf::WindsurfWriter::default()),
+            Self::Copilot     => Box::new(copilot::CopilotWriter::new(options)),
+            Self::Claude      => Box::new(claude::ClaudeWriter::new(options)),
+            Self::Gemini      => Box::new(gemini::GeminiWriter::default()),
+            Self::Antigravity => Box::new(antigravity::AntigravityWriter),
+            Self::Aider       => Box::new(aider::AiderWriter),
+            Self::AgentsMd    => Box::new(agents_md::AgentsMdWriter),
+            Self::AmazonQ     => Box::new(amazonq::AmazonQWriter),
+            Self::Zed         => Box::new(zed::ZedWriter),
+            Self::Warp        => Box::new(warp::WarpWriter),
+            Self::OpenHands   => Box::new(openhands::OpenHandsWriter),
+            Self::Kiro        => Box::new(kiro::KiroWriter),
+            Self::Qodo        => Box::new(qodo::QodoWriter),
+            Self::Custom(c)   => c.writer(),
+        }
+    }
+
+    /// Like [`writer`](Self::writer), but single-file user configs
+    /// (`global_rules.md`, `~/.gemini/GEMINI.md`) are replaced instead of merged into.
+    pub fn overwriting_writer(&self) -> Box<dyn Writer> {
+        match self {
+            Self::Windsurf => Box::new(windsurf::WindsurfWriter { overwrite: true }),
+            Self::Gemini   => Box::new(gemini::GeminiWriter { overwrite: true }),
+            other          => other.writer(),
+        }
+    }
+
+    /// Project-relative paths (`/`-separated) this format reads and writes.
+    pub fn project_paths(&self) -> &'static [&'static str] {
+        match self {
+            Self::Cursor      => &[".cursor/rules", cursor::LEGACY_FILE],
+            Self::Windsurf    => &[".windsurf/rules"],
+            Self::Copilot     => &[".github/copilot-instructions.md", ".github/instructions", copilot::PROMPTS_DIR],
+            Self::Claude      => &["CLAUDE.md", ".claude"],
+            Self::Gemini      => &["GEMINI.md"],
+            Self::Antigravity => &[".agent/rules"],
+            Self::Aider       => &[aider::CONVENTIONS_FILE],
+            Self::AgentsMd    => &[agents_md::AGENTS_FILE],
+            Self::AmazonQ     => &[amazonq::RULES_DIR],
+            Self::Zed         => &[zed::RULES_FILE],
+            Self::Warp        => &[warp::WARP_FILE],
+            Self::OpenHands   => &[openhands::MICROAGENTS_DIR],
+            Self::Kiro        => &[kiro::STEERING_DIR],
+            Self::Qodo        => &[qodo::BEST_PRACTICES_FILE],
+            Self::Custom(c)   => c.project_paths,
+        }
+    }
+
+    /// Project directories this format owns, with the files its parser reads
+    /// from each; `polyrc status` reports any other file there as unmanaged.
+    /// Directories shared with other tools (`.github`) and those whose
+    /// entries carry support files (`.claude/skills`) are not listed.
+    pub fn rule_dirs(&self) -> &'static [RuleDir] {
+        match self {
+            Self::Cursor      => &[RuleDir { path: ".cursor/rules", suffix: ".mdc", recursive: true }],
+            Self::Windsurf    => &[RuleDir { path: ".windsurf/rules", suffix: ".md", recursive: true }],
+            Self::Copilot     => &[
+                RuleDir { path: ".github/instructions", suffix: ".instructions.md", recursive: false },
+                RuleDir { path: copilot::PROMPTS_DIR, suffix: copilot::PROMPT_SUFFIX, recursive: false },
+            ],
+            Self::Claude      => &[
+                RuleDir { path: ".claude/rules", suffix: ".md", recursive: true },
+                RuleDir { path: ".claude/commands", suffix: ".md", recursive: true },
+                RuleDir { path: ".claude/agents", suffix: ".md", recursive: true },
+            ],
+            Self::Gemini      => &[],
+            Self::Antigravity => &[RuleDir { path: ".agent/rules", suffix: ".md", recursive: false }],
+            Self::Aider       => &[],
+            Self::AgentsMd    => &[],
+            Self::AmazonQ     => &[RuleDir { path: amazonq::RULES_DIR, suffix: ".md", recursive: false }],
+            Self::Zed         => &[],
+            Self::Warp        => &[],
+            Self::OpenHands   => &[RuleDir { path: openhands::MICROAGENTS_DIR, suffix: ".md", recursive: false }],
+            Self::Kiro        => &[RuleDir { path: kiro::STEERING_DIR, suffix: ".md", recursive: false }],
+            Self::Qodo        => &[],
+            Self::Custom(c)   => c.rule_dirs,
+        }
+    }
+
+    /// Returns the root directory to pass as `--input` / `--output` when operating in user scope.
+    ///
+    /// `None` means the format has no locally-parseable user-level config
+    /// (e.g. Cursor embeds user rules in a JSON settings file; Copilot is web-UI only).
+    /// See [`resolve_user_dir`](Self::resolve_user_dir) for the precedence.
+    pub fn user_input_dir(&self) -> Option<PathBuf> {
+        self.resolve_user_dir().map(|(dir, _)| dir)
+    }
+
+    /// [`user_input_dir`](Self::user_input_dir) and where it came from:
+    /// `POLYRC_<FORMAT>_USER_DIR`, then `[user_dirs]` in config.toml (see
+    /// [`set_user_dirs`]), then the built-in location.
+    pub fn resolve_user_dir(&self) -> Option<(PathBuf, UserDirSource)> {
+        let configured = USER_DIRS.read().expect("user dirs lock").get(self.name()).cloned();
+        self.resolve_user_dir_with(|name| std::env::var(name).ok(), configured)
+    }
+
+    fn resolve_user_dir_with(
+        &self,
+        env: impl Fn(&str) -> Option<String>,
+        configured: Option<PathBuf>,
+    ) -> Option<(PathBuf, UserDirSource)> {
+        let default = self.default_user_dir_with(&env)?;
+        let var = self.user_dir_env_var();
+        if let Some(dir) = env(&var).filter(|d| !d.is_empty()) {
+            return Some((crate::config::expand_path(&dir), UserDirSource::Env(var)));
+        }
+        if let Some(dir) = configured {
+            return Some((dir, UserDirSource::Config));
+        }
+        Some(default)
+    }
+
+    /// Environment variable overriding this format's user config dir.
+    pub fn user_dir_env_var(&self) -> String {
+        format!("POLYRC_{}_USER_DIR", self.name().to_uppercase())
+    }
+
+    /// Where the tool itself keeps user config, ignoring polyrc's overrides.
+    pub fn default_user_dir(&self) -> Option<(PathBuf, UserDirSource)> {
+        self.default_user_dir_with(|name| std::env::var(name).ok())
+    }
+
+    fn default_user_dir_with(&self, env: impl Fn(&str) -> Option<String>) -> Option<(PathBuf, UserDirSource)> {
+        let home = crate::config::home_dir();
+        let builtin = |dir: PathBuf| Some((dir, UserDirSource::Default));
+        match self {
+            Self::Claude => match env("CLAUDE_CONFIG_DIR") {
+                Some(dir) => Some((PathBuf::from(dir), UserDirSource::ClaudeConfigDir)),
+                None => builtin(home.join(".claude")),
+            },
+            // Parser detects GEMINI.md directly in the dir → pass ~/.gemini
+            Self::Gemini => builtin(home.join(".gemini")),
+            // Parser detects rules/ directly in the dir → pass ~/.gemini/antigravity
+            Self::Antigravity => builtin(home.join(".gemini").join("antigravity")),
+            // Parser detects global_rules.md directly in the dir → pass the memories dir
+            Self::Windsurf => builtin(home.join(".codeium").join("windsurf").join("memories")),
+            // User rules embedded in Cursor's settings.json — not parseable as plain files
+            Self::Cursor => None,
+            // User instructions live in the GitHub web UI, no local file
+            Self::Copilot => None,
+            // ~/.aider.conf.yml only points at conventions files, it holds none
+            Self::Aider => None,
+            // A project convention; Codex's ~/.codex/AGENTS.md is its own
+            Self::AgentsMd => None,
+            // Rules are per project only
+            Self::AmazonQ => None,
+            // User rules live in Zed's Rules Library, inside the app
+            Self::Zed => None,
+            // Global rules are kept in Warp Drive, not on disk
+            Self::Warp => None,
+            // Only project microagents are supported
+            Self::OpenHands => None,
+            // Only workspace steering is supported
+            Self::Kiro => None,
+            // Best practices are per repository
+            Self::Qodo => None,
+            Self::Custom(c) => c.user_dir.clone().map(|dir| (dir, UserDirSource::FormatsFile)),
+        }
+    }
+
+    /// Every format: the built-in ones, then any registered with
+    /// [`custom::register`].
+    pub fn all() -> &'static [Self] {
+        ALL.read().expect("formats lock").unwrap_or(Self::built_in())
+    }
+
+    /// The formats polyrc ships with.
+    pub fn built_in() -> &'static [Self] {
+        &[
+            Self::Cursor,
+            Self::Windsurf,
+            Self::Copilot,
+            Self::Claude,
+            Self::Gemini,
+            Self::Antigravity,
+            Self::Aider,
+            Self::AgentsMd,
+            Self::AmazonQ,
+            Self::Zed,
+            Self::Warp,
+            Self::OpenHands,
+            Self::Kiro,
+            Self::Qodo,
+        ]
+    }
+}
+
+/// Where a format's user config dir came from. See [`Format::resolve_user_dir`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserDirSource {
+    /// `POLYRC_<FORMAT>_USER_DIR`, named here.
+    Env(String),
+    /// `[user_dirs]` in config.toml.
+    Config,
+    /// Claude Code's own `CLAUDE_CONFIG_DIR`.
+    ClaudeConfigDir,
+    /// `user_dir` of a custom format in formats.toml.
+    FormatsFile,
+    Default,
+}
+
+impl std::fmt::Display for UserDirSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Env(var) => write!(f, "${var}"),
+            Self::Config => write!(f, "[user_dirs] in config.toml"),
+            Self::ClaudeConfigDir => write!(f, "$CLAUDE_CONFIG_DIR"),
+            Self::FormatsFile => write!(f, "user_dir in formats.toml"),
+            Self::Default => write!(f, "built-in default"),
+        }
+    }
+}
+
+/// Built-in plus custom formats, once [`custom::register`] has run.
+static ALL: RwLock<Option<&'static [Format]>> = RwLock::new(None);
+
+static USER_DIRS: RwLock<BTreeMap<String, PathBuf>> = RwLock::new(BTreeMap::new());
+
+/// Set the process-wide `[user_dirs]` overrides, keyed by canonical format
+/// name (see [`crate::config::Config::user_dirs`]).
+pub fn set_user_dirs(dirs: BTreeMap<String, PathBuf>) {
+    *USER_DIRS.write().expect("user dirs lock") = dirs;
+}
+
+/// A format as `polyrc supported-formats --json` describes it to tooling.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FormatInfo {
+    pub name: &'static str,
+    /// Every other name `from_str` and the CLI accept.
+    pub aliases: &'static [&'static str],
+    pub description: &'static str,
+    /// Where user-scope rules live on this machine, if the format has any.
+    pub user_input_dir: Option<PathBuf>,
+    /// Project-relative paths that mark a project as using this format.
+    pub project_paths: &'static [&'static str],
+    pub capabilities: Capabilities,
+    /// Defined in formats.toml rather than built in.
+    pub custom: bool,
+}
+
+impl From<&Format> for FormatInfo {
+    fn from(fmt: &Format) -> Self {
+        Self {
+            name: fmt.name(),
+            aliases: fmt.aliases(),
+            description: fmt.description(),
+            user_input_dir: fmt.user_input_dir(),
+            project_paths: fmt.project_paths(),
+            capabilities: fmt.capabilities(),
+            custom: matches!(fmt, Format::Custom(_)),
+        }
+    }
+}
+
+/// A directory a format owns. See [`Format::rule_dirs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleDir {
+    /// Project-relative, `/`-separated.
+    pub path: &'static str,
+    /// File name ending the parser picks up.
+    pub suffix: &'static str,
+    /// Whether the parser descends into subdirectories.
+    pub recursive: bool,
+}
+
+impl RuleDir {
+    /// Whether the parser reads the file at `rel`, relative to this directory.
+    pub fn claims(&self, rel: &Path) -> bool {
+        let name = rel.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        name.ends_with(self.suffix) && (self.recursive || rel.components().count() == 1)
+    }
+}
+
+/// Create the directory `file` will be written into.
+pub(crate) fn create_parent_dir(file: &Path) -> Result<()> {
+    match file.parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(dir) => std::fs::create_dir_all(dir).map_err(|e| PolyrcError::Io { path: dir.to_path_buf(), source: e }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn from_str_accepts_names_and_aliases() {
+        assert_eq!(Format::from_str("Claude-Code").unwrap(), Format::Claude);
+        assert_eq!(Format::from_str("ghcopilot").unwrap(), Format::Copilot);
+    }
+
+    #[test]
+    fn from_str_suggests_close_formats() {
+        match Format::from_str("clade") {
+            Err(PolyrcError::UnknownFormat { suggestions, .. }) => {
+                assert_eq!(suggestions, vec!["claude"]);
+            }
+            other => panic!("expected UnknownFormat, got {other:?}"),
+        }
+        let msg = Format::from_str("gemni-cli").unwrap_err().to_string();
+        assert!(msg.contains("Did you mean 'gemini-cli'?"), "{msg}");
+    }
+
+    #[test]
+    fn user_dir_precedence() {
+        let home = crate::config::home_dir();
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        let configured = Some(PathBuf::from("/cfg/claude"));
+
+        let (dir, source) = Format::Claude.resolve_user_dir_with(env(&[]), None).unwrap();
+        assert_eq!((dir, source), (home.join(".claude"), UserDirSource::Default));
+        let claude_env = env(&[("CLAUDE_CONFIG_DIR", "/xdg/claude")]);
+        let (dir, source) = Format::Claude.resolve_user_dir_with(claude_env, None).unwrap();
+        assert_eq!((dir, source), (PathBuf::from("/xdg/claude"), UserDirSource::ClaudeConfigDir));
+        let (dir, source) = Format::Claude.resolve_user_dir_with(claude_env, configured.clone()).unwrap();
+        assert_eq!((dir, source), (PathBuf::from("/cfg/claude"), UserDirSource::Config));
+        let both = env(&[("CLAUDE_CONFIG_DIR", "/xdg/claude"), ("POLYRC_CLAUDE_USER_DIR", "/env/claude")]);
+        let (dir, source) = Format::Claude.resolve_user_dir_with(both, configured).unwrap();
+        assert_eq!((dir, source), (PathBuf::from("/env/claude"), UserDirSource::Env("POLYRC_CLAUDE_USER_DIR".into())));
+
+        // An empty variable counts as unset; formats without a user dir never get one
+        let empty = env(&[("POLYRC_WINDSURF_USER_DIR", "")]);
+        assert_eq!(Format::Windsurf.resolve_user_dir_with(empty, None).unwrap().1, UserDirSource::Default);
+        let cursor = env(&[("POLYRC_CURSOR_USER_DIR", "/x")]);
+        assert_eq!(Format::Cursor.resolve_user_dir_with(cursor, Some("/y".into())), None);
+    }
+
+    #[test]
+    fn multi_file_writers_keep_colliding_rules_apart() {
+        use crate::ir::{Activation, Rule};
+        let rule = |name: &str, content: &str| Rule {
+            name: Some(name.into()),
+            content: content.into(),
+            activation: Activation::Glob,
+            globs: Some(vec!["*.rs".into()]),
+            ..Default::default()
+        };
+        let rules = vec![rule("api-design", "second"), rule("API Design", "first")];
+
+        for fmt in [Format::Cursor, Format::Claude, Format::Windsurf, Format::Copilot, Format::Antigravity] {
+            let dir = tempfile::tempdir().unwrap();
+            fmt.writer().write(&rules, dir.path()).unwrap();
+            let mut contents: Vec<String> =
+                fmt.parser().parse(dir.path()).unwrap().into_iter().map(|r| r.content.trim().to_string()).collect();
+            contents.sort();
+            assert_eq!(contents, vec!["first", "second"], "{}", fmt.name());
+        }
+    }
+
+    #[test]
+    fn order_prefixes_survive_claude_store_windsurf() {
+        let src = tempfile::tempdir().unwrap();
+        let rules_dir = src.path().join(".claude").join("rules");
+        std::fs::create_dir_all(&rules_dir).unwrap();
+        std::fs::write(rules_dir.join("01-overview.md"), "Overview.").unwrap();
+        std::fs::write(rules_dir.join("02_style.md"), "Style.").unwrap();
+        std::fs::write(rules_dir.join("testing.md"), "Tests.").unwrap();
+
+        let parsed = Format::Claude.parser().parse(src.path()).unwrap();
+        let overview = parsed.iter().find(|r| r.content == "Overview.").unwrap();
+        assert_eq!((overview.name.as_deref(), overview.order), (Some("overview"), Some(1)));
+
+        let store_dir = tempfile::tempdir().unwrap();
+        let store = crate::store::Store::new(store_dir.path());
+        store.save_rules(Some("app"), &parsed, "claude").unwrap();
+        let stored = store.load_rules(Some("app")).unwrap();
+
+        let out = tempfile::tempdir().unwrap();
+        Format::Windsurf.writer().write(&stored, out.path()).unwrap();
+        let mut files: Vec<String> = std::fs::read_dir(out.path().join(".windsurf").join("rules"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["01-overview.md", "02-style.md", "testing.md"]);
+    }
+
+    #[test]
+    fn multi_file_writers_reject_output_file() {
+        use crate::writer::WriteOptions;
+        let rules = vec![crate::ir::Rule { name: Some("style".into()), content: "x".into(), ..Default::default() }];
+        for fmt in [Format::Cursor, Format::Windsurf, Format::Claude, Format::Antigravity] {
+            let dir = tempfile::tempdir().unwrap();
+            let options = WriteOptions { output_file: Some(dir.path().join("OUT.md")) };
+            let err = fmt.writer().write_with(&rules, dir.path(), &options).unwrap_err();
+            assert!(err.to_string().contains("--output-file only applies to single-file formats"), "{}", fmt.name());
+            assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0, "{} wrote files", fmt.name());
+        }
+    }
+
+    #[test]
+    fn from_str_without_suggestion() {
+        match Format::from_str("notepad") {
+            Err(PolyrcError::UnknownFormat { suggestions, .. }) => assert!(suggestions.is_empty()),
+            other => panic!("expected UnknownFormat, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! OpenHands microagents in `.openhands/microagents/`.
+//!
+//! `repo.md` is loaded into every conversation. Any other `.md` file with a
+//! `triggers:` list in its frontmatter is loaded when a message mentions one
+//! of the triggers; that is an on-demand rule whose `description` holds the
+//! triggers, comma-separated. A microagent without triggers is always loaded.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+use crate::error::{PolyrcError, Result};
+use crate::formats::copilot::split_frontmatter;
+use crate::formats::single_file;
+use crate::ir::{split_order_prefix, unique_output_stems, Activation, Rule, Scope};
+use crate::parser::{read_source_file, Parser};
+use crate::writer::Writer;
+
+/// Microagents directory, relative to the project root.
+pub const MICROAGENTS_DIR: &str = ".openhands/microagents";
+
+/// The always-loaded microagent, relative to the project root.
+pub const REPO_FILE: &str = ".openhands/microagents/repo.md";
+
+/// Name the parser gives `repo.md`.
+pub const REPO_RULE_NAME: &str = "repo";
+
+pub struct OpenHandsParser;
+pub struct OpenHandsWriter;
+
+#[derive(Debug, Deserialize, Default)]
+struct MicroagentFrontmatter {
+    triggers: Option<Vec<String>>,
+}
+
+/// Frontmatter of a written knowledge microagent, as in the OpenHands docs.
+#[derive(Debug, Serialize)]
+struct MicroagentFrontmatterOut<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    version: &'static str,
+    agent: &'static str,
+    triggers: Vec<String>,
+}
+
+fn microagents_dir(path: &Path) -> PathBuf {
+    path.join(".openhands").join("microagents")
+}
+
+impl Parser for OpenHandsParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        let dir = microagents_dir(path);
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut rules = vec![];
+        for entry in WalkDir::new(&dir).min_depth(1).max_depth(1).sort_by_file_name() {
+            let entry = entry.map_err(|e| PolyrcError::Io {
+                path: dir.clone(),
+                source: e.into(),
+            })?;
+            let p = entry.path();
+            if p.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            let Some(raw) = read_source_file(p)? else { continue };
+            let (fm_str, body) = split_frontmatter(&raw);
+            let fm: MicroagentFrontmatter = fm_str
+                .map(|s| {
+                    serde_yml::from_str::<Option<MicroagentFrontmatter>>(s).map_err(|e| PolyrcError::YamlParse {
+                        path: p.to_path_buf(),
+                        source: e,
+                    })
+                })
+                .transpose()?
+                .flatten()
+                .unwrap_or_default();
+            let triggers: Vec<String> = fm.triggers.unwrap_or_default().into_iter().filter(|t| !t.trim().is_empty()).collect();
+            let (order, stem) = split_order_prefix(p.file_stem().and_then(|s| s.to_str()).unwrap_or("rule"));
+            let on_demand = !triggers.is_empty() && stem != REPO_RULE_NAME;
+            rules.push(Rule {
+                scope: Scope::Project,
+                activation: if on_demand { Activation::OnDemand } else { Activation::Always },
+                name: Some(stem.to_string()),
+                description: on_demand.then(|| triggers.join(", ")),
+                content: body.trim().to_string(),
+                order,
+                ..Default::default()
+            });
+        }
+        Ok(rules)
+    }
+}
+
+impl Writer for OpenHandsWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        let dir = microagents_dir(target);
+        fs::create_dir_all(&dir).map_err(|e| PolyrcError::Io {
+            path: dir.clone(),
+            source: e,
+        })?;
+        let (on_demand, always): (Vec<&Rule>, Vec<&Rule>) = rules.iter().partition(|r| r.activation == Activation::OnDemand);
+        for (rule, stem) in on_demand.iter().zip(unique_output_stems(on_demand.iter().copied())) {
+            // repo.md is the always-loaded microagent
+            let stem = if stem == REPO_RULE_NAME { format!("{stem}-1") } else { stem };
+            let file = dir.join(format!("{stem}.md"));
+            let fm = MicroagentFrontmatterOut {
+                name: &stem,
+                kind: "knowledge",
+                version: "1.0.0",
+                agent: "CodeActAgent",
+                triggers: triggers(rule),
+            };
+            let fm_str = serde_yml::to_string(&fm).map_err(|e| PolyrcError::YamlParse { path: file.clone(), source: e })?;
+            let content = format!("---\n{}---\n\n{}\n", fm_str, rule.content.trim_end());
+            fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        }
+        if !always.is_empty() {
+            let always: Vec<Rule> = always.into_iter().cloned().collect();
+            single_file::write(&dir.join("repo.md"), &always)?;
+        }
+        Ok(())
+    }
+}
+
+/// A rule's triggers: its comma-separated `description`, or else its name.
+fn triggers(rule: &Rule) -> Vec<String> {
+    let listed: Vec<String> = rule
+        .description
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    if !listed.is_empty() {
+        return listed;
+    }
+    vec![rule.name.clone().unwrap_or_else(|| rule.filename_stem())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triggers_round_trip_through_frontmatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = vec![
+            Rule { name: Some("style".into()), content: "Use tabs.".into(), ..Default::default() },
+            Rule {
+                name: Some("docker".into()),
+                activation: Activation::OnDemand,
+                description: Some("docker, compose".into()),
+                content: "Use multi-stage builds.".into(),
+                ..Default::default()
+            },
+            Rule { name: Some("deploy".into()), activation: Activation::OnDemand, content: "Run make deploy.".into(), ..Default::default() },
+        ];
+        OpenHandsWriter.write(&rules, dir.path()).unwrap();
+        let agents = microagents_dir(dir.path());
+        assert_eq!(fs::read_to_string(agents.join("repo.md")).unwrap(), "Use tabs.\n");
+        assert_eq!(
+            fs::read_to_string(agents.join("docker.md")).unwrap(),
+            "---\nname: docker\ntype: knowledge\nversion: '1.0.0'\nagent: CodeActAgent\ntriggers:\n- docker\n- compose\n---\n\nUse multi-stage builds.\n"
+        );
+
+        let parsed = OpenHandsParser.parse(dir.path()).unwrap();
+        let summary: Vec<_> = parsed.iter().map(|r| (r.name.as_deref().unwrap(), &r.activation, r.description.as_deref())).collect();
+        assert_eq!(
+            summary,
+            [
+                ("deploy", &Activation::OnDemand, Some("deploy")),
+                ("docker", &Activation::OnDemand, Some("docker, compose")),
+                ("repo", &Activation::Always, None),
+            ]
+        );
+        assert_eq!(parsed[1].content, "Use multi-stage builds.");
+    }
+
+    #[test]
+    fn microagents_without_triggers_are_always_on() {
+        let dir = tempfile::tempdir().unwrap();
+        let agents = microagents_dir(dir.path());
+        fs::create_dir_all(&agents).unwrap();
+        fs::write(agents.join("repo.md"), "---\nname: repo\ntype: repo\n---\n\nRun make test.\n").unwrap();
+        fs::write(agents.join("notes.md"), "Plain notes.\n").unwrap();
+        let parsed = OpenHandsParser.parse(dir.path()).unwrap();
+        assert!(parsed.iter().all(|r| r.activation == Activation::Always && r.description.is_none()));
+        assert_eq!(parsed[1].content, "Run make test.");
+    }
+}
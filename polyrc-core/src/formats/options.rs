@@ -0,0 +1,148 @@
+//! Per-format options: `[formats.<name>]` tables in `config.toml` and
+//! `--format-option <name>.<key>=<value>` on the command line.
+//!
+//! Each format declares the options it understands in [`Format::options`];
+//! anything else is rejected. Formats read their values from the
+//! [`FormatOptions`] handed to [`Format::parser_with`] / [`Format::writer_with`].
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::RwLock;
+use serde::de::DeserializeOwned;
+use crate::error::{PolyrcError, Result};
+use super::Format;
+
+/// Value type of a declared option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    Bool,
+    Integer,
+}
+
+/// An option a format understands.
+#[derive(Debug, Clone, Copy)]
+pub struct OptionSpec {
+    pub key: &'static str,
+    pub kind: OptionKind,
+    pub description: &'static str,
+}
+
+/// Soft limit in characters on a format's whole output, checked by `polyrc budget`.
+pub const BUDGET_CHARS: &str = "budget_chars";
+
+/// [`BUDGET_CHARS`], declared by every format.
+pub(crate) const BUDGET_SPEC: OptionSpec = OptionSpec {
+    key: BUDGET_CHARS,
+    kind: OptionKind::Integer,
+    description: "soft limit in characters on everything written, for `polyrc budget`",
+};
+
+/// Option values for one format. Unset options take the format's default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormatOptions {
+    values: BTreeMap<String, toml::Value>,
+}
+
+impl FormatOptions {
+    /// The value of `key`, if set and of type `T`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.values.get(key).and_then(|v| v.clone().try_into().ok())
+    }
+
+    /// A boolean option, `false` when unset.
+    pub fn flag(&self, key: &str) -> bool {
+        self.get(key).unwrap_or(false)
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: toml::Value) {
+        self.values.insert(key.into(), value);
+    }
+}
+
+/// Options for every format, keyed by canonical format name.
+pub type OptionsMap = BTreeMap<String, FormatOptions>;
+
+static CONFIGURED: RwLock<OptionsMap> = RwLock::new(BTreeMap::new());
+
+/// Set the process-wide options used by [`Format::parser`] and [`Format::writer`]
+/// (config merged with `--format-option`).
+pub fn set_configured(options: OptionsMap) {
+    *CONFIGURED.write().expect("format options lock") = options;
+}
+
+/// The process-wide options for `fmt`.
+pub fn configured(fmt: &Format) -> FormatOptions {
+    CONFIGURED.read().expect("format options lock").get(fmt.name()).cloned().unwrap_or_default()
+}
+
+/// Check `value` against `format`'s declared `key`. Returns the canonical format.
+pub fn validate(format: &str, key: &str, value: &toml::Value) -> Result<Format> {
+    let fmt = Format::from_str(format)?;
+    let specs = fmt.options();
+    let Some(spec) = specs.iter().find(|s| s.key == key) else {
+        let known: Vec<&str> = specs.iter().map(|s| s.key).collect();
+        let known = if known.is_empty() { "it has none".to_string() } else { format!("known: {}", known.join(", ")) };
+        return Err(PolyrcError::ConfigError { msg: format!("unknown option '{key}' for format '{}' ({known})", fmt.name()) });
+    };
+    let ok = match spec.kind {
+        OptionKind::Bool => value.is_bool(),
+        OptionKind::Integer => value.as_integer().is_some_and(|n| n >= 0),
+    };
+    if !ok {
+        return Err(PolyrcError::ConfigError {
+            msg: format!("option {}.{key} must be a {:?}, got {value}", fmt.name(), spec.kind).to_lowercase(),
+        });
+    }
+    Ok(fmt)
+}
+
+/// A `--format-option` argument: `<format>.<key>=<value>`.
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    /// Canonical format name.
+    pub format: String,
+    pub key: String,
+    pub value: toml::Value,
+}
+
+/// Parse and validate `<format>.<key>=<value>`. The value is read as TOML
+/// (`true`, `3`, `"text"`), falling back to a bare string.
+pub fn parse_assignment(raw: &str) -> Result<Assignment> {
+    let invalid = || PolyrcError::ConfigError { msg: format!("expected <format>.<key>=<value>, got '{raw}'") };
+    let (path, value) = raw.split_once('=').ok_or_else(invalid)?;
+    let (format, key) = path.trim().split_once('.').ok_or_else(invalid)?;
+    let value = value.trim();
+    let value = toml::from_str::<toml::Table>(&format!("v = {value}"))
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()));
+    let fmt = validate(format, key, &value)?;
+    Ok(Assignment { format: fmt.name().to_string(), key: key.to_string(), value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_validates_assignments() {
+        let a = parse_assignment("github-copilot.split_always=true").unwrap();
+        assert_eq!((a.format.as_str(), a.key.as_str(), a.value), ("copilot", "split_always", toml::Value::Boolean(true)));
+
+        let unknown = parse_assignment("windsurf.autosplit=true").unwrap_err().to_string();
+        assert!(unknown.contains("unknown option 'autosplit' for format 'windsurf' (known: budget_chars)"), "{unknown}");
+        let typed = parse_assignment("copilot.split_always=yes").unwrap_err().to_string();
+        assert!(typed.contains("must be a bool"), "{typed}");
+        assert!(parse_assignment("gemini.budget_chars=-1").is_err());
+        assert_eq!(parse_assignment("gemini.budget_chars=8000").unwrap().value, toml::Value::Integer(8000));
+        assert!(parse_assignment("copilot=true").is_err());
+    }
+
+    #[test]
+    fn unset_flags_are_false() {
+        let mut options = FormatOptions::default();
+        assert!(!options.flag("split_always"));
+        options.set("split_always", toml::Value::Boolean(true));
+        assert!(options.flag("split_always"));
+    }
+}
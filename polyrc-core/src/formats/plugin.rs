@@ -0,0 +1,154 @@
+//! Formats backed by an external command, declared in `config.toml`:
+//!
+//! ```toml
+//! [plugins.mytool]
+//! command = "mytool-polyrc"
+//! ```
+//!
+//! Reading runs `mytool-polyrc parse <dir>`, which prints the rules it finds
+//! as a JSON array on stdout. Writing runs `mytool-polyrc write <dir>` with the
+//! rules on stdin in the same shape. [`RULE_SCHEMA`] describes it. The
+//! plugin owns the files; polyrc never looks inside `<dir>` itself.
+
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use crate::config::PluginConfig;
+use crate::error::{PolyrcError, Result};
+use crate::formats::custom::CustomFormat;
+use crate::ir::Rule;
+use crate::parser::Parser;
+use crate::writer::Writer;
+
+/// JSON schema for the rules exchanged with a plugin.
+pub const RULE_SCHEMA: &str = include_str!("../../schema/rule.schema.json");
+
+/// How much of a failing plugin's stderr goes into the error.
+const STDERR_LINES: usize = 5;
+
+pub struct PluginParser(pub &'static CustomFormat);
+pub struct PluginWriter(pub &'static CustomFormat);
+
+/// Check `config` and turn it into a [`CustomFormat`] named `name`.
+pub fn define(name: &str, config: PluginConfig) -> Result<CustomFormat> {
+    if config.command.split_whitespace().next().is_none() {
+        return Err(PolyrcError::ConfigError { msg: format!("[plugins.{name}] in config.toml: command is empty") });
+    }
+    let description = config.description.unwrap_or_else(|| format!("{name} (plugin: {})", config.command));
+    CustomFormat::plugin(name, &config.command, description, config.user_dir.as_deref().map(crate::config::expand_path))
+}
+
+impl Parser for PluginParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        let fmt = self.0;
+        let out = run(fmt, "parse", path, None)?;
+        serde_json::from_slice(&out.stdout).map_err(|e| PolyrcError::Plugin {
+            name: fmt.name.to_string(),
+            reason: format!("`{} parse` printed invalid rule JSON: {e}", command_line(fmt)),
+        })
+    }
+}
+
+impl Writer for PluginWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        let json = serde_json::to_vec(rules).expect("rules serialize");
+        run(self.0, "write", target, Some(json)).map(|_| ())
+    }
+}
+
+fn command_line(fmt: &CustomFormat) -> &str {
+    fmt.command.unwrap_or_default()
+}
+
+/// Run `<command> <action> <dir>`, feeding it `stdin`, and fail unless it exits 0.
+fn run(fmt: &CustomFormat, action: &str, dir: &Path, stdin: Option<Vec<u8>>) -> Result<Output> {
+    let failed = |reason: String| PolyrcError::Plugin { name: fmt.name.to_string(), reason };
+    let mut words = command_line(fmt).split_whitespace();
+    let program = words.next().unwrap_or_default();
+    crate::debug_log!("plugin {}: {} {action} {}", fmt.name, command_line(fmt), dir.display());
+    let mut child = Command::new(program)
+        .args(words)
+        .arg(action)
+        .arg(dir)
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(format!("could not run `{}`: {e}", command_line(fmt))))?;
+    // From another thread, so a plugin that prints before reading can't deadlock
+    let feeder = stdin.zip(child.stdin.take()).map(|(bytes, mut pipe)| std::thread::spawn(move || pipe.write_all(&bytes)));
+    let out = child.wait_with_output().map_err(|e| failed(format!("`{}` did not finish: {e}", command_line(fmt))))?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        let lines: Vec<&str> = stderr.lines().filter(|l| !l.trim().is_empty()).collect();
+        let tail = lines[lines.len().saturating_sub(STDERR_LINES)..].join("\n");
+        let detail = if tail.is_empty() { String::new() } else { format!(":\n{tail}") };
+        return Err(failed(format!("`{} {action}` exited with {}{detail}", command_line(fmt), out.status)));
+    }
+    if let Some(Ok(Err(e))) = feeder.map(|f| f.join()) {
+        return Err(failed(format!("`{} {action}` did not read the rules: {e}", command_line(fmt))));
+    }
+    Ok(out)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use crate::ir::Activation;
+
+    /// A plugin that keeps the rules JSON in `<dir>/rules.json`.
+    fn plugin(dir: &Path, script: &str) -> &'static CustomFormat {
+        let bin = dir.join("plugin.sh");
+        fs::write(&bin, format!("#!/bin/sh\n{script}\n")).unwrap();
+        fs::set_permissions(&bin, fs::Permissions::from_mode(0o755)).unwrap();
+        let config = PluginConfig { command: bin.display().to_string(), ..Default::default() };
+        Box::leak(Box::new(define("mytool", config).unwrap()))
+    }
+
+    #[test]
+    fn rules_round_trip_through_the_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let fmt = plugin(dir.path(), r#"case "$1" in parse) cat "$2/rules.json" ;; write) cat > "$2/rules.json" ;; esac"#);
+        let rules = vec![Rule {
+            name: Some("ts".into()),
+            activation: Activation::Glob,
+            globs: Some(vec!["*.ts".into()]),
+            content: "Prefer interfaces.".into(),
+            ..Default::default()
+        }];
+        PluginWriter(fmt).write(&rules, dir.path()).unwrap();
+        assert_eq!(PluginParser(fmt).parse(dir.path()).unwrap(), rules);
+    }
+
+    #[test]
+    fn failures_name_the_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let fmt = plugin(dir.path(), "echo 'database is locked' >&2; exit 3");
+        let err = PluginParser(fmt).parse(dir.path()).unwrap_err().to_string();
+        assert!(err.starts_with("Plugin 'mytool' failed:") && err.ends_with("exit status: 3:\ndatabase is locked"), "{err}");
+
+        let fmt = plugin(dir.path(), "echo '[{\"content\": 1}]'");
+        let err = PluginParser(fmt).parse(dir.path()).unwrap_err().to_string();
+        assert!(err.contains("printed invalid rule JSON"), "{err}");
+    }
+
+    #[test]
+    fn schema_lists_every_rule_field() {
+        let schema: serde_json::Value = serde_json::from_str(RULE_SCHEMA).unwrap();
+        let properties = schema["$defs"]["rule"]["properties"].as_object().unwrap();
+        let full = Rule { globs: Some(vec![]), name: Some(String::new()), description: Some(String::new()), order: Some(1), ..Default::default() };
+        let full = Rule {
+            project: Some(String::new()),
+            source_format: Some(String::new()),
+            source_path: Some(String::new()),
+            created_at: Some(String::new()),
+            updated_at: Some(String::new()),
+            ..full
+        };
+        for field in serde_json::to_value(full).unwrap().as_object().unwrap().keys() {
+            assert!(properties.contains_key(field), "{field} missing from the schema");
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Qodo Gen (formerly Codium) reads `best_practices.md` at the repository
+//! root. Its `.qodo/` directory holds tool settings, not rules.
+
+use std::path::Path;
+use crate::error::Result;
+use crate::formats::single_file;
+use crate::ir::Rule;
+use crate::parser::Parser;
+use crate::writer::{WriteOptions, Writer};
+
+/// The best-practices file, relative to the repository root.
+pub const BEST_PRACTICES_FILE: &str = "best_practices.md";
+
+/// Name the parser gives the whole of `best_practices.md`.
+pub const QODO_RULE_NAME: &str = "best-practices";
+
+pub struct QodoParser;
+pub struct QodoWriter;
+
+impl Parser for QodoParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        Ok(single_file::parse(&path.join(BEST_PRACTICES_FILE), QODO_RULE_NAME)?.into_iter().collect())
+    }
+}
+
+impl Writer for QodoWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        self.write_with(rules, target, &WriteOptions::default())
+    }
+
+    fn write_with(&self, rules: &[Rule], target: &Path, options: &WriteOptions) -> Result<()> {
+        if rules.is_empty() {
+            return Ok(());
+        }
+        single_file::write(&options.output_file.clone().unwrap_or_else(|| target.join(BEST_PRACTICES_FILE)), rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use crate::formats::Format;
+
+    #[test]
+    fn best_practices_round_trip() {
+        assert_eq!(Format::from_str("codium").unwrap(), Format::Qodo);
+        let dir = tempfile::tempdir().unwrap();
+        let rule = |name: &str, content: &str| Rule { name: Some(name.into()), content: content.into(), ..Default::default() };
+        QodoWriter.write(&[rule("errors", "Never unwrap."), rule("naming", "Use snake_case.")], dir.path()).unwrap();
+        let written = std::fs::read_to_string(dir.path().join(BEST_PRACTICES_FILE)).unwrap();
+        assert_eq!(written, "## errors\n\nNever unwrap.\n\n## naming\n\nUse snake_case.\n");
+
+        let parsed = QodoParser.parse(dir.path()).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name.as_deref(), Some(QODO_RULE_NAME));
+        assert_eq!(parsed[0].content, written.trim_end());
+    }
+}
@@ -0,0 +1,232 @@
+//! Section-level merging for single-file user configs (`global_rules.md`,
+//! `~/.gemini/GEMINI.md`).
+//!
+//! The file is split on `## ` headers. Sections named after a rule being written
+//! are replaced, new rules are appended as sections, and anything else — text the
+//! user maintains only locally — is left as it was.
+
+use std::fs;
+use std::path::Path;
+use crate::error::{PolyrcError, Result};
+use crate::ir::Rule;
+
+/// One `## ` section of a markdown file. `header` is `None` for the text before
+/// the first header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Section {
+    header: Option<String>,
+    /// The section's raw text, including its header line.
+    text: String,
+}
+
+/// What merging did to each section, by header. The text before the first
+/// header is reported as [`PREAMBLE`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    pub replaced: Vec<String>,
+    pub added: Vec<String>,
+    pub kept: Vec<String>,
+}
+
+/// Label used in a [`MergeReport`] for the text before the first header.
+pub const PREAMBLE: &str = "(top of file)";
+
+/// Split markdown into sections at `## ` headers outside code fences.
+fn parse_sections(text: &str) -> Vec<Section> {
+    let mut sections = vec![Section { header: None, text: String::new() }];
+    let mut fence: Option<&str> = None;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        match fence {
+            Some(marker) if trimmed.starts_with(marker) => fence = None,
+            Some(_) => {}
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            None => {
+                if let Some(header) = line.strip_prefix("## ") {
+                    sections.push(Section { header: Some(header.trim().to_string()), text: String::new() });
+                }
+            }
+        }
+        sections.last_mut().expect("at least one section").text.push_str(line);
+    }
+    sections.retain(|s| s.header.is_some() || !s.text.trim().is_empty());
+    sections
+}
+
+/// The sections `rule` occupies once written. A rule named `whole_file` is the
+/// whole file as a parser saw it, so its own sections are matched individually;
+/// any other rule is a `## <name>` section (plus any `##` headers in its content).
+fn rule_sections(rule: &Rule, whole_file: &str) -> Vec<Section> {
+    let name = rule.name.as_deref().unwrap_or("Rule");
+    if name == whole_file {
+        return parse_sections(&rule.content);
+    }
+    parse_sections(&format!("## {}\n\n{}\n", name, rule.content.trim()))
+}
+
+fn render(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(|s| format!("{}\n", s.text.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn label(header: &Option<String>) -> String {
+    header.clone().unwrap_or_else(|| PREAMBLE.to_string())
+}
+
+/// Merge `rules` into the markdown `existing`. See the module docs.
+pub fn merge(existing: &str, rules: &[Rule], whole_file: &str) -> (String, MergeReport) {
+    let groups: Vec<Vec<Section>> = rules
+        .iter()
+        .map(|r| rule_sections(r, whole_file))
+        .filter(|g| !g.is_empty())
+        .collect();
+    let existing = parse_sections(existing);
+    let mut used = vec![false; groups.len()];
+    let mut report = MergeReport::default();
+    let mut out: Vec<Section> = vec![];
+    // Emit a rule's sections, reporting each as replaced or added
+    let mut emit = |group: &[Section], out: &mut Vec<Section>| {
+        for section in group {
+            let seen = existing.iter().any(|e| e.header == section.header);
+            let list = if seen { &mut report.replaced } else { &mut report.added };
+            list.push(label(&section.header));
+            out.push(section.clone());
+        }
+    };
+
+    for section in &existing {
+        if let Some(i) = groups.iter().position(|g| g[0].header == section.header) {
+            if !used[i] {
+                used[i] = true;
+                emit(&groups[i], &mut out);
+            }
+        } else if !groups.iter().any(|g| g[1..].iter().any(|s| s.header == section.header)) {
+            // Not written by any rule: the user's own section
+            out.push(section.clone());
+        }
+    }
+    for (group, _) in groups.iter().zip(&used).filter(|(_, used)| !**used) {
+        emit(group, &mut out);
+    }
+    report.kept = out
+        .iter()
+        .filter(|s| !groups.iter().flatten().any(|g| g.header == s.header))
+        .map(|s| label(&s.header))
+        .collect();
+    (render(&out), report)
+}
+
+/// Split a file written by [`join_rules`](crate::formats::single_file::join_rules)
+/// back into `(name, content)` pairs: one per `## ` section, provided there
+/// are at least two and nothing precedes the first. Anything else was not
+/// joined from several rules, and `None` is returned.
+pub(crate) fn split_joined(text: &str) -> Option<Vec<(String, String)>> {
+    let sections = parse_sections(text);
+    if sections.len() < 2 || sections.iter().any(|s| s.header.as_deref().is_none_or(str::is_empty)) {
+        return None;
+    }
+    let split = sections
+        .into_iter()
+        .map(|s| {
+            let body = s.text.split_once('\n').map_or("", |(_, body)| body);
+            (s.header.unwrap_or_default(), body.trim().to_string())
+        })
+        .collect();
+    Some(split)
+}
+
+/// Write `rules` to the single-file config `file`. Unless `overwrite` is set, an
+/// existing file is merged into section by section and the outcome is raised as a [notice](crate::notice).
+pub(crate) fn write_single_file(file: &Path, rules: &[Rule], whole_file: &str, overwrite: bool) -> Result<()> {
+    let io_err = |e| PolyrcError::Io { path: file.to_path_buf(), source: e };
+    // On a new machine the tool's user config dir may not exist yet
+    crate::formats::create_parent_dir(file)?;
+    if overwrite {
+        return fs::write(file, crate::formats::single_file::join_rules(rules)).map_err(io_err);
+    }
+    if !file.exists() {
+        return fs::write(file, merge("", rules, whole_file).0).map_err(io_err);
+    }
+    let existing = fs::read_to_string(file).map_err(io_err)?;
+    let (merged, report) = merge(&existing, rules, whole_file);
+    fs::write(file, merged).map_err(io_err)?;
+
+    let mut message = format!("merged into {} (use --overwrite to replace it)", file.display());
+    for (what, names) in [("replaced", &report.replaced), ("added", &report.added), ("kept", &report.kept)] {
+        if !names.is_empty() {
+            message.push_str(&format!("\n  {what}: {}", names.join(", ")));
+        }
+    }
+    crate::notice::note(message);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, content: &str) -> Rule {
+        Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+    }
+
+    const EXISTING: &str = "\
+Intro I wrote by hand.
+
+## tone
+
+Be verbose.
+
+## local-only
+
+Never pushed.
+```sh
+## not a header
+```
+";
+
+    #[test]
+    fn replaces_adds_and_keeps_sections() {
+        let rules = vec![rule("tone", "Be terse."), rule("style", "Use tabs.")];
+        let (merged, report) = merge(EXISTING, &rules, "global-rules");
+        assert_eq!(
+            merged,
+            "Intro I wrote by hand.\n\n## tone\n\nBe terse.\n\n## local-only\n\nNever pushed.\n```sh\n## not a header\n```\n\n## style\n\nUse tabs.\n"
+        );
+        assert_eq!(report.replaced, vec!["tone"]);
+        assert_eq!(report.added, vec!["style"]);
+        assert_eq!(report.kept, vec![PREAMBLE, "local-only"]);
+    }
+
+    #[test]
+    fn merging_is_idempotent() {
+        let rules = vec![rule("tone", "Be terse.\n\n## tone details\n\nShort sentences.")];
+        let (once, _) = merge(EXISTING, &rules, "global-rules");
+        let (twice, report) = merge(&once, &rules, "global-rules");
+        assert_eq!(once, twice);
+        assert_eq!(report.kept, vec![PREAMBLE, "local-only"]);
+    }
+
+    #[test]
+    fn joined_files_split_back_into_rules() {
+        let rules = vec![rule("tone", "Be terse."), rule("style", "Use tabs.\n```sh\n## not a header\n```")];
+        let joined = crate::formats::single_file::join_rules(&rules);
+        let split = split_joined(&joined).unwrap();
+        assert_eq!(split, [("tone".into(), "Be terse.".into()), ("style".into(), rules[1].content.clone())]);
+        assert_eq!(split_joined(EXISTING), None);
+        assert_eq!(split_joined("## only\n\nOne section.\n"), None);
+    }
+
+    #[test]
+    fn whole_file_rule_matches_section_by_section() {
+        // What the parser returns for the file, pushed back unchanged
+        let whole = rule("global-rules", "Intro, updated.\n\n## tone\n\nBe terse.");
+        let (merged, report) = merge(EXISTING, &[whole], "global-rules");
+        assert!(merged.starts_with("Intro, updated.\n\n## tone\n\nBe terse.\n\n## local-only\n"));
+        assert_eq!(report.replaced, vec![PREAMBLE, "tone"]);
+        assert_eq!(report.kept, vec!["local-only"]);
+    }
+}
@@ -0,0 +1,64 @@
+//! Shared by the formats that keep every project rule in one markdown file
+//! (`GEMINI.md`, `WARP.md`, `.rules`, `CONVENTIONS.md`, `AGENTS.md`,
+//! `best_practices.md`, Cursor's legacy `.cursorrules`).
+//!
+//! The file is parsed as a single always-on rule and written back with one
+//! `## <name>` section per rule, or just the content when there is one rule.
+
+use std::fs;
+use std::path::Path;
+use crate::error::{PolyrcError, Result};
+use crate::ir::{Activation, Rule, Scope};
+use crate::parser::read_source_file;
+
+/// The markdown file at `file` as one always-on project rule named `name`.
+/// `None` if it is missing, empty, or not a regular file.
+pub(crate) fn parse(file: &Path, name: &str) -> Result<Option<Rule>> {
+    if !file.exists() {
+        return Ok(None);
+    }
+    // Another tool may use the same name for a directory (`.rules/`)
+    if !file.is_file() {
+        crate::debug_log!("{} is not a file, skipping", file.display());
+        return Ok(None);
+    }
+    let content = read_source_file(file)?.unwrap_or_default();
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Rule {
+        scope: Scope::Project,
+        activation: Activation::Always,
+        name: Some(name.to_string()),
+        content: content.trim_end().to_string(),
+        ..Default::default()
+    }))
+}
+
+/// Write `rules` to `file` as [`join_rules`] joins them. Refuses to replace a
+/// directory of the same name.
+pub(crate) fn write(file: &Path, rules: &[Rule]) -> Result<()> {
+    if file.is_dir() {
+        return Err(PolyrcError::WriteFailure {
+            path: file.to_path_buf(),
+            reason: "is a directory (from another tool?); move it to write this file".to_string(),
+        });
+    }
+    crate::formats::create_parent_dir(file)?;
+    fs::write(file, join_rules(rules)).map_err(|e| PolyrcError::Io { path: file.to_path_buf(), source: e })
+}
+
+/// Concatenate multiple rules into a single markdown file with section headers.
+pub(crate) fn join_rules(rules: &[Rule]) -> String {
+    if rules.len() == 1 {
+        return rules[0].content.clone() + "\n";
+    }
+    rules
+        .iter()
+        .map(|r| {
+            let header = r.name.as_deref().unwrap_or("Rule");
+            format!("## {}\n\n{}\n", header, r.content.trim_end())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,123 @@
+//! The `trigger` frontmatter Windsurf and Antigravity rule files share:
+//! `always_on`, `manual`, `model_decision` (picked by its `description`) or
+//! `glob` (with a comma-separated `globs`). Files without frontmatter are
+//! always-on, and always-on rules without a description are written that way.
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::error::{PolyrcError, Result};
+use crate::formats::copilot::split_frontmatter;
+use crate::formats::cursor::{globs_line, take_bare_globs};
+use crate::ir::{Activation, Rule};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Trigger {
+    AlwaysOn,
+    Manual,
+    ModelDecision,
+    Glob,
+}
+
+/// `globs` is written separately by [`render`], as a bare scalar like Cursor's.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct TriggerFrontmatter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trigger: Option<Trigger>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing)]
+    globs: Option<String>,
+}
+
+/// What a rule file's frontmatter says, and the body after it.
+pub(crate) struct Parsed<'a> {
+    pub activation: Activation,
+    pub globs: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub body: &'a str,
+}
+
+/// Split the frontmatter off `raw`, read from `p`.
+pub(crate) fn parse<'a>(p: &Path, raw: &'a str) -> Result<Parsed<'a>> {
+    let (fm_str, body) = split_frontmatter(raw);
+    let Some(fm_str) = fm_str else {
+        return Ok(Parsed { activation: Activation::Always, globs: None, description: None, body: raw });
+    };
+    let (fm_str, bare_globs) = take_bare_globs(fm_str);
+    let mut fm: TriggerFrontmatter = serde_yml::from_str::<Option<TriggerFrontmatter>>(&fm_str)
+        .map_err(|e| PolyrcError::YamlParse { path: p.to_path_buf(), source: e })?
+        .unwrap_or_default();
+    fm.globs = bare_globs.or(fm.globs);
+    let globs: Option<Vec<String>> = fm
+        .globs
+        .map(|g| crate::globs::split_list(&g))
+        .filter(|g| !g.is_empty());
+    let activation = match fm.trigger {
+        Some(Trigger::Glob) if globs.is_some() => Activation::Glob,
+        Some(Trigger::Manual) => Activation::OnDemand,
+        Some(Trigger::ModelDecision) => Activation::AiDecides,
+        // A glob trigger without globs matches nothing; keep it rather than drop it
+        _ => Activation::Always,
+    };
+    Ok(Parsed {
+        globs: globs.filter(|_| activation == Activation::Glob),
+        activation,
+        description: fm.description,
+        // Without the blank line that follows the frontmatter
+        body: body.trim_start_matches(['\r', '\n']),
+    })
+}
+
+/// `rule` as file content, with frontmatter unless it is a plain always-on rule.
+pub(crate) fn render(rule: &Rule, path: &Path) -> Result<String> {
+    let globs = rule.globs.as_ref().filter(|g| !g.is_empty() && rule.activation == Activation::Glob);
+    let trigger = match rule.activation {
+        Activation::Glob if globs.is_some() => Trigger::Glob,
+        Activation::OnDemand => Trigger::Manual,
+        Activation::AiDecides => Trigger::ModelDecision,
+        _ if rule.description.is_none() => return Ok(rule.content.trim_end().to_string() + "\n"),
+        _ => Trigger::AlwaysOn,
+    };
+    let fm = TriggerFrontmatter { trigger: Some(trigger), description: rule.description.clone(), globs: None };
+    let mut fm_str =
+        serde_yml::to_string(&fm).map_err(|e| PolyrcError::YamlParse { path: path.to_path_buf(), source: e })?;
+    if let Some(globs) = globs {
+        fm_str.push_str(&globs_line(globs));
+    }
+    Ok(format!("---\n{}---\n\n{}\n", fm_str, rule.content.trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triggers_round_trip() {
+        let rule = |activation: Activation, globs: Option<&[&str]>, description: Option<&str>| Rule {
+            activation,
+            globs: globs.map(|g| g.iter().map(|s| s.to_string()).collect()),
+            description: description.map(str::to_string),
+            content: "Body.".into(),
+            ..Default::default()
+        };
+        let path = Path::new("rule.md");
+        for rule in [
+            rule(Activation::Always, None, None),
+            rule(Activation::Always, None, Some("House style")),
+            rule(Activation::Glob, Some(&["*.ts", "src/**"]), None),
+            rule(Activation::OnDemand, None, None),
+            rule(Activation::AiDecides, None, Some("SQL migrations")),
+        ] {
+            let raw = render(&rule, path).unwrap();
+            let parsed = parse(path, &raw).unwrap();
+            assert_eq!(
+                (parsed.activation, parsed.globs, parsed.description, parsed.body.trim_end()),
+                (rule.activation, rule.globs, rule.description, "Body."),
+                "{raw}"
+            );
+        }
+        let glob = render(&rule(Activation::Glob, Some(&["*.ts"]), None), path).unwrap();
+        assert_eq!(glob, "---\ntrigger: glob\nglobs: *.ts\n---\n\nBody.\n");
+    }
+}
@@ -0,0 +1,62 @@
+//! Warp's agent mode reads `WARP.md` in the project root, like `CLAUDE.md`
+//! or `GEMINI.md`.
+
+use std::path::Path;
+use crate::error::Result;
+use crate::formats::single_file;
+use crate::ir::Rule;
+use crate::parser::Parser;
+use crate::writer::{WriteOptions, Writer};
+
+/// The rules file, relative to the project root.
+pub const WARP_FILE: &str = "WARP.md";
+
+/// Name the parser gives the whole of `WARP.md`.
+pub const WARP_RULE_NAME: &str = "warp";
+
+pub struct WarpParser;
+pub struct WarpWriter;
+
+impl Parser for WarpParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        Ok(single_file::parse(&path.join(WARP_FILE), WARP_RULE_NAME)?.into_iter().collect())
+    }
+}
+
+impl Writer for WarpWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        self.write_with(rules, target, &WriteOptions::default())
+    }
+
+    fn write_with(&self, rules: &[Rule], target: &Path, options: &WriteOptions) -> Result<()> {
+        if rules.is_empty() {
+            return Ok(());
+        }
+        single_file::write(&options.output_file.clone().unwrap_or_else(|| target.join(WARP_FILE)), rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::Format;
+
+    #[test]
+    fn claude_project_converts_to_one_warp_file() {
+        let input = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        let rules_dir = input.path().join(".claude").join("rules");
+        std::fs::create_dir_all(&rules_dir).unwrap();
+        std::fs::write(rules_dir.join("style.md"), "Use tabs.\n").unwrap();
+        std::fs::write(rules_dir.join("tests.md"), "Add one.\n").unwrap();
+
+        let options = crate::ops::ConvertOptions::default();
+        crate::ops::convert(&Format::Claude, &Format::Warp, input.path(), output.path(), &options).unwrap();
+        let written = std::fs::read_to_string(output.path().join(WARP_FILE)).unwrap();
+        assert_eq!(written, "## style\n\nUse tabs.\n\n## tests\n\nAdd one.\n");
+
+        let parsed = WarpParser.parse(output.path()).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name.as_deref(), Some(WARP_RULE_NAME));
+    }
+}
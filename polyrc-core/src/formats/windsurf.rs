@@ -0,0 +1,224 @@
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+use crate::error::{PolyrcError, Result};
+use crate::formats::trigger;
+use crate::ir::{nested_output_stem, split_order_prefix, uniquify, Activation, Rule, Scope};
+use crate::parser::{read_source_file, Parser};
+use crate::writer::Writer;
+
+/// Windsurf's per-rule-file character limit.
+pub const FILE_CHAR_LIMIT: usize = 6_000;
+/// Windsurf's limit on all rule files combined.
+pub const TOTAL_CHAR_LIMIT: usize = 12_000;
+/// User-scope rules file inside the memories directory.
+pub const GLOBAL_RULES_FILE: &str = "global_rules.md";
+/// Name the parser gives the whole of [`GLOBAL_RULES_FILE`].
+pub const GLOBAL_RULES_NAME: &str = "global-rules";
+
+pub struct WindsurfParser;
+
+/// `overwrite` replaces `global_rules.md` wholesale instead of merging into its
+/// `## ` sections.
+#[derive(Default)]
+pub struct WindsurfWriter {
+    pub overwrite: bool,
+}
+
+/// Characters `rule` takes up once written as a Windsurf rule file.
+pub fn rule_chars(rule: &Rule) -> usize {
+    rule.content.trim_end().chars().count() + 1
+}
+
+/// Per-rule and total character counts against Windsurf's limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharBudget {
+    /// `(rule name, chars)` in input order.
+    pub rules: Vec<(String, usize)>,
+    pub total: usize,
+}
+
+impl CharBudget {
+    pub fn of(rules: &[Rule]) -> Self {
+        let rules: Vec<(String, usize)> = rules
+            .iter()
+            .map(|r| (r.name.clone().unwrap_or_else(|| r.filename_stem()), rule_chars(r)))
+            .collect();
+        let total = rules.iter().map(|(_, n)| n).sum();
+        Self { rules, total }
+    }
+
+    /// Names of rules over [`FILE_CHAR_LIMIT`].
+    pub fn oversized(&self) -> Vec<&str> {
+        self.rules.iter().filter(|(_, n)| *n > FILE_CHAR_LIMIT).map(|(name, _)| name.as_str()).collect()
+    }
+
+    pub fn over_total(&self) -> bool {
+        self.total > TOTAL_CHAR_LIMIT
+    }
+}
+
+impl Parser for WindsurfParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        // User layout: ~/.codeium/windsurf/memories/global_rules.md (single file)
+        let global_rules = path.join(GLOBAL_RULES_FILE);
+        if global_rules.exists() {
+            let content = read_source_file(&global_rules)?.unwrap_or_default();
+            if content.trim().is_empty() {
+                return Ok(vec![]);
+            }
+            return Ok(vec![Rule {
+                scope: Scope::User,
+                activation: Activation::Always,
+                name: Some(GLOBAL_RULES_NAME.to_string()),
+                content: content.trim_end().to_string(),
+                ..Default::default()
+            }]);
+        }
+
+        // Project layout: .windsurf/rules/**/*.md — nested files are named by
+        // their relative path (`team/style`)
+        let rules_dir = path.join(".windsurf").join("rules");
+        if !rules_dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut rules = vec![];
+        for entry in WalkDir::new(&rules_dir).min_depth(1).sort_by_file_name() {
+            let entry = entry.map_err(|e| PolyrcError::Io {
+                path: rules_dir.clone(),
+                source: e.into(),
+            })?;
+            let p = entry.path();
+            if p.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            let Some(raw) = read_source_file(p)? else { continue };
+            let parsed = trigger::parse(p, &raw)?;
+            let (order, stem) = split_order_prefix(p.file_stem().and_then(|s| s.to_str()).unwrap_or("rule"));
+            let dirs = p.parent().and_then(|d| d.strip_prefix(&rules_dir).ok()).unwrap_or(Path::new(""));
+            let name = dirs
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .chain(std::iter::once(stem.to_string()))
+                .collect::<Vec<_>>()
+                .join("/");
+            rules.push(Rule {
+                scope: Scope::Project,
+                activation: parsed.activation,
+                globs: parsed.globs,
+                name: Some(name),
+                description: parsed.description,
+                content: parsed.body.trim_end().to_string(),
+                order,
+                ..Default::default()
+            });
+        }
+        Ok(rules)
+    }
+}
+
+impl Writer for WindsurfWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        // User layout: target is the memories dir → write everything as global_rules.md
+        let is_user = rules.iter().any(|r| r.scope == Scope::User);
+        if is_user {
+            fs::create_dir_all(target).map_err(|e| PolyrcError::Io {
+                path: target.to_path_buf(),
+                source: e,
+            })?;
+            let file = target.join(GLOBAL_RULES_FILE);
+            return crate::formats::sections::write_single_file(&file, rules, GLOBAL_RULES_NAME, self.overwrite);
+        }
+
+        // Project layout: .windsurf/rules/**/*.md (one file per rule)
+        let rules_dir = target.join(".windsurf").join("rules");
+        fs::create_dir_all(&rules_dir).map_err(|e| PolyrcError::Io {
+            path: rules_dir.clone(),
+            source: e,
+        })?;
+
+        let mut total_chars = 0usize;
+        let refs: Vec<&Rule> = rules.iter().collect();
+        let stems = uniquify(&refs, rules.iter().map(|r| nested_output_stem(r, '/')).collect());
+        for (rule, stem) in rules.iter().zip(stems) {
+            let file = rules_dir.join(format!("{}.md", stem));
+            let content = trigger::render(rule, &file)?;
+            let char_count = rule_chars(rule);
+            let name = rule.name.as_deref().unwrap_or("rule");
+
+            if char_count > FILE_CHAR_LIMIT {
+                crate::notice::warn(format!(
+                    "rule '{}' is {} chars, exceeds Windsurf per-file limit of {}",
+                    name, char_count, FILE_CHAR_LIMIT
+                ));
+            }
+            total_chars += char_count;
+
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent).map_err(|e| PolyrcError::Io { path: parent.to_path_buf(), source: e })?;
+            }
+            fs::write(&file, &content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        }
+
+        if total_chars > TOTAL_CHAR_LIMIT {
+            crate::notice::warn(format!(
+                "total rules content is {} chars, exceeds Windsurf total limit of {}",
+                total_chars, TOTAL_CHAR_LIMIT
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_rules_with_path_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules_dir = dir.path().join(".windsurf").join("rules");
+        fs::create_dir_all(rules_dir.join("team").join("backend")).unwrap();
+        fs::write(rules_dir.join("general.md"), "General.").unwrap();
+        fs::write(rules_dir.join("team").join("01-style.md"), "Style.").unwrap();
+        fs::write(rules_dir.join("team").join("backend").join("db.md"), "Use migrations.").unwrap();
+
+        let rules = WindsurfParser.parse(dir.path()).unwrap();
+        let mut names: Vec<&str> = rules.iter().filter_map(|r| r.name.as_deref()).collect();
+        names.sort();
+        assert_eq!(names, vec!["general", "team/backend/db", "team/style"]);
+        assert_eq!(rules.iter().find(|r| r.content == "Style.").unwrap().order, Some(1));
+
+        let out = tempfile::tempdir().unwrap();
+        WindsurfWriter::default().write(&rules, out.path()).unwrap();
+        let out_rules = out.path().join(".windsurf").join("rules");
+        assert!(out_rules.join("team").join("01-style.md").exists());
+        assert_eq!(fs::read_to_string(out_rules.join("team").join("backend").join("db.md")).unwrap(), "Use migrations.\n");
+    }
+
+    #[test]
+    fn activations_survive_a_round_trip() {
+        let rules = vec![
+            Rule { name: Some("api".into()), activation: Activation::Glob, globs: Some(vec!["*.ts".into()]), content: "Use zod.".into(), ..Default::default() },
+            Rule { name: Some("review".into()), activation: Activation::OnDemand, content: "Review the diff.".into(), ..Default::default() },
+            Rule { name: Some("style".into()), content: "Use tabs.".into(), ..Default::default() },
+        ];
+        let out = tempfile::tempdir().unwrap();
+        WindsurfWriter::default().write(&rules, out.path()).unwrap();
+        let rules_dir = out.path().join(".windsurf").join("rules");
+        assert_eq!(fs::read_to_string(rules_dir.join("review.md")).unwrap(), "---\ntrigger: manual\n---\n\nReview the diff.\n");
+        assert_eq!(fs::read_to_string(rules_dir.join("style.md")).unwrap(), "Use tabs.\n");
+        assert_eq!(WindsurfParser.parse(out.path()).unwrap(), rules);
+    }
+
+    #[test]
+    fn char_budget_flags_limits() {
+        let rule = |name: &str, len: usize| Rule { name: Some(name.into()), content: "x".repeat(len), ..Default::default() };
+        let budget = CharBudget::of(&[rule("small", 99), rule("big", FILE_CHAR_LIMIT), rule("huge", 7_000)]);
+        assert_eq!(budget.rules[0], ("small".to_string(), 100));
+        assert_eq!(budget.total, 100 + FILE_CHAR_LIMIT + 1 + 7_001);
+        assert_eq!(budget.oversized(), vec!["big", "huge"]);
+        assert!(budget.over_total());
+    }
+}
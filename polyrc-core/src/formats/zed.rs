@@ -0,0 +1,69 @@
+//! Zed reads project rules from a `.rules` file in the project root. (It also
+//! falls back to `AGENTS.md`, `CLAUDE.md` and others, which have their own
+//! formats here.)
+
+use std::path::Path;
+use crate::error::Result;
+use crate::formats::single_file;
+use crate::ir::Rule;
+use crate::parser::Parser;
+use crate::writer::{WriteOptions, Writer};
+
+/// The rules file, relative to the project root.
+pub const RULES_FILE: &str = ".rules";
+
+/// Name the parser gives the whole of `.rules`.
+pub const ZED_RULE_NAME: &str = "zed";
+
+pub struct ZedParser;
+pub struct ZedWriter;
+
+impl Parser for ZedParser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>> {
+        // A `.rules` directory belongs to some other tool and is skipped
+        Ok(single_file::parse(&path.join(RULES_FILE), ZED_RULE_NAME)?.into_iter().collect())
+    }
+}
+
+impl Writer for ZedWriter {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()> {
+        self.write_with(rules, target, &WriteOptions::default())
+    }
+
+    fn write_with(&self, rules: &[Rule], target: &Path, options: &WriteOptions) -> Result<()> {
+        if rules.is_empty() {
+            return Ok(());
+        }
+        single_file::write(&options.output_file.clone().unwrap_or_else(|| target.join(RULES_FILE)), rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn rules_file_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let rule = |name: &str, content: &str| Rule { name: Some(name.into()), content: content.into(), ..Default::default() };
+        ZedWriter.write(&[rule("style", "Use tabs."), rule("tests", "Add one.")], dir.path()).unwrap();
+        let written = fs::read_to_string(dir.path().join(RULES_FILE)).unwrap();
+        assert_eq!(written, "## style\n\nUse tabs.\n\n## tests\n\nAdd one.\n");
+
+        let parsed = ZedParser.parse(dir.path()).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name.as_deref(), Some(ZED_RULE_NAME));
+        assert_eq!(parsed[0].content, written.trim_end());
+    }
+
+    #[test]
+    fn rules_directory_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(RULES_FILE).join("lint")).unwrap();
+        assert!(ZedParser.parse(dir.path()).unwrap().is_empty());
+        let rule = Rule { name: Some("style".into()), content: "Use tabs.".into(), ..Default::default() };
+        let err = ZedWriter.write(&[rule], dir.path()).unwrap_err();
+        assert!(err.to_string().contains("is a directory"), "{err}");
+    }
+}
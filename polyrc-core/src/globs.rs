@@ -0,0 +1,177 @@
+//! Glob canonicalization and `--consolidate-globs`.
+//!
+//! Tools spell the same glob in different ways (`./src/**`, `src//**`, a
+//! comma list in one string). [`canonicalize`] maps a glob list to one
+//! spelling so rules can be compared by what they match, and [`consolidate`]
+//! uses that to merge rules that target the same files.
+
+use crate::ir::{Activation, Rule, Scope};
+
+/// More names than this and a merged rule is named after its globs instead.
+const MAX_JOINED_NAMES: usize = 3;
+
+/// Split a comma-separated glob list such as `*.md,src/**/*.{ts,tsx}` into
+/// trimmed globs. Commas inside `{}` belong to the glob. Empty entries are dropped.
+pub fn split_list(list: &str) -> Vec<String> {
+    let mut globs = vec![];
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in list.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                globs.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    globs.push(&list[start..]);
+    globs.into_iter().map(str::trim).filter(|g| !g.is_empty()).map(str::to_string).collect()
+}
+
+/// Trim each glob, split comma lists, drop a leading `./` and doubled
+/// slashes, then sort and deduplicate. Empty entries are dropped.
+pub fn canonicalize(globs: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = globs
+        .iter()
+        .flat_map(|g| split_list(g))
+        .map(|g| canonicalize_one(&g))
+        .filter(|g| !g.is_empty())
+        .collect();
+    out.sort();
+    out.dedup();
+    out
+}
+
+fn canonicalize_one(glob: &str) -> String {
+    let mut glob = glob.trim();
+    while let Some(rest) = glob.strip_prefix("./") {
+        glob = rest;
+    }
+    let mut out = String::with_capacity(glob.len());
+    for c in glob.chars() {
+        if !(c == '/' && out.ends_with('/')) {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Merge glob rules whose [canonical](canonicalize) glob sets are equal into
+/// one rule per set. Other rules, and glob sets only one rule targets, are
+/// left as they are. A merged rule takes the place of the first rule in its
+/// group; its content is each rule under a `## <name>` header.
+pub fn consolidate(rules: Vec<Rule>) -> Vec<Rule> {
+    let key = |r: &Rule| match (&r.activation, &r.globs) {
+        (Activation::Glob, Some(globs)) => Some(canonicalize(globs)).filter(|g| !g.is_empty()),
+        _ => None,
+    };
+    let mut groups: Vec<(Option<Vec<String>>, Vec<Rule>)> = vec![];
+    for rule in rules {
+        let globs = key(&rule);
+        match groups.iter_mut().find(|(k, _)| k.is_some() && *k == globs) {
+            Some((_, group)) => group.push(rule),
+            None => groups.push((globs, vec![rule])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(globs, mut group)| match globs {
+            Some(globs) if group.len() > 1 => merge(globs, group),
+            _ => group.remove(0),
+        })
+        .collect()
+}
+
+fn merge(globs: Vec<String>, group: Vec<Rule>) -> Rule {
+    let names: Vec<String> = group.iter().map(|r| r.name.clone().unwrap_or_else(|| r.filename_stem())).collect();
+    let name = if names.len() <= MAX_JOINED_NAMES { names.join("-") } else { name_for(&globs) };
+    let content = group
+        .iter()
+        .zip(&names)
+        .map(|(r, name)| format!("## {name}\n\n{}", r.content.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let descriptions: Vec<&str> = group.iter().filter_map(|r| r.description.as_deref()).collect();
+    let first = &group[0];
+    Rule {
+        scope: if group.iter().all(|r| r.scope == first.scope) { first.scope.clone() } else { Scope::Path },
+        activation: Activation::Glob,
+        globs: Some(globs),
+        name: Some(name),
+        description: (!descriptions.is_empty()).then(|| descriptions.join("; ")),
+        content,
+        order: group.iter().filter_map(|r| r.order).min(),
+        project: first.project.clone(),
+        source_format: first.source_format.clone(),
+        ..Default::default()
+    }
+}
+
+/// `["**/*.ts", "**/*.tsx"]` → `ts-tsx-files`.
+fn name_for(globs: &[String]) -> String {
+    let mut words: Vec<&str> = vec![];
+    for word in globs.iter().flat_map(|g| g.split(|c: char| !c.is_alphanumeric())).filter(|w| !w.is_empty()) {
+        if !words.contains(&word) {
+            words.push(word);
+        }
+    }
+    if words.is_empty() {
+        return "all-files".to_string();
+    }
+    format!("{}-files", words.join("-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob_rule(name: &str, globs: &[&str]) -> Rule {
+        Rule {
+            scope: Scope::Path,
+            activation: Activation::Glob,
+            globs: Some(globs.iter().map(|g| g.to_string()).collect()),
+            name: Some(name.into()),
+            content: format!("{name} content\n"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn canonical_spellings() {
+        let globs = |g: &[&str]| canonicalize(&g.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        assert_eq!(globs(&["./src//**/*.ts", " *.md "]), ["*.md", "src/**/*.ts"]);
+        assert_eq!(globs(&["*.ts,*.tsx", "*.ts", ""]), ["*.ts", "*.tsx"]);
+        assert_eq!(globs(&["*.tsx", "*.ts"]), globs(&["*.ts", "*.tsx"]));
+        assert_eq!(globs(&["src/**/*.{ts,tsx},*.md"]), ["*.md", "src/**/*.{ts,tsx}"]);
+    }
+
+    #[test]
+    fn same_glob_sets_are_merged_in_place() {
+        let always = Rule { name: Some("tone".into()), content: "Be terse.".into(), ..Default::default() };
+        let rules = vec![
+            glob_rule("api", &["**/*.ts"]),
+            always.clone(),
+            glob_rule("tests", &["src/**/*.rs"]),
+            glob_rule("style", &["./**/*.ts"]),
+        ];
+        let merged = consolidate(rules);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].name.as_deref(), Some("api-style"));
+        assert_eq!(merged[0].globs, Some(vec!["**/*.ts".to_string()]));
+        assert_eq!(merged[0].content, "## api\n\napi content\n\n## style\n\nstyle content");
+        assert_eq!(merged[1], always);
+        assert_eq!(merged[2], glob_rule("tests", &["src/**/*.rs"]));
+    }
+
+    #[test]
+    fn large_groups_are_named_after_their_globs() {
+        let rules: Vec<Rule> = ["a", "b", "c", "d"].iter().map(|n| glob_rule(n, &["**/*.ts", "**/*.tsx"])).collect();
+        let merged = consolidate(rules);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].name.as_deref(), Some("ts-tsx-files"));
+        assert_eq!(merged[0].content.matches("\n## ").count(), 3);
+        assert_eq!(name_for(&["**".to_string()]), "all-files");
+    }
+}
@@ -0,0 +1,298 @@
+//! Git pre-commit hooks that keep a repo's format files and the store in step.
+//!
+//! Generated files carry [`HOOK_MARKER`] so reinstalling replaces them in place
+//! and uninstalling never touches a hook polyrc did not write.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::{PolyrcError, Result};
+use crate::formats::Format;
+
+/// Marker line identifying polyrc-generated hooks.
+pub const HOOK_MARKER: &str = "# polyrc-managed-hook";
+/// File name of the pre-commit framework config.
+pub const PRE_COMMIT_CONFIG: &str = ".pre-commit-config.yaml";
+/// Prefix of hook ids polyrc adds to `.pre-commit-config.yaml`.
+const PRE_COMMIT_ID_PREFIX: &str = "polyrc-";
+
+/// What the hook does on commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookMode {
+    /// Run `polyrc check`; block the commit when files drifted from the store.
+    Check,
+    /// Run `polyrc push-format`, which only writes to the store; the files
+    /// being committed are left as they are.
+    Push,
+}
+
+impl HookMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Check => "check",
+            Self::Push => "push",
+        }
+    }
+}
+
+/// Which formats the hook covers: one, or all of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookFormats {
+    All,
+    One(Format),
+}
+
+impl HookFormats {
+    fn cli_args(&self) -> String {
+        match self {
+            Self::All => "--all".to_string(),
+            Self::One(f) => format!("--format {}", f.name()),
+        }
+    }
+}
+
+/// Render the `.git/hooks/pre-commit` shell script.
+pub fn render_script(mode: HookMode, project: &str, formats: &HookFormats) -> String {
+    let project = shell_quote(project);
+    let fmt_args = formats.cli_args();
+    let body = match mode {
+        HookMode::Check => format!(
+            r#"polyrc check --project {project} {fmt_args}
+status=$?
+if [ "$status" -eq 6 ]; then
+  echo "polyrc: rule files drifted from store project {project} — see above." >&2
+  echo "polyrc: run push-format or pull-format, or commit with --no-verify." >&2
+  exit 1
+elif [ "$status" -ne 0 ]; then
+  echo "polyrc: check failed (exit $status); not blocking the commit." >&2
+fi
+exit 0
+"#
+        ),
+        HookMode::Push => format!(
+            r#"polyrc push-format --project {project} {fmt_args} || exit 1
+exit 0
+"#
+        ),
+    };
+    format!(
+        r#"#!/bin/sh
+{HOOK_MARKER} (mode: {mode})
+# Reinstall with `polyrc hook install`, remove with `polyrc hook uninstall`.
+if ! command -v polyrc >/dev/null 2>&1; then
+  echo "polyrc: not found on PATH; skipping rule {mode}." >&2
+  exit 0
+fi
+{body}"#,
+        mode = mode.name()
+    )
+}
+
+/// Write the hook script into `hooks_dir/pre-commit`.
+///
+/// An existing polyrc hook is replaced; any other hook is left alone unless
+/// `force` is set.
+pub fn install_script(hooks_dir: &Path, script: &str, force: bool) -> Result<PathBuf> {
+    let file = hooks_dir.join("pre-commit");
+    if let Ok(existing) = fs::read_to_string(&file)
+        && !existing.contains(HOOK_MARKER)
+        && !force
+    {
+        return Err(PolyrcError::WriteFailure {
+            path: file,
+            reason: "a pre-commit hook not written by polyrc already exists (use --force to replace it)".to_string(),
+        });
+    }
+    fs::create_dir_all(hooks_dir).map_err(|e| PolyrcError::Io { path: hooks_dir.to_path_buf(), source: e })?;
+    fs::write(&file, script).map_err(|e| PolyrcError::Io { path: file.clone(), source: e })?;
+    make_executable(&file)?;
+    Ok(file)
+}
+
+/// Remove `hooks_dir/pre-commit` if polyrc wrote it. Returns whether a file was removed.
+pub fn uninstall_script(hooks_dir: &Path) -> Result<bool> {
+    let file = hooks_dir.join("pre-commit");
+    match fs::read_to_string(&file) {
+        Ok(existing) if existing.contains(HOOK_MARKER) => {
+            fs::remove_file(&file).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+            Ok(true)
+        }
+        Ok(_) => Err(PolyrcError::WriteFailure {
+            path: file,
+            reason: "pre-commit hook was not written by polyrc; leaving it alone".to_string(),
+        }),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Add (or replace) the polyrc hook in a pre-commit framework config.
+/// Other repos and hooks in the file are kept.
+pub fn install_pre_commit(config: &Path, mode: HookMode, project: &str, formats: &HookFormats) -> Result<()> {
+    let entry = match mode {
+        HookMode::Check => format!("polyrc check --project {} {}", shell_quote(project), formats.cli_args()),
+        HookMode::Push => format!("polyrc push-format --project {} {}", shell_quote(project), formats.cli_args()),
+    };
+    let hook = serde_yml::from_str::<serde_yml::Value>(&format!(
+        "id: {PRE_COMMIT_ID_PREFIX}{mode}\nname: polyrc {mode}\nentry: {entry:?}\nlanguage: system\npass_filenames: false\nalways_run: true\n",
+        mode = mode.name(),
+    ))
+    .expect("static hook YAML parses");
+
+    let mut doc = read_pre_commit(config)?;
+    remove_polyrc_hooks(&mut doc);
+    let repos = doc
+        .as_mapping_mut()
+        .expect("read_pre_commit returns a mapping")
+        .entry("repos".into())
+        .or_insert_with(|| serde_yml::Value::Sequence(vec![]));
+    let Some(repos) = repos.as_sequence_mut() else {
+        return Err(PolyrcError::WriteFailure { path: config.to_path_buf(), reason: "`repos` is not a list".to_string() });
+    };
+    let mut local = serde_yml::Mapping::new();
+    local.insert("repo".into(), "local".into());
+    local.insert("hooks".into(), serde_yml::Value::Sequence(vec![hook]));
+    repos.push(serde_yml::Value::Mapping(local));
+    write_pre_commit(config, &doc)
+}
+
+/// Remove polyrc hooks from a pre-commit framework config. Returns whether any were removed.
+pub fn uninstall_pre_commit(config: &Path) -> Result<bool> {
+    if !config.exists() {
+        return Ok(false);
+    }
+    let mut doc = read_pre_commit(config)?;
+    let removed = remove_polyrc_hooks(&mut doc);
+    if removed {
+        write_pre_commit(config, &doc)?;
+    }
+    Ok(removed)
+}
+
+fn read_pre_commit(config: &Path) -> Result<serde_yml::Value> {
+    if !config.exists() {
+        return Ok(serde_yml::Value::Mapping(Default::default()));
+    }
+    let raw = fs::read_to_string(config).map_err(|e| PolyrcError::Io { path: config.to_path_buf(), source: e })?;
+    let doc: serde_yml::Value = serde_yml::from_str(&raw)
+        .map_err(|e| PolyrcError::YamlParse { path: config.to_path_buf(), source: e })?;
+    match doc {
+        serde_yml::Value::Mapping(_) => Ok(doc),
+        serde_yml::Value::Null => Ok(serde_yml::Value::Mapping(Default::default())),
+        _ => Err(PolyrcError::WriteFailure { path: config.to_path_buf(), reason: "not a YAML mapping".to_string() }),
+    }
+}
+
+fn write_pre_commit(config: &Path, doc: &serde_yml::Value) -> Result<()> {
+    let out = serde_yml::to_string(doc).map_err(|e| PolyrcError::YamlParse { path: config.to_path_buf(), source: e })?;
+    fs::write(config, out).map_err(|e| PolyrcError::Io { path: config.to_path_buf(), source: e })
+}
+
+/// Drop polyrc hooks, and any `local` repo entry left without hooks.
+fn remove_polyrc_hooks(doc: &mut serde_yml::Value) -> bool {
+    let Some(repos) = doc.get_mut("repos").and_then(|r| r.as_sequence_mut()) else {
+        return false;
+    };
+    let mut removed = false;
+    for repo in repos.iter_mut() {
+        if let Some(hooks) = repo.get_mut("hooks").and_then(|h| h.as_sequence_mut()) {
+            let before = hooks.len();
+            hooks.retain(|h| {
+                !h.get("id").and_then(|id| id.as_str()).is_some_and(|id| id.starts_with(PRE_COMMIT_ID_PREFIX))
+            });
+            removed |= hooks.len() != before;
+        }
+    }
+    repos.retain(|repo| {
+        repo.get("repo").and_then(|r| r.as_str()) != Some("local")
+            || repo.get("hooks").and_then(|h| h.as_sequence()).is_some_and(|h| !h.is_empty())
+    });
+    removed
+}
+
+/// Single-quote `s` for POSIX sh.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(unix)]
+fn make_executable(file: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(file, fs::Permissions::from_mode(0o755))
+        .map_err(|e| PolyrcError::Io { path: file.to_path_buf(), source: e })
+}
+
+#[cfg(not(unix))]
+fn make_executable(_file: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_script_blocks_on_drift_and_tolerates_missing_binary() {
+        let script = render_script(HookMode::Check, "myApp", &HookFormats::All);
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains(HOOK_MARKER));
+        assert!(script.contains("command -v polyrc"));
+        assert!(script.contains("polyrc check --project 'myApp' --all"));
+        assert!(script.contains(r#"if [ "$status" -eq 6 ]; then"#));
+    }
+
+    #[test]
+    fn push_script_pushes_without_staging() {
+        let script = render_script(HookMode::Push, "myApp", &HookFormats::One(Format::Cursor));
+        assert!(script.contains("polyrc push-format --project 'myApp' --format cursor || exit 1"));
+        assert!(!script.contains("git add"));
+    }
+
+    #[test]
+    fn shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn reinstall_is_idempotent_and_uninstall_removes() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = dir.path().join("hooks");
+        let first = render_script(HookMode::Check, "a", &HookFormats::All);
+        let file = install_script(&hooks, &first, false).unwrap();
+        let second = render_script(HookMode::Push, "a", &HookFormats::All);
+        install_script(&hooks, &second, false).unwrap();
+        install_script(&hooks, &second, false).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), second);
+
+        assert!(uninstall_script(&hooks).unwrap());
+        assert!(!file.exists());
+        assert!(!uninstall_script(&hooks).unwrap());
+    }
+
+    #[test]
+    fn foreign_hook_is_not_overwritten_or_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("pre-commit"), "#!/bin/sh\nmake lint\n").unwrap();
+        let script = render_script(HookMode::Check, "a", &HookFormats::All);
+        assert!(install_script(dir.path(), &script, false).is_err());
+        assert!(uninstall_script(dir.path()).is_err());
+        install_script(dir.path(), &script, true).unwrap();
+    }
+
+    #[test]
+    fn pre_commit_config_keeps_other_hooks_and_reinstalls_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join(PRE_COMMIT_CONFIG);
+        fs::write(&config, "repos:\n  - repo: https://github.com/pre-commit/pre-commit-hooks\n    rev: v4.0.0\n    hooks:\n      - id: trailing-whitespace\n").unwrap();
+
+        install_pre_commit(&config, HookMode::Check, "a", &HookFormats::All).unwrap();
+        install_pre_commit(&config, HookMode::Check, "a", &HookFormats::All).unwrap();
+        let raw = fs::read_to_string(&config).unwrap();
+        assert_eq!(raw.matches("polyrc-check").count(), 1);
+        assert!(raw.contains("trailing-whitespace"));
+
+        assert!(uninstall_pre_commit(&config).unwrap());
+        let raw = fs::read_to_string(&config).unwrap();
+        assert!(!raw.contains("polyrc"));
+        assert!(!raw.contains("repo: local"));
+        assert!(raw.contains("trailing-whitespace"));
+    }
+}
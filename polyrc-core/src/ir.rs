@@ -0,0 +1,547 @@
+use serde::{Deserialize, Serialize};
+use crate::error::{PolyrcError, Result};
+
+/// The canonical scope of a rule in the interlingua.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    User,
+    #[default]
+    Project,
+    Path,
+}
+
+/// The activation mode of a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+    /// Always injected into context.
+    #[default]
+    Always,
+    /// Injected when a file matching any glob in `globs` is open/edited.
+    Glob,
+    /// User must manually invoke (slash command, @mention, etc.).
+    OnDemand,
+    /// AI decides whether to load based on `description`.
+    AiDecides,
+}
+
+impl Activation {
+    /// The serialized name (`on_demand`, …).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Glob => "glob",
+            Self::OnDemand => "on_demand",
+            Self::AiDecides => "ai_decides",
+        }
+    }
+}
+
+/// A single rule in the polyrc intermediate representation.
+///
+/// Core fields (scope, activation, globs, name, description, content) are used by
+/// all format parsers and writers. Metadata fields (id, project, source_format,
+/// created_at, updated_at, store_version) are only populated when rules pass through
+/// the store; format writers ignore them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Rule {
+    // --- Core IR fields ---
+    pub scope: Scope,
+    pub activation: Activation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub globs: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Raw markdown content — opaque, not parsed by polyrc.
+    pub content: String,
+    /// Load-order position, from a `01-` style filename prefix. Multi-file
+    /// writers re-emit it as a zero-padded prefix.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
+    /// Frontmatter keys polyrc has no field for, as YAML text (e.g. a Claude
+    /// command's `allowed-tools`), written back by the format that read them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frontmatter: Option<String>,
+    /// Files next to the rule that belong to it, relative to its directory (e.g.
+    /// a Claude skill's `scripts/run.py`). Recorded, not copied into the store.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<Vec<String>>,
+
+    // --- Store metadata (populated by push-format; ignored by format writers) ---
+    /// Stable UUIDv4 identifier assigned on first push to the store.
+    #[serde(default)]
+    pub id: String,
+    /// Project key in the store (e.g. "myapp", "_user").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// The format that last wrote this rule (e.g. "cursor", "claude").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_format: Option<String>,
+    /// Where the rule was imported from (file path or URL), for `push-rule`.
+    /// The Claude writer reads it to put agents back in `.claude/agents/`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+    /// RFC3339 timestamp of first push.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// RFC3339 timestamp of last push.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    /// Store format version for future migration handling.
+    #[serde(default = "default_store_version")]
+    pub store_version: String,
+}
+
+fn default_store_version() -> String {
+    "1".to_string()
+}
+
+impl Rule {
+    /// Returns a stable filename stem for use in multi-file format writers.
+    pub fn filename_stem(&self) -> String {
+        match &self.name {
+            Some(n) => sanitize_filename(n),
+            None => format!("rule_{:08x}", fnv1a(self.content.as_bytes())),
+        }
+    }
+
+    /// Filename stem for format output: [`filename_stem`](Self::filename_stem)
+    /// behind a zero-padded `NN-` prefix when `order` is set.
+    pub fn output_stem(&self) -> String {
+        match self.order {
+            Some(n) => format!("{:02}-{}", n, self.filename_stem()),
+            None => self.filename_stem(),
+        }
+    }
+
+    /// Equal in every field a format carries (scope, activation, globs,
+    /// description, order, extra frontmatter, resources and content, ignoring
+    /// surrounding whitespace). Store metadata is not compared.
+    pub fn same_content(&self, other: &Rule) -> bool {
+        self.scope == other.scope
+            && self.activation == other.activation
+            && self.globs == other.globs
+            && self.description == other.description
+            && self.order == other.order
+            && self.frontmatter.as_deref().map(str::trim) == other.frontmatter.as_deref().map(str::trim)
+            && self.resources == other.resources
+            && self.content.trim() == other.content.trim()
+    }
+}
+
+/// Longest description [`derive_description`] produces, in characters.
+pub const DERIVED_DESCRIPTION_MAX_CHARS: usize = 150;
+
+/// Guess a one-line description from markdown rule content: the text of the
+/// first heading, else the first paragraph cut at a word boundary to at most
+/// [`DERIVED_DESCRIPTION_MAX_CHARS`]. Code blocks, HTML comments and
+/// horizontal rules are skipped; returns `None` when nothing usable remains.
+///
+/// ```
+/// use polyrc_core::ir::derive_description;
+///
+/// assert_eq!(derive_description("# Rust style\n\nUse rustfmt.").as_deref(), Some("Rust style"));
+/// ```
+pub fn derive_description(content: &str) -> Option<String> {
+    let mut in_fence = false;
+    let mut in_comment = false;
+    let mut paragraph: Vec<&str> = vec![];
+    let mut first_paragraph: Option<String> = None;
+
+    for line in content.lines() {
+        let t = line.trim();
+        if t.starts_with("```") || t.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if in_comment || t.starts_with("<!--") {
+            in_comment = !t.contains("-->");
+            continue;
+        }
+        if let Some(heading) = markdown_heading(t) {
+            return Some(truncate_words(&heading, DERIVED_DESCRIPTION_MAX_CHARS));
+        }
+        let is_rule = t.len() >= 3 && t.chars().all(|c| c == '-' || c == '*' || c == '_' || c == ' ');
+        if t.is_empty() || is_rule {
+            if first_paragraph.is_none() && !paragraph.is_empty() {
+                first_paragraph = Some(paragraph.join(" "));
+            }
+            paragraph.clear();
+            continue;
+        }
+        paragraph.push(t.trim_start_matches(['-', '*', '+', '>', ' ']));
+    }
+    if first_paragraph.is_none() && !paragraph.is_empty() {
+        first_paragraph = Some(paragraph.join(" "));
+    }
+    let text = strip_inline_markup(&first_paragraph?);
+    (!text.is_empty()).then(|| truncate_words(&text, DERIVED_DESCRIPTION_MAX_CHARS))
+}
+
+/// Text of an ATX heading (`## Title ##`), without inline markup.
+fn markdown_heading(line: &str) -> Option<String> {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&hashes) {
+        return None;
+    }
+    let rest = line[hashes..].strip_prefix([' ', '\t'])?;
+    let text = strip_inline_markup(rest.trim().trim_end_matches('#').trim());
+    (!text.is_empty()).then_some(text)
+}
+
+/// Drop emphasis/code markers and collapse whitespace.
+fn strip_inline_markup(text: &str) -> String {
+    let text: String = text.chars().filter(|c| !matches!(c, '*' | '`')).collect();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Cut `text` to at most `max` characters at a word boundary, marking the cut with `…`.
+fn truncate_words(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    for word in text.split_whitespace() {
+        let needed = if out.is_empty() { word.chars().count() } else { out.chars().count() + 1 + word.chars().count() };
+        if needed + 1 > max {
+            break;
+        }
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    if out.is_empty() {
+        out = text.chars().take(max - 1).collect();
+    }
+    out.trim_end_matches([',', ';', ':', '.']).to_string() + "…"
+}
+
+/// Relative output path (without extension, `/`-separated) for a rule whose
+/// name may be nested with `sep` (`git/commit`). Each level is sanitized like
+/// [`Rule::filename_stem`]; `.`/`..` levels are dropped.
+pub(crate) fn nested_output_stem(rule: &Rule, sep: char) -> String {
+    let Some(name) = rule.name.as_deref().filter(|n| n.contains(sep)) else {
+        return rule.output_stem();
+    };
+    let mut parts: Vec<String> = name
+        .split(sep)
+        .filter(|s| !s.is_empty() && *s != "." && *s != "..")
+        .map(sanitize_filename)
+        .collect();
+    let Some(last) = parts.pop() else {
+        return rule.output_stem();
+    };
+    parts.push(match rule.order {
+        Some(n) => format!("{:02}-{}", n, last),
+        None => last,
+    });
+    parts.join("/")
+}
+
+/// Split a load-order prefix (`01-`, `2_`) off a file stem. At most three
+/// digits are recognised so date-like names (`2024-notes`) are left alone, and
+/// the remainder must be non-empty.
+///
+/// ```
+/// use polyrc_core::ir::split_order_prefix;
+///
+/// assert_eq!(split_order_prefix("01-overview"), (Some(1), "overview"));
+/// assert_eq!(split_order_prefix("2024-notes"), (None, "2024-notes"));
+/// ```
+pub fn split_order_prefix(stem: &str) -> (Option<u32>, &str) {
+    let digits = stem.bytes().take_while(u8::is_ascii_digit).count();
+    if (1..=3).contains(&digits)
+        && let Some(rest) = stem[digits..].strip_prefix(['-', '_'])
+        && !rest.is_empty()
+    {
+        return (stem[..digits].parse().ok(), rest);
+    }
+    (None, stem)
+}
+
+/// Filename stems for `rules` in the store, one per rule in the same order,
+/// with collisions resolved. Rules whose [`Rule::filename_stem`] clash (e.g.
+/// "API Design" and "api-design") are ordered by name, then content; the first
+/// keeps the stem and the rest get `-2`, `-3`, … Each collision is raised as a [notice](crate::notice).
+pub fn unique_filename_stems<'a>(rules: impl IntoIterator<Item = &'a Rule>) -> Vec<String> {
+    let rules: Vec<&Rule> = rules.into_iter().collect();
+    let stems = rules.iter().map(|r| r.filename_stem()).collect();
+    uniquify(&rules, stems)
+}
+
+/// [`unique_filename_stems`] without the warnings: the file names rules already
+/// in the store were saved under.
+pub fn stored_filename_stems<'a>(rules: impl IntoIterator<Item = &'a Rule>) -> Vec<String> {
+    let rules: Vec<&Rule> = rules.into_iter().collect();
+    let stems = rules.iter().map(|r| r.filename_stem()).collect();
+    resolve_collisions(&rules, stems).0
+}
+
+/// Like [`unique_filename_stems`], but for format writers: stems carry the
+/// rule's `order` prefix ([`Rule::output_stem`]).
+pub fn unique_output_stems<'a>(rules: impl IntoIterator<Item = &'a Rule>) -> Vec<String> {
+    let rules: Vec<&Rule> = rules.into_iter().collect();
+    let stems = rules.iter().map(|r| r.output_stem()).collect();
+    uniquify(&rules, stems)
+}
+
+/// Resolve collisions among precomputed `stems` (one per rule); see [`unique_filename_stems`].
+pub(crate) fn uniquify(rules: &[&Rule], stems: Vec<String>) -> Vec<String> {
+    let (out, warnings) = resolve_collisions(rules, stems);
+    for warning in warnings {
+        crate::notice::warn(warning);
+    }
+    out
+}
+
+/// The stems with collisions resolved, and a line naming each group of colliding rules.
+fn resolve_collisions(rules: &[&Rule], stems: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut warnings = vec![];
+    let mut taken: std::collections::HashSet<String> = stems.iter().cloned().collect();
+    let mut out = stems.clone();
+
+    let mut groups: std::collections::BTreeMap<&str, Vec<usize>> = std::collections::BTreeMap::new();
+    for (i, stem) in stems.iter().enumerate() {
+        groups.entry(stem).or_default().push(i);
+    }
+    for (stem, mut members) in groups.into_iter().filter(|(_, m)| m.len() > 1) {
+        members.sort_by(|&a, &b| {
+            (&rules[a].name, &rules[a].content, a).cmp(&(&rules[b].name, &rules[b].content, b))
+        });
+        let mut n = 2;
+        for &i in &members[1..] {
+            while taken.contains(&format!("{stem}-{n}")) {
+                n += 1;
+            }
+            out[i] = format!("{stem}-{n}");
+            taken.insert(out[i].clone());
+        }
+        let names: Vec<String> = members
+            .iter()
+            .map(|&i| {
+                let name = rules[i].name.as_deref().unwrap_or("<unnamed>");
+                format!("'{}' ({}) → {}", name, rules[i].activation.as_str(), out[i])
+            })
+            .collect();
+        warnings.push(format!("rules share the file name '{}'; writing {}", stem, names.join(", ")));
+    }
+    (out, warnings)
+}
+
+/// Longest rule name accepted, in bytes (leaves room for the extension on any filesystem).
+pub const MAX_RULE_NAME_LEN: usize = 128;
+
+/// Device names Windows refuses as file names, with or without an extension.
+const WINDOWS_RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Validate a user-supplied rule name before it is used as a store file name.
+///
+/// Rejects empty names, path separators, `..` and leading dots, control and
+/// Windows-forbidden characters, reserved device names, trailing dots/spaces,
+/// and names longer than `MAX_RULE_NAME_LEN`.
+///
+/// ```
+/// use polyrc_core::ir::validate_rule_name;
+///
+/// assert!(validate_rule_name("rust-style").is_ok());
+/// assert!(validate_rule_name("../escape").is_err());
+/// assert!(validate_rule_name("CON").is_err());
+/// ```
+pub fn validate_rule_name(name: &str) -> Result<()> {
+    let reject = |reason: &str| {
+        Err(PolyrcError::InvalidName { name: name.to_string(), reason: reason.to_string() })
+    };
+    if name.trim().is_empty() {
+        return reject("name is empty");
+    }
+    if name.len() > MAX_RULE_NAME_LEN {
+        return reject(&format!("longer than {} bytes", MAX_RULE_NAME_LEN));
+    }
+    if name.contains(['/', '\\']) {
+        return reject("contains a path separator");
+    }
+    if name.contains("..") || name.starts_with('.') {
+        return reject("must not start with '.' or contain '..'");
+    }
+    if name.chars().any(char::is_control) {
+        return reject("contains control characters");
+    }
+    if name.contains(['<', '>', ':', '"', '|', '?', '*']) {
+        return reject("contains a character not allowed in file names (<>:\"|?*)");
+    }
+    if name.ends_with(['.', ' ']) {
+        return reject("must not end with '.' or a space");
+    }
+    let base = name.split('.').next().unwrap_or(name).trim_end();
+    if WINDOWS_RESERVED.iter().any(|r| r.eq_ignore_ascii_case(base)) {
+        return reject("is a reserved device name on Windows");
+    }
+    Ok(())
+}
+
+pub(crate) fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else if c == ' ' {
+                '-'
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn fnv1a(data: &[u8]) -> u32 {
+    data.iter()
+        .fold(2_166_136_261u32, |acc, &b| acc.wrapping_mul(16_777_619).wrapping_add(b as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_rule(name: Option<&str>, content: &str) -> Rule {
+        Rule {
+            scope: Scope::Project,
+            activation: Activation::Always,
+            globs: None,
+            name: name.map(str::to_string),
+            description: None,
+            content: content.to_string(),
+            id: String::new(),
+            project: None,
+            source_format: None,
+            source_path: None,
+            created_at: None,
+            updated_at: None,
+            order: None,
+            frontmatter: None,
+            resources: None,
+            store_version: "1".to_string(),
+        }
+    }
+
+    #[test]
+    fn filename_stem_uses_name() {
+        let rule = make_rule(Some("My Rule"), "content");
+        assert_eq!(rule.filename_stem(), "my-rule");
+    }
+
+    #[test]
+    fn filename_stem_fallback_is_stable() {
+        let rule = make_rule(None, "hello world");
+        let stem1 = rule.filename_stem();
+        let stem2 = rule.filename_stem();
+        assert_eq!(stem1, stem2);
+        assert!(stem1.starts_with("rule_"));
+    }
+
+    #[test]
+    fn same_content_compares_resources() {
+        let skill = Rule { resources: Some(vec!["run.py".into()]), ..make_rule(Some("pdf"), "Run it.") };
+        let more = Rule { resources: Some(vec!["run.py".into(), "reference.md".into()]), ..skill.clone() };
+        assert!(skill.same_content(&skill.clone()));
+        assert!(!skill.same_content(&more));
+        assert!(!skill.same_content(&Rule { resources: None, ..skill.clone() }));
+    }
+
+    #[test]
+    fn colliding_stems_get_numbered_by_name() {
+        let rules = vec![
+            make_rule(Some("api-design"), "b"),
+            make_rule(Some("API Design"), "a"),
+            make_rule(Some("api-design-2"), "c"),
+            make_rule(Some("other"), "d"),
+        ];
+        assert_eq!(unique_filename_stems(&rules), vec!["api-design-3", "api-design", "api-design-2", "other"]);
+    }
+
+    #[test]
+    fn derived_description_prefers_first_heading() {
+        let content = "<!-- generated -->\nSome intro.\n\n```md\n# not a heading\n```\n## **Error** handling ##\nBody";
+        assert_eq!(derive_description(content).as_deref(), Some("Error handling"));
+        assert_eq!(derive_description("#hashtag only\n").as_deref(), Some("#hashtag only"));
+    }
+
+    #[test]
+    fn derived_description_falls_back_to_first_paragraph() {
+        let content = "\n\n---\n- Use `anyhow` for\n  application errors.\n\nSecond paragraph.";
+        assert_eq!(derive_description(content).as_deref(), Some("Use anyhow for application errors."));
+        assert_eq!(derive_description("```json\n{}\n```\n"), None);
+        assert_eq!(derive_description("  \n"), None);
+    }
+
+    #[test]
+    fn derived_description_truncates_at_word_boundary() {
+        let long = "word ".repeat(60);
+        let d = derive_description(&long).unwrap();
+        assert!(d.chars().count() <= DERIVED_DESCRIPTION_MAX_CHARS);
+        assert!(d.ends_with("word…"), "{d}");
+
+        let unbroken = "x".repeat(400);
+        assert_eq!(derive_description(&unbroken).unwrap().chars().count(), DERIVED_DESCRIPTION_MAX_CHARS);
+    }
+
+    #[test]
+    fn order_prefix_parsing() {
+        assert_eq!(split_order_prefix("02_style"), (Some(2), "style"));
+        assert_eq!(split_order_prefix("100-last"), (Some(100), "last"));
+        assert_eq!(split_order_prefix("01-"), (None, "01-"));
+        assert_eq!(split_order_prefix("v1-style"), (None, "v1-style"));
+
+        let rule = Rule { name: Some("Style".into()), order: Some(3), ..Default::default() };
+        assert_eq!(rule.output_stem(), "03-style");
+    }
+
+    #[test]
+    fn validate_accepts_ordinary_names() {
+        for name in ["rust-gitignore", "My Rule", "auto_pr", "v1.2-style", "日本語"] {
+            assert!(validate_rule_name(name).is_ok(), "{name}");
+        }
+    }
+
+    #[test]
+    fn validate_rejects_traversal_and_separators() {
+        for name in ["../../evil", "a/b", "a\\b", "..", "x..y", ".hidden"] {
+            assert!(validate_rule_name(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn validate_rejects_control_and_forbidden_chars() {
+        for name in ["a\nb", "tab\there", "a:b", "what?", "star*", "pipe|x"] {
+            assert!(validate_rule_name(name).is_err(), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn validate_rejects_windows_reserved_names() {
+        for name in ["con", "CON", "nul.txt", "Com1", "lpt9", "trailing.", "trailing "] {
+            assert!(validate_rule_name(name).is_err(), "{name}");
+        }
+        assert!(validate_rule_name("console").is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_empty_and_overlong() {
+        assert!(validate_rule_name("").is_err());
+        assert!(validate_rule_name("   ").is_err());
+        assert!(validate_rule_name(&"a".repeat(MAX_RULE_NAME_LEN)).is_ok());
+        assert!(validate_rule_name(&"a".repeat(MAX_RULE_NAME_LEN + 1)).is_err());
+    }
+
+}
@@ -0,0 +1,72 @@
+//! polyrc-core — the library behind the `polyrc` CLI, for converting AI coding
+//! agent configurations between tools from your own Rust code.
+//!
+//! The core pieces:
+//!
+//! - [`ir`] — the tool-neutral [`ir::Rule`] every format converts through.
+//! - [`formats`] — the [`formats::Format`] registry with a [`parser::Parser`]
+//!   and [`writer::Writer`] per tool.
+//! - [`store`] — the git-backed rule store (`polyrc init`, `push-format`, …).
+//! - [`config`] — `~/polyrc/config.toml`.
+//! - [`ops`] — high-level operations such as [`convert`].
+//!
+//! The types most callers need are re-exported at the root: [`Rule`],
+//! [`Scope`], [`Activation`], [`Format`], [`Parser`], [`Writer`] and [`Store`].
+//! Reading a project and checking it against another tool's files needs nothing
+//! else:
+//!
+//! ```no_run
+//! use polyrc_core::{Format, Parser};
+//!
+//! let cursor = Format::Cursor.parser().parse("./my-project".as_ref())?;
+//! let claude = Format::Claude.parser().parse("./my-project".as_ref())?;
+//! assert_eq!(cursor.len(), claude.len(), "cursor and claude rules out of sync");
+//! # Ok::<(), polyrc_core::PolyrcError>(())
+//! ```
+//!
+//! [`formats::split_frontmatter`] is public for tools that read the same
+//! markdown-with-YAML files. How the single-file formats join several rules
+//! into one file is not: that is whatever their [`Writer`] writes.
+//!
+//! ```
+//! use std::str::FromStr;
+//! use polyrc_core::formats::Format;
+//!
+//! let fmt = Format::from_str("claude-code").unwrap();
+//! assert_eq!(fmt, Format::Claude);
+//! assert!(Format::from_str("cursr").is_err());
+//! ```
+
+pub mod budget;
+pub mod config;
+pub mod diagnostics;
+pub mod drift;
+pub mod error;
+pub mod fetch;
+pub mod formats;
+pub mod globs;
+pub mod hook;
+pub mod ir;
+pub mod lock;
+pub mod logfile;
+pub mod loss;
+pub mod monorepo;
+pub mod notice;
+pub mod ops;
+pub mod pack;
+pub mod parser;
+pub mod plan;
+pub mod search;
+pub mod store;
+pub mod suggest;
+pub mod sync;
+pub mod verify;
+pub mod writer;
+
+pub use error::{PolyrcError, Result};
+pub use formats::Format;
+pub use ir::{Activation, Rule, Scope};
+pub use parser::Parser;
+pub use store::Store;
+pub use writer::Writer;
+pub use ops::{convert, ConvertOptions, ConvertReport};
@@ -0,0 +1,360 @@
+//! `.polyrc.lock` — the files polyrc wrote into an output root.
+//!
+//! `pull-format` and `convert` record, per format, each file they wrote with the
+//! rules it came from and a hash of its content. That tells polyrc-written files
+//! apart from hand-made ones: `pull-format --prune` only deletes files listed
+//! here, and a file whose hash no longer matches was edited since it was written.
+//!
+//! The lock is pretty-printed JSON with sorted keys so it diffs cleanly when
+//! committed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::error::{PolyrcError, Result};
+use crate::formats::Format;
+use crate::ir::Rule;
+use crate::writer::WriteOptions;
+
+/// Lock file name at the output root.
+pub const LOCK_FILE: &str = ".polyrc.lock";
+const LOCK_VERSION: u32 = 1;
+/// Where `--output-file` is redirected inside the scratch directory of [`plan`].
+const OUTPUT_FILE_SLOT: &str = "polyrc-output-file";
+
+/// Lock key → content, as rendered by [`render`].
+type Rendered = BTreeMap<String, Vec<u8>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    /// Format name → `/`-separated path relative to the output root → file.
+    #[serde(default)]
+    pub formats: BTreeMap<String, BTreeMap<String, LockedFile>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedFile {
+    /// Hex SHA-256 of the file as written.
+    pub sha256: String,
+    /// Rules rendered into the file, sorted by name.
+    pub rules: Vec<LockedRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LockedRule {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+}
+
+/// State of a lock-listed file on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileState {
+    Clean,
+    /// Content changed since polyrc wrote it.
+    Modified,
+    Missing,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self { version: LOCK_VERSION, formats: BTreeMap::new() }
+    }
+}
+
+impl Lockfile {
+    /// The lock in `root`, or an empty one if there is none.
+    pub fn load(root: &Path) -> Result<Self> {
+        let file = root.join(LOCK_FILE);
+        if !file.exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(&file).map_err(|e| PolyrcError::Io { path: file.clone(), source: e })?;
+        serde_json::from_str(&raw).map_err(|e| PolyrcError::ConfigError {
+            msg: format!("invalid lock file {}: {e}", file.display()),
+        })
+    }
+
+    /// Write the lock to `root`, or remove it when no files are listed.
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let file = root.join(LOCK_FILE);
+        if self.formats.values().all(BTreeMap::is_empty) {
+            if file.exists() {
+                fs::remove_file(&file).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+            }
+            return Ok(());
+        }
+        let json = serde_json::to_string_pretty(self).expect("lock serializes");
+        fs::write(&file, json + "\n").map_err(|e| PolyrcError::Io { path: file, source: e })
+    }
+
+    /// Every listed file with its state on disk, sorted by format then path.
+    pub fn status(&self, root: &Path) -> Vec<(String, String, FileState)> {
+        self.formats
+            .iter()
+            .flat_map(|(format, files)| {
+                files.iter().map(move |(path, locked)| {
+                    (format.clone(), path.clone(), file_state(&root.join(path), &locked.sha256))
+                })
+            })
+            .collect()
+    }
+
+    /// Files under `root` inside a format's [`rule_dirs`](Format::rule_dirs)
+    /// that its parser doesn't read and no format has written, as
+    /// `(format, path)` in format order. Dotfiles (`.gitkeep`,
+    /// `.DS_Store`) are not reported.
+    pub fn unmanaged(&self, root: &Path) -> Vec<(String, String)> {
+        let written = |key: &str| self.formats.values().any(|files| files.contains_key(key));
+        let mut found = vec![];
+        for fmt in Format::all() {
+            for dir in fmt.rule_dirs() {
+                let base = root.join(dir.path);
+                let files = walkdir::WalkDir::new(&base).min_depth(1).sort_by_file_name().into_iter().flatten();
+                for entry in files.filter(|e| e.file_type().is_file()) {
+                    let rel = entry.path().strip_prefix(&base).expect("walk stays under its root");
+                    let hidden = rel.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+                    let key = format!("{}/{}", dir.path, rel.to_string_lossy().replace('\\', "/"));
+                    if !hidden && !dir.claims(rel) && !written(&key) {
+                        found.push((fmt.name().to_string(), key));
+                    }
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Compare the file at `path` with the hash it was written with.
+pub fn file_state(path: &Path, sha256: &str) -> FileState {
+    match hash_file(path) {
+        None => FileState::Missing,
+        Some(hash) if hash == sha256 => FileState::Clean,
+        Some(_) => FileState::Modified,
+    }
+}
+
+/// Hex SHA-256 of a file's content, `None` if it can't be read.
+pub fn hash_file(path: &Path) -> Option<String> {
+    fs::read(path).ok().map(|bytes| hex::encode(Sha256::digest(bytes)))
+}
+
+/// A file as `fmt`'s writer renders it, before it is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedFile {
+    /// Rules rendered into the file, sorted by name.
+    pub rules: Vec<LockedRule>,
+    pub content: Vec<u8>,
+}
+
+/// The files `fmt` writes for `rules` into `root`, keyed by lock path.
+/// Rendered in a scratch directory; nothing under `root` is touched and no
+/// notices are raised. Empty for plugin formats, whose command would have to
+/// run to find out.
+///
+/// Each rule is also rendered on its own to tell which rules went into which
+/// file: a file belongs to the rule that renders it alone byte for byte, else
+/// (a single file collecting several rules) to every rule rendered into that path.
+pub fn plan(fmt: &Format, rules: &[Rule], root: &Path, options: &WriteOptions) -> Result<BTreeMap<String, RenderedFile>> {
+    if fmt.is_plugin() {
+        return Ok(BTreeMap::new());
+    }
+    let all = render(fmt, rules, root, options)?;
+    let alone: Vec<Rendered> =
+        rules.iter().map(|r| render(fmt, std::slice::from_ref(r), root, options)).collect::<Result<_>>()?;
+    let owners = |key: &str, content: &[u8]| -> Vec<LockedRule> {
+        let matching = |matches: &dyn Fn(&Rendered) -> bool| -> Vec<usize> {
+            (0..rules.len()).filter(|&i| matches(&alone[i])).collect()
+        };
+        let mut found = matching(&|files| files.get(key).is_some_and(|c| c == content));
+        if found.is_empty() {
+            // Renamed apart from a rule with the same file name
+            found = matching(&|files| files.values().any(|c| c == content));
+        }
+        if found.is_empty() {
+            found = matching(&|files| files.contains_key(key));
+        }
+        let mut found: Vec<LockedRule> = found
+            .into_iter()
+            .map(|i| LockedRule { name: rules[i].name.clone().unwrap_or_else(|| rules[i].filename_stem()), id: rules[i].id.clone() })
+            .collect();
+        found.sort();
+        found.dedup();
+        found
+    };
+    Ok(all
+        .into_iter()
+        .map(|(key, content)| {
+            let rules = owners(&key, &content);
+            (key, RenderedFile { rules, content })
+        })
+        .collect())
+}
+
+/// Render `rules` as `fmt` in a scratch directory.
+fn render(fmt: &Format, rules: &[Rule], root: &Path, options: &WriteOptions) -> Result<Rendered> {
+    let scratch = std::env::temp_dir().join(format!("polyrc-lock-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&scratch).map_err(|e| PolyrcError::Io { path: scratch.clone(), source: e })?;
+    let scratch_options = WriteOptions { output_file: options.output_file.as_ref().map(|_| scratch.join(OUTPUT_FILE_SLOT)) };
+    let written = crate::notice::silenced(|| fmt.writer().write_with(rules, &scratch, &scratch_options));
+    let result = written.map(|_| {
+        walkdir::WalkDir::new(&scratch)
+            .sort_by_file_name()
+            .into_iter()
+            .flatten()
+            .filter(|e| e.file_type().is_file())
+            .map(|e| {
+                let rel = e.path().strip_prefix(&scratch).expect("walked under scratch");
+                let key = match &options.output_file {
+                    Some(file) if rel == Path::new(OUTPUT_FILE_SLOT) => lock_key(root, file),
+                    _ => lock_key(Path::new(""), rel),
+                };
+                (key, fs::read(e.path()).unwrap_or_default())
+            })
+            .collect()
+    });
+    let _ = fs::remove_dir_all(&scratch);
+    result
+}
+
+/// `path` as a lock key: relative to `root` when under it, `/`-separated.
+pub fn lock_key(root: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    rel.components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Record what was written for `fmt` after a write: every planned file is
+/// listed with its current hash. Entries for files no longer planned are
+/// returned (and dropped from the lock) so the caller can prune them.
+pub fn record(lock: &mut Lockfile, fmt: &Format, root: &Path, planned: BTreeMap<String, RenderedFile>) -> BTreeMap<String, LockedFile> {
+    let mut previous = lock.formats.remove(fmt.name()).unwrap_or_default();
+    let files: BTreeMap<String, LockedFile> = planned
+        .into_iter()
+        .filter_map(|(key, rendered)| {
+            previous.remove(&key);
+            hash_file(&root.join(&key)).map(|sha256| (key, LockedFile { sha256, rules: rendered.rules }))
+        })
+        .collect();
+    lock.formats.insert(fmt.name().to_string(), files);
+    previous
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, content: &str) -> Rule {
+        Rule { name: Some(name.into()), content: content.into(), id: format!("id-{name}"), ..Default::default() }
+    }
+
+    #[test]
+    fn plans_one_file_per_rule_for_multi_file_formats() {
+        let root = tempfile::tempdir().unwrap();
+        let planned = plan(&Format::Cursor, &[rule("style", "Use tabs."), rule("tests", "Write tests.")], root.path(), &WriteOptions::default()).unwrap();
+        let keys: Vec<&String> = planned.keys().collect();
+        assert_eq!(keys, vec![".cursor/rules/style.mdc", ".cursor/rules/tests.mdc"]);
+        assert_eq!(planned[".cursor/rules/style.mdc"].rules, vec![LockedRule { name: "style".into(), id: "id-style".into() }]);
+        assert!(!root.path().join(".cursor").exists());
+    }
+
+    #[test]
+    fn output_file_is_keyed_by_its_real_path() {
+        let root = tempfile::tempdir().unwrap();
+        let options = WriteOptions { output_file: Some(root.path().join("docs").join("AI.md")) };
+        let planned = plan(&Format::Gemini, &[rule("a", "One."), rule("b", "Two.")], root.path(), &options).unwrap();
+        assert_eq!(planned.keys().collect::<Vec<_>>(), vec!["docs/AI.md"]);
+        assert_eq!(planned["docs/AI.md"].rules.len(), 2);
+    }
+
+    #[test]
+    fn rules_are_matched_to_the_files_they_render() {
+        let root = tempfile::tempdir().unwrap();
+        let rules = [rule("a", "A"), rule("b", "Set alwaysApply."), rule("B", "Other.")];
+        let planned = plan(&Format::Cursor, &rules, root.path(), &WriteOptions::default()).unwrap();
+        let owners: Vec<(&str, Vec<&str>)> =
+            planned.iter().map(|(key, file)| (key.as_str(), file.rules.iter().map(|r| r.name.as_str()).collect())).collect();
+        assert_eq!(
+            owners,
+            vec![
+                (".cursor/rules/a.mdc", vec!["a"]),
+                (".cursor/rules/b-2.mdc", vec!["b"]),
+                (".cursor/rules/b.mdc", vec!["B"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn planning_raises_no_notices() {
+        let root = tempfile::tempdir().unwrap();
+        crate::notice::take();
+        plan(&Format::Windsurf, &[rule("big", &"x".repeat(7000))], root.path(), &WriteOptions::default()).unwrap();
+        assert!(crate::notice::take().is_empty());
+    }
+
+    #[test]
+    fn unmanaged_lists_strays_in_format_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        Format::Cursor.writer().write(&[rule("style", "Use tabs.")], root.path()).unwrap();
+        let mut lock = Lockfile::default();
+        let planned = plan(&Format::Cursor, &[rule("style", "Use tabs.")], root.path(), &WriteOptions::default()).unwrap();
+        record(&mut lock, &Format::Cursor, root.path(), planned);
+
+        let put = |rel: &str| {
+            let file = root.path().join(rel);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, "x").unwrap();
+        };
+        for rel in [
+            ".cursor/rules/scratch.md",
+            ".cursor/rules/deprecated.mdc.bak",
+            ".cursor/rules/old/nested.md",
+            ".cursor/rules/old/nested.mdc",
+            ".cursor/rules/hand-made.mdc",
+            ".cursor/rules/.gitkeep",
+            ".github/README.md",
+            ".github/instructions/notes.md",
+            ".claude/rules/team/style.md",
+            ".claude/skills/lint/run.sh",
+        ] {
+            put(rel);
+        }
+
+        let unmanaged = lock.unmanaged(root.path());
+        let found: Vec<(&str, &str)> = unmanaged.iter().map(|(f, p)| (f.as_str(), p.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("cursor", ".cursor/rules/deprecated.mdc.bak"),
+                ("cursor", ".cursor/rules/old/nested.md"),
+                ("cursor", ".cursor/rules/scratch.md"),
+                ("copilot", ".github/instructions/notes.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn status_flags_edited_and_missing_files() {
+        let root = tempfile::tempdir().unwrap();
+        let rules = [rule("style", "Use tabs."), rule("tests", "Write tests.")];
+        Format::Cursor.writer().write(&rules, root.path()).unwrap();
+        let mut lock = Lockfile::default();
+        let planned = plan(&Format::Cursor, &rules, root.path(), &WriteOptions::default()).unwrap();
+        assert!(record(&mut lock, &Format::Cursor, root.path(), planned).is_empty());
+        lock.save(root.path()).unwrap();
+
+        let dir = root.path().join(".cursor").join("rules");
+        fs::write(dir.join("style.mdc"), "edited").unwrap();
+        fs::remove_file(dir.join("tests.mdc")).unwrap();
+        let status = Lockfile::load(root.path()).unwrap().status(root.path());
+        let states: Vec<_> = status.iter().map(|(_, path, state)| (path.as_str(), *state)).collect();
+        assert_eq!(states, vec![(".cursor/rules/style.mdc", FileState::Modified), (".cursor/rules/tests.mdc", FileState::Missing)]);
+    }
+}
@@ -0,0 +1,128 @@
+//! What a format would drop from a set of rules — the check behind `--strict`.
+//!
+//! Everything here comes from [`Format::capabilities`] and
+//! [`Format::expresses`]: activations the writer can't express, globs that only a glob activation carries,
+//! descriptions, and the format's hard size limits. `budget_chars` is a soft
+//! limit and is not a loss.
+
+use std::fmt;
+use serde::Serialize;
+use crate::error::{PolyrcError, Result};
+use crate::formats::options::FormatOptions;
+use crate::formats::Format;
+use crate::ir::{Activation, Rule};
+
+/// Why a rule doesn't survive writing as a format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LossKind {
+    Activation,
+    Globs,
+    Description,
+    SizeLimit,
+}
+
+/// One rule (or, for size limits, one file or the whole output) and what it loses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Loss {
+    pub rule: String,
+    pub kind: LossKind,
+    pub reason: String,
+}
+
+impl fmt::Display for Loss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.rule, self.reason)
+    }
+}
+
+/// Everything `fmt` would drop from `rules`, rule by rule in input order, then
+/// size limits.
+pub fn analyze(fmt: &Format, rules: &[Rule]) -> Result<Vec<Loss>> {
+    let capabilities = fmt.capabilities();
+    let name = fmt.name();
+    let mut losses = vec![];
+    for rule in rules {
+        let label = rule.name.clone().unwrap_or_else(|| rule.filename_stem());
+        let mut lose = |kind, reason: String| losses.push(Loss { rule: label.clone(), kind, reason });
+        if !fmt.expresses(rule) {
+            lose(LossKind::Activation, format!("{name} can't express {} activation", rule.activation.as_str()));
+        }
+        let globs_kept = capabilities.supports(&Activation::Glob) || fmt.expresses(rule);
+        if rule.globs.as_ref().is_some_and(|g| !g.is_empty()) && !globs_kept {
+            lose(LossKind::Globs, format!("{name} drops globs ({})", rule.globs.as_deref().unwrap_or_default().join(", ")));
+        }
+        if rule.description.is_some() && !fmt.keeps_description(rule) {
+            lose(LossKind::Description, format!("{name} drops descriptions"));
+        }
+    }
+
+    if capabilities.total_char_limit.is_some() || capabilities.file_char_limit.is_some() {
+        let budget = crate::budget::measure(fmt, rules, &FormatOptions::default())?;
+        for file in budget.files.iter().filter(|f| f.over_limit) {
+            let limit = capabilities.file_char_limit.unwrap_or_default();
+            let reason = format!("{} chars, over {name}'s {limit}-char file limit", file.chars);
+            losses.push(Loss { rule: file.path.clone(), kind: LossKind::SizeLimit, reason });
+        }
+        if let Some(limit) = capabilities.total_char_limit.filter(|l| budget.chars > *l) {
+            let reason = format!("{} chars, over {name}'s {limit}-char total limit", budget.chars);
+            losses.push(Loss { rule: "(all rules)".to_string(), kind: LossKind::SizeLimit, reason });
+        }
+    }
+    Ok(losses)
+}
+
+/// [`analyze`], failing with [`PolyrcError::LossyConversion`] if anything
+/// would be lost.
+pub fn ensure_lossless(fmt: &Format, rules: &[Rule]) -> Result<()> {
+    let losses = analyze(fmt, rules)?;
+    if losses.is_empty() {
+        return Ok(());
+    }
+    Err(PolyrcError::LossyConversion { format: fmt.name().to_string(), losses: losses.iter().map(Loss::to_string).collect() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str) -> Rule {
+        Rule { name: Some(name.into()), content: format!("{name} rule"), ..Default::default() }
+    }
+
+    #[test]
+    fn each_kind_of_loss_is_found() {
+        let rules = vec![
+            Rule { activation: Activation::OnDemand, ..rule("review") },
+            Rule { activation: Activation::Glob, globs: Some(vec!["*.ts".into()]), ..rule("ts") },
+            Rule { description: Some("Tone of voice".into()), ..rule("tone") },
+            rule("plain"),
+        ];
+        let losses = analyze(&Format::Gemini, &rules).unwrap();
+        let found: Vec<(&str, LossKind)> = losses.iter().map(|l| (l.rule.as_str(), l.kind)).collect();
+        assert_eq!(
+            found,
+            [
+                ("review", LossKind::Activation),
+                ("ts", LossKind::Activation),
+                ("ts", LossKind::Globs),
+                ("tone", LossKind::Description),
+            ]
+        );
+        assert_eq!(losses[2].to_string(), "ts: gemini drops globs (*.ts)");
+        assert!(analyze(&Format::Cursor, &rules).unwrap().is_empty());
+    }
+
+    #[test]
+    fn hard_size_limits_are_losses() {
+        let big = Rule { content: "x".repeat(crate::formats::windsurf::FILE_CHAR_LIMIT + 1), ..rule("big") };
+        let losses = analyze(&Format::Windsurf, &[big, rule("small")]).unwrap();
+        assert_eq!(losses.len(), 1);
+        assert_eq!(losses[0].kind, LossKind::SizeLimit);
+        assert!(losses[0].reason.contains("file limit"), "{}", losses[0].reason);
+
+        let err = ensure_lossless(&Format::Gemini, &[Rule { activation: Activation::OnDemand, ..rule("review") }]).unwrap_err();
+        assert_eq!(err.exit_code(), crate::error::EXIT_LOSSY);
+        assert!(ensure_lossless(&Format::Gemini, &[rule("plain")]).is_ok());
+    }
+}
@@ -0,0 +1,185 @@
+//! Monorepo mode (`--recursive`): package-level configs as path-scoped rules.
+//!
+//! Pushing or converting with `--recursive` also parses each format's project
+//! layout in the subdirectories below the root (`packages/api/CLAUDE.md`,
+//! `packages/web/.cursor/rules/`, …). Those rules get [`Scope::Path`], a
+//! `packages/api/**` glob and a `packages-api-` name prefix so that rules from
+//! different packages don't collide in the store. Always-on package rules
+//! become glob rules, which is what they mean from the repo root.
+//!
+//! Pulling with `--recursive` reverses this: a path-scoped rule whose only glob
+//! is a plain `<dir>/**` is written into `<dir>`'s own layout again.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use walkdir::WalkDir;
+use crate::error::Result;
+use crate::formats::Format;
+use crate::ir::{Activation, Rule, Scope};
+
+/// How many directory levels below the root are searched by default
+/// (`packages/api` is two).
+pub const DEFAULT_DEPTH: usize = 2;
+
+/// Never searched, besides hidden directories.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target"];
+
+/// Subdirectories of `root` up to `depth` levels down, as sorted
+/// `/`-separated relative paths. Hidden directories (`.git`, `.cursor`, …),
+/// `node_modules` and `target` are neither listed nor descended into.
+pub fn package_dirs(root: &Path, depth: usize) -> Vec<String> {
+    let walker = WalkDir::new(root).min_depth(1).max_depth(depth).sort_by_file_name().into_iter();
+    walker
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            let skipped = name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref());
+            e.depth() == 0 || (e.file_type().is_dir() && !skipped)
+        })
+        .flatten()
+        .filter_map(|e| {
+            let rel = e.path().strip_prefix(root).ok()?;
+            Some(rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"))
+        })
+        .collect()
+}
+
+/// Parse `fmt` at `root` and in every [package dir](package_dirs) below it.
+/// Root rules are returned as parsed; package rules are
+/// [scoped to their package](scope_to_package).
+pub fn parse(fmt: &Format, root: &Path, depth: usize) -> Result<Vec<Rule>> {
+    let parser = fmt.parser();
+    let mut rules = parser.parse(root)?;
+    if *fmt == Format::AgentsMd {
+        // Its parser already reads the AGENTS.md of every subdirectory
+        return Ok(rules);
+    }
+    for package in package_dirs(root, depth) {
+        let found = parser.parse(&root.join(&package))?;
+        crate::debug_log!("{}: parsed {} rule(s) from package {}", fmt.name(), found.len(), package);
+        rules.extend(found.into_iter().map(|r| scope_to_package(r, &package)));
+    }
+    Ok(rules)
+}
+
+/// Tag a rule parsed in `package` (a relative dir) as applying to that package
+/// from the repo root.
+pub fn scope_to_package(mut rule: Rule, package: &str) -> Rule {
+    let name = rule.name.take().unwrap_or_else(|| rule.filename_stem());
+    rule.name = Some(format!("{}{name}", name_prefix(package)));
+    rule.scope = Scope::Path;
+    match rule.activation {
+        Activation::Glob => {
+            let globs = rule.globs.take().unwrap_or_default();
+            rule.globs = Some(globs.iter().map(|g| format!("{package}/{g}")).collect());
+        }
+        Activation::Always => {
+            rule.activation = Activation::Glob;
+            rule.globs = Some(vec![package_glob(package)]);
+        }
+        Activation::OnDemand | Activation::AiDecides => rule.globs = Some(vec![package_glob(package)]),
+    }
+    rule
+}
+
+/// Split `rules` into root rules and rules to write into a package dir, keyed
+/// by that dir. Package rules get back the scope, activation and name they had
+/// before [`scope_to_package`].
+pub fn split(rules: Vec<Rule>) -> (Vec<Rule>, BTreeMap<String, Vec<Rule>>) {
+    let mut root = vec![];
+    let mut packages: BTreeMap<String, Vec<Rule>> = BTreeMap::new();
+    for mut rule in rules {
+        let Some(package) = package_of(&rule) else {
+            root.push(rule);
+            continue;
+        };
+        let prefix = name_prefix(&package);
+        if let Some(name) = rule.name.as_deref().and_then(|n| n.strip_prefix(&prefix)).map(str::to_string) {
+            rule.name = Some(name);
+        }
+        rule.scope = Scope::Project;
+        rule.globs = None;
+        if rule.activation == Activation::Glob {
+            rule.activation = Activation::Always;
+        }
+        packages.entry(package).or_default().push(rule);
+    }
+    (root, packages)
+}
+
+/// The package a path-scoped rule belongs to: the directory of its single
+/// `<dir>/**` glob, when `<dir>` has no wildcards.
+pub fn package_of(rule: &Rule) -> Option<String> {
+    if rule.scope != Scope::Path {
+        return None;
+    }
+    let [glob] = rule.globs.as_deref()? else {
+        return None;
+    };
+    let dir = glob.strip_suffix("/**")?;
+    let plain = !dir.is_empty() && !dir.contains(['*', '?', '[', ']', '{', '}']) && !dir.starts_with('/');
+    let relative = dir.split('/').all(|c| !c.is_empty() && c != "." && c != "..");
+    (plain && relative).then(|| dir.to_string())
+}
+
+fn package_glob(package: &str) -> String {
+    format!("{package}/**")
+}
+
+/// `packages/api` → `packages-api-`.
+fn name_prefix(package: &str) -> String {
+    format!("{}-", package.replace('/', "-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_dirs_skip_hidden_and_build_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        for dir in ["packages/api/src/deep", "packages/web", "node_modules/x", "target/debug", ".git/objects", "docs"] {
+            std::fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+        std::fs::write(root.path().join("packages").join("README.md"), "").unwrap();
+        assert_eq!(package_dirs(root.path(), 2), ["docs", "packages", "packages/api", "packages/web"]);
+        assert_eq!(package_dirs(root.path(), 1), ["docs", "packages"]);
+    }
+
+    #[test]
+    fn scoping_round_trips_through_split() {
+        let rule = |name: &str, activation: Activation, globs: Option<Vec<&str>>| Rule {
+            name: Some(name.into()),
+            content: name.into(),
+            activation,
+            globs: globs.map(|g| g.into_iter().map(String::from).collect()),
+            ..Default::default()
+        };
+        let always = scope_to_package(rule("claude", Activation::Always, None), "packages/api");
+        assert_eq!(always.name.as_deref(), Some("packages-api-claude"));
+        assert_eq!((always.scope.clone(), always.activation.clone()), (Scope::Path, Activation::Glob));
+        assert_eq!(always.globs, Some(vec!["packages/api/**".to_string()]));
+        let review = scope_to_package(rule("review", Activation::OnDemand, None), "packages/web");
+        let tsx = scope_to_package(rule("tsx", Activation::Glob, Some(vec!["src/**/*.tsx"])), "packages/web");
+        assert_eq!(tsx.globs, Some(vec!["packages/web/src/**/*.tsx".to_string()]));
+        let root_rule = rule("style", Activation::Glob, Some(vec!["*.rs"]));
+
+        let (root, packages) = split(vec![always, review, tsx.clone(), root_rule.clone()]);
+        // Package glob rules with narrower globs stay at the root, where they still apply
+        assert_eq!(root, vec![tsx, root_rule]);
+        assert_eq!(packages.keys().collect::<Vec<_>>(), ["packages/api", "packages/web"]);
+        assert_eq!(packages["packages/api"], vec![rule("claude", Activation::Always, None)]);
+        let review = &packages["packages/web"][0];
+        assert_eq!((review.name.as_deref(), &review.activation, &review.globs), (Some("review"), &Activation::OnDemand, &None));
+    }
+
+    #[test]
+    fn only_plain_relative_dirs_are_packages() {
+        let path_rule = |glob: &str| Rule { scope: Scope::Path, globs: Some(vec![glob.into()]), ..Default::default() };
+        assert_eq!(package_of(&path_rule("apps/web/**")), Some("apps/web".into()));
+        for glob in ["**", "apps/*/**", "../x/**", "/abs/**", "apps/web/*.ts", "apps//web/**"] {
+            assert_eq!(package_of(&path_rule(glob)), None, "{glob}");
+        }
+        let project = Rule { scope: Scope::Project, globs: Some(vec!["apps/web/**".into()]), ..Default::default() };
+        assert_eq!(package_of(&project), None);
+    }
+}
@@ -0,0 +1,89 @@
+//! Warnings and notes raised by library code. polyrc-core doesn't print them:
+//! they are queued on the calling thread for [`take`], or passed to the
+//! handler installed with [`set_handler`] (the `polyrc` binary prints them as
+//! they come).
+//!
+//! ```
+//! use polyrc_core::{notice, Format, Rule, Writer};
+//!
+//! let rule = Rule { name: Some("big".into()), content: "x".repeat(7000), ..Default::default() };
+//! let dir = tempfile::tempdir().unwrap();
+//! Format::Windsurf.writer().write(&[rule], dir.path()).unwrap();
+//! let notices = notice::take();
+//! assert_eq!(notices[0].level, notice::Level::Warning);
+//! assert!(notices[0].message.contains("exceeds Windsurf per-file limit"));
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::sync::RwLock;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Note,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Notice {
+    pub level: Level,
+    /// One line, or a first line followed by indented detail lines.
+    pub message: String,
+}
+
+impl fmt::Display for Notice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.level {
+            Level::Note => write!(f, "note: {}", self.message),
+            Level::Warning => write!(f, "warning: {}", self.message),
+        }
+    }
+}
+
+static HANDLER: RwLock<Option<fn(&Notice)>> = RwLock::new(None);
+
+thread_local! {
+    static QUEUE: RefCell<Vec<Notice>> = const { RefCell::new(Vec::new()) };
+    static SILENCED: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Pass every notice to `handler` as it is raised instead of queueing it;
+/// `None` goes back to queueing.
+pub fn set_handler(handler: Option<fn(&Notice)>) {
+    *HANDLER.write().unwrap_or_else(|e| e.into_inner()) = handler;
+}
+
+/// The notices queued on this thread, oldest first, emptying the queue.
+pub fn take() -> Vec<Notice> {
+    QUEUE.with(|q| std::mem::take(&mut *q.borrow_mut()))
+}
+
+pub(crate) fn note(message: impl Into<String>) {
+    raise(Level::Note, message.into());
+}
+
+pub(crate) fn warn(message: impl Into<String>) {
+    raise(Level::Warning, message.into());
+}
+
+/// Run `f` dropping the notices it raises, for renders that only preview
+/// what a later write will do (and raise) for real.
+pub(crate) fn silenced<T>(f: impl FnOnce() -> T) -> T {
+    SILENCED.with(|n| n.set(n.get() + 1));
+    let result = f();
+    SILENCED.with(|n| n.set(n.get() - 1));
+    result
+}
+
+fn raise(level: Level, message: String) {
+    if SILENCED.with(Cell::get) > 0 {
+        return;
+    }
+    let notice = Notice { level, message };
+    match *HANDLER.read().unwrap_or_else(|e| e.into_inner()) {
+        Some(handler) => handler(&notice),
+        None => QUEUE.with(|q| q.borrow_mut().push(notice)),
+    }
+}
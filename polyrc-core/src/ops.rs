@@ -0,0 +1,353 @@
+//! High-level operations shared by the CLI and library users.
+//!
+//! Nothing here prints; each function returns a structured result and leaves
+//! presentation to the caller.
+
+use std::path::{Path, PathBuf};
+use crate::error::{PolyrcError, Result};
+use crate::formats::Format;
+use crate::ir::{Rule, Scope};
+use crate::lock::{self, FileState, Lockfile};
+use crate::store::{SaveMode, Store};
+use crate::writer::{self, ActivationPolicy, WriteOptions};
+
+/// Options for [`convert`].
+#[derive(Debug, Clone, Default)]
+pub struct ConvertOptions {
+    /// Keep only rules with this scope.
+    pub scope: Option<Scope>,
+    /// Parse and filter, but don't write anything.
+    pub dry_run: bool,
+    /// Exact path of the target's main file (single-file formats only).
+    pub output_file: Option<PathBuf>,
+    /// Policy for rules the target can't activate; `None` uses
+    /// [`writer::activation_policy`].
+    pub activation_policy: Option<ActivationPolicy>,
+    /// Also read package configs this many levels below `input` and write them
+    /// back into the same packages under `output` (see [`crate::monorepo`]).
+    pub recursive: Option<usize>,
+    /// Merge glob rules that target the same files (see [`crate::globs::consolidate`]).
+    pub consolidate_globs: bool,
+    /// Fail with [`PolyrcError::LossyConversion`] before writing if the target
+    /// would lose anything (see [`crate::loss`]); overrides `activation_policy`.
+    pub strict: bool,
+}
+
+/// Result of [`convert`]: the rules that were (or, on a dry run, would be) written.
+#[derive(Debug, Clone)]
+pub struct ConvertReport {
+    pub rules: Vec<Rule>,
+    pub written: bool,
+    /// Rules the target can't activate (see [`apply_activation_policy`]).
+    pub unsupported: Vec<String>,
+}
+
+/// Parse `from` rules under `input` and write them as `to` under `output`.
+///
+/// Nothing is written when no rules survive the scope filter or when
+/// `options.dry_run` is set. `options.output_file` fails for formats that write
+/// several files. Written files are recorded in the output's
+/// [`.polyrc.lock`](crate::lock).
+///
+/// ```
+/// use polyrc_core::formats::Format;
+/// use polyrc_core::ops::{convert, ConvertOptions};
+///
+/// let input = tempfile::tempdir().unwrap();
+/// let output = tempfile::tempdir().unwrap();
+/// std::fs::write(input.path().join("GEMINI.md"), "Prefer small functions.").unwrap();
+///
+/// let report = convert(&Format::Gemini, &Format::Claude, input.path(), output.path(), &ConvertOptions::default()).unwrap();
+/// assert_eq!(report.rules.len(), 1);
+/// assert!(output.path().join("CLAUDE.md").exists());
+/// ```
+pub fn convert(
+    from: &Format,
+    to: &Format,
+    input: &Path,
+    output: &Path,
+    options: &ConvertOptions,
+) -> Result<ConvertReport> {
+    let mut rules = match options.recursive {
+        Some(depth) => crate::monorepo::parse(from, input, depth)?,
+        None => from.parser().parse(input)?,
+    };
+    crate::debug_log!("{}: parsed {} rule(s) from {}", from.name(), rules.len(), input.display());
+    if let Some(scope) = &options.scope {
+        rules.retain(|r| &r.scope == scope);
+    }
+    if options.consolidate_globs {
+        rules = crate::globs::consolidate(rules);
+    }
+    if options.strict {
+        crate::loss::ensure_lossless(to, &rules)?;
+    }
+    let policy = options.activation_policy.unwrap_or_else(writer::activation_policy);
+    let unsupported = apply_activation_policy(to, &mut rules, policy)?;
+    let write = !rules.is_empty() && !options.dry_run;
+    if write {
+        let write_options = WriteOptions { output_file: options.output_file.clone() };
+        if options.recursive.is_some() {
+            let (root, packages) = crate::monorepo::split(rules.clone());
+            if !root.is_empty() {
+                write_locked(to, &root, output, &write_options)?;
+            }
+            for (package, package_rules) in packages {
+                write_locked(to, &package_rules, &output.join(package), &write_options)?;
+            }
+        } else {
+            write_locked(to, &rules, output, &write_options)?;
+        }
+    }
+    Ok(ConvertReport { rules, written: write, unsupported })
+}
+
+/// Write `rules` as `to` under `output` and record the files in its lock.
+fn write_locked(to: &Format, rules: &[Rule], output: &Path, write_options: &WriteOptions) -> Result<()> {
+    let planned = lock::plan(to, rules, output, write_options)?;
+    to.writer().write_with(rules, output, write_options)?;
+    let mut lock = Lockfile::load(output)?;
+    let stale = lock::record(&mut lock, to, output, planned);
+    lock.formats.entry(to.name().to_string()).or_default().extend(stale);
+    lock.save(output)
+}
+
+/// What [`push_format`] did for one format.
+#[derive(Debug, Clone)]
+pub enum PushOutcome {
+    /// `user` was requested but the format has no local user-level config.
+    NoUserConfig,
+    /// The parser found no rules (after scope filtering).
+    NoRules,
+    /// Dry run: these rules would have been stored.
+    DryRun(Vec<Rule>),
+    /// Rules as stored, with ids and timestamps assigned.
+    Stored(Vec<Rule>),
+}
+
+impl PushOutcome {
+    /// Number of rules stored or, on a dry run, that would be stored.
+    pub fn count(&self) -> usize {
+        match self {
+            Self::NoUserConfig | Self::NoRules => 0,
+            Self::DryRun(rules) | Self::Stored(rules) => rules.len(),
+        }
+    }
+}
+
+/// Options for [`push_format`].
+#[derive(Debug, Clone, Copy)]
+pub struct PushOptions {
+    /// Keep only user-scope rules, and read the format's user config directory
+    /// when `input` is `.`.
+    pub user: bool,
+    /// Parse, but don't store anything.
+    pub dry_run: bool,
+    /// Also read package configs this many levels below `input` (see [`crate::monorepo`]).
+    pub recursive: Option<usize>,
+    /// Whether rules pushed from other formats survive (see [`SaveMode`]).
+    pub mode: SaveMode,
+}
+
+impl Default for PushOptions {
+    fn default() -> Self {
+        Self { user: false, dry_run: false, recursive: None, mode: SaveMode::Merge }
+    }
+}
+
+/// Parse one format and save its rules into `project` in the store. Does not commit.
+pub fn push_format(store: &Store, fmt: &Format, input: &Path, project: &str, options: &PushOptions) -> Result<PushOutcome> {
+    let PushOptions { user, dry_run, recursive, mode } = *options;
+    let Some(input) = effective_dir(fmt, input, user) else {
+        return Ok(PushOutcome::NoUserConfig);
+    };
+    let mut rules = match recursive {
+        Some(depth) => crate::monorepo::parse(fmt, &input, depth)?,
+        None => fmt.parser().parse(&input)?,
+    };
+    crate::debug_log!("{}: parsed {} rule(s) from {}", fmt.name(), rules.len(), input.display());
+    if user {
+        rules.retain(|r| r.scope == Scope::User);
+    }
+    if rules.is_empty() {
+        return Ok(PushOutcome::NoRules);
+    }
+    if dry_run {
+        return Ok(PushOutcome::DryRun(rules));
+    }
+    let stored = match mode {
+        SaveMode::Merge => store.save_rules(Some(project), &rules, fmt.name())?,
+        SaveMode::Replace => store.replace_rules(Some(project), &rules, fmt.name())?,
+    };
+    Ok(PushOutcome::Stored(stored))
+}
+
+/// What [`pull_format`] did for one format.
+#[derive(Debug, Clone)]
+pub enum PullOutcome {
+    /// No rules to write (after scope filtering).
+    NoRules,
+    /// `user` was requested but the format has no local user-level config.
+    NoUserConfig,
+    /// Dry run: this many rules would have been written to `output`.
+    DryRun { rules: Vec<Rule>, output: PathBuf },
+    /// Nothing written: these files (lock paths) were edited since the last
+    /// pull and would have been overwritten. Pass `force` to overwrite them.
+    LocalEdits { files: Vec<String>, output: PathBuf },
+    /// This many rules were written to `output`. With `prune`, `pruned` lists the
+    /// deleted files and `kept` the stale ones left alone because they were edited.
+    /// `unsupported` lists rules the format can't activate (downgraded or skipped).
+    Written { count: usize, output: PathBuf, pruned: Vec<String>, kept: Vec<String>, unsupported: Vec<String> },
+}
+
+/// Options for [`pull_format`].
+#[derive(Debug, Clone, Default)]
+pub struct PullOptions {
+    /// Resolve the output directory, but don't write anything.
+    pub dry_run: bool,
+    /// Replace single-file user configs instead of merging into their sections.
+    pub overwrite: bool,
+    /// Overwrite files edited since the last pull.
+    pub force: bool,
+    /// Delete files from earlier pulls that no rule produces any more.
+    pub prune: bool,
+    /// Policy for rules the format can't activate; `None` uses
+    /// [`writer::activation_policy`].
+    pub activation_policy: Option<ActivationPolicy>,
+    /// Merge glob rules that target the same files (see [`crate::globs::consolidate`]).
+    pub consolidate_globs: bool,
+    /// Fail with [`PolyrcError::LossyConversion`] before writing (even on a dry
+    /// run) if the format would lose anything; overrides `activation_policy`.
+    pub strict: bool,
+}
+
+/// Write already-loaded store `rules` as one format, recording the written files
+/// in the output's [`.polyrc.lock`](crate::lock).
+///
+/// With `user`, only user-scope rules are written, and when `output` is `.` the
+/// format's user config directory is used instead. Files listed in the lock are
+/// the only ones `prune` deletes, and files edited since they were written are
+/// neither overwritten (without `force`) nor pruned.
+pub fn pull_format(fmt: &Format, mut rules: Vec<Rule>, output: &Path, user: bool, options: &PullOptions) -> Result<PullOutcome> {
+    if user {
+        rules.retain(|r| r.scope == Scope::User);
+    }
+    if rules.is_empty() {
+        return Ok(PullOutcome::NoRules);
+    }
+    let Some(output) = effective_dir(fmt, output, user) else {
+        return Ok(PullOutcome::NoUserConfig);
+    };
+    if options.consolidate_globs {
+        rules = crate::globs::consolidate(rules);
+    }
+    if options.strict {
+        crate::loss::ensure_lossless(fmt, &rules)?;
+    }
+    let policy = options.activation_policy.unwrap_or_else(writer::activation_policy);
+    let unsupported = apply_activation_policy(fmt, &mut rules, policy)?;
+    if options.dry_run {
+        return Ok(PullOutcome::DryRun { rules, output });
+    }
+
+    let mut lock = Lockfile::load(&output)?;
+    let planned = lock::plan(fmt, &rules, &output, &WriteOptions::default())?;
+    let locked = lock.formats.get(fmt.name()).cloned().unwrap_or_default();
+    let edited: Vec<String> = planned
+        .keys()
+        .filter(|key| locked.get(*key).is_some_and(|f| lock::file_state(&output.join(key), &f.sha256) == FileState::Modified))
+        .cloned()
+        .collect();
+    if !edited.is_empty() && !options.force {
+        return Ok(PullOutcome::LocalEdits { files: edited, output });
+    }
+
+    let writer = if options.overwrite { fmt.overwriting_writer() } else { fmt.writer() };
+    writer.write(&rules, &output)?;
+
+    let stale = lock::record(&mut lock, fmt, &output, planned);
+    let (mut pruned, mut kept) = (vec![], vec![]);
+    for (key, file) in stale {
+        let path = output.join(&key);
+        match lock::file_state(&path, &file.sha256) {
+            FileState::Clean if options.prune => {
+                std::fs::remove_file(&path).map_err(|e| PolyrcError::Io { path, source: e })?;
+                pruned.push(key);
+            }
+            FileState::Modified if options.prune => kept.push(key),
+            FileState::Missing => {}
+            // Stays listed so a later --prune can still remove it
+            _ => {
+                lock.formats.entry(fmt.name().to_string()).or_default().insert(key, file);
+            }
+        }
+    }
+    lock.save(&output)?;
+    Ok(PullOutcome::Written { count: rules.len(), output, pruned, kept, unsupported })
+}
+
+/// Resolve rules whose activation `fmt` can't express, returned as
+/// `name (activation)`. `Downgrade` keeps them (the tool loads them as
+/// always-on), `Skip` removes them from `rules`, and `Error` fails so that
+/// nothing is written.
+pub fn apply_activation_policy(fmt: &Format, rules: &mut Vec<Rule>, policy: ActivationPolicy) -> Result<Vec<String>> {
+    let label = |r: &Rule| format!("{} ({})", r.name.clone().unwrap_or_else(|| r.filename_stem()), r.activation.as_str());
+    let unsupported: Vec<String> = rules.iter().filter(|r| !fmt.expresses(r)).map(label).collect();
+    if unsupported.is_empty() {
+        return Ok(unsupported);
+    }
+    match policy {
+        ActivationPolicy::Downgrade => {}
+        ActivationPolicy::Skip => rules.retain(|r| fmt.expresses(r)),
+        ActivationPolicy::Error => {
+            return Err(PolyrcError::UnsupportedActivation { format: fmt.name().to_string(), rules: unsupported });
+        }
+    }
+    Ok(unsupported)
+}
+
+/// In user mode a default `.` directory means "the format's user config dir".
+fn effective_dir(fmt: &Format, dir: &Path, user: bool) -> Option<PathBuf> {
+    if user && dir == Path::new(".") {
+        fmt.user_input_dir()
+    } else {
+        Some(dir.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Activation;
+
+    #[test]
+    fn convert_dry_run_writes_nothing() {
+        let input = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        std::fs::write(input.path().join("GEMINI.md"), "rule").unwrap();
+        let opts = ConvertOptions { dry_run: true, ..Default::default() };
+        let report = convert(&Format::Gemini, &Format::Claude, input.path(), output.path(), &opts).unwrap();
+        assert_eq!(report.rules.len(), 1);
+        assert!(!report.written);
+        assert!(!output.path().join("CLAUDE.md").exists());
+    }
+
+    #[test]
+    fn pull_without_user_rules_is_a_no_op() {
+        let output = tempfile::tempdir().unwrap();
+        let rules = vec![Rule { scope: Scope::Project, content: "x".into(), ..Default::default() }];
+        let outcome = pull_format(&Format::Gemini, rules, output.path(), true, &PullOptions::default()).unwrap();
+        assert!(matches!(outcome, PullOutcome::NoRules));
+    }
+
+    #[test]
+    fn pull_error_policy_writes_nothing() {
+        let output = tempfile::tempdir().unwrap();
+        let rule = |name: &str, activation| Rule { name: Some(name.into()), content: name.into(), activation, ..Default::default() };
+        let rules = vec![rule("tone", Activation::Always), rule("review", Activation::OnDemand)];
+        let options = PullOptions { activation_policy: Some(ActivationPolicy::Error), ..Default::default() };
+        let err = pull_format(&Format::Gemini, rules, output.path(), false, &options).unwrap_err();
+        assert!(matches!(err, PolyrcError::UnsupportedActivation { ref rules, .. } if rules == &["review (on_demand)"]));
+        assert!(!output.path().join("GEMINI.md").exists());
+    }
+}
@@ -0,0 +1,171 @@
+//! Starter rule packs: named sets of IR rules, built in or loaded from JSON.
+//!
+//! A pack file is JSON of the form
+//! `{"name": "...", "description": "...", "rules": [<Rule>, ...]}`.
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::error::{PolyrcError, Result};
+use crate::ir::{Rule, Scope};
+use crate::store::Store;
+
+/// File looked up when a pack source is a directory.
+pub const PACK_FILE: &str = "pack.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pack {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub rules: Vec<Rule>,
+}
+
+/// Built-in packs, compiled into the binary.
+const BUILTIN: &[(&str, &str)] = &[
+    ("conventional-commits", include_str!("../packs/conventional-commits.json")),
+    ("rust-basics", include_str!("../packs/rust-basics.json")),
+    ("security-review", include_str!("../packs/security-review.json")),
+];
+
+/// All built-in packs, sorted by name.
+pub fn builtin_packs() -> Vec<Pack> {
+    BUILTIN
+        .iter()
+        .map(|(name, raw)| parse(raw, Path::new(name)).expect("built-in pack is valid"))
+        .collect()
+}
+
+/// Look up a built-in pack by name.
+pub fn builtin(name: &str) -> Option<Pack> {
+    builtin_packs().into_iter().find(|p| p.name == name)
+}
+
+/// Parse pack JSON. `origin` is only used in error messages.
+pub fn parse(raw: &str, origin: &Path) -> Result<Pack> {
+    let pack: Pack = serde_json::from_str(raw).map_err(|e| PolyrcError::ConfigError {
+        msg: format!("invalid pack {}: {e}", origin.display()),
+    })?;
+    for rule in &pack.rules {
+        let name = rule.name.as_deref().ok_or_else(|| PolyrcError::ConfigError {
+            msg: format!("invalid pack {}: every rule needs a name", origin.display()),
+        })?;
+        crate::ir::validate_rule_name(name)?;
+    }
+    Ok(pack)
+}
+
+/// Load a pack from a JSON file, or from `pack.json` inside a directory.
+pub fn load_path(path: &Path) -> Result<Pack> {
+    let file = if path.is_dir() { path.join(PACK_FILE) } else { path.to_path_buf() };
+    crate::parser::check_file_size(&file)?;
+    let raw = std::fs::read_to_string(&file).map_err(|e| PolyrcError::Io { path: file.clone(), source: e })?;
+    parse(&raw, &file)
+}
+
+/// Load a pack from an http(s) URL.
+pub fn load_url(url: &str) -> Result<Pack> {
+    let fetched = crate::fetch::fetch_text(url, crate::parser::max_file_size())?;
+    parse(&fetched.content, Path::new(url))
+}
+
+/// Whether installing a pack rule adds a new rule or replaces an existing one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Add,
+    Replace,
+}
+
+/// The pack's rules as they would be installed into `namespace`, each with the
+/// change it makes. User-namespace installs get user scope.
+pub fn plan(store: &Store, pack: &Pack, namespace: &str) -> Result<Vec<(Change, Rule)>> {
+    let existing = store.rule_names(Some(namespace))?;
+    Ok(pack
+        .rules
+        .iter()
+        .map(|r| {
+            let mut rule = r.clone();
+            if namespace == crate::store::USER_PROJECT {
+                rule.scope = Scope::User;
+            }
+            rule.source_path = Some(format!("pack:{}", pack.name));
+            let name = rule.name.as_deref().unwrap_or_default();
+            let change = if existing.iter().any(|n| n == name) { Change::Replace } else { Change::Add };
+            (change, rule)
+        })
+        .collect())
+}
+
+/// Save planned rules into `namespace`, keeping ids of rules being replaced and
+/// leaving every other rule in the namespace untouched.
+pub fn install(store: &Store, namespace: &str, planned: &[(Change, Rule)]) -> Result<Vec<Rule>> {
+    planned
+        .iter()
+        .map(|(_, rule)| store.save_rule_to_namespace(namespace, rule.name.as_deref().unwrap_or_default(), rule))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_packs_are_valid() {
+        let packs = builtin_packs();
+        assert_eq!(packs.len(), BUILTIN.len());
+        for (pack, (name, _)) in packs.iter().zip(BUILTIN) {
+            assert_eq!(&pack.name, name);
+            assert!(!pack.description.is_empty(), "{name} has no description");
+            assert!(!pack.rules.is_empty(), "{name} has no rules");
+            for rule in &pack.rules {
+                assert!(!rule.content.trim().is_empty(), "{name}: empty rule");
+                assert!(rule.description.is_some(), "{name}: rule without description");
+            }
+        }
+    }
+
+    #[test]
+    fn install_merges_with_existing_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        let mine = |name: &str| Rule { name: Some(name.into()), content: "mine".into(), ..Default::default() };
+        store.save_rules(Some("app"), &[mine("keep-me"), mine("rust-style")], "cursor").unwrap();
+        let old_id = store.load_rule_by_name("rust-style", Some("app")).unwrap().unwrap().1.id;
+
+        let pack = builtin("rust-basics").unwrap();
+        let planned = plan(&store, &pack, "app").unwrap();
+        let changes: Vec<_> = planned.iter().map(|(c, r)| (*c, r.name.clone().unwrap())).collect();
+        assert!(changes.contains(&(Change::Replace, "rust-style".into())));
+        assert!(changes.contains(&(Change::Add, "rust-errors".into())));
+
+        install(&store, "app", &planned).unwrap();
+        let rules = store.load_rules(Some("app")).unwrap();
+        assert_eq!(rules.len(), 4);
+        let style = rules.iter().find(|r| r.name.as_deref() == Some("rust-style")).unwrap();
+        assert_eq!(style.id, old_id);
+        assert_ne!(style.content, "mine");
+        assert_eq!(style.source_path.as_deref(), Some("pack:rust-basics"));
+        assert!(rules.iter().any(|r| r.name.as_deref() == Some("keep-me") && r.content == "mine"));
+    }
+
+    #[test]
+    fn user_install_sets_user_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        let planned = plan(&store, &builtin("conventional-commits").unwrap(), crate::store::USER_PROJECT).unwrap();
+        assert!(planned.iter().all(|(_, r)| r.scope == Scope::User));
+    }
+
+    #[test]
+    fn loads_pack_from_directory_and_rejects_unnamed_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(PACK_FILE),
+            r#"{"name": "team", "rules": [{"name": "a", "scope": "project", "activation": "always", "content": "x"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(load_path(dir.path()).unwrap().rules.len(), 1);
+
+        let bad = r#"{"name": "bad", "rules": [{"scope": "project", "activation": "always", "content": "x"}]}"#;
+        assert!(parse(bad, Path::new("bad.json")).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use serde::{Deserialize, Serialize};
+use crate::error::{PolyrcError, Result};
+use crate::ir::Rule;
+
+/// Reads a tool-specific configuration location and produces a list of Rules.
+/// `path` is the project root directory (or user home for user-scope formats).
+pub trait Parser {
+    fn parse(&self, path: &Path) -> Result<Vec<Rule>>;
+}
+
+/// Default per-file size limit for source and store files (4 MiB).
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 4 * 1024 * 1024;
+
+static MAX_FILE_SIZE: AtomicU64 = AtomicU64::new(DEFAULT_MAX_FILE_SIZE);
+
+/// Set the process-wide file size limit (from `--max-file-size` or config).
+pub fn set_max_file_size(bytes: u64) {
+    MAX_FILE_SIZE.store(bytes, Ordering::Relaxed);
+}
+
+pub fn max_file_size() -> u64 {
+    MAX_FILE_SIZE.load(Ordering::Relaxed)
+}
+
+/// What to do with source files that aren't valid UTF-8 (`invalid_utf8` in
+/// config). UTF-16 files with a byte order mark are always transcoded, and
+/// binary files are always skipped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvalidUtf8Policy {
+    /// Replace invalid bytes with U+FFFD and warn.
+    #[default]
+    Lossy,
+    /// Leave the file out and warn.
+    Skip,
+}
+
+static INVALID_UTF8_POLICY: AtomicU8 = AtomicU8::new(0);
+
+/// Set the process-wide policy (from `invalid_utf8` in config).
+pub fn set_invalid_utf8_policy(policy: InvalidUtf8Policy) {
+    INVALID_UTF8_POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+pub fn invalid_utf8_policy() -> InvalidUtf8Policy {
+    match INVALID_UTF8_POLICY.load(Ordering::Relaxed) {
+        1 => InvalidUtf8Policy::Skip,
+        _ => InvalidUtf8Policy::Lossy,
+    }
+}
+
+/// Fail with `FileTooLarge` if `path` is over the current limit. Uses metadata
+/// only, so nothing is read.
+pub fn check_file_size(path: &Path) -> Result<()> {
+    check_file_size_with(path, max_file_size())
+}
+
+fn check_file_size_with(path: &Path, limit: u64) -> Result<()> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| PolyrcError::Io { path: path.to_path_buf(), source: e })?
+        .len();
+    if size > limit {
+        return Err(PolyrcError::FileTooLarge { path: path.to_path_buf(), size, limit });
+    }
+    Ok(())
+}
+
+/// Read a source config file for parsing. Files over the size limit or with
+/// binary content are skipped with a warning and yield `None`; see
+/// [`decode_source`] for how other encodings are handled.
+pub fn read_source_file(path: &Path) -> Result<Option<String>> {
+    read_source_file_with(path, max_file_size(), invalid_utf8_policy())
+}
+
+fn read_source_file_with(path: &Path, limit: u64, policy: InvalidUtf8Policy) -> Result<Option<String>> {
+    match check_file_size_with(path, limit) {
+        Err(PolyrcError::FileTooLarge { path, size, limit }) => {
+            crate::notice::warn(format!(
+                "skipping {} ({} bytes, limit {}; raise it with --max-file-size)",
+                path.display(), size, limit
+            ));
+            return Ok(None);
+        }
+        other => other?,
+    }
+    crate::debug_log!("read {}", path.display());
+    let bytes = std::fs::read(path).map_err(|e| PolyrcError::Io { path: path.to_path_buf(), source: e })?;
+    let (text, problem) = decode_source(&bytes, policy);
+    if let Some(problem) = problem {
+        crate::notice::warn(format!("{}: {problem}", path.display()));
+    }
+    Ok(text)
+}
+
+/// Decode a source file's bytes. UTF-8 (with or without a BOM) is taken as
+/// is and UTF-16 with a BOM is transcoded. Content with NUL bytes is treated
+/// as binary and skipped; other invalid UTF-8 follows `policy`. The second
+/// value describes anything that needs a warning.
+pub fn decode_source(bytes: &[u8], policy: InvalidUtf8Policy) -> (Option<String>, Option<String>) {
+    if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return decode_source(rest, policy);
+    }
+    let utf16 = match bytes {
+        [0xFF, 0xFE, rest @ ..] => Some((rest, u16::from_le_bytes as fn([u8; 2]) -> u16, "UTF-16LE")),
+        [0xFE, 0xFF, rest @ ..] => Some((rest, u16::from_be_bytes as fn([u8; 2]) -> u16, "UTF-16BE")),
+        _ => None,
+    };
+    if let Some((rest, unit, name)) = utf16 {
+        let units: Vec<u16> = rest.chunks(2).map(|c| unit([c[0], *c.get(1).unwrap_or(&0)])).collect();
+        return match String::from_utf16(&units) {
+            Ok(text) => (Some(text), None),
+            Err(_) => (None, Some(format!("skipped; invalid {name} despite its byte order mark"))),
+        };
+    }
+    if bytes.contains(&0) {
+        return (None, Some("skipped; looks like a binary file".to_string()));
+    }
+    match String::from_utf8(bytes.to_vec()) {
+        Ok(text) => (Some(text), None),
+        Err(_) => match policy {
+            InvalidUtf8Policy::Lossy => (
+                Some(String::from_utf8_lossy(bytes).into_owned()),
+                Some("not valid UTF-8; invalid bytes replaced with U+FFFD".to_string()),
+            ),
+            InvalidUtf8Policy::Skip => (None, Some("skipped; not valid UTF-8".to_string())),
+        },
+    }
+}
+
+/// Parse a size such as `4194304`, `512K`, `10M` or `1G` (binary units).
+pub fn parse_size(s: &str) -> std::result::Result<u64, String> {
+    let s = s.trim();
+    let (digits, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, ""),
+    };
+    let n: u64 = digits.parse().map_err(|_| format!("invalid size '{s}'"))?;
+    let mult: u64 = match unit.to_ascii_uppercase().trim_end_matches(['B', 'b']) {
+        "" => 1,
+        "K" | "KI" => 1024,
+        "M" | "MI" => 1024 * 1024,
+        "G" | "GI" => 1024 * 1024 * 1024,
+        _ => return Err(format!("invalid size unit in '{s}' (use K, M or G)")),
+    };
+    n.checked_mul(mult).ok_or_else(|| format!("size '{s}' is too large"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn oversized(dir: &Path, name: &str, len: u64) -> std::path::PathBuf {
+        let p = dir.join(name);
+        // Sparse file: cheap to create regardless of length
+        fs::File::create(&p).unwrap().set_len(len).unwrap();
+        p
+    }
+
+    #[test]
+    fn oversized_source_file_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let big = oversized(dir.path(), "notes.md", 2048);
+        assert!(read_source_file_with(&big, 1024, InvalidUtf8Policy::Lossy).unwrap().is_none());
+        assert!(matches!(
+            check_file_size_with(&big, 1024),
+            Err(PolyrcError::FileTooLarge { size: 2048, limit: 1024, .. })
+        ));
+
+        let small = dir.path().join("ok.md");
+        fs::write(&small, "hello").unwrap();
+        assert_eq!(read_source_file_with(&small, 1024, InvalidUtf8Policy::Lossy).unwrap().as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn default_limit_skips_files_in_format_parsers() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = dir.path().join(".windsurf").join("rules");
+        fs::create_dir_all(&rules).unwrap();
+        fs::write(rules.join("small.md"), "keep me").unwrap();
+        oversized(&rules, "huge.md", DEFAULT_MAX_FILE_SIZE + 1);
+
+        let parsed = crate::formats::windsurf::WindsurfParser.parse(dir.path()).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].content, "keep me");
+    }
+
+    #[test]
+    fn utf16_files_are_transcoded() {
+        let text = "# Style\nUse tabs — always.\n";
+        let mut le = vec![0xFF, 0xFE];
+        le.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        let mut be = vec![0xFE, 0xFF];
+        be.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        for bytes in [le, be] {
+            assert_eq!(decode_source(&bytes, InvalidUtf8Policy::Skip), (Some(text.to_string()), None));
+        }
+        let bom = [b"\xEF\xBB\xBF".as_slice(), text.as_bytes()].concat();
+        assert_eq!(decode_source(&bom, InvalidUtf8Policy::Skip).0.as_deref(), Some(text));
+    }
+
+    #[test]
+    fn binary_and_latin1_files_do_not_abort_the_parse() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = dir.path().join(".windsurf").join("rules");
+        fs::create_dir_all(&rules).unwrap();
+        fs::write(rules.join("good.md"), "keep me").unwrap();
+        fs::write(rules.join("logo.md"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        // "café" in Latin-1
+        fs::write(rules.join("latin1.md"), b"caf\xe9").unwrap();
+
+        let mut parsed = crate::formats::windsurf::WindsurfParser.parse(dir.path()).unwrap();
+        parsed.sort_by(|a, b| a.content.cmp(&b.content));
+        let contents: Vec<&str> = parsed.iter().map(|r| r.content.as_str()).collect();
+        assert_eq!(contents, ["caf\u{FFFD}", "keep me"]);
+
+        let latin1 = rules.join("latin1.md");
+        assert!(read_source_file_with(&latin1, 1024, InvalidUtf8Policy::Skip).unwrap().is_none());
+        let (_, problem) = decode_source(b"caf\xe9", InvalidUtf8Policy::Skip);
+        assert_eq!(problem.as_deref(), Some("skipped; not valid UTF-8"));
+    }
+
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("1234"), Ok(1234));
+        assert_eq!(parse_size("512K"), Ok(512 * 1024));
+        assert_eq!(parse_size("10MB"), Ok(10 * 1024 * 1024));
+        assert_eq!(parse_size("1g"), Ok(1024 * 1024 * 1024));
+        assert!(parse_size("ten").is_err());
+        assert!(parse_size("5X").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Structured dry-run plans, printed by `--dry-run --json` on `convert`,
+//! `push-format` and `pull-format`.
+//!
+//! Field names are part of the JSON output and kept stable.
+
+use std::path::Path;
+use serde::Serialize;
+use crate::error::Result;
+use crate::formats::Format;
+use crate::ir::Rule;
+use crate::store::{SaveMode, Store};
+use crate::writer::WriteOptions;
+
+/// The `--dry-run --json` document: what `command` would do, per format.
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunPlan {
+    pub command: String,
+    pub formats: Vec<FormatPlan>,
+}
+
+/// One format's part of a [`DryRunPlan`]. Writes carry `files`, pushes carry
+/// `rules`; a format with nothing to do has `skipped` or `error` instead.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FormatPlan {
+    pub format: String,
+    /// Directory written to (writes only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Why nothing would happen, e.g. `no rules`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<PlannedFile>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules: Option<Vec<PlannedRule>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileAction {
+    Create,
+    Overwrite,
+    /// The file already has exactly this content.
+    Skip,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedFile {
+    pub action: FileAction,
+    /// `/`-separated, relative to the output directory when under it.
+    pub path: String,
+    pub rule_names: Vec<String>,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    Add,
+    Update,
+    Remove,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedRule {
+    pub rule: String,
+    pub action: RuleAction,
+    pub project: String,
+}
+
+impl FormatPlan {
+    pub fn new(fmt: &Format) -> Self {
+        Self { format: fmt.name().to_string(), ..Default::default() }
+    }
+
+    pub fn skipped(fmt: &Format, reason: &str) -> Self {
+        Self { skipped: Some(reason.to_string()), ..Self::new(fmt) }
+    }
+
+    pub fn error(fmt: &Format, error: String) -> Self {
+        Self { error: Some(error), ..Self::new(fmt) }
+    }
+}
+
+/// The files writing `rules` as `fmt` into `output` would create or overwrite.
+/// Nothing under `output` is touched.
+pub fn plan_writes(fmt: &Format, rules: &[Rule], output: &Path, options: &WriteOptions) -> Result<Vec<PlannedFile>> {
+    Ok(crate::lock::plan(fmt, rules, output, options)?
+        .into_iter()
+        .map(|(path, rendered)| {
+            let action = match std::fs::read(output.join(&path)) {
+                Err(_) => FileAction::Create,
+                Ok(existing) if existing == rendered.content => FileAction::Skip,
+                Ok(_) => FileAction::Overwrite,
+            };
+            PlannedFile {
+                action,
+                rule_names: rendered.rules.into_iter().map(|r| r.name).collect(),
+                bytes: rendered.content.len() as u64,
+                path,
+            }
+        })
+        .collect())
+}
+
+/// What saving `rules` from `source_format` into `project` would do to each
+/// rule, sorted by name. Stored rules missing from `rules` are removed if
+/// `mode` replaces them (see [`SaveMode::replaces`]).
+pub fn plan_push(store: &Store, project: &str, rules: &[Rule], source_format: &str, mode: SaveMode) -> Result<Vec<PlannedRule>> {
+    let existing = store.load_rules(Some(project))?;
+    let name = |r: &Rule| r.name.clone().unwrap_or_else(|| r.filename_stem());
+    let entry = |rule: String, action| PlannedRule { rule, action, project: project.to_string() };
+
+    let mut plan: Vec<PlannedRule> = rules
+        .iter()
+        .map(|rule| {
+            let action = match existing.iter().find(|e| name(e) == name(rule)) {
+                None => RuleAction::Add,
+                Some(old) if old.same_content(rule) => RuleAction::Unchanged,
+                Some(_) => RuleAction::Update,
+            };
+            entry(name(rule), action)
+        })
+        .collect();
+    plan.extend(
+        existing
+            .iter()
+            .filter(|e| !rules.iter().any(|r| name(r) == name(e)) && mode.replaces(e, rules, source_format))
+            .map(|e| entry(name(e), RuleAction::Remove)),
+    );
+    plan.sort_by(|a, b| a.rule.cmp(&b.rule));
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, content: &str) -> Rule {
+        Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn write_plan_marks_new_changed_and_identical_files() {
+        let out = tempfile::tempdir().unwrap();
+        let rules = vec![rule("a", "One."), rule("b", "Two."), rule("c", "Three.")];
+        Format::Windsurf.writer().write(&rules[..2], out.path()).unwrap();
+        std::fs::write(out.path().join(".windsurf/rules/b.md"), "edited").unwrap();
+
+        let plan = plan_writes(&Format::Windsurf, &rules, out.path(), &WriteOptions::default()).unwrap();
+        let actions: Vec<_> = plan.iter().map(|f| (f.path.as_str(), f.action)).collect();
+        assert_eq!(
+            actions,
+            vec![
+                (".windsurf/rules/a.md", FileAction::Skip),
+                (".windsurf/rules/b.md", FileAction::Overwrite),
+                (".windsurf/rules/c.md", FileAction::Create),
+            ]
+        );
+        assert_eq!(plan[2].rule_names, vec!["c"]);
+        assert_eq!(plan[2].bytes, "Three.\n".len() as u64);
+    }
+
+    #[test]
+    fn push_plan_covers_every_rule_action() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        store.save_rules(Some("app"), &[rule("keep", "Same."), rule("edit", "Old."), rule("gone", "Bye.")], "cursor").unwrap();
+
+        let pushed = [rule("keep", "Same.\n"), rule("edit", "New."), rule("new", "Hi.")];
+        let plan = plan_push(&store, "app", &pushed, "cursor", SaveMode::Merge).unwrap();
+        let actions: Vec<_> = plan.iter().map(|r| (r.rule.as_str(), r.action)).collect();
+        assert_eq!(
+            actions,
+            vec![("edit", RuleAction::Update), ("gone", RuleAction::Remove), ("keep", RuleAction::Unchanged), ("new", RuleAction::Add)]
+        );
+        assert!(plan.iter().all(|r| r.project == "app"));
+
+        // Another format's push keeps "gone", unless it replaces everything
+        let removed = |mode| plan_push(&store, "app", &pushed, "claude", mode).unwrap().iter().any(|r| r.action == RuleAction::Remove);
+        assert!(!removed(SaveMode::Merge));
+        assert!(removed(SaveMode::Replace));
+    }
+}
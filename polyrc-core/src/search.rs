@@ -0,0 +1,118 @@
+//! `polyrc search`: find stored rules by name and description, and optionally
+//! by content. Plain queries match case-insensitively as literal text; regex
+//! queries match as written (add `(?i)` to ignore case).
+
+use std::ops::Range;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use crate::error::{PolyrcError, Result};
+use crate::ir::Rule;
+
+pub struct Query {
+    pattern: Regex,
+    content: bool,
+}
+
+/// How a rule matched a [`Query`].
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Hit {
+    /// The fields that matched: `name`, `description` and/or `content`.
+    pub fields: Vec<&'static str>,
+    /// The matching content lines, when content is searched.
+    pub lines: Vec<LineHit>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct LineHit {
+    /// 1-based line number in the rule's content.
+    pub line: usize,
+    pub text: String,
+    /// Byte ranges of the matches within `text`.
+    #[serde(skip)]
+    pub ranges: Vec<Range<usize>>,
+}
+
+impl Query {
+    /// `text` as a literal, or with `regex` as a regular expression. With
+    /// `content`, rule content is searched as well as names and descriptions.
+    pub fn new(text: &str, regex: bool, content: bool) -> Result<Self> {
+        let pattern = if regex {
+            Regex::new(text)
+        } else {
+            RegexBuilder::new(&regex::escape(text)).case_insensitive(true).build()
+        }
+        .map_err(|e| PolyrcError::InvalidPattern { pattern: text.to_string(), reason: e.to_string() })?;
+        Ok(Self { pattern, content })
+    }
+
+    /// Where `rule` matches, or `None`. A nameless rule is matched by its filename stem.
+    pub fn find(&self, rule: &Rule) -> Option<Hit> {
+        let mut hit = Hit::default();
+        let name = rule.name.clone().unwrap_or_else(|| rule.filename_stem());
+        if self.pattern.is_match(&name) {
+            hit.fields.push("name");
+        }
+        if rule.description.as_deref().is_some_and(|d| self.pattern.is_match(d)) {
+            hit.fields.push("description");
+        }
+        if self.content {
+            hit.lines = self.find_lines(&rule.content);
+            if !hit.lines.is_empty() {
+                hit.fields.push("content");
+            }
+        }
+        (!hit.fields.is_empty()).then_some(hit)
+    }
+
+    fn find_lines(&self, content: &str) -> Vec<LineHit> {
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(i, text)| {
+                let ranges: Vec<Range<usize>> = self.pattern.find_iter(text).map(|m| m.range()).filter(|r| !r.is_empty()).collect();
+                (!ranges.is_empty()).then(|| LineHit { line: i + 1, text: text.to_string(), ranges })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, description: Option<&str>, content: &str) -> Rule {
+        Rule {
+            name: Some(name.into()),
+            description: description.map(str::to_string),
+            content: content.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn names_and_descriptions_by_default_content_on_request() {
+        let style = rule("rust-style", Some("Formatting for Rust"), "Use rustfmt.\nRun Clippy in CI.");
+        let query = Query::new("clippy", false, false).unwrap();
+        assert_eq!(query.find(&style), None);
+
+        let query = Query::new("clippy", false, true).unwrap();
+        let hit = query.find(&style).unwrap();
+        assert_eq!(hit.fields, vec!["content"]);
+        assert_eq!((hit.lines.len(), hit.lines[0].line, hit.lines[0].ranges[0].clone()), (1, 2, 4..10));
+
+        let query = Query::new("RUST", false, false).unwrap();
+        assert_eq!(query.find(&style).unwrap().fields, vec!["name", "description"]);
+    }
+
+    #[test]
+    fn regex_queries_match_as_written() {
+        let style = rule("rust-style", None, "Use rustfmt.");
+        assert!(Query::new("^rust-", true, false).unwrap().find(&style).is_some());
+        assert!(Query::new("^RUST-", true, false).unwrap().find(&style).is_none());
+        // A literal query doesn't treat `.` as a wildcard
+        assert!(Query::new("rust.style", false, false).unwrap().find(&style).is_none());
+
+        let err = Query::new("rust(", true, false).err().unwrap();
+        assert_eq!(err.kind(), "invalid_pattern");
+    }
+}
@@ -0,0 +1,80 @@
+//! One-file store backups (`polyrc store backup` / `store restore`), kept as
+//! git bundles under `~/polyrc/backups/`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::{PolyrcError, Result};
+use super::Store;
+
+/// Backups kept by [`rotate_backups`] when `[store] backup_retention` is unset.
+pub const DEFAULT_BACKUP_RETENTION: usize = 10;
+const BACKUP_PREFIX: &str = "store-";
+const BACKUP_EXT: &str = "bundle";
+
+/// Default directory for backups: `~/polyrc/backups`.
+pub fn backups_dir() -> PathBuf {
+    crate::config::polyrc_dir().join("backups")
+}
+
+/// A new timestamped backup path in `dir` (`store-<UTC time>.bundle`). Names
+/// sort chronologically.
+pub fn timestamped_backup_path(dir: &Path) -> PathBuf {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+    dir.join(format!("{BACKUP_PREFIX}{stamp}.{BACKUP_EXT}"))
+}
+
+/// Timestamped backups in `dir`, oldest first.
+pub fn list_backups(dir: &Path) -> Result<Vec<PathBuf>> {
+    let Ok(entries) = fs::read_dir(dir) else { return Ok(vec![]) };
+    let mut backups: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension().and_then(|e| e.to_str()) == Some(BACKUP_EXT)
+                && p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(BACKUP_PREFIX))
+        })
+        .collect();
+    backups.sort();
+    Ok(backups)
+}
+
+/// Delete all but the newest `keep` backups in `dir`. Returns the removed files.
+pub fn rotate_backups(dir: &Path, keep: usize) -> Result<Vec<PathBuf>> {
+    let backups = list_backups(dir)?;
+    let excess = backups.len().saturating_sub(keep);
+    let removed: Vec<PathBuf> = backups.into_iter().take(excess).collect();
+    for file in &removed {
+        fs::remove_file(file).map_err(|e| PolyrcError::Io { path: file.clone(), source: e })?;
+    }
+    Ok(removed)
+}
+
+impl Store {
+    /// Write a backup bundle of the store to `file`, creating its directory.
+    pub fn backup(&self, file: &Path) -> Result<()> {
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).map_err(|e| PolyrcError::Io { path: parent.to_path_buf(), source: e })?;
+        }
+        crate::sync::git_bundle_create(&self.path, file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_keeps_the_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        for stamp in ["20260101T000000.000Z", "20260102T000000.000Z", "20260103T000000.000Z"] {
+            fs::write(dir.path().join(format!("store-{stamp}.bundle")), "").unwrap();
+        }
+        fs::write(dir.path().join("notes.txt"), "").unwrap();
+
+        let removed = rotate_backups(dir.path(), 2).unwrap();
+        assert_eq!(removed, vec![dir.path().join("store-20260101T000000.000Z.bundle")]);
+        assert_eq!(list_backups(dir.path()).unwrap().len(), 2);
+        assert!(dir.path().join("notes.txt").exists());
+        assert!(rotate_backups(dir.path(), 5).unwrap().is_empty());
+    }
+}
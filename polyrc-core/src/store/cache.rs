@@ -0,0 +1,112 @@
+//! In-memory cache of parsed project rules, for the life of one [`Store`].
+//!
+//! Commands such as `pull-format --all` or `sync` load the same projects
+//! several times. A cached project is reused only while its `*.yaml` files
+//! still have the mtimes and sizes they had when parsed (the same
+//! [`FileStamp`]s the index uses), so edits made behind the store's back, such
+//! as a `git pull`, are picked up. Every `Store` method that writes rule files
+//! also drops the whole cache. Projects with unparseable files are not cached.
+
+use std::path::{Path, PathBuf};
+use crate::error::Result;
+use crate::ir::Rule;
+use super::index::FileStamp;
+use super::Store;
+
+/// Parsed rules of one project directory and the stamps they were parsed from.
+#[derive(Debug, Clone)]
+pub(super) struct CachedRules {
+    stamps: Vec<FileStamp>,
+    rules: Vec<Rule>,
+}
+
+impl Store {
+    /// Forget every cached project. Called by each method that writes rule
+    /// files; callers that change the store some other way don't need to, since
+    /// stamps are re-checked on every load.
+    pub fn invalidate_cache(&self) {
+        self.rule_cache.borrow_mut().clear();
+    }
+
+    /// The stamps of `dir` now, and its cached rules if they are still current.
+    pub(super) fn cached_rules(&self, dir: &Path) -> Result<(Vec<FileStamp>, Option<Vec<Rule>>)> {
+        let stamps = self.stamp_dir(dir)?;
+        let cache = self.rule_cache.borrow();
+        let hit = cache.get(dir).filter(|c| c.stamps == stamps).map(|c| c.rules.clone());
+        Ok((stamps, hit))
+    }
+
+    pub(super) fn cache_rules(&self, dir: PathBuf, stamps: Vec<FileStamp>, rules: &[Rule]) {
+        self.rule_cache.borrow_mut().insert(dir, CachedRules { stamps, rules: rules.to_vec() });
+    }
+
+    /// Number of cached projects, for tests.
+    #[cfg(test)]
+    pub(super) fn cached_projects(&self) -> usize {
+        self.rule_cache.borrow().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use crate::ir::Rule;
+    use super::Store;
+
+    fn rule(name: &str, content: &str) -> Rule {
+        Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+    }
+
+    fn contents(store: &Store, project: &str) -> Vec<String> {
+        store.load_rules(Some(project)).unwrap().into_iter().map(|r| r.content).collect()
+    }
+
+    #[test]
+    fn saves_and_project_moves_invalidate() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        store.save_rules(Some("app"), &[rule("style", "Use tabs.")], "cursor").unwrap();
+        assert_eq!(contents(&store, "app"), ["Use tabs."]);
+        assert_eq!(store.cached_projects(), 1);
+
+        store.save_rules(Some("app"), &[rule("style", "Use spaces.")], "cursor").unwrap();
+        assert_eq!(contents(&store, "app"), ["Use spaces."]);
+
+        store.rename_project("app", "web").unwrap();
+        assert_eq!(store.cached_projects(), 0);
+        assert!(contents(&store, "app").is_empty());
+        assert_eq!(contents(&store, "web"), ["Use spaces."]);
+        store.archive_project("web").unwrap();
+        assert!(contents(&store, "web").is_empty());
+        store.unarchive_project("web").unwrap();
+        assert_eq!(contents(&store, "web"), ["Use spaces."]);
+    }
+
+    #[test]
+    fn changes_behind_the_stores_back_are_noticed() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        store.save_rules(Some("app"), &[rule("style", "Use tabs.")], "cursor").unwrap();
+        assert_eq!(contents(&store, "app"), ["Use tabs."]);
+
+        let file = dir.path().join("app").join("style.yaml");
+        let edited = fs::read_to_string(&file).unwrap().replace("Use tabs.", "Use two spaces.");
+        fs::write(&file, edited).unwrap();
+        assert_eq!(contents(&store, "app"), ["Use two spaces."]);
+
+        // Another Store on the same directory (another invocation) adds a rule
+        Store::new(dir.path()).save_rules(Some("app"), &[rule("style", "x"), rule("tone", "y")], "cursor").unwrap();
+        assert_eq!(contents(&store, "app"), ["x", "y"]);
+    }
+
+    #[test]
+    fn projects_with_unparseable_files_are_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        store.save_rules(Some("app"), &[rule("style", "Use tabs.")], "cursor").unwrap();
+        fs::write(dir.path().join("app").join("broken.yaml"), "scope: [\n").unwrap();
+        assert_eq!(store.load_rules_lossy(Some("app")).unwrap().skipped.len(), 1);
+        assert_eq!(store.cached_projects(), 0);
+        assert_eq!(store.load_rules_lossy(Some("app")).unwrap().skipped.len(), 1);
+    }
+}
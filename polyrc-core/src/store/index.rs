@@ -0,0 +1,310 @@
+//! `.polyrc-index.json` — a cache of rule metadata for fast listing and lookup.
+//!
+//! Each project entry records the mtime and size of every `*.yaml` file it was
+//! built from. On every read the project directories are re-`stat`ed; any
+//! project whose files disagree with the recorded stamps is re-parsed, so the
+//! index is never trusted over the filesystem.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+use crate::error::{PolyrcError, Result};
+use crate::ir::{Activation, Rule, Scope};
+use super::Store;
+
+/// Index file name at the store root (git-ignored).
+pub const INDEX_FILE: &str = ".polyrc-index.json";
+const INDEX_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoreIndex {
+    pub version: u32,
+    pub projects: BTreeMap<String, ProjectIndex>,
+}
+
+/// Indexed rules of one project, plus the file stamps they were built from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectIndex {
+    pub rules: Vec<IndexEntry>,
+    /// Stamps of files that failed to parse — kept so they don't force a re-parse every time.
+    #[serde(default)]
+    pub unparsed: Vec<FileStamp>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub id: String,
+    pub scope: Scope,
+    pub activation: Activation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    /// Content length in bytes.
+    pub size: usize,
+    /// SHA-256 of the rule content, hex encoded.
+    pub content_hash: String,
+    /// The rule description, or the first non-blank content line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(flatten)]
+    pub file: FileStamp,
+}
+
+impl IndexEntry {
+    /// Display name: the rule name, or its filename stem.
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            Path::new(&self.file.path).file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string()
+        })
+    }
+}
+
+/// Identity of a rule file on disk: store-relative path, mtime (ns) and byte length.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub path: String,
+    pub mtime_ns: u64,
+    pub file_len: u64,
+}
+
+impl ProjectIndex {
+    fn stamps(&self) -> Vec<FileStamp> {
+        let mut all: Vec<FileStamp> = self
+            .rules
+            .iter()
+            .map(|r| r.file.clone())
+            .chain(self.unparsed.iter().cloned())
+            .collect();
+        all.sort();
+        all
+    }
+}
+
+impl Store {
+    /// Return an index that agrees with the filesystem, re-parsing only projects
+    /// whose files changed since the index was written. Writes the index back if
+    /// anything changed.
+    pub fn index(&self) -> Result<StoreIndex> {
+        let mut index = self.read_index_file().unwrap_or_default();
+        if index.version != INDEX_VERSION {
+            index = StoreIndex { version: INDEX_VERSION, ..Default::default() };
+        }
+        let projects = self.list_projects()?;
+        let mut changed = false;
+
+        let before = index.projects.len();
+        index.projects.retain(|name, _| projects.contains(name));
+        changed |= index.projects.len() != before;
+
+        for project in &projects {
+            let stamps = self.stamp_dir(&self.path.join(project))?;
+            let fresh = index.projects.get(project).is_some_and(|p| p.stamps() == stamps);
+            if !fresh {
+                index.projects.insert(project.clone(), self.build_project_index(project)?);
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.write_index_file(&index)?;
+        }
+        Ok(index)
+    }
+
+    /// Discard the index file and rebuild it from scratch.
+    pub fn rebuild_index(&self) -> Result<StoreIndex> {
+        let file = self.path.join(INDEX_FILE);
+        if file.exists() {
+            fs::remove_file(&file).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        }
+        self.index()
+    }
+
+    /// Bring the index (and, if enabled, the store README) up to date after a
+    /// mutation. Only projects whose files changed are re-parsed; failures are
+    /// ignored since both are derived data.
+    pub(crate) fn refresh_index(&self) {
+        let Ok(index) = self.index() else { return };
+        if self.generate_readme {
+            let _ = self.write_readme(&index);
+        }
+    }
+
+    fn read_index_file(&self) -> Option<StoreIndex> {
+        let raw = fs::read_to_string(self.path.join(INDEX_FILE)).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    fn write_index_file(&self, index: &StoreIndex) -> Result<()> {
+        let file = self.path.join(INDEX_FILE);
+        let json = serde_json::to_string_pretty(index).map_err(|e| PolyrcError::WriteFailure {
+            path: file.clone(),
+            reason: e.to_string(),
+        })?;
+        fs::write(&file, json).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        self.ignore(&[INDEX_FILE]).map(|_| ())
+    }
+
+    /// Stat every `*.yaml` file in a project directory, sorted by path.
+    pub(super) fn stamp_dir(&self, dir: &Path) -> Result<Vec<FileStamp>> {
+        let mut stamps: Vec<FileStamp> = yaml_files(dir)?
+            .iter()
+            .map(|p| self.stamp(p))
+            .collect::<Result<_>>()?;
+        stamps.sort();
+        Ok(stamps)
+    }
+
+    fn stamp(&self, path: &Path) -> Result<FileStamp> {
+        let meta = fs::metadata(path).map_err(|e| PolyrcError::Io { path: path.to_path_buf(), source: e })?;
+        let mtime_ns = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .and_then(|d| u64::try_from(d.as_nanos()).ok())
+            .unwrap_or(0);
+        let rel = path.strip_prefix(&self.path).unwrap_or(path);
+        Ok(FileStamp {
+            path: rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"),
+            mtime_ns,
+            file_len: meta.len(),
+        })
+    }
+
+    fn build_project_index(&self, project: &str) -> Result<ProjectIndex> {
+        let mut out = ProjectIndex::default();
+        for path in yaml_files(&self.path.join(project))? {
+            let stamp = self.stamp(&path)?;
+            match super::read_rule_file(&path) {
+                Ok(rule) => out.rules.push(entry_for(&rule, stamp)),
+                Err(_) => out.unparsed.push(stamp),
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn entry_for(rule: &Rule, file: FileStamp) -> IndexEntry {
+    IndexEntry {
+        name: rule.name.clone(),
+        id: rule.id.clone(),
+        scope: rule.scope.clone(),
+        activation: rule.activation.clone(),
+        source_format: rule.source_format.clone(),
+        updated_at: rule.updated_at.clone(),
+        size: rule.content.len(),
+        content_hash: hex::encode(Sha256::digest(rule.content.as_bytes())),
+        summary: rule
+            .description
+            .as_deref()
+            .or_else(|| rule.content.lines().map(str::trim).find(|l| !l.is_empty()))
+            .map(str::to_string),
+        file,
+    }
+}
+
+fn yaml_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut files = vec![];
+    for entry in WalkDir::new(dir).min_depth(1).max_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| PolyrcError::Io { path: dir.to_path_buf(), source: e.into() })?;
+        if entry.path().extension().and_then(|e| e.to_str()) == Some("yaml") {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> (tempfile::TempDir, Store) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        (dir, store)
+    }
+
+    fn named(name: &str, content: &str) -> Rule {
+        Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn builds_when_absent_and_gitignores_itself() {
+        let (_dir, store) = store();
+        store.save_rules(Some("myApp"), &[named("style", "x")], "cursor").unwrap();
+        fs::remove_file(store.path.join(INDEX_FILE)).unwrap();
+
+        let index = store.index().unwrap();
+        let entries = &index.projects["myApp"].rules;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].display_name(), "style");
+        assert_eq!(entries[0].file.path, "myApp/style.yaml");
+        assert!(store.path.join(INDEX_FILE).exists());
+        let gitignore = fs::read_to_string(store.path.join(".gitignore")).unwrap();
+        assert!(gitignore.lines().any(|l| l == INDEX_FILE));
+    }
+
+    #[test]
+    fn detects_files_changed_behind_its_back() {
+        let (_dir, store) = store();
+        store.save_rules(Some("myApp"), &[named("style", "short")], "cursor").unwrap();
+        let before = store.index().unwrap().projects["myApp"].rules[0].content_hash.clone();
+
+        // Edit the file directly — size changes, so the stamp disagrees
+        let file = store.path.join("myApp").join("style.yaml");
+        let raw = fs::read_to_string(&file).unwrap().replace("content: short", "content: much longer now");
+        fs::write(&file, raw).unwrap();
+
+        let after = &store.index().unwrap().projects["myApp"].rules[0];
+        assert_ne!(after.content_hash, before);
+        assert_eq!(after.size, "much longer now".len());
+    }
+
+    #[test]
+    fn detects_added_and_removed_projects() {
+        let (_dir, store) = store();
+        store.save_rules(Some("a"), &[named("one", "1")], "cursor").unwrap();
+        store.save_rules(Some("b"), &[named("two", "2")], "cursor").unwrap();
+        assert_eq!(store.index().unwrap().projects.len(), 2);
+
+        fs::remove_dir_all(store.path.join("b")).unwrap();
+        let index = store.index().unwrap();
+        assert_eq!(index.projects.keys().collect::<Vec<_>>(), vec!["a"]);
+    }
+
+    #[test]
+    fn incremental_update_on_save_and_rename() {
+        let (_dir, store) = store();
+        store.save_rules(Some("a"), &[named("one", "1")], "cursor").unwrap();
+        store.save_rules(Some("a"), &[named("one", "1"), named("two", "2")], "cursor").unwrap();
+        let on_disk: StoreIndex =
+            serde_json::from_str(&fs::read_to_string(store.path.join(INDEX_FILE)).unwrap()).unwrap();
+        assert_eq!(on_disk.projects["a"].rules.len(), 2);
+
+        store.rename_project("a", "b").unwrap();
+        let on_disk: StoreIndex =
+            serde_json::from_str(&fs::read_to_string(store.path.join(INDEX_FILE)).unwrap()).unwrap();
+        assert!(!on_disk.projects.contains_key("a"));
+        assert_eq!(on_disk.projects["b"].rules.len(), 2);
+    }
+
+    #[test]
+    fn corrupt_files_are_tracked_not_indexed() {
+        let (_dir, store) = store();
+        store.save_rules(Some("a"), &[named("one", "1")], "cursor").unwrap();
+        fs::write(store.path.join("a").join("bad.yaml"), "scope: [").unwrap();
+        let index = store.index().unwrap();
+        assert_eq!(index.projects["a"].rules.len(), 1);
+        assert_eq!(index.projects["a"].unparsed.len(), 1);
+    }
+}
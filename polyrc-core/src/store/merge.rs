@@ -0,0 +1,259 @@
+//! Rule-level merge of two versions of a project's rules. `sync` uses it for
+//! rule files changed on both sides, in place of git's line-by-line merge,
+//! which would mix the lines of two versions of one rule.
+
+use chrono::{DateTime, Utc};
+use crate::ir::Rule;
+use crate::sync::report::Side;
+use super::Tombstone;
+
+/// How [`merge_rules`] pairs local rules with remote ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// After matching by id, pair rules that have different ids but the same
+    /// name and project: one rule created separately on two machines.
+    pub match_by_name: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self { match_by_name: true }
+    }
+}
+
+/// A rule both sides have with different content, or one side deleted and the
+/// other edited after, and whose version was kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    /// The id the merged rule keeps (the local one).
+    pub id: String,
+    pub winner: Side,
+    /// Paired by name because the ids differ.
+    pub by_name: bool,
+    pub local_updated_at: Option<String>,
+    pub remote_updated_at: Option<String>,
+    /// When the other side deleted the rule, for an edit kept over a deletion.
+    pub deleted_at: Option<String>,
+}
+
+/// What [`merge_rules`] made of the two sides.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergedRules {
+    /// Local rules, with the remote version where it won, then the remote
+    /// rules that matched none, less the deleted ones.
+    pub rules: Vec<Rule>,
+    pub resolutions: Vec<Resolution>,
+}
+
+/// Merge the `remote` version of a project's rules into the `local` one.
+///
+/// Rules are paired by id, then with [`MergeOptions::match_by_name`] by name.
+/// Of a pair, the version updated last is kept (local on a tie or when neither
+/// has `updated_at`), under the local id and `created_at`. Unpaired rules from
+/// either side are kept as they are, unless `tombstones` (both sides', see
+/// [`merge_tombstones`](super::merge_tombstones)) say the other side deleted
+/// them: then they are dropped, or kept with a warning when updated after the
+/// deletion. A pair made by name raises a warning too.
+pub fn merge_rules(local: &[Rule], remote: &[Rule], tombstones: &[Tombstone], options: MergeOptions) -> MergedRules {
+    let mut merged = MergedRules { rules: local.to_vec(), resolutions: vec![] };
+    let mut paired = vec![false; local.len()];
+    let mut unmatched = vec![];
+    for theirs in remote {
+        let free = |i: &usize| !paired[*i];
+        let by_id = (0..local.len()).filter(free).find(|&i| !theirs.id.is_empty() && local[i].id == theirs.id);
+        let by_name = || {
+            (0..local.len())
+                .filter(free)
+                .find(|&i| theirs.name.is_some() && local[i].name == theirs.name && local[i].project == theirs.project)
+        };
+        let (i, by_name) = match (by_id, options.match_by_name) {
+            (Some(i), _) => (i, false),
+            (None, true) => match by_name() {
+                Some(i) => (i, true),
+                None => {
+                    unmatched.push(theirs.clone());
+                    continue;
+                }
+            },
+            (None, false) => {
+                unmatched.push(theirs.clone());
+                continue;
+            }
+        };
+        paired[i] = true;
+        let ours = &local[i];
+        let winner = if timestamp(theirs) > timestamp(ours) { Side::Remote } else { Side::Local };
+        if by_name {
+            crate::notice::warn(format!(
+                "{}: the local and remote rules have different ids but the same name; merged as one rule, keeping the {} version",
+                rule_label(ours),
+                side_name(winner),
+            ));
+        }
+        if winner == Side::Remote {
+            merged.rules[i] = Rule { id: ours.id.clone(), created_at: ours.created_at.clone(), ..theirs.clone() };
+        }
+        if !ours.same_content(theirs) {
+            merged.resolutions.push(Resolution {
+                id: ours.id.clone(),
+                winner,
+                by_name,
+                local_updated_at: ours.updated_at.clone(),
+                remote_updated_at: theirs.updated_at.clone(),
+                deleted_at: None,
+            });
+        }
+    }
+
+    // A rule only one side has may be one the other side deleted
+    let mut kept = vec![];
+    let unpaired = merged.rules.into_iter().zip(paired).map(|(rule, paired)| (rule, paired, Side::Local));
+    for (rule, paired, side) in unpaired.chain(unmatched.into_iter().map(|rule| (rule, false, Side::Remote))) {
+        let deletion = tombstones.iter().find(|t| !paired && !rule.id.is_empty() && t.id == rule.id);
+        match deletion {
+            None => kept.push(rule),
+            Some(t) if t.edited_after(&rule) => {
+                crate::notice::warn(format!(
+                    "{}: deleted on the {} side at {} but edited on the {} side after; keeping it",
+                    rule_label(&rule),
+                    side_name(other(side)),
+                    t.deleted_at,
+                    side_name(side),
+                ));
+                let updated_at = rule.updated_at.clone();
+                merged.resolutions.push(Resolution {
+                    id: rule.id.clone(),
+                    winner: side,
+                    by_name: false,
+                    local_updated_at: if side == Side::Local { updated_at.clone() } else { None },
+                    remote_updated_at: if side == Side::Remote { updated_at } else { None },
+                    deleted_at: Some(t.deleted_at.clone()),
+                });
+                kept.push(rule);
+            }
+            Some(_) => {}
+        }
+    }
+    merged.rules = kept;
+    merged
+}
+
+fn other(side: Side) -> Side {
+    match side {
+        Side::Local => Side::Remote,
+        Side::Remote => Side::Local,
+    }
+}
+
+/// `updated_at` as a time, so differently written offsets compare correctly.
+fn timestamp(rule: &Rule) -> Option<DateTime<Utc>> {
+    let raw = rule.updated_at.as_deref()?;
+    DateTime::parse_from_rfc3339(raw).ok().map(|t| t.with_timezone(&Utc))
+}
+
+fn side_name(side: Side) -> &'static str {
+    match side {
+        Side::Local => "local",
+        Side::Remote => "remote",
+    }
+}
+
+/// `project/name` for messages.
+fn rule_label(rule: &Rule) -> String {
+    let name = rule.name.clone().unwrap_or_else(|| rule.filename_stem());
+    match &rule.project {
+        Some(project) => format!("{project}/{name}"),
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, content: &str, updated_at: &str) -> Rule {
+        Rule {
+            id: id.into(),
+            name: Some("rust-style".into()),
+            project: Some("app".into()),
+            content: content.into(),
+            created_at: Some(updated_at.into()),
+            updated_at: Some(updated_at.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn same_id_keeps_the_version_updated_last() {
+        let local = rule("a", "Use tabs.", "2026-01-02T00:00:00Z");
+        let remote = rule("a", "Use spaces.", "2026-01-03T00:00:00+02:00");
+        let merged = merge_rules(std::slice::from_ref(&local), std::slice::from_ref(&remote), &[], MergeOptions::default());
+        assert_eq!(merged.rules, vec![Rule { created_at: local.created_at.clone(), ..remote }]);
+        assert_eq!(merged.resolutions.len(), 1);
+        assert_eq!(merged.resolutions[0].winner, Side::Remote);
+        assert!(!merged.resolutions[0].by_name);
+
+        let older = rule("a", "Use spaces.", "2026-01-01T00:00:00Z");
+        let merged = merge_rules(std::slice::from_ref(&local), &[older], &[], MergeOptions::default());
+        assert_eq!(merged.rules, vec![local]);
+        assert_eq!(merged.resolutions[0].winner, Side::Local);
+    }
+
+    #[test]
+    fn a_rule_created_on_both_machines_is_merged_by_name() {
+        let local = rule("local-id", "Use tabs.", "2026-01-02T00:00:00Z");
+        let remote = rule("remote-id", "Use spaces.", "2026-01-03T00:00:00Z");
+
+        // Matched by id only, the two copies stay apart
+        let merged = merge_rules(std::slice::from_ref(&local), std::slice::from_ref(&remote), &[], MergeOptions { match_by_name: false });
+        assert_eq!(merged.rules, vec![local.clone(), remote.clone()]);
+        assert!(merged.resolutions.is_empty());
+
+        crate::notice::take();
+        let merged = merge_rules(std::slice::from_ref(&local), std::slice::from_ref(&remote), &[], MergeOptions { match_by_name: true });
+        assert_eq!(merged.rules.len(), 1);
+        assert_eq!(merged.rules[0].id, "local-id");
+        assert_eq!(merged.rules[0].content, "Use spaces.");
+        assert_eq!(merged.rules[0].created_at, local.created_at);
+        assert_eq!(merged.resolutions.len(), 1);
+        assert!(merged.resolutions[0].by_name);
+        let notices = crate::notice::take();
+        assert_eq!(notices.len(), 1);
+        assert!(notices[0].message.contains("app/rust-style: the local and remote rules have different ids but the same name"));
+        assert!(notices[0].message.contains("keeping the remote version"));
+    }
+
+    #[test]
+    fn names_only_match_within_a_project() {
+        let local = rule("local-id", "Use tabs.", "2026-01-02T00:00:00Z");
+        let other = Rule { project: Some("lib".into()), ..rule("remote-id", "Use spaces.", "2026-01-03T00:00:00Z") };
+        let merged = merge_rules(&[local], &[other], &[], MergeOptions::default());
+        assert_eq!(merged.rules.len(), 2);
+    }
+
+    #[test]
+    fn tombstones_drop_rules_unless_edited_after_the_deletion() {
+        let stale = rule("a", "Use tabs.", "2026-01-02T00:00:00Z");
+        let edited = rule("b", "Use spaces.", "2026-01-04T00:00:00Z");
+        let tombstones = [
+            Tombstone { id: "a".into(), name: None, deleted_at: "2026-01-03T00:00:00Z".into() },
+            Tombstone { id: "b".into(), name: None, deleted_at: "2026-01-03T00:00:00Z".into() },
+        ];
+
+        // Deleted remotely: the stale local copy goes, the later edit stays
+        crate::notice::take();
+        let merged = merge_rules(&[stale.clone(), edited.clone()], &[], &tombstones, MergeOptions::default());
+        assert_eq!(merged.rules, vec![edited.clone()]);
+        assert_eq!(merged.resolutions.len(), 1);
+        assert_eq!(merged.resolutions[0].winner, Side::Local);
+        assert_eq!(merged.resolutions[0].deleted_at.as_deref(), Some("2026-01-03T00:00:00Z"));
+        let notices = crate::notice::take();
+        assert_eq!(notices.len(), 1);
+        assert!(notices[0].message.contains("app/rust-style: deleted on the remote side at 2026-01-03T00:00:00Z but edited on the local side after"));
+
+        // Deleted locally: the same goes for the remote copies
+        let merged = merge_rules(&[], &[stale, edited.clone()], &tombstones, MergeOptions::default());
+        assert_eq!(merged.rules, vec![edited]);
+        assert_eq!(merged.resolutions[0].winner, Side::Remote);
+    }
+}
@@ -0,0 +1,1496 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use walkdir::WalkDir;
+use crate::config::Config;
+use crate::error::{PolyrcError, Result};
+use crate::ir::{Rule, Scope};
+
+mod backup;
+mod cache;
+mod index;
+mod merge;
+mod readme;
+mod relocate;
+mod tombstone;
+pub use backup::{backups_dir, list_backups, rotate_backups, timestamped_backup_path, DEFAULT_BACKUP_RETENTION};
+pub use index::{IndexEntry, StoreIndex, INDEX_FILE};
+pub use merge::{merge_rules, MergeOptions, MergedRules, Resolution};
+pub use readme::{render_readme, README_FILE};
+pub use relocate::{check_destination, relocate, MoveMethod};
+pub use tombstone::{merge_tombstones, tombstones_from_yaml, Tombstone, DEFAULT_TOMBSTONE_RETENTION_DAYS, TOMBSTONE_FILE};
+pub(crate) use tombstone::write_tombstones;
+
+/// Directory name for user-scope rules (always-on ambient + on-demand commands).
+pub const USER_PROJECT: &str = "user";
+/// Legacy name — migrated to USER_PROJECT on first open.
+const USER_PROJECT_LEGACY: &str = "_user";
+/// Environment variable that overrides the store location.
+pub const STORE_ENV: &str = "POLYRC_STORE";
+/// Directory (under the store root) holding archived projects.
+pub const ARCHIVE_DIR: &str = ".archive";
+/// Optional per-project metadata file inside a project directory.
+pub const PROJECT_META_FILE: &str = "project.toml";
+/// Marker file at the store root; lets `Store::discover` find a store checked
+/// out inside another repo without any config.
+pub const STORE_MARKER: &str = "polyrc.toml";
+/// Patterns every store's `.gitignore` carries: derived files and OS/editor litter.
+pub const DEFAULT_IGNORES: &[&str] = &[
+    INDEX_FILE,
+    ".polyrc.lock",
+    ".DS_Store",
+    "Thumbs.db",
+    "*.swp",
+    "*.swo",
+    "*~",
+    "*.bak",
+    "*.orig",
+    "\\#*#",
+    ".#*",
+];
+
+/// Optional metadata stored in `<project>/project.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// RFC3339 timestamp of project creation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// Formats the project's rules are meant for, set by `project init`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_formats: Option<Vec<String>>,
+}
+
+/// Rules loaded from a project, plus the files that could not be parsed.
+#[derive(Debug, Default)]
+pub struct LoadedRules {
+    pub rules: Vec<Rule>,
+    /// Files that failed to read or parse, with the reason.
+    pub skipped: Vec<(PathBuf, PolyrcError)>,
+}
+
+/// Summary of one project, as shown by `project list` / `project show`.
+#[derive(Debug, Clone)]
+pub struct ProjectInfo {
+    pub name: String,
+    pub meta: ProjectMeta,
+    pub rule_count: usize,
+    /// Most recent `updated_at` across the project's rules.
+    pub last_updated: Option<String>,
+}
+
+/// The polyrc local store — a git repo containing IR rules as YAML files.
+pub struct Store {
+    /// Root of the store git repo (~/.polyrc/store or user-configured).
+    pub path: PathBuf,
+    /// Regenerate the root `README.md` whenever the index is refreshed.
+    pub generate_readme: bool,
+    /// Parsed rules by project directory (see [`cache`]).
+    rule_cache: RefCell<BTreeMap<PathBuf, cache::CachedRules>>,
+}
+
+impl Store {
+    /// A handle on the store directory at `path`, without any checks.
+    pub fn new(path: &Path) -> Self {
+        Self { path: path.to_path_buf(), generate_readme: false, rule_cache: RefCell::default() }
+    }
+
+    /// Open an existing store at `store_path`.
+    ///
+    /// A store carrying a `polyrc.toml` marker is self-describing. Otherwise
+    /// `~/polyrc/config.toml` is read to verify the store has been initialised.
+    pub fn open(store_path: &Path) -> Result<Self> {
+        if !store_path.join(STORE_MARKER).is_file() {
+            let config = Config::load().map_err(|_| PolyrcError::StoreNotFound)?;
+            if !config.store_initialized() {
+                return Err(PolyrcError::StoreNotFound);
+            }
+        }
+        let store = Self::new(store_path);
+        store.migrate_legacy_user_dir()?;
+        store.migrate_gitignore()?;
+        Ok(store)
+    }
+
+    /// Open a store checkout at `store_path` for reading only — no config lookup
+    /// and no migrations. Used by `polyrc check`, which must work against a plain
+    /// checkout on a CI machine.
+    pub fn open_read_only(store_path: &Path) -> Result<Self> {
+        if !store_path.is_dir() {
+            return Err(PolyrcError::StoreNotFound);
+        }
+        Ok(Self::new(store_path))
+    }
+
+    /// Append `entries` to the store's `.gitignore`, skipping any that already
+    /// appear as a line. Returns the entries that were added.
+    pub fn ignore(&self, entries: &[&str]) -> Result<Vec<String>> {
+        let file = self.path.join(".gitignore");
+        let mut content = fs::read_to_string(&file).unwrap_or_default();
+        let mut added = vec![];
+        for entry in entries {
+            if content.lines().any(|l| l.trim() == *entry) {
+                continue;
+            }
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str(entry);
+            content.push('\n');
+            added.push(entry.to_string());
+        }
+        if !added.is_empty() {
+            fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        }
+        Ok(added)
+    }
+
+    /// Open the store chosen by `resolve_store_path` for the current environment
+    /// and working directory. All commands go through this.
+    pub fn open_resolved(config: &Config) -> Result<Self> {
+        let env = std::env::var(STORE_ENV).ok();
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let mut store = Self::open(&resolve_store_path(env.as_deref(), config, &cwd))?;
+        store.generate_readme = config.generate_readme();
+        Ok(store)
+    }
+
+    /// Walk up from `start_dir` looking for a directory containing `polyrc.toml`.
+    pub fn discover(start_dir: &Path) -> Option<PathBuf> {
+        start_dir
+            .ancestors()
+            .find(|dir| dir.join(STORE_MARKER).is_file())
+            .map(Path::to_path_buf)
+    }
+
+    /// Rename `_user/` → `user/` if it still exists.
+    fn migrate_legacy_user_dir(&self) -> Result<()> {
+        self.invalidate_cache();
+        let legacy = self.path.join(USER_PROJECT_LEGACY);
+        let current = self.path.join(USER_PROJECT);
+        if legacy.exists() && !current.exists() {
+            if self.path.join(".git").exists() {
+                let file = timestamped_backup_path(&backups_dir());
+                match self.backup(&file) {
+                    Ok(()) => crate::notice::note(format!("backed up the store to {} before migrating", file.display())),
+                    Err(e) => crate::notice::warn(format!("could not back up the store before migrating: {e}")),
+                }
+            }
+            fs::rename(&legacy, &current).map_err(|e| PolyrcError::Io {
+                path: legacy.clone(),
+                source: e,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Give a store made before polyrc managed `.gitignore` the
+    /// [`DEFAULT_IGNORES`]. A store that has one is left as it is, so opening
+    /// the store doesn't write to it.
+    fn migrate_gitignore(&self) -> Result<()> {
+        if !self.path.join(".gitignore").exists() {
+            self.ignore(DEFAULT_IGNORES)?;
+        }
+        Ok(())
+    }
+
+    /// Load all rules for a given project key from the store.
+    /// Use `None` for user-scope rules (maps to `user/` directory).
+    ///
+    /// Strict: fails on the first file that cannot be read or parsed.
+    /// See `load_rules_lossy` for the tolerant variant.
+    pub fn load_rules(&self, project: Option<&str>) -> Result<Vec<Rule>> {
+        let loaded = self.load_rules_lossy(project)?;
+        match loaded.skipped.into_iter().next() {
+            Some((_, err)) => Err(err),
+            None => Ok(loaded.rules),
+        }
+    }
+
+    /// Load all rules for a project, skipping files that fail to read or parse.
+    /// Repeated loads of an unchanged project are served from memory.
+    ///
+    /// Only a failure to walk the directory itself is returned as an error.
+    pub fn load_rules_lossy(&self, project: Option<&str>) -> Result<LoadedRules> {
+        let dir = self.project_dir(project);
+        let mut loaded = LoadedRules::default();
+        if !dir.exists() {
+            return Ok(loaded);
+        }
+        let (stamps, cached) = self.cached_rules(&dir)?;
+        if let Some(rules) = cached {
+            return Ok(LoadedRules { rules, skipped: vec![] });
+        }
+        for entry in WalkDir::new(&dir).min_depth(1).max_depth(1).sort_by_file_name() {
+            let entry = entry.map_err(|e| PolyrcError::Io {
+                path: dir.clone(),
+                source: e.into(),
+            })?;
+            let p = entry.path();
+            if p.extension().and_then(|e| e.to_str()) != Some("yaml") {
+                continue;
+            }
+            match read_rule_file(p) {
+                Ok(rule) => loaded.rules.push(rule),
+                Err(e) => loaded.skipped.push((p.to_path_buf(), e)),
+            }
+        }
+        if loaded.skipped.is_empty() {
+            self.cache_rules(dir, stamps, &loaded.rules);
+        }
+        Ok(loaded)
+    }
+
+    /// Save rules from `source_format` for a project into the store, as an
+    /// [`SaveReason::Edit`], merged with the rules other formats pushed (see
+    /// [`SaveMode::Merge`]). Auto-assigns IDs and timestamps. Returns `rules` as stored.
+    ///
+    /// Files that fail to parse are left on disk untouched (they may be recoverable);
+    /// a new rule that would overwrite one is rejected.
+    pub fn save_rules(&self, project: Option<&str>, rules: &[Rule], source_format: &str) -> Result<Vec<Rule>> {
+        self.save_rules_with(project, rules, source_format, SaveReason::Edit, SaveMode::Merge)
+    }
+
+    /// [`save_rules`](Self::save_rules), but the project ends up with exactly
+    /// `rules` (see [`SaveMode::Replace`]).
+    pub fn replace_rules(&self, project: Option<&str>, rules: &[Rule], source_format: &str) -> Result<Vec<Rule>> {
+        self.save_rules_with(project, rules, source_format, SaveReason::Edit, SaveMode::Replace)
+    }
+
+    /// Rewrite a project with exactly `rules`, for a reason which decides
+    /// whether `updated_at` moves. A [`SaveReason::Normalize`] keeps each
+    /// rule's own `source_format`.
+    pub fn save_rules_for(
+        &self,
+        project: Option<&str>,
+        rules: &[Rule],
+        source_format: &str,
+        reason: SaveReason,
+    ) -> Result<Vec<Rule>> {
+        self.save_rules_with(project, rules, source_format, reason, SaveMode::Replace)
+    }
+
+    fn save_rules_with(
+        &self,
+        project: Option<&str>,
+        rules: &[Rule],
+        source_format: &str,
+        reason: SaveReason,
+        mode: SaveMode,
+    ) -> Result<Vec<Rule>> {
+        let dir = self.project_dir(project);
+        fs::create_dir_all(&dir).map_err(|e| PolyrcError::Io {
+            path: dir.clone(),
+            source: e,
+        })?;
+
+        // Load existing rules to preserve IDs and created_at
+        let LoadedRules { rules: existing, skipped } = self.load_rules_lossy(project)?;
+        let unparseable: Vec<PathBuf> = skipped.into_iter().map(|(p, _)| p).collect();
+        self.invalidate_cache();
+
+        // Rewritten as they are, ahead of the new rules so their file names hold
+        let kept: Vec<Rule> = existing.iter().filter(|e| !mode.replaces(e, rules, source_format)).cloned().collect();
+        let rules: &[Rule] = &[kept.as_slice(), rules].concat();
+        let stems = crate::ir::unique_filename_stems(rules);
+        for stem in &stems {
+            let file = dir.join(format!("{}.yaml", stem));
+            if unparseable.contains(&file) {
+                return Err(PolyrcError::WriteFailure {
+                    path: file,
+                    reason: "refusing to overwrite a rule file that failed to parse; fix or remove it first".to_string(),
+                });
+            }
+        }
+
+        // Remove old files (except ones we could not parse)
+        for e in WalkDir::new(&dir).min_depth(1).max_depth(1).into_iter().flatten() {
+            let p = e.path();
+            if p.extension().and_then(|ex| ex.to_str()) == Some("yaml") && !unparseable.iter().any(|u| u == p) {
+                fs::remove_file(p).map_err(|err| PolyrcError::Io {
+                    path: p.to_path_buf(),
+                    source: err,
+                })?;
+            }
+        }
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let project_key = project.unwrap_or(USER_PROJECT).to_string();
+
+        let matches = match_existing(rules, &existing);
+        let mut ids = std::collections::HashSet::new();
+        let mut stored = vec![];
+        for (i, ((rule, stem), existing_match)) in rules.iter().zip(&stems).zip(matches).enumerate() {
+            let is_kept = i < kept.len();
+            let renamed_from = existing_match.filter(|ex| ex.name != rule.name);
+            if let Some(ex) = renamed_from {
+                let show = |r: &Rule| r.name.clone().unwrap_or_else(|| r.filename_stem());
+                crate::notice::note(format!("renamed: {} → {}", show(ex), show(rule)));
+            }
+
+            let mut r = rule.clone();
+            r.project = Some(project_key.clone());
+            if !is_kept && (reason == SaveReason::Edit || r.source_format.is_none()) {
+                r.source_format = Some(source_format.to_string());
+            }
+            r.store_version = "1".to_string();
+
+            if let Some(ex) = existing_match {
+                r.id = ex.id.clone();
+                r.created_at = ex.created_at.clone();
+            } else {
+                if r.id.is_empty() {
+                    r.id = Uuid::new_v4().to_string();
+                }
+                r.created_at = Some(now.clone());
+            }
+            // Older saves could give same-named rules one id
+            if !ids.insert(r.id.clone()) {
+                r.id = Uuid::new_v4().to_string();
+                ids.insert(r.id.clone());
+            }
+            // A rename is a change even when the content is the same
+            let unchanged = existing_match.filter(|_| renamed_from.is_none() || reason == SaveReason::Normalize);
+            r.updated_at = reason.updated_at(rule, unchanged, &now);
+
+            let filename = format!("{}.yaml", stem);
+            let file = dir.join(&filename);
+            let content = rule_to_yaml(&r, &file)?;
+            crate::debug_log!("store: wrote {}", file.display());
+            fs::write(&file, content).map_err(|e| PolyrcError::Io {
+                path: file,
+                source: e,
+            })?;
+            if !is_kept {
+                stored.push(r);
+            }
+        }
+        let deleted: Vec<&Rule> = existing.iter().filter(|e| !ids.contains(&e.id)).collect();
+        let written: Vec<&str> = ids.iter().map(String::as_str).collect();
+        self.record_deletions(project, &deleted, &written)?;
+        self.refresh_index();
+        Ok(stored)
+    }
+
+    /// Find a rule by name. With `Some(ns)` only that namespace is searched; with
+    /// `None` every project is searched (alphabetical, `user` last).
+    ///
+    /// Returns `(namespace_key, rule)`, or `PolyrcError::AmbiguousRule` listing the
+    /// candidate namespaces when the name exists in more than one project.
+    pub fn load_rule_by_name(&self, name: &str, namespace: Option<&str>) -> Result<Option<(String, Rule)>> {
+        Ok(self.locate_rule(name, namespace)?.map(|(ns, _, rule)| (ns, rule)))
+    }
+
+    /// Like [`Store::load_rule_by_name`], but also returns the rule's file.
+    pub fn locate_rule(&self, name: &str, namespace: Option<&str>) -> Result<Option<(String, PathBuf, Rule)>> {
+        let mut matches = self.find_rule_files(name, namespace)?;
+        if matches.len() > 1 {
+            return Err(PolyrcError::AmbiguousRule {
+                name: name.to_string(),
+                candidates: matches.into_iter().map(|(ns, _, _)| ns).collect(),
+            });
+        }
+        Ok(matches.pop())
+    }
+
+    /// All `(namespace_key, rule)` pairs whose file stem, name or filename stem equals
+    /// `name` — at most one per namespace, in the same order as `load_rule_by_name`.
+    pub fn find_rules_by_name(&self, name: &str, namespace: Option<&str>) -> Result<Vec<(String, Rule)>> {
+        Ok(self.find_rule_files(name, namespace)?.into_iter().map(|(ns, _, rule)| (ns, rule)).collect())
+    }
+
+    fn find_rule_files(&self, name: &str, namespace: Option<&str>) -> Result<Vec<(String, PathBuf, Rule)>> {
+        let search_order: Vec<String> = if let Some(ns) = namespace {
+            vec![ns.to_string()]
+        } else {
+            let mut all = self.list_projects()?;
+            // Move "user" to the back so project rules are reported first
+            if let Some(pos) = all.iter().position(|n| n == USER_PROJECT) {
+                all.remove(pos);
+                all.push(USER_PROJECT.to_string());
+            }
+            all
+        };
+
+        let mut found = vec![];
+        for ns in &search_order {
+            let dir = self.path.join(ns);
+            if !dir.exists() {
+                continue;
+            }
+            for entry in WalkDir::new(&dir).min_depth(1).max_depth(1).sort_by_file_name() {
+                let entry = entry.map_err(|e| PolyrcError::Io { path: dir.clone(), source: e.into() })?;
+                let p = entry.path();
+                if p.extension().and_then(|e| e.to_str()) != Some("yaml") {
+                    continue;
+                }
+                // Unparseable files can't be matched by name; they surface via load_rules_lossy
+                let Ok(rule) = read_rule_file(p) else { continue };
+                let stem = p.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+                if stem == name || rule.name.as_deref() == Some(name) || rule.filename_stem() == name {
+                    found.push((ns.clone(), p.to_path_buf(), rule));
+                    break;
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Names of all rules in `namespace`, or in every project when `None`.
+    /// Read from the index; unparseable files are skipped.
+    pub fn rule_names(&self, namespace: Option<&str>) -> Result<Vec<String>> {
+        let index = self.index()?;
+        let mut names: Vec<String> = index
+            .projects
+            .iter()
+            .filter(|(ns, _)| namespace.is_none_or(|want| want == ns.as_str()))
+            .flat_map(|(_, p)| p.rules.iter().map(IndexEntry::display_name))
+            .collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    /// Save a single named rule into the given namespace (e.g. "user", "myApp").
+    /// Returns the stored rule (with id and timestamps set).
+    ///
+    /// `name` is validated, and the resolved file path must stay inside the
+    /// namespace directory, which in turn must stay inside the store.
+    pub fn save_rule_to_namespace(&self, namespace: &str, name: &str, rule: &Rule) -> Result<Rule> {
+        self.invalidate_cache();
+        crate::ir::validate_rule_name(name)?;
+        let mut components = Path::new(namespace).components();
+        if !matches!(
+            (components.next(), components.next()),
+            (Some(std::path::Component::Normal(_)), None)
+        ) {
+            return Err(PolyrcError::WriteFailure {
+                path: self.path.join(namespace),
+                reason: "namespace must be a single directory name".to_string(),
+            });
+        }
+        let dir = self.path.join(namespace);
+        fs::create_dir_all(&dir).map_err(|e| PolyrcError::Io { path: dir.clone(), source: e })?;
+        ensure_inside(&self.path, &dir)?;
+        ensure_inside(&dir, &dir.join(format!("{}.yaml", name)))?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+
+        // Preserve existing id / created_at if rule already exists
+        let existing = self.load_rule_by_name(name, Some(namespace)).unwrap_or(None);
+        let mut r = rule.clone();
+        r.project = Some(namespace.to_string());
+        r.store_version = "1".to_string();
+
+        r.updated_at = SaveReason::Edit.updated_at(rule, existing.as_ref().map(|(_, ex)| ex), &now);
+        match existing {
+            Some((_, ex)) => {
+                r.id = ex.id;
+                r.created_at = ex.created_at;
+            }
+            None => {
+                if r.id.is_empty() { r.id = Uuid::new_v4().to_string(); }
+                r.created_at = Some(now);
+            }
+        }
+        if r.name.is_none() { r.name = Some(name.to_string()); }
+
+        let filename = format!("{}.yaml", name);
+        let file = dir.join(&filename);
+        let content = rule_to_yaml(&r, &file)?;
+        crate::debug_log!("store: wrote {}", file.display());
+        fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        self.record_deletions(Some(namespace), &[], &[&r.id])?;
+        self.refresh_index();
+        Ok(r)
+    }
+
+    /// Move the rule `name` from namespace `from` to `to`, or with `copy` copy it.
+    /// The file name is kept and `project` updated, as is `scope` when the rule
+    /// crosses between user and project. A move keeps the id and timestamps; a
+    /// copy is a new rule. A rule of that name already in `to` is an error unless
+    /// `force` replaces it. Returns the rule as written. Does not commit.
+    pub fn transfer_rule(&self, name: &str, from: &str, to: &str, copy: bool, force: bool) -> Result<Rule> {
+        self.invalidate_cache();
+        let Some((_, src, rule)) = self.locate_rule(name, Some(from))? else {
+            return Err(PolyrcError::RuleNotFound {
+                name: name.to_string(),
+                namespace: Some(from.to_string()),
+                available: self.rule_names(Some(from))?,
+            });
+        };
+        let dir = self.path.join(to);
+        if from == to {
+            return Err(PolyrcError::WriteFailure { path: dir, reason: format!("'{name}' is already in {to}") });
+        }
+        fs::create_dir_all(&dir).map_err(|e| PolyrcError::Io { path: dir.clone(), source: e })?;
+        ensure_inside(&self.path, &dir)?;
+        let dest = dir.join(src.file_name().unwrap_or_default());
+
+        let mut clashes: Vec<PathBuf> = self.locate_rule(name, Some(to))?.map(|(_, file, _)| file).into_iter().collect();
+        if dest.exists() && !clashes.contains(&dest) {
+            clashes.push(dest.clone());
+        }
+        if let Some(clash) = clashes.first().filter(|_| !force) {
+            return Err(PolyrcError::WriteFailure {
+                path: clash.clone(),
+                reason: format!("{to} already has a rule '{name}' (use --force to replace it)"),
+            });
+        }
+        let replaced: Vec<Rule> = clashes.iter().filter_map(|clash| read_rule_file(clash).ok()).collect();
+        for clash in &clashes {
+            fs::remove_file(clash).map_err(|e| PolyrcError::Io { path: clash.clone(), source: e })?;
+        }
+
+        let mut r = rule;
+        r.project = Some(to.to_string());
+        if to == USER_PROJECT {
+            r.scope = Scope::User;
+        } else if r.scope == Scope::User {
+            r.scope = Scope::Project;
+        }
+        if copy {
+            let now = chrono::Utc::now().to_rfc3339();
+            r.id = Uuid::new_v4().to_string();
+            r.created_at = Some(now.clone());
+            r.updated_at = Some(now);
+        }
+        let content = rule_to_yaml(&r, &dest)?;
+        crate::debug_log!("store: wrote {}", dest.display());
+        fs::write(&dest, content).map_err(|e| PolyrcError::Io { path: dest.clone(), source: e })?;
+        self.record_deletions(Some(to), &replaced.iter().collect::<Vec<_>>(), &[&r.id])?;
+        if !copy {
+            fs::remove_file(&src).map_err(|e| PolyrcError::Io { path: src.clone(), source: e })?;
+            self.record_deletions(Some(from), &[&r], &[])?;
+        }
+        self.refresh_index();
+        Ok(r)
+    }
+
+    /// Rewrite the rule file `file`, found by [`Store::locate_rule`], with `rule`
+    /// and a fresh `updated_at`. Returns the rule as written. Does not commit.
+    pub fn update_rule_file(&self, file: &Path, rule: &Rule) -> Result<Rule> {
+        self.invalidate_cache();
+        ensure_inside(&self.path, file)?;
+        let mut r = rule.clone();
+        r.updated_at = Some(chrono::Utc::now().to_rfc3339());
+        let content = rule_to_yaml(&r, file)?;
+        crate::debug_log!("store: wrote {}", file.display());
+        fs::write(file, content).map_err(|e| PolyrcError::Io { path: file.to_path_buf(), source: e })?;
+        self.refresh_index();
+        Ok(r)
+    }
+
+    /// List all namespace directories in the store (direct subdirs of store root).
+    /// Hidden directories (`.git`, `.archive`) are excluded.
+    pub fn list_projects(&self) -> Result<Vec<String>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let mut projects = vec![];
+        for entry in WalkDir::new(&self.path).min_depth(1).max_depth(1) {
+            let entry = entry.map_err(|e| PolyrcError::Io {
+                path: self.path.clone(),
+                source: e.into(),
+            })?;
+            if entry.file_type().is_dir()
+                && let Some(name) = entry.file_name().to_str()
+                && !name.starts_with('.')
+            {
+                projects.push(name.to_string());
+            }
+        }
+        projects.sort();
+        Ok(projects)
+    }
+
+    /// Files in a project directory that are neither rule files (`*.yaml`),
+    /// `project.toml` nor `.deleted.yml`, as store-relative paths. No command reads them, so they
+    /// are only carried around.
+    pub fn orphan_files(&self, project: &str) -> Result<Vec<PathBuf>> {
+        let dir = self.path.join(project);
+        if !dir.is_dir() {
+            return Err(self.project_not_found(project));
+        }
+        let mut orphans = vec![];
+        for entry in WalkDir::new(&dir).min_depth(1).sort_by_file_name() {
+            let entry = entry.map_err(|e| PolyrcError::Io { path: dir.clone(), source: e.into() })?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let is_rule = entry.depth() == 1 && entry.path().extension().and_then(|e| e.to_str()) == Some("yaml");
+            let is_meta = entry.depth() == 1 && (entry.file_name() == PROJECT_META_FILE || entry.file_name() == TOMBSTONE_FILE);
+            if !is_rule && !is_meta {
+                orphans.push(entry.path().strip_prefix(&self.path).unwrap_or(entry.path()).to_path_buf());
+            }
+        }
+        Ok(orphans)
+    }
+
+    /// Projects whose directory holds nothing but (optionally) `project.toml`
+    /// and `.deleted.yml`.
+    /// The user project is never reported.
+    pub fn empty_projects(&self) -> Result<Vec<String>> {
+        let mut empty = vec![];
+        for project in self.list_projects()? {
+            if project == USER_PROJECT {
+                continue;
+            }
+            let dir = self.path.join(&project);
+            let has_content = fs::read_dir(&dir)
+                .map_err(|e| PolyrcError::Io { path: dir.clone(), source: e })?
+                .filter_map(|e| e.ok())
+                .any(|e| e.file_name() != PROJECT_META_FILE && e.file_name() != TOMBSTONE_FILE);
+            if !has_content {
+                empty.push(project);
+            }
+        }
+        Ok(empty)
+    }
+
+    /// Delete the directories of all [`empty_projects`](Self::empty_projects).
+    /// Returns the removed project names. Does not commit.
+    pub fn prune_empty_projects(&self) -> Result<Vec<String>> {
+        self.invalidate_cache();
+        let empty = self.empty_projects()?;
+        for project in &empty {
+            let dir = self.path.join(project);
+            fs::remove_dir_all(&dir).map_err(|e| PolyrcError::Io { path: dir, source: e })?;
+        }
+        if !empty.is_empty() {
+            self.refresh_index();
+        }
+        Ok(empty)
+    }
+
+    /// Rewrite every rule file whose bytes differ from its canonical form (see
+    /// [`rule_to_yaml`]). Unparseable files are left alone. Returns the
+    /// rewritten files relative to the store root. Does not commit.
+    pub fn normalize(&self) -> Result<Vec<PathBuf>> {
+        self.invalidate_cache();
+        let mut rewritten = vec![];
+        for project in self.list_projects()? {
+            let dir = self.path.join(&project);
+            for entry in WalkDir::new(&dir).min_depth(1).max_depth(1).sort_by_file_name() {
+                let entry = entry.map_err(|e| PolyrcError::Io { path: dir.clone(), source: e.into() })?;
+                let p = entry.path();
+                if p.extension().and_then(|e| e.to_str()) != Some("yaml") {
+                    continue;
+                }
+                let Ok(rule) = read_rule_file(p) else { continue };
+                let canonical = rule_to_yaml(&rule, p)?;
+                if fs::read(p).is_ok_and(|raw| raw == canonical.as_bytes()) {
+                    continue;
+                }
+                fs::write(p, canonical).map_err(|e| PolyrcError::Io { path: p.to_path_buf(), source: e })?;
+                rewritten.push(p.strip_prefix(&self.path).unwrap_or(p).to_path_buf());
+            }
+        }
+        if !rewritten.is_empty() {
+            self.refresh_index();
+        }
+        Ok(rewritten)
+    }
+
+    /// Rename a project directory in the store.
+    pub fn rename_project(&self, old_name: &str, new_name: &str) -> Result<()> {
+        self.invalidate_cache();
+        let old_dir = self.path.join(old_name);
+        let new_dir = self.path.join(new_name);
+        if !old_dir.exists() {
+            return Err(self.project_not_found(old_name));
+        }
+        if new_dir.exists() {
+            return Err(PolyrcError::WriteFailure {
+                path: new_dir,
+                reason: "target project already exists".to_string(),
+            });
+        }
+        fs::rename(&old_dir, &new_dir).map_err(|e| PolyrcError::Io {
+            path: old_dir,
+            source: e,
+        })?;
+        self.refresh_index();
+        Ok(())
+    }
+
+    /// Read `<project>/project.toml`, or defaults if it doesn't exist.
+    pub fn project_meta(&self, name: &str) -> Result<ProjectMeta> {
+        let file = self.path.join(name).join(PROJECT_META_FILE);
+        if !file.exists() {
+            return Ok(ProjectMeta::default());
+        }
+        let raw = fs::read_to_string(&file).map_err(|e| PolyrcError::Io {
+            path: file.clone(),
+            source: e,
+        })?;
+        toml::from_str(&raw).map_err(|e| PolyrcError::TomlParse { path: file, source: e })
+    }
+
+    /// Write `<project>/project.toml`, creating the project directory.
+    pub fn write_project_meta(&self, name: &str, meta: &ProjectMeta) -> Result<()> {
+        let dir = self.path.join(name);
+        fs::create_dir_all(&dir).map_err(|e| PolyrcError::Io { path: dir.clone(), source: e })?;
+        ensure_inside(&self.path, &dir)?;
+        let file = dir.join(PROJECT_META_FILE);
+        let content = toml::to_string(meta).map_err(|e| PolyrcError::ConfigError {
+            msg: format!("failed to serialize {}: {e}", file.display()),
+        })?;
+        fs::write(&file, content).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        self.refresh_index();
+        Ok(())
+    }
+
+    /// Collect metadata, rule count and last update for a project.
+    pub fn project_info(&self, name: &str) -> Result<ProjectInfo> {
+        let dir = self.path.join(name);
+        if !dir.is_dir() {
+            return Err(self.project_not_found(name));
+        }
+        let index = self.index()?;
+        let rules = index.projects.get(name).map(|p| p.rules.as_slice()).unwrap_or_default();
+        let last_updated = rules.iter().filter_map(|r| r.updated_at.clone()).max();
+        Ok(ProjectInfo {
+            name: name.to_string(),
+            meta: self.project_meta(name)?,
+            rule_count: rules.len(),
+            last_updated,
+        })
+    }
+
+    /// List archived project names (subdirs of `.archive/`), sorted.
+    pub fn list_archived_projects(&self) -> Result<Vec<String>> {
+        let dir = self.path.join(ARCHIVE_DIR);
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut names = vec![];
+        for entry in WalkDir::new(&dir).min_depth(1).max_depth(1) {
+            let entry = entry.map_err(|e| PolyrcError::Io { path: dir.clone(), source: e.into() })?;
+            if entry.file_type().is_dir()
+                && let Some(name) = entry.file_name().to_str()
+            {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Move a project to `.archive/<name>`. The `user` project cannot be archived.
+    pub fn archive_project(&self, name: &str) -> Result<()> {
+        self.invalidate_cache();
+        if name == USER_PROJECT {
+            return Err(PolyrcError::WriteFailure {
+                path: self.path.join(name),
+                reason: "the user project cannot be archived".to_string(),
+            });
+        }
+        if !self.path.join(name).is_dir() {
+            return Err(self.project_not_found(name));
+        }
+        let archive = self.path.join(ARCHIVE_DIR);
+        fs::create_dir_all(&archive).map_err(|e| PolyrcError::Io { path: archive.clone(), source: e })?;
+        move_project_dir(&self.path.join(name), &archive.join(name))?;
+        self.refresh_index();
+        Ok(())
+    }
+
+    /// Move `.archive/<name>` back to the store root.
+    pub fn unarchive_project(&self, name: &str) -> Result<()> {
+        self.invalidate_cache();
+        let from = self.path.join(ARCHIVE_DIR).join(name);
+        if !from.is_dir() {
+            return Err(PolyrcError::ProjectNotFound {
+                name: name.to_string(),
+                available: self.list_archived_projects().unwrap_or_default(),
+            });
+        }
+        move_project_dir(&from, &self.path.join(name))?;
+        self.refresh_index();
+        Ok(())
+    }
+
+    /// `ProjectNotFound` for `name`, listing the projects that do exist.
+    pub fn project_not_found(&self, name: &str) -> PolyrcError {
+        PolyrcError::ProjectNotFound {
+            name: name.to_string(),
+            available: self.list_projects().unwrap_or_default(),
+        }
+    }
+
+    fn project_dir(&self, project: Option<&str>) -> PathBuf {
+        let key = project.unwrap_or(USER_PROJECT);
+        self.path.join(key)
+    }
+}
+
+/// Rename `from` to `to`, refusing to overwrite an existing project.
+fn move_project_dir(from: &Path, to: &Path) -> Result<()> {
+    if to.exists() {
+        return Err(PolyrcError::WriteFailure {
+            path: to.to_path_buf(),
+            reason: "target project already exists".to_string(),
+        });
+    }
+    fs::rename(from, to).map_err(|e| PolyrcError::Io { path: from.to_path_buf(), source: e })
+}
+
+/// Guard against path traversal: `path` (or, if it doesn't exist yet, its parent)
+/// must canonicalize to a location under `base`.
+fn ensure_inside(base: &Path, path: &Path) -> Result<()> {
+    let canon = |p: &Path| {
+        p.canonicalize().map_err(|e| PolyrcError::Io { path: p.to_path_buf(), source: e })
+    };
+    let base_canon = canon(base)?;
+    let resolved = if path.exists() {
+        canon(path)?
+    } else {
+        let parent = path.parent().unwrap_or(path);
+        match path.file_name() {
+            Some(file) => canon(parent)?.join(file),
+            None => canon(parent)?,
+        }
+    };
+    if resolved.starts_with(&base_canon) && resolved != base_canon {
+        Ok(())
+    } else {
+        Err(PolyrcError::WriteFailure {
+            path: path.to_path_buf(),
+            reason: format!("resolves outside {}", base.display()),
+        })
+    }
+}
+
+/// The stored rule each of `rules` continues, whose id and `created_at` it
+/// keeps. Rules are matched by name first, each stored rule at most once and
+/// preferring one with the same activation, so same-named rules keep their own
+/// ids. A rule left over is then matched to a leftover stored rule with the
+/// same content, as a rename, but only when that pairing is one-to-one.
+fn match_existing<'a>(rules: &[Rule], existing: &'a [Rule]) -> Vec<Option<&'a Rule>> {
+    let existing: Vec<&Rule> = existing.iter().filter(|e| !e.id.is_empty()).collect();
+    let mut matches: Vec<Option<&Rule>> = vec![None; rules.len()];
+    for same_activation in [true, false] {
+        for (i, rule) in rules.iter().enumerate() {
+            if matches[i].is_some() {
+                continue;
+            }
+            let taken = |e: &Rule| matches.iter().flatten().any(|m| std::ptr::eq(*m, e));
+            let found = existing.iter().copied().find(|e| {
+                e.name == rule.name && (!same_activation || e.activation == rule.activation) && !taken(e)
+            });
+            matches[i] = found;
+        }
+    }
+
+    let unmatched_existing: Vec<&Rule> = existing
+        .iter()
+        .copied()
+        .filter(|e| !rules.iter().any(|r| r.name == e.name))
+        .collect();
+    let unmatched_rules: Vec<usize> = (0..rules.len()).filter(|&i| matches[i].is_none()).collect();
+    for &i in &unmatched_rules {
+        let key = normalized_content(&rules[i].content);
+        let candidates: Vec<&Rule> =
+            unmatched_existing.iter().copied().filter(|e| normalized_content(&e.content) == key).collect();
+        let rivals = unmatched_rules.iter().filter(|&&j| normalized_content(&rules[j].content) == key).count();
+        if let [only] = candidates[..]
+            && rivals == 1
+        {
+            matches[i] = Some(only);
+        }
+    }
+    matches
+}
+
+/// Content with line endings unified and surrounding whitespace trimmed.
+fn normalized_content(content: &str) -> String {
+    content.replace("\r\n", "\n").trim().to_string()
+}
+
+/// What happens to a project's stored rules that a save doesn't include.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveMode {
+    /// Only rules from the same source format, or with the name of a saved
+    /// rule, are replaced; rules pushed from other formats are kept.
+    Merge,
+    /// Every stored rule is replaced (`push-format --replace`).
+    Replace,
+}
+
+impl SaveMode {
+    /// Whether saving `rules` from `source_format` replaces the stored `existing` rule.
+    pub fn replaces(self, existing: &Rule, rules: &[Rule], source_format: &str) -> bool {
+        self == Self::Replace
+            || existing.source_format.as_deref() == Some(source_format)
+            || existing.name.is_some() && rules.iter().any(|r| r.name == existing.name)
+    }
+}
+
+/// Why rules are being saved, which decides whether `updated_at` moves, so the
+/// timestamp records when someone last changed what a rule says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveReason {
+    /// A push or import: `updated_at` is bumped for new rules and for rules
+    /// whose [content](Rule::same_content) changed, and kept otherwise.
+    Edit,
+    /// A metadata-only rewrite (sync re-save, ID backfill): `updated_at` is
+    /// kept byte-for-byte and only set when a rule has none.
+    Normalize,
+}
+
+impl SaveReason {
+    fn updated_at(self, rule: &Rule, existing: Option<&Rule>, now: &str) -> Option<String> {
+        let kept = match self {
+            SaveReason::Edit => existing.filter(|ex| ex.same_content(rule)).and_then(|ex| ex.updated_at.clone()),
+            SaveReason::Normalize => rule.updated_at.clone().or_else(|| existing.and_then(|ex| ex.updated_at.clone())),
+        };
+        Some(kept.unwrap_or_else(|| now.to_string()))
+    }
+}
+
+/// A rule as stored on disk, in canonical form: fields in [`Rule`]
+/// declaration order, empty optional fields omitted and exactly one trailing
+/// newline, so unrelated changes never reshuffle a file. Any map- or
+/// list-valued metadata added to [`Rule`] must be sorted here as well; `globs`
+/// keeps its authored order since writers emit it as is.
+pub fn rule_to_yaml(rule: &Rule, file: &Path) -> Result<String> {
+    let yaml = serde_yml::to_string(rule).map_err(|e| PolyrcError::YamlParse { path: file.to_path_buf(), source: e })?;
+    Ok(format!("{}\n", yaml.trim_end_matches('\n')))
+}
+
+/// Read and parse a single stored rule file. Files over the size limit are
+/// rejected before being read.
+fn read_rule_file(p: &Path) -> Result<Rule> {
+    crate::parser::check_file_size(p)?;
+    let raw = fs::read_to_string(p).map_err(|e| PolyrcError::Io {
+        path: p.to_path_buf(),
+        source: e,
+    })?;
+    rule_from_yaml(&raw, p)
+}
+
+/// Parse a stored rule, read from `file`; the inverse of [`rule_to_yaml`].
+pub fn rule_from_yaml(raw: &str, file: &Path) -> Result<Rule> {
+    serde_yml::from_str(raw).map_err(|e| PolyrcError::YamlParse { path: file.to_path_buf(), source: e })
+}
+
+/// Decide which store to use. Precedence, highest first:
+///
+/// 1. `$POLYRC_STORE` (passed in as `env`)
+/// 2. `[store] path` in `~/polyrc/config.toml`
+/// 3. the nearest ancestor of `start_dir` containing `polyrc.toml`
+/// 4. the default `~/polyrc/store`
+pub fn resolve_store_path(env: Option<&str>, config: &Config, start_dir: &Path) -> PathBuf {
+    if let Some(p) = env.filter(|p| !p.is_empty()) {
+        return crate::config::expand_tilde(p);
+    }
+    if let Some(p) = config.configured_store_path() {
+        return p;
+    }
+    if let Some(p) = Store::discover(start_dir) {
+        return p;
+    }
+    crate::config::default_store_path()
+}
+
+/// Set up the git repo for the store at `store_path`.
+///
+/// Creates the store directory skeleton (`user/`, `polyrc.toml` marker) and
+/// initialises git if not already present. Config fields (version, remote_url) are written
+/// by the caller so there is no double-load / overwrite race.
+pub fn init_git(store_path: &Path) -> Result<()> {
+    fs::create_dir_all(store_path).map_err(|e| PolyrcError::Io {
+        path: store_path.to_path_buf(),
+        source: e,
+    })?;
+
+    // Eagerly create the user/ dir so the store structure is visible immediately.
+    let user_dir = store_path.join(USER_PROJECT);
+    if !user_dir.exists() {
+        fs::create_dir_all(&user_dir).map_err(|e| PolyrcError::Io { path: user_dir, source: e })?;
+    }
+
+    let marker = store_path.join(STORE_MARKER);
+    if !marker.exists() {
+        fs::write(&marker, "store_version = \"1\"\n")
+            .map_err(|e| PolyrcError::Io { path: marker, source: e })?;
+    }
+
+    Store::new(store_path).ignore(DEFAULT_IGNORES)?;
+
+    let git_dir = store_path.join(".git");
+    if !git_dir.exists() {
+        crate::sync::git_init(store_path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StoreConfig;
+    use crate::ir::Activation;
+
+    fn config_with_path(path: Option<&str>) -> Config {
+        Config {
+            store: StoreConfig { path: path.map(str::to_string), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    fn store_with_projects(names: &[&str]) -> (tempfile::TempDir, Store) {
+        let dir = tempfile::tempdir().unwrap();
+        for name in names {
+            fs::create_dir_all(dir.path().join(name)).unwrap();
+        }
+        let store = Store::new(dir.path());
+        (dir, store)
+    }
+
+    #[test]
+    fn list_projects_skips_hidden_and_archived() {
+        let (_dir, store) = store_with_projects(&["user", "myApp", ".git", ".archive/oldApp"]);
+        assert_eq!(store.list_projects().unwrap(), vec!["myApp", "user"]);
+        assert_eq!(store.list_archived_projects().unwrap(), vec!["oldApp"]);
+    }
+
+    #[test]
+    fn project_info_reads_meta_and_rules() {
+        let (_dir, store) = store_with_projects(&["myApp"]);
+        fs::write(
+            store.path.join("myApp").join(PROJECT_META_FILE),
+            "description = \"The app\"\n",
+        )
+        .unwrap();
+        let rule = Rule { name: Some("style".into()), content: "x".into(), ..Default::default() };
+        store.save_rules(Some("myApp"), &[rule], "cursor").unwrap();
+
+        let info = store.project_info("myApp").unwrap();
+        assert_eq!(info.rule_count, 1);
+        assert_eq!(info.meta.description.as_deref(), Some("The app"));
+        assert!(info.last_updated.is_some());
+        assert!(store.project_info("missing").is_err());
+    }
+
+    #[test]
+    fn archive_and_unarchive_round_trip() {
+        let (_dir, store) = store_with_projects(&["myApp"]);
+        store.archive_project("myApp").unwrap();
+        assert!(store.list_projects().unwrap().is_empty());
+        assert_eq!(store.list_archived_projects().unwrap(), vec!["myApp"]);
+
+        store.unarchive_project("myApp").unwrap();
+        assert_eq!(store.list_projects().unwrap(), vec!["myApp"]);
+        assert!(store.list_archived_projects().unwrap().is_empty());
+    }
+
+    #[test]
+    fn archive_rejects_user_and_missing() {
+        let (_dir, store) = store_with_projects(&["user"]);
+        assert!(store.archive_project("user").is_err());
+        assert!(store.archive_project("nope").is_err());
+        assert!(store.unarchive_project("nope").is_err());
+    }
+
+    #[test]
+    fn missing_projects_report_what_exists() {
+        let (_dir, store) = store_with_projects(&["myApp", "user", ".archive/oldApp"]);
+        match store.project_info("myAp") {
+            Err(PolyrcError::ProjectNotFound { available, .. }) => assert_eq!(available, vec!["myApp", "user"]),
+            other => panic!("expected ProjectNotFound, got {other:?}"),
+        }
+        let msg = store.unarchive_project("old").unwrap_err().to_string();
+        assert!(msg.contains("Available projects: oldApp."), "{msg}");
+        assert!(store.rename_project("nope", "x").unwrap_err().to_string().contains("myApp"));
+    }
+
+    #[test]
+    fn unarchive_refuses_to_overwrite() {
+        let (_dir, store) = store_with_projects(&["myApp", ".archive/myApp"]);
+        assert!(store.unarchive_project("myApp").is_err());
+    }
+
+    fn named(name: &str) -> Rule {
+        Rule { name: Some(name.into()), content: format!("{name} body"), ..Default::default() }
+    }
+
+    fn with_content(name: &str, content: &str) -> Rule {
+        Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn renamed_rule_keeps_its_id() {
+        let (_dir, store) = store_with_projects(&[]);
+        let old = store.save_rules(Some("app"), &[named("style"), named("tests")], "cursor").unwrap();
+
+        // `style` renamed to `code-style`, same content; `docs` is new
+        let new = store
+            .save_rules(Some("app"), &[with_content("code-style", "style body\r\n"), named("tests"), named("docs")], "cursor")
+            .unwrap();
+        assert_eq!(new[0].id, old[0].id);
+        assert_eq!(new[0].created_at, old[0].created_at);
+        assert_eq!(new[1].id, old[1].id);
+        assert!(!old.iter().any(|r| r.id == new[2].id));
+    }
+
+    #[test]
+    fn ambiguous_content_match_is_not_a_rename() {
+        let (_dir, store) = store_with_projects(&[]);
+        let old = store.save_rules(Some("app"), &[with_content("a", "Same."), with_content("b", "Same.")], "cursor").unwrap();
+
+        let new = store.save_rules(Some("app"), &[with_content("c", "Same.")], "cursor").unwrap();
+        assert!(!old.iter().any(|r| r.id == new[0].id));
+
+        let old = new;
+        let new = store.save_rules(Some("app"), &[with_content("d", "Same."), with_content("e", "Same.")], "cursor").unwrap();
+        assert!(new.iter().all(|r| r.id != old[0].id));
+    }
+
+    #[test]
+    fn load_rule_by_name_in_namespace() {
+        let (_dir, store) = store_with_projects(&[]);
+        store.save_rules(Some("myApp"), &[named("style")], "cursor").unwrap();
+        let (ns, rule) = store.load_rule_by_name("style", Some("myApp")).unwrap().unwrap();
+        assert_eq!(ns, "myApp");
+        assert_eq!(rule.name.as_deref(), Some("style"));
+        assert!(store.load_rule_by_name("style", Some("other")).unwrap().is_none());
+    }
+
+    #[test]
+    fn load_rule_by_name_matches_rule_name() {
+        let (_dir, store) = store_with_projects(&[]);
+        store.save_rules(Some("myApp"), &[named("My Style")], "cursor").unwrap();
+        assert!(store.load_rule_by_name("My Style", None).unwrap().is_some());
+        assert!(store.load_rule_by_name("my-style", None).unwrap().is_some());
+    }
+
+    #[test]
+    fn load_rule_by_name_ambiguous_lists_candidates() {
+        let (_dir, store) = store_with_projects(&[]);
+        store.save_rules(Some("zeta"), &[named("style")], "cursor").unwrap();
+        store.save_rules(Some("alpha"), &[named("style")], "cursor").unwrap();
+        store.save_rules(Some(USER_PROJECT), &[named("style")], "claude").unwrap();
+
+        match store.load_rule_by_name("style", None) {
+            Err(PolyrcError::AmbiguousRule { candidates, .. }) => {
+                assert_eq!(candidates, vec!["alpha", "zeta", "user"]);
+            }
+            other => panic!("expected AmbiguousRule, got {other:?}"),
+        }
+        // Restricting the namespace resolves the ambiguity
+        let (ns, _) = store.load_rule_by_name("style", Some("alpha")).unwrap().unwrap();
+        assert_eq!(ns, "alpha");
+    }
+
+    #[test]
+    fn lossy_load_skips_corrupt_file_and_save_keeps_it() {
+        let (_dir, store) = store_with_projects(&[]);
+        store.save_rules(Some("myApp"), &[named("good")], "cursor").unwrap();
+        let corrupt = store.path.join("myApp").join("broken.yaml");
+        fs::write(&corrupt, "<<<<<<< HEAD\nscope: [\n").unwrap();
+
+        let loaded = store.load_rules_lossy(Some("myApp")).unwrap();
+        assert_eq!(loaded.rules.len(), 1);
+        assert_eq!(loaded.skipped.len(), 1);
+        assert_eq!(loaded.skipped[0].0, corrupt);
+        assert!(store.load_rules(Some("myApp")).is_err());
+
+        store.save_rules(Some("myApp"), &[named("other")], "cursor").unwrap();
+        assert!(corrupt.exists());
+        assert!(!store.path.join("myApp").join("good.yaml").exists());
+    }
+
+    #[test]
+    fn lossy_load_skips_oversized_file() {
+        let (_dir, store) = store_with_projects(&[]);
+        store.save_rules(Some("myApp"), &[named("good")], "cursor").unwrap();
+        let huge = store.path.join("myApp").join("huge.yaml");
+        fs::File::create(&huge).unwrap().set_len(crate::parser::DEFAULT_MAX_FILE_SIZE + 1).unwrap();
+
+        let loaded = store.load_rules_lossy(Some("myApp")).unwrap();
+        assert_eq!(loaded.rules.len(), 1);
+        assert!(matches!(loaded.skipped[0].1, PolyrcError::FileTooLarge { .. }));
+    }
+
+    #[test]
+    fn save_refuses_to_overwrite_corrupt_file() {
+        let (_dir, store) = store_with_projects(&["myApp"]);
+        let corrupt = store.path.join("myApp").join("style.yaml");
+        fs::write(&corrupt, ": : :\n\t- [").unwrap();
+        assert!(store.save_rules(Some("myApp"), &[named("style")], "cursor").is_err());
+        assert_eq!(fs::read_to_string(&corrupt).unwrap(), ": : :\n\t- [");
+    }
+
+    #[test]
+    fn save_rule_to_namespace_rejects_traversal() {
+        let (dir, store) = store_with_projects(&["user"]);
+        let rule = named("evil");
+        assert!(store.save_rule_to_namespace("user", "../../evil", &rule).is_err());
+        assert!(store.save_rule_to_namespace("../outside", "evil", &rule).is_err());
+        assert!(!dir.path().parent().unwrap().join("evil.yaml").exists());
+        assert!(store.save_rule_to_namespace("user", "fine", &rule).is_ok());
+    }
+
+    #[test]
+    fn ensure_inside_detects_escape() {
+        let (dir, _store) = store_with_projects(&["ns"]);
+        let ns = dir.path().join("ns");
+        assert!(ensure_inside(&ns, &ns.join("rule.yaml")).is_ok());
+        assert!(ensure_inside(&ns, &ns.join("..").join("rule.yaml")).is_err());
+        assert!(ensure_inside(dir.path(), &dir.path().join("..")).is_err());
+    }
+
+    #[test]
+    fn discover_walks_up_to_marker() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("rules-store");
+        let nested = store.join("a/b/c");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(store.join(STORE_MARKER), "").unwrap();
+
+        assert_eq!(Store::discover(&nested), Some(store.clone()));
+        assert_eq!(Store::discover(&store), Some(store));
+    }
+
+    #[test]
+    fn discover_without_marker_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(Store::discover(dir.path()), None);
+    }
+
+    #[test]
+    fn resolve_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(STORE_MARKER), "").unwrap();
+        let discovered = dir.path().to_path_buf();
+        let configured = config_with_path(Some("/from/config"));
+        let unset = config_with_path(None);
+
+        assert_eq!(
+            resolve_store_path(Some("/from/env"), &configured, dir.path()),
+            PathBuf::from("/from/env")
+        );
+        assert_eq!(
+            resolve_store_path(None, &configured, dir.path()),
+            PathBuf::from("/from/config")
+        );
+        assert_eq!(resolve_store_path(None, &unset, dir.path()), discovered);
+        assert_eq!(resolve_store_path(Some(""), &unset, dir.path()), discovered);
+    }
+
+    #[test]
+    fn stored_yaml_field_order_is_fixed() {
+        use crate::ir::{Activation, Scope};
+        let rule = Rule {
+            scope: Scope::Path,
+            activation: Activation::Glob,
+            globs: Some(vec!["src/**/*.rs".to_string(), "*.toml".to_string()]),
+            name: Some("rust-style".to_string()),
+            description: Some("Rust conventions".to_string()),
+            content: "Use rustfmt.\n\n\n".to_string(),
+            order: Some(2),
+            frontmatter: Some("alwaysApply: false\n".to_string()),
+            resources: Some(vec!["scripts/run.py".to_string()]),
+            id: "0b6c2f3e-5d1a-4c8e-9f7a-1e2d3c4b5a69".to_string(),
+            project: Some("app".to_string()),
+            source_format: Some("cursor".to_string()),
+            source_path: Some(".cursor/rules/rust-style.mdc".to_string()),
+            created_at: Some("2025-01-02T03:04:05+00:00".to_string()),
+            updated_at: Some("2025-06-07T08:09:10+00:00".to_string()),
+            store_version: "1".to_string(),
+        };
+        let yaml = rule_to_yaml(&rule, Path::new("rust-style.yaml")).unwrap();
+        let expected = "\
+scope: path
+activation: glob
+globs:
+- src/**/*.rs
+- '*.toml'
+name: rust-style
+description: Rust conventions
+content: |+
+  Use rustfmt.
+
+
+order: 2
+frontmatter: |
+  alwaysApply: false
+resources:
+- scripts/run.py
+id: '0b6c2f3e-5d1a-4c8e-9f7a-1e2d3c4b5a69'
+project: app
+source_format: cursor
+source_path: '.cursor/rules/rust-style.mdc'
+created_at: '2025-01-02T03:04:05+00:00'
+updated_at: '2025-06-07T08:09:10+00:00'
+store_version: '1'
+";
+        assert_eq!(yaml, expected);
+        assert_eq!(serde_yml::from_str::<Rule>(&yaml).unwrap(), rule);
+    }
+
+    #[test]
+    fn normalize_rewrites_only_non_canonical_files() {
+        let (dir, store) = store_with_projects(&["app"]);
+        let app = dir.path().join("app");
+        fs::write(app.join("style.yaml"), "content: Use tabs.\nscope: project\nactivation: always\nid: x\n\n\n").unwrap();
+        fs::write(app.join("broken.yaml"), "content: [").unwrap();
+        store.save_rule_to_namespace("app", "tests", &Rule { content: "Test it.".into(), ..Default::default() }).unwrap();
+
+        assert_eq!(store.normalize().unwrap(), vec![PathBuf::from("app/style.yaml")]);
+        assert_eq!(
+            fs::read_to_string(app.join("style.yaml")).unwrap(),
+            "scope: project\nactivation: always\ncontent: Use tabs.\nid: x\nstore_version: '1'\n"
+        );
+        assert_eq!(fs::read_to_string(app.join("broken.yaml")).unwrap(), "content: [");
+        assert!(store.normalize().unwrap().is_empty());
+    }
+
+    #[test]
+    fn updated_at_moves_only_when_content_changes() {
+        let (_dir, store) = store_with_projects(&[]);
+        let rule = |content: &str| Rule { name: Some("style".into()), content: content.into(), ..Default::default() };
+        let stamp = |store: &Store| store.load_rules(Some("app")).unwrap()[0].updated_at.clone().unwrap();
+        let backdate = |store: &Store| {
+            let file = store.path.join("app").join("style.yaml");
+            let raw = fs::read_to_string(&file).unwrap();
+            let old = raw.lines().find(|l| l.starts_with("updated_at:")).unwrap().to_string();
+            fs::write(&file, raw.replace(&old, "updated_at: '2020-01-01T00:00:00+00:00'")).unwrap();
+        };
+        store.save_rules(Some("app"), &[rule("Use tabs.")], "cursor").unwrap();
+        backdate(&store);
+
+        // Re-pushing the same content, a sync re-save and a normalize keep it
+        store.save_rules(Some("app"), &[rule("Use tabs.\n")], "claude").unwrap();
+        let loaded = store.load_rules(Some("app")).unwrap();
+        store.save_rules_for(Some("app"), &loaded, "sync", SaveReason::Normalize).unwrap();
+        store.normalize().unwrap();
+        assert_eq!(stamp(&store), "2020-01-01T00:00:00+00:00");
+
+        store.save_rules(Some("app"), &[rule("Use spaces.")], "cursor").unwrap();
+        assert_ne!(stamp(&store), "2020-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn save_rules_keeps_colliding_names_apart() {
+        let (_dir, store) = store_with_projects(&[]);
+        let rule = |name: &str| Rule { name: Some(name.into()), content: name.into(), ..Default::default() };
+        store.save_rules(Some("app"), &[rule("api-design"), rule("API Design")], "cursor").unwrap();
+
+        assert!(store.path.join("app").join("api-design.yaml").exists());
+        assert!(store.path.join("app").join("api-design-2.yaml").exists());
+        let mut names: Vec<String> = store.load_rules(Some("app")).unwrap().into_iter().filter_map(|r| r.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["API Design", "api-design"]);
+    }
+
+    #[test]
+    fn save_rules_merges_other_formats() {
+        let (_dir, store) = store_with_projects(&[]);
+        let names = |store: &Store| {
+            let mut names: Vec<String> = store.load_rules(Some("app")).unwrap().into_iter().filter_map(|r| r.name).collect();
+            names.sort();
+            names
+        };
+        store.save_rules(Some("app"), &[named("style"), named("tone")], "claude").unwrap();
+        store.save_rules(Some("app"), &[named("tests"), with_content("tone", "Be terse.")], "cursor").unwrap();
+        assert_eq!(names(&store), vec!["style", "tests", "tone"]);
+        let (_, tone) = store.load_rule_by_name("tone", Some("app")).unwrap().unwrap();
+        assert_eq!((tone.content.as_str(), tone.source_format.as_deref()), ("Be terse.", Some("cursor")));
+
+        // Pushing cursor again drops only cursor's stale rules
+        store.save_rules(Some("app"), &[named("tests")], "cursor").unwrap();
+        assert_eq!(names(&store), vec!["style", "tests"]);
+
+        store.replace_rules(Some("app"), &[named("tests")], "cursor").unwrap();
+        assert_eq!(names(&store), vec!["tests"]);
+    }
+
+    #[test]
+    fn same_named_rules_are_all_stored() {
+        let (_dir, store) = store_with_projects(&[]);
+        let command = Rule { activation: Activation::OnDemand, ..with_content("api", "API command") };
+        let rules = [with_content("api", "API rule"), command];
+        let first = store.save_rules(Some("app"), &rules, "claude").unwrap();
+        let second = store.save_rules(Some("app"), &rules, "claude").unwrap();
+
+        let mut files: Vec<_> = fs::read_dir(store.path.join("app")).unwrap().map(|e| e.unwrap().file_name()).collect();
+        files.sort();
+        assert_eq!(files, vec!["api-2.yaml", "api.yaml"]);
+        assert_eq!(store.load_rules(Some("app")).unwrap().len(), 2);
+        assert_ne!(second[0].id, second[1].id);
+        assert_eq!((&first[0].id, &first[1].id), (&second[0].id, &second[1].id));
+    }
+
+    #[test]
+    fn prune_removes_only_empty_projects() {
+        let (_dir, store) = store_with_projects(&["empty", "described", "notes", "user"]);
+        fs::write(store.path.join("described").join(PROJECT_META_FILE), "description = \"x\"\n").unwrap();
+        fs::write(store.path.join("notes").join("notes.txt"), "todo").unwrap();
+        let rule = Rule { name: Some("style".into()), content: "x".into(), ..Default::default() };
+        store.save_rules(Some("full"), &[rule], "cursor").unwrap();
+
+        assert_eq!(store.prune_empty_projects().unwrap(), vec!["described", "empty"]);
+        assert_eq!(store.list_projects().unwrap(), vec!["full", "notes", "user"]);
+        assert!(store.prune_empty_projects().unwrap().is_empty());
+    }
+
+    #[test]
+    fn orphan_files_skip_rules_and_metadata() {
+        let (_dir, store) = store_with_projects(&["app"]);
+        let rule = Rule { name: Some("style".into()), content: "x".into(), ..Default::default() };
+        store.save_rules(Some("app"), &[rule], "cursor").unwrap();
+        let dir = store.path.join("app");
+        fs::write(dir.join(PROJECT_META_FILE), "").unwrap();
+        fs::write(dir.join("notes.txt"), "todo").unwrap();
+        fs::create_dir_all(dir.join("old")).unwrap();
+        fs::write(dir.join("old").join("legacy.yaml"), "content: x").unwrap();
+
+        let orphans = store.orphan_files("app").unwrap();
+        assert_eq!(orphans, vec![PathBuf::from("app/notes.txt"), PathBuf::from("app/old/legacy.yaml")]);
+    }
+
+    #[test]
+    fn ignore_appends_only_missing_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        fs::write(dir.path().join(".gitignore"), "notes/\n.DS_Store").unwrap();
+
+        let added = store.ignore(DEFAULT_IGNORES).unwrap();
+        assert_eq!(added.len(), DEFAULT_IGNORES.len() - 1);
+        assert!(!added.iter().any(|e| e == ".DS_Store"));
+        let first = fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        assert!(first.starts_with("notes/\n.DS_Store\n"));
+
+        assert!(store.ignore(DEFAULT_IGNORES).unwrap().is_empty());
+        assert_eq!(fs::read_to_string(dir.path().join(".gitignore")).unwrap(), first);
+    }
+
+    #[test]
+    fn open_only_creates_a_missing_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(STORE_MARKER), "store_version = \"1\"\n").unwrap();
+        Store::open(dir.path()).unwrap();
+        let created = fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        assert!(created.lines().any(|l| l == ".DS_Store"));
+
+        fs::write(dir.path().join(".gitignore"), "notes/\n").unwrap();
+        Store::open(dir.path()).unwrap();
+        assert_eq!(fs::read_to_string(dir.path().join(".gitignore")).unwrap(), "notes/\n");
+    }
+
+    #[test]
+    fn resolve_falls_back_to_default() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            resolve_store_path(None, &config_with_path(None), dir.path()),
+            crate::config::default_store_path()
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! `README.md` at the store root — a browsable overview generated from the index.
+//!
+//! The output depends only on indexed rule metadata (never on mtimes or the
+//! current time), so regenerating an unchanged store produces identical bytes
+//! and no git churn.
+
+use std::fs;
+use crate::error::{PolyrcError, Result};
+use super::index::{IndexEntry, StoreIndex};
+use super::{Store, USER_PROJECT};
+
+/// Generated README file name at the store root.
+pub const README_FILE: &str = "README.md";
+/// Summaries longer than this many characters are cut with an ellipsis.
+const SUMMARY_MAX_CHARS: usize = 80;
+
+/// Render the store README for `index`. Projects are listed user-first, then
+/// alphabetically; rules within a project are sorted by name.
+pub fn render_readme(index: &StoreIndex) -> String {
+    let mut projects: Vec<(&String, Vec<&IndexEntry>)> = index
+        .projects
+        .iter()
+        .map(|(name, p)| {
+            let mut rules: Vec<&IndexEntry> = p.rules.iter().collect();
+            rules.sort_by_key(|r| r.display_name());
+            (name, rules)
+        })
+        .collect();
+    projects.sort_by_key(|(name, _)| (name.as_str() != USER_PROJECT, name.as_str().to_string()));
+    let total: usize = projects.iter().map(|(_, rules)| rules.len()).sum();
+
+    let mut out = String::new();
+    out.push_str("<!-- Generated by polyrc from the store index. Do not edit; changes are overwritten. -->\n\n");
+    out.push_str("# polyrc store\n\n");
+    out.push_str(&format!(
+        "{total} {} across {} {}.\n",
+        plural(total, "rule", "rules"),
+        projects.len(),
+        plural(projects.len(), "project", "projects"),
+    ));
+
+    for (name, rules) in &projects {
+        out.push_str(&format!("\n## {name}\n\n"));
+        if rules.is_empty() {
+            out.push_str("_No rules._\n");
+            continue;
+        }
+        out.push_str("| Rule | Activation | Description | Updated |\n");
+        out.push_str("| --- | --- | --- | --- |\n");
+        for rule in rules {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                cell(&rule.display_name()),
+                format!("{:?}", rule.activation).to_lowercase(),
+                cell(&truncate(rule.summary.as_deref().unwrap_or(""))),
+                rule.updated_at.as_deref().map(|t| t.get(..10).unwrap_or(t)).unwrap_or(""),
+            ));
+        }
+    }
+    out
+}
+
+impl Store {
+    /// Write `README.md` for `index` at the store root. Returns whether the file
+    /// changed; an up-to-date README is left untouched.
+    pub fn write_readme(&self, index: &StoreIndex) -> Result<bool> {
+        let file = self.path.join(README_FILE);
+        let rendered = render_readme(index);
+        if fs::read_to_string(&file).is_ok_and(|existing| existing == rendered) {
+            return Ok(false);
+        }
+        fs::write(&file, rendered).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+        Ok(true)
+    }
+}
+
+fn plural<'a>(n: usize, one: &'a str, many: &'a str) -> &'a str {
+    if n == 1 { one } else { many }
+}
+
+/// Make text safe for a single Markdown table cell.
+fn cell(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= SUMMARY_MAX_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(SUMMARY_MAX_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Activation, Rule};
+
+    fn store() -> (tempfile::TempDir, Store) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = Store::new(dir.path());
+        store.generate_readme = true;
+        (dir, store)
+    }
+
+    fn rule(name: &str, content: &str) -> Rule {
+        Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn renders_tables_user_first() {
+        let (_dir, store) = store();
+        let mut described = rule("b-style", "ignored");
+        described.description = Some("Use | pipes carefully".into());
+        described.activation = Activation::OnDemand;
+        store.save_rules(Some("app"), &[described, rule("a-tests", "\n  Write tests first.\nMore.")], "cursor").unwrap();
+        store.save_rules(Some(USER_PROJECT), &[rule("tone", "Be terse.")], "claude").unwrap();
+
+        let readme = fs::read_to_string(store.path.join(README_FILE)).unwrap();
+        let today = &store.load_rules(Some("app")).unwrap()[0].updated_at.clone().unwrap()[..10];
+        let expected = format!(
+            "<!-- Generated by polyrc from the store index. Do not edit; changes are overwritten. -->\n\n\
+             # polyrc store\n\n\
+             3 rules across 2 projects.\n\n\
+             ## user\n\n\
+             | Rule | Activation | Description | Updated |\n\
+             | --- | --- | --- | --- |\n\
+             | tone | always | Be terse. | {today} |\n\n\
+             ## app\n\n\
+             | Rule | Activation | Description | Updated |\n\
+             | --- | --- | --- | --- |\n\
+             | a-tests | always | Write tests first. | {today} |\n\
+             | b-style | ondemand | Use \\| pipes carefully | {today} |\n"
+        );
+        assert_eq!(readme, expected);
+    }
+
+    #[test]
+    fn unchanged_store_regenerates_identical_bytes() {
+        let (_dir, store) = store();
+        store.save_rules(Some("app"), &[rule("one", "1"), rule("two", "2")], "cursor").unwrap();
+        let file = store.path.join(README_FILE);
+        let first = fs::read(&file).unwrap();
+
+        let index = store.rebuild_index().unwrap();
+        assert!(!store.write_readme(&index).unwrap());
+        assert_eq!(fs::read(&file).unwrap(), first);
+    }
+
+    #[test]
+    fn disabled_by_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        store.save_rules(Some("app"), &[rule("one", "1")], "cursor").unwrap();
+        assert!(!store.path.join(README_FILE).exists());
+    }
+
+    #[test]
+    fn long_summaries_are_truncated() {
+        let long = "x".repeat(200);
+        let out = truncate(&long);
+        assert_eq!(out.chars().count(), SUMMARY_MAX_CHARS);
+        assert!(out.ends_with('…'));
+    }
+}
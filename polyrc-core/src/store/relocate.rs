@@ -0,0 +1,172 @@
+//! `polyrc store move` — relocate the store directory.
+//!
+//! A plain rename is used when possible. Across filesystems the store is copied,
+//! the copy is compared byte for byte with the original, and only then is the
+//! original deleted.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use walkdir::WalkDir;
+use crate::error::{PolyrcError, Result};
+
+/// How [`relocate`] got the store to its new place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveMethod {
+    Renamed,
+    Copied,
+}
+
+/// Fail unless `to` can receive the store at `from`: it must not exist or be
+/// an empty directory, and must not lie inside the store.
+pub fn check_destination(from: &Path, to: &Path) -> Result<()> {
+    let reject = |reason: &str| Err(PolyrcError::WriteFailure { path: to.to_path_buf(), reason: reason.to_string() });
+    let from = fs::canonicalize(from).map_err(|e| PolyrcError::Io { path: from.to_path_buf(), source: e })?;
+    let to_abs = std::path::absolute(to).map_err(|e| PolyrcError::Io { path: to.to_path_buf(), source: e })?;
+    if to_abs.starts_with(&from) || fs::canonicalize(to).is_ok_and(|t| t.starts_with(&from)) {
+        return reject("destination is inside the store");
+    }
+    if to.is_file() {
+        return reject("destination is a file");
+    }
+    if fs::read_dir(to).is_ok_and(|mut entries| entries.next().is_some()) {
+        return reject("destination is not empty");
+    }
+    Ok(())
+}
+
+/// Move the store at `from` to `to`, or copy it when `keep_original`. On
+/// failure nothing is left at `to` and `from` is untouched.
+pub fn relocate(from: &Path, to: &Path, keep_original: bool) -> Result<MoveMethod> {
+    relocate_with(from, to, keep_original, |a, b| fs::rename(a, b))
+}
+
+fn relocate_with(
+    from: &Path,
+    to: &Path,
+    keep_original: bool,
+    rename: impl Fn(&Path, &Path) -> io::Result<()>,
+) -> Result<MoveMethod> {
+    check_destination(from, to)?;
+    if to.is_dir() {
+        fs::remove_dir(to).map_err(io_err(to))?;
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(io_err(parent))?;
+    }
+    if !keep_original {
+        match rename(from, to) {
+            Ok(()) => return Ok(MoveMethod::Renamed),
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {}
+            Err(e) => return Err(io_err(from)(e)),
+        }
+    }
+    if let Err(e) = copy_tree(from, to).and_then(|()| verify_copy(from, to)) {
+        let _ = fs::remove_dir_all(to);
+        return Err(e);
+    }
+    if !keep_original {
+        fs::remove_dir_all(from).map_err(io_err(from))?;
+    }
+    Ok(MoveMethod::Copied)
+}
+
+fn io_err(path: &Path) -> impl FnOnce(io::Error) -> PolyrcError + '_ {
+    move |e| PolyrcError::Io { path: path.to_path_buf(), source: e }
+}
+
+/// Copy the tree at `from` (including `.git`) to `to`. Symlinks are copied as
+/// links, not followed.
+fn copy_tree(from: &Path, to: &Path) -> Result<()> {
+    for entry in WalkDir::new(from) {
+        let entry = entry.map_err(|e| PolyrcError::Io { path: from.to_path_buf(), source: e.into() })?;
+        let rel = entry.path().strip_prefix(from).expect("walk stays under its root");
+        let dest = to.join(rel);
+        let dest_err = |e| PolyrcError::Io { path: dest.clone(), source: e };
+        let kind = entry.file_type();
+        if kind.is_dir() {
+            fs::create_dir_all(&dest).map_err(dest_err)?;
+        } else if kind.is_symlink() {
+            let target = fs::read_link(entry.path()).map_err(dest_err)?;
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&target, &dest).map_err(dest_err)?;
+            #[cfg(not(unix))]
+            fs::copy(entry.path(), &dest).map_err(dest_err)?;
+        } else {
+            fs::copy(entry.path(), &dest).map_err(dest_err)?;
+        }
+    }
+    Ok(())
+}
+
+/// Fail unless every regular file under `from` has an identical copy under `to`.
+fn verify_copy(from: &Path, to: &Path) -> Result<()> {
+    for entry in WalkDir::new(from).into_iter().flatten().filter(|e| e.file_type().is_file()) {
+        let rel = entry.path().strip_prefix(from).expect("walk stays under its root");
+        let same = matches!((fs::read(entry.path()), fs::read(to.join(rel))), (Ok(a), Ok(b)) if a == b);
+        if !same {
+            return Err(PolyrcError::WriteFailure {
+                path: to.join(rel),
+                reason: "copy does not match the original".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("store");
+        fs::create_dir_all(store.join(".git").join("refs")).unwrap();
+        fs::create_dir_all(store.join("app")).unwrap();
+        fs::write(store.join(".git").join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(store.join("app").join("style.yaml"), "content: Use tabs.\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn falls_back_to_copy_across_devices() {
+        let dir = store_tree();
+        let (from, to) = (dir.path().join("store"), dir.path().join("elsewhere").join("store"));
+        let cross_device = |_: &Path, _: &Path| Err(io::Error::from(io::ErrorKind::CrossesDevices));
+
+        assert_eq!(relocate_with(&from, &to, false, cross_device).unwrap(), MoveMethod::Copied);
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(to.join("app").join("style.yaml")).unwrap(), "content: Use tabs.\n");
+        assert!(to.join(".git").join("refs").is_dir());
+    }
+
+    #[test]
+    fn other_rename_errors_leave_the_store_alone() {
+        let dir = store_tree();
+        let (from, to) = (dir.path().join("store"), dir.path().join("new"));
+        let denied = |_: &Path, _: &Path| Err(io::Error::from(io::ErrorKind::PermissionDenied));
+
+        assert!(relocate_with(&from, &to, false, denied).is_err());
+        assert!(from.join("app").join("style.yaml").exists());
+        assert!(!to.exists());
+    }
+
+    #[test]
+    fn destination_must_be_empty_and_outside_the_store() {
+        let dir = store_tree();
+        let from = dir.path().join("store");
+        let occupied = dir.path().join("occupied");
+        fs::create_dir_all(&occupied).unwrap();
+        fs::write(occupied.join("notes.md"), "mine").unwrap();
+
+        let err = relocate(&from, &occupied, false).unwrap_err().to_string();
+        assert!(err.contains("not empty"), "{err}");
+        assert!(check_destination(&from, &from.join("nested")).is_err());
+        assert!(from.exists() && occupied.join("notes.md").exists());
+
+        let empty = dir.path().join("empty");
+        fs::create_dir_all(&empty).unwrap();
+        assert_eq!(relocate(&from, &empty, true).unwrap(), MoveMethod::Copied);
+        assert!(from.exists() && empty.join("app").join("style.yaml").exists());
+    }
+}
@@ -0,0 +1,186 @@
+//! `<project>/.deleted.yml` — tombstones for rules deleted from a project, so
+//! `sync` can tell a rule deleted on one machine from one new on another.
+//!
+//! A tombstone is written whenever the store deletes a rule and dropped when a
+//! rule with its id is written to the project again. `sync` expires tombstones
+//! older than `[sync] tombstone_retention_days`.
+
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::error::{PolyrcError, Result};
+use crate::ir::Rule;
+use super::Store;
+
+/// Tombstone file name inside a project directory.
+pub const TOMBSTONE_FILE: &str = ".deleted.yml";
+/// Days a tombstone is kept when `[sync] tombstone_retention_days` is unset.
+pub const DEFAULT_TOMBSTONE_RETENTION_DAYS: u64 = 90;
+
+/// A rule deleted from a project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub deleted_at: String,
+}
+
+impl Tombstone {
+    /// A tombstone for `rule`, deleted at `now`.
+    pub fn for_rule(rule: &Rule, now: &str) -> Self {
+        Self { id: rule.id.clone(), name: rule.name.clone(), deleted_at: now.to_string() }
+    }
+
+    /// Whether `rule` was updated after this deletion, so the edit outlives it.
+    pub fn edited_after(&self, rule: &Rule) -> bool {
+        match (time(rule.updated_at.as_deref()), time(Some(&self.deleted_at))) {
+            (Some(updated), Some(deleted)) => updated > deleted,
+            _ => false,
+        }
+    }
+}
+
+/// Tombstones from both sides of a merge, one per id, keeping the latest
+/// deletion. Sorted by `deleted_at`, then id.
+pub fn merge_tombstones(ours: &[Tombstone], theirs: &[Tombstone]) -> Vec<Tombstone> {
+    let mut merged: Vec<Tombstone> = vec![];
+    for t in ours.iter().chain(theirs) {
+        match merged.iter_mut().find(|m| m.id == t.id) {
+            Some(m) if time(Some(&t.deleted_at)) > time(Some(&m.deleted_at)) => *m = t.clone(),
+            Some(_) => {}
+            None => merged.push(t.clone()),
+        }
+    }
+    sort(&mut merged);
+    merged
+}
+
+/// Parse a tombstone file, read from `file`. An empty file has none.
+pub fn tombstones_from_yaml(raw: &str, file: &Path) -> Result<Vec<Tombstone>> {
+    if raw.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    serde_yml::from_str(raw).map_err(|e| PolyrcError::YamlParse { path: file.to_path_buf(), source: e })
+}
+
+/// Write `tombstones` to `file`, or remove it when there are none.
+pub(crate) fn write_tombstones(file: &Path, tombstones: &[Tombstone]) -> Result<()> {
+    if tombstones.is_empty() {
+        if file.exists() {
+            fs::remove_file(file).map_err(|e| PolyrcError::Io { path: file.to_path_buf(), source: e })?;
+        }
+        return Ok(());
+    }
+    let yaml = serde_yml::to_string(tombstones).map_err(|e| PolyrcError::YamlParse { path: file.to_path_buf(), source: e })?;
+    fs::write(file, yaml).map_err(|e| PolyrcError::Io { path: file.to_path_buf(), source: e })
+}
+
+fn time(raw: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(raw?).ok().map(|t| t.with_timezone(&Utc))
+}
+
+fn sort(tombstones: &mut [Tombstone]) {
+    tombstones.sort_by(|a, b| time(Some(&a.deleted_at)).cmp(&time(Some(&b.deleted_at))).then_with(|| a.id.cmp(&b.id)));
+}
+
+impl Store {
+    /// The tombstones of a project (`None` for user scope), oldest first.
+    pub fn tombstones(&self, project: Option<&str>) -> Result<Vec<Tombstone>> {
+        let file = self.project_dir(project).join(TOMBSTONE_FILE);
+        if !file.exists() {
+            return Ok(vec![]);
+        }
+        let raw = fs::read_to_string(&file).map_err(|e| PolyrcError::Io { path: file.clone(), source: e })?;
+        tombstones_from_yaml(&raw, &file)
+    }
+
+    /// Add tombstones for the `deleted` rules of a project and drop those of
+    /// the ids in `written`, which are in the project again.
+    pub(crate) fn record_deletions(&self, project: Option<&str>, deleted: &[&Rule], written: &[&str]) -> Result<()> {
+        let existing = self.tombstones(project)?;
+        let now = Utc::now().to_rfc3339();
+        let mut tombstones: Vec<Tombstone> = existing
+            .iter()
+            .filter(|t| !written.contains(&t.id.as_str()) && !deleted.iter().any(|r| r.id == t.id))
+            .cloned()
+            .collect();
+        tombstones.extend(deleted.iter().filter(|r| !r.id.is_empty()).map(|r| Tombstone::for_rule(r, &now)));
+        sort(&mut tombstones);
+        if tombstones != existing {
+            write_tombstones(&self.project_dir(project).join(TOMBSTONE_FILE), &tombstones)?;
+        }
+        Ok(())
+    }
+
+    /// Drop a project's tombstones deleted more than `retention_days` days ago;
+    /// `0` keeps them all. Returns how many were dropped. Does not commit.
+    pub fn expire_tombstones(&self, project: &str, retention_days: u64) -> Result<usize> {
+        let existing = self.tombstones(Some(project))?;
+        if retention_days == 0 || existing.is_empty() {
+            return Ok(0);
+        }
+        let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
+        let kept: Vec<Tombstone> =
+            existing.iter().filter(|t| time(Some(&t.deleted_at)).is_none_or(|at| at > cutoff)).cloned().collect();
+        if kept.len() < existing.len() {
+            write_tombstones(&self.project_dir(Some(project)).join(TOMBSTONE_FILE), &kept)?;
+        }
+        Ok(existing.len() - kept.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tombstone(id: &str, deleted_at: &str) -> Tombstone {
+        Tombstone { id: id.into(), name: Some(id.into()), deleted_at: deleted_at.into() }
+    }
+
+    #[test]
+    fn merging_keeps_one_tombstone_per_id() {
+        let ours = [tombstone("a", "2026-01-02T00:00:00Z"), tombstone("b", "2026-01-01T00:00:00Z")];
+        let theirs = [tombstone("a", "2026-01-03T00:00:00Z")];
+        assert_eq!(merge_tombstones(&ours, &theirs), vec![tombstone("b", "2026-01-01T00:00:00Z"), tombstone("a", "2026-01-03T00:00:00Z")]);
+    }
+
+    #[test]
+    fn deleting_a_rule_leaves_a_tombstone_until_it_is_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        let rule = |name: &str| Rule { name: Some(name.into()), content: format!("{name} rule"), ..Default::default() };
+        let saved = store.save_rules(Some("app"), &[rule("style"), rule("old")], "cursor").unwrap();
+        assert!(store.tombstones(Some("app")).unwrap().is_empty());
+
+        store.save_rules(Some("app"), &[rule("style")], "cursor").unwrap();
+        let tombstones = store.tombstones(Some("app")).unwrap();
+        assert_eq!(tombstones.len(), 1);
+        assert_eq!((tombstones[0].id.as_str(), tombstones[0].name.as_deref()), (saved[1].id.as_str(), Some("old")));
+        assert!(store.orphan_files("app").unwrap().is_empty());
+
+        store.save_rules(Some("app"), &[rule("style"), saved[1].clone()], "cursor").unwrap();
+        assert!(store.tombstones(Some("app")).unwrap().is_empty());
+        assert!(!store.path.join("app").join(TOMBSTONE_FILE).exists());
+    }
+
+    #[test]
+    fn old_tombstones_expire() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::new(dir.path());
+        let fresh = Utc::now().to_rfc3339();
+        let stale = (Utc::now() - chrono::Duration::days(100)).to_rfc3339();
+        fs::create_dir_all(store.path.join("app")).unwrap();
+        let file = store.path.join("app").join(TOMBSTONE_FILE);
+        write_tombstones(&file, &[tombstone("old", &stale), tombstone("new", &fresh)]).unwrap();
+
+        assert_eq!(store.expire_tombstones("app", 0).unwrap(), 0);
+        assert_eq!(store.expire_tombstones("app", 90).unwrap(), 1);
+        assert_eq!(store.tombstones(Some("app")).unwrap(), vec![tombstone("new", &fresh)]);
+
+        write_tombstones(&file, &[tombstone("old", &stale)]).unwrap();
+        assert_eq!(store.expire_tombstones("app", 90).unwrap(), 1);
+        assert!(!file.exists());
+    }
+}
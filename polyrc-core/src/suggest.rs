@@ -0,0 +1,84 @@
+/// Maximum edit distance for a candidate to count as a "did you mean" match.
+const MAX_DISTANCE: usize = 2;
+/// Maximum number of suggestions returned.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Levenshtein edit distance between two strings (by chars, case-sensitive).
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// Candidates within edit distance 2 of `input` (case-insensitive), closest first,
+/// at most three. Exact matches and duplicates are excluded.
+///
+/// ```
+/// let hits = polyrc_core::suggest::suggest("cursr", ["cursor", "claude", "gemini"]);
+/// assert_eq!(hits, vec!["cursor"]);
+/// ```
+pub fn suggest<I, S>(input: &str, candidates: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let needle = input.to_lowercase();
+    let mut scored: Vec<(usize, String)> = candidates
+        .into_iter()
+        .map(|c| c.as_ref().to_string())
+        .filter(|c| *c != input)
+        .map(|c| (levenshtein(&needle, &c.to_lowercase()), c))
+        .filter(|(d, _)| *d <= MAX_DISTANCE)
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored.into_iter().take(MAX_SUGGESTIONS).map(|(_, c)| c).collect()
+}
+
+/// Render suggestions as a sentence suffix: `" Did you mean 'a' or 'b'?"`, or `""`.
+pub fn did_you_mean(suggestions: &[String]) -> String {
+    let quoted: Vec<String> = suggestions.iter().map(|s| format!("'{}'", s)).collect();
+    match quoted.as_slice() {
+        [] => String::new(),
+        [one] => format!(" Did you mean {}?", one),
+        [init @ .., last] => format!(" Did you mean {} or {}?", init.join(", "), last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levenshtein_basics() {
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("clade", "claude"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggest_closest_first_and_capped() {
+        let names = ["style", "styles", "stale", "stile", "totally-different"];
+        assert_eq!(suggest("stye", names), vec!["style", "stale", "stile"]);
+    }
+
+    #[test]
+    fn suggest_none_when_far() {
+        assert!(suggest("xyz", ["cursor", "claude"]).is_empty());
+    }
+
+    #[test]
+    fn did_you_mean_rendering() {
+        assert_eq!(did_you_mean(&[]), "");
+        assert_eq!(did_you_mean(&["a".into()]), " Did you mean 'a'?");
+        assert_eq!(did_you_mean(&["a".into(), "b".into(), "c".into()]), " Did you mean 'a', 'b' or 'c'?");
+    }
+}
@@ -0,0 +1,747 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use crate::error::{PolyrcError, Result};
+
+pub mod report;
+pub mod undo;
+
+use report::{Conflict, SyncReport};
+use crate::ir::Rule;
+use crate::store::{merge_rules, merge_tombstones, tombstones_from_yaml, write_tombstones, MergeOptions, TOMBSTONE_FILE};
+
+/// Remote branch the store syncs with.
+const REMOTE_BRANCH: &str = "origin/main";
+/// Commits fetched per attempt when a shallow store lacks a merge base.
+const SHALLOW_DEEPEN_STEPS: [u32; 2] = [50, 500];
+/// Git's well-known empty tree, to diff against before the first commit.
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+fn run_git(args: &[&str], dir: &Path) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| PolyrcError::GitError {
+            msg: format!("failed to run git: {e}"),
+        })?;
+    log_git(args, dir, &output);
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(PolyrcError::GitError { msg: stderr })
+    }
+}
+
+/// Record a finished git command in the debug log.
+fn log_git(args: &[&str], dir: &Path, output: &std::process::Output) {
+    crate::debug_log!(
+        "git {} (in {}) → {}{}",
+        args.join(" "),
+        dir.display(),
+        output.status.code().map_or("killed".to_string(), |c| c.to_string()),
+        match String::from_utf8_lossy(&output.stderr).trim() {
+            "" => String::new(),
+            stderr => format!(": {}", crate::logfile::truncate(stderr)),
+        }
+    );
+}
+
+/// Initialize a new git repository at `path`.
+pub fn git_init(path: &Path) -> Result<()> {
+    run_git(&["init"], path)?;
+    Ok(())
+}
+
+/// Hooks directory of the repo containing `dir` (honours worktrees and `core.hooksPath`).
+pub fn git_hooks_dir(dir: &Path) -> Result<std::path::PathBuf> {
+    let hooks = std::path::PathBuf::from(run_git(&["rev-parse", "--git-path", "hooks"], dir)?);
+    Ok(if hooks.is_absolute() { hooks } else { dir.join(hooks) })
+}
+
+/// Top-level directory of the working tree containing `dir`.
+pub fn git_toplevel(dir: &Path) -> Result<std::path::PathBuf> {
+    Ok(std::path::PathBuf::from(run_git(&["rev-parse", "--show-toplevel"], dir)?))
+}
+
+/// Clone `url` into `dest`. `shallow` fetches only the latest commit of the
+/// default branch (`--depth 1 --single-branch`).
+///
+/// If `dest` is already a git repo, the remote URL is updated to `url` instead
+/// of re-cloning (idempotent re-init). Otherwise the parent directory is
+/// created as needed before the clone.
+pub fn git_clone(url: &str, dest: &Path, shallow: bool) -> Result<()> {
+    // Already a git repo → just point origin at the new URL
+    if dest.join(".git").exists() {
+        let set = run_git(&["remote", "set-url", "origin", url], dest);
+        if set.is_err() {
+            run_git(&["remote", "add", "origin", url], dest)?;
+        }
+        return Ok(());
+    }
+
+    // Create parent directory so that e.g. ~/.polyrc/ exists before cloning
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| PolyrcError::GitError {
+            msg: format!("failed to create {}: {e}", parent.display()),
+        })?;
+    }
+
+    let dest_str = dest.to_string_lossy().into_owned();
+
+    // Use the home dir (or cwd) as the working directory — it is always
+    // guaranteed to exist, unlike the not-yet-created dest parent.
+    let work_dir = crate::config::home_dir();
+
+    let mut args = vec!["clone"];
+    if shallow {
+        args.extend(["--depth", "1", "--single-branch"]);
+    }
+    args.extend([url, dest_str.as_str()]);
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(&work_dir)
+        .output()
+        .map_err(|e| PolyrcError::GitError {
+            msg: format!("failed to run git clone: {e}"),
+        })?;
+    log_git(&args, &work_dir, &output);
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(PolyrcError::GitError { msg: stderr });
+    }
+    Ok(())
+}
+
+/// A throwaway clone in the system temp dir, removed on drop.
+pub struct TempClone {
+    pub path: std::path::PathBuf,
+}
+
+impl Drop for TempClone {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Shallow-clone `url` (depth 1) into a fresh temp directory.
+pub fn git_clone_temp(url: &str) -> Result<TempClone> {
+    let clone = TempClone {
+        path: std::env::temp_dir().join(format!("polyrc-repo-{}", uuid::Uuid::new_v4())),
+    };
+    let dest = clone.path.to_string_lossy().into_owned();
+    let args = ["clone", "--depth", "1", "--quiet", url, &dest];
+    let output = Command::new("git")
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| PolyrcError::GitError { msg: format!("failed to run git clone: {e}") })?;
+    log_git(&args, &std::env::current_dir().unwrap_or_default(), &output);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(PolyrcError::GitError { msg: friendly_git_error(url, &stderr) });
+    }
+    Ok(clone)
+}
+
+/// Prefix git's stderr with a hint for the common remote-access failures.
+pub fn friendly_git_error(url: &str, stderr: &str) -> String {
+    let lower = stderr.to_lowercase();
+    let hint = if lower.contains("authentication failed")
+        || lower.contains("could not read username")
+        || lower.contains("terminal prompts disabled")
+    {
+        Some(format!("authentication to {url} failed — check your credential helper or use an SSH URL"))
+    } else if lower.contains("permission denied (publickey)") {
+        Some(format!("SSH key rejected by {url} — check `ssh -T` for that host"))
+    } else if lower.contains("repository not found") || lower.contains("does not appear to be a git repository") {
+        Some(format!("{url} not found, or you have no access to it"))
+    } else if lower.contains("could not resolve host") {
+        Some(format!("cannot reach {url} — are you offline?"))
+    } else {
+        None
+    };
+    match hint {
+        Some(h) => format!("{h}\n{stderr}"),
+        None => stderr.to_string(),
+    }
+}
+
+/// File inside the store's git dir holding the time of the last successful sync.
+const LAST_SYNC_FILE: &str = "polyrc-last-sync";
+
+/// Whether the store has an `origin` remote to sync with.
+pub fn git_has_remote(store_path: &Path) -> bool {
+    run_git(&["remote", "get-url", "origin"], store_path).is_ok()
+}
+
+fn last_sync_file(store_path: &Path) -> Result<std::path::PathBuf> {
+    Ok(std::path::PathBuf::from(run_git(&["rev-parse", "--absolute-git-dir"], store_path)?).join(LAST_SYNC_FILE))
+}
+
+/// Remember that the store was just synced (or cloned). Kept in the git dir so
+/// it is per checkout and never committed.
+pub fn record_sync(store_path: &Path) -> Result<()> {
+    let file = last_sync_file(store_path)?;
+    std::fs::write(&file, chrono::Utc::now().to_rfc3339()).map_err(|e| PolyrcError::Io { path: file, source: e })
+}
+
+/// When the store was last synced, if polyrc has recorded it.
+pub fn last_sync(store_path: &Path) -> Option<chrono::DateTime<chrono::Utc>> {
+    let raw = std::fs::read_to_string(last_sync_file(store_path).ok()?).ok()?;
+    chrono::DateTime::parse_from_rfc3339(raw.trim()).ok().map(|t| t.with_timezone(&chrono::Utc))
+}
+
+/// A reminder to sync when the store has a remote and its recorded last sync is
+/// more than `stale_after_days` days old. `0` disables the reminder.
+pub fn stale_notice(store_path: &Path, stale_after_days: u64) -> Option<String> {
+    if stale_after_days == 0 || !git_has_remote(store_path) {
+        return None;
+    }
+    let days = (chrono::Utc::now() - last_sync(store_path)?).num_days();
+    (days > stale_after_days as i64).then(|| format!("store last synced {days} days ago — run `polyrc sync`"))
+}
+
+/// Ref that holds a snapshot of the working tree (untracked and uncommitted
+/// files included) inside a backup bundle.
+const WORKTREE_SNAPSHOT_REF: &str = "refs/polyrc/backup-worktree";
+
+/// Write every ref of the store at `store_path` into the bundle `file`, plus a
+/// snapshot commit of the working tree so untracked and uncommitted rule files
+/// are in the same single file. Ignored files are left out.
+pub fn git_bundle_create(store_path: &Path, file: &Path) -> Result<()> {
+    // Stage the working tree into a scratch index so the real one is untouched
+    let index = std::env::temp_dir().join(format!("polyrc-backup-index-{}", uuid::Uuid::new_v4()));
+    let snapshot = (|| {
+        let git = |args: &[&str]| -> Result<String> {
+            let output = Command::new("git")
+                .args(["-c", "user.name=polyrc", "-c", "user.email=polyrc@localhost"])
+                .args(args)
+                .current_dir(store_path)
+                .env("GIT_INDEX_FILE", &index)
+                .output()
+                .map_err(|e| PolyrcError::GitError { msg: format!("failed to run git: {e}") })?;
+            log_git(args, store_path, &output);
+            if !output.status.success() {
+                return Err(PolyrcError::GitError { msg: String::from_utf8_lossy(&output.stderr).trim().to_string() });
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+        git(&["add", "-A"])?;
+        let tree = git(&["write-tree"])?;
+        let mut args = vec!["commit-tree", tree.as_str(), "-m", "polyrc backup: working tree"];
+        let head = run_git(&["rev-parse", "--verify", "HEAD"], store_path).ok();
+        if let Some(head) = &head {
+            args.extend(["-p", head.as_str()]);
+        }
+        git(&args)
+    })();
+    let _ = std::fs::remove_file(&index);
+    run_git(&["update-ref", WORKTREE_SNAPSHOT_REF, &snapshot?], store_path)?;
+
+    let file_str = file.to_string_lossy().into_owned();
+    let bundled = run_git(&["bundle", "create", "--quiet", &file_str, "--all"], store_path);
+    let _ = run_git(&["update-ref", "-d", WORKTREE_SNAPSHOT_REF], store_path);
+    bundled.map(|_| ())
+}
+
+/// Clone the bundle `file` into `dest` and bring back the working-tree snapshot
+/// taken by [`git_bundle_create`]: files that were untracked or uncommitted at
+/// backup time reappear that way. `origin` is pointed at `remote_url` when
+/// given; otherwise the bundle remote is removed.
+pub fn git_bundle_restore(file: &Path, dest: &Path, remote_url: Option<&str>) -> Result<()> {
+    let file_str = file.to_string_lossy().into_owned();
+    git_clone(&file_str, dest, false)?;
+
+    let refspec = format!("{WORKTREE_SNAPSHOT_REF}:{WORKTREE_SNAPSHOT_REF}");
+    if run_git(&["fetch", "--quiet", &file_str, &refspec], dest).is_ok() {
+        run_git(&["checkout", WORKTREE_SNAPSHOT_REF, "--", "."], dest)?;
+        // Unstage: restored files keep their untracked/modified status
+        if run_git(&["rev-parse", "--verify", "HEAD"], dest).is_ok() {
+            run_git(&["reset", "--quiet"], dest)?;
+        } else {
+            run_git(&["rm", "-r", "--cached", "--quiet", "."], dest)?;
+        }
+        run_git(&["update-ref", "-d", WORKTREE_SNAPSHOT_REF], dest)?;
+    }
+    match remote_url {
+        Some(url) => run_git(&["remote", "set-url", "origin", url], dest)?,
+        None => run_git(&["remote", "remove", "origin"], dest)?,
+    };
+    Ok(())
+}
+
+/// Declares [`StoreCommit`] with one subject prefix per kind, so every commit
+/// polyrc makes is one `polyrc undo` recognizes.
+macro_rules! store_commits {
+    ($($kind:ident => $prefix:literal,)*) => {
+        /// The kinds of commit polyrc makes in the store.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum StoreCommit {
+            $($kind,)*
+        }
+
+        impl StoreCommit {
+            pub const ALL: &[StoreCommit] = &[$(Self::$kind,)*];
+
+            /// What every subject of this kind starts with.
+            pub fn prefix(self) -> &'static str {
+                match self {
+                    $(Self::$kind => $prefix,)*
+                }
+            }
+        }
+    };
+}
+
+store_commits! {
+    InitStore => "init store",
+    PushFormat => "push-format ",
+    PushRule => "push-rule: ",
+    PullRule => "pull-rule ",
+    EditRule => "edit-rule: ",
+    MoveRule => "move-rule: ",
+    CopyRule => "copy-rule: ",
+    Convert => "convert from ",
+    PackInstall => "pack install ",
+    InitProject => "init project ",
+    UpdateProject => "update project ",
+    RenameProject => "rename project ",
+    ArchiveProject => "archive project ",
+    UnarchiveProject => "unarchive project ",
+    Prune => "prune ",
+    Normalize => "normalize ",
+    Untrack => "untrack ignored files",
+    Regenerate => "regenerate ",
+}
+
+impl StoreCommit {
+    /// The commit subject: the kind's prefix followed by `detail`.
+    pub fn subject(self, detail: &str) -> String {
+        format!("{}{detail}", self.prefix())
+    }
+}
+
+/// Stage all changes and commit them as `kind` with `detail` after its
+/// prefix. Returns the subject; nothing is committed when nothing changed.
+pub fn git_commit(store_path: &Path, kind: StoreCommit, detail: &str) -> Result<String> {
+    let message = kind.subject(detail);
+    run_git(&["add", "-A"], store_path)?;
+
+    // Check if there's anything to commit
+    let status = run_git(&["status", "--porcelain"], store_path)?;
+    if status.is_empty() {
+        return Ok(message); // nothing to commit
+    }
+
+    run_git(&["commit", "-m", &message], store_path)?;
+    Ok(message)
+}
+
+/// Tracked files that the repo's ignore rules would now exclude
+/// (e.g. committed before the pattern was added to `.gitignore`).
+pub fn git_tracked_ignored(store_path: &Path) -> Result<Vec<String>> {
+    let out = run_git(&["ls-files", "--cached", "--ignored", "--exclude-standard"], store_path)?;
+    Ok(out.lines().map(str::to_string).collect())
+}
+
+/// Stop tracking `files` without deleting them from the working tree.
+pub fn git_untrack(store_path: &Path, files: &[String]) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    let mut args = vec!["rm", "--cached", "--quiet", "--"];
+    args.extend(files.iter().map(String::as_str));
+    run_git(&args, store_path)?;
+    Ok(())
+}
+
+/// How long [`probe_remote`] waits for the remote before calling it unreachable.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Whether the store's remote answered a quick probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteStatus {
+    Reachable,
+    /// Offline, DNS failure, refused or timed-out connection; carries the reason.
+    Unreachable(String),
+}
+
+/// Check that `origin` answers within `timeout` with `git ls-remote`, so sync can
+/// skip its network phases instead of waiting out git's own timeouts. Failures
+/// that aren't network problems (no remote, auth) count as reachable and are
+/// left for the real fetch/push to report.
+pub fn probe_remote(store_path: &Path, timeout: Duration) -> RemoteStatus {
+    let child = Command::new("git")
+        .args(["ls-remote", "--heads", "origin"])
+        .current_dir(store_path)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn();
+    let Ok(mut child) = child else { return RemoteStatus::Reachable };
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return RemoteStatus::Reachable,
+            Ok(Some(_)) => {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = child.stderr.take() {
+                    let _ = std::io::Read::read_to_string(&mut pipe, &mut stderr);
+                }
+                crate::debug_log!("git ls-remote --heads origin (in {}) failed: {}", store_path.display(), crate::logfile::truncate(stderr.trim()));
+                return match network_failure(&stderr) {
+                    Some(reason) => RemoteStatus::Unreachable(reason),
+                    None => RemoteStatus::Reachable,
+                };
+            }
+            Ok(None) if started.elapsed() < timeout => std::thread::sleep(Duration::from_millis(20)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                crate::debug_log!("git ls-remote --heads origin (in {}) timed out after {:?}", store_path.display(), timeout);
+                return RemoteStatus::Unreachable(format!("no answer within {}s", timeout.as_secs()));
+            }
+        }
+    }
+}
+
+/// The line of git's stderr that says the network is the problem, if any.
+fn network_failure(stderr: &str) -> Option<String> {
+    const PATTERNS: &[&str] = &[
+        "could not resolve host",
+        "could not resolve hostname",
+        "temporary failure in name resolution",
+        "name or service not known",
+        "network is unreachable",
+        "no route to host",
+        "connection refused",
+        "connection timed out",
+        "operation timed out",
+        "failed to connect",
+    ];
+    stderr
+        .lines()
+        .find(|line| {
+            let lower = line.to_lowercase();
+            PATTERNS.iter().any(|p| lower.contains(p))
+        })
+        .map(|line| line.trim().trim_start_matches("fatal: ").to_string())
+}
+
+/// Push to the configured remote (origin).
+///
+/// Uses `--set-upstream` so it works correctly for both the initial push to an
+/// empty remote and subsequent pushes. A shallow store whose history the remote
+/// doesn't have ("shallow update not allowed") is unshallowed and pushed again.
+pub fn git_push(store_path: &Path) -> Result<()> {
+    let push = || run_git(&["push", "--set-upstream", "origin", "HEAD"], store_path);
+    match push() {
+        Err(PolyrcError::GitError { msg }) if msg.contains("shallow update not allowed") => {
+            git_unshallow(store_path)?;
+            push()?;
+        }
+        other => {
+            other?;
+        }
+    }
+    Ok(())
+}
+
+/// How many commits the store is ahead of and behind `origin/main`, as of the
+/// last fetch; `None` when there is no remote branch to compare with.
+pub fn git_ahead_behind(store_path: &Path) -> Result<Option<(usize, usize)>> {
+    if run_git(&["rev-parse", "--verify", REMOTE_BRANCH], store_path).is_err() {
+        return Ok(None);
+    }
+    let counts = run_git(&["rev-list", "--left-right", "--count", &format!("HEAD...{REMOTE_BRANCH}")], store_path)?;
+    let mut counts = counts.split_whitespace().map(|n| n.parse().unwrap_or(0));
+    Ok(Some((counts.next().unwrap_or(0), counts.next().unwrap_or(0))))
+}
+
+/// Fail unless git can read the repo at `store_path` (`git status`).
+pub fn git_check_repo(store_path: &Path) -> Result<()> {
+    run_git(&["status", "--porcelain"], store_path).map(|_| ())
+}
+
+/// Whether the store is a shallow clone (`polyrc init --shallow`).
+pub fn git_is_shallow(store_path: &Path) -> Result<bool> {
+    Ok(run_git(&["rev-parse", "--is-shallow-repository"], store_path)? == "true")
+}
+
+/// Fetch the store's full history and all remote branches, turning a shallow
+/// clone into a normal one. A no-op for a full clone.
+pub fn git_unshallow(store_path: &Path) -> Result<()> {
+    if !git_is_shallow(store_path)? {
+        return Ok(());
+    }
+    run_git(&["config", "remote.origin.fetch", "+refs/heads/*:refs/remotes/origin/*"], store_path)?;
+    run_git(&["fetch", "--unshallow", "origin"], store_path)?;
+    Ok(())
+}
+
+/// In a shallow store, fetch older history until `HEAD` and the remote branch
+/// share a commit, so the merge has a base and doesn't treat every difference
+/// as a conflict. Falls back to the full history.
+fn deepen_to_merge_base(store_path: &Path) -> Result<()> {
+    for depth in SHALLOW_DEEPEN_STEPS {
+        if run_git(&["merge-base", "HEAD", REMOTE_BRANCH], store_path).is_ok() || !git_is_shallow(store_path)? {
+            return Ok(());
+        }
+        run_git(&["fetch", &format!("--deepen={depth}"), "origin"], store_path)?;
+    }
+    if run_git(&["merge-base", "HEAD", REMOTE_BRANCH], store_path).is_err() {
+        git_unshallow(store_path)?;
+    }
+    Ok(())
+}
+
+/// Pull remote changes into the store, handling conflicts automatically.
+///
+/// Strategy:
+///  1. `git fetch` — not fatal if the remote is offline or empty. A shallow
+///     store fetches only new commits, then deepens if it has no merge base.
+///  2. If `origin/main` doesn't exist yet the remote is empty; skip pull.
+///  3. If we are already up-to-date; skip merge.
+///  4. `git merge -X ours --no-commit --allow-unrelated-histories origin/main`
+///     — integrates all new files/commits from the remote and auto-resolves
+///     conflicting hunks in other files by keeping the local version.
+///     `--allow-unrelated-histories` handles remotes that were initialised
+///     independently (e.g. via GitHub's "Add a README" checkbox).
+///  5. On a merge failure outside rule files (binary conflicts, etc.) the
+///     merge is aborted and a clear, actionable error is returned.
+///  6. Rule files changed on both sides are merged rule by rule with
+///     [`merge_rules`] instead of line by line, and the merge is committed.
+///
+/// Returns the rules the merge added, updated and deleted, plus the rules whose
+/// two versions differed (see [`Conflict`]); empty when skipped.
+pub fn git_pull(store_path: &Path, options: MergeOptions) -> Result<SyncReport> {
+    let mut report = SyncReport::now();
+
+    // Step 1: fetch — not fatal (offline, empty remote, etc.)
+    let _ = run_git(&["fetch", "origin"], store_path);
+
+    // Step 2: skip if remote has no main branch yet (freshly created repo)
+    if run_git(&["rev-parse", "--verify", REMOTE_BRANCH], store_path).is_err() {
+        return Ok(report);
+    }
+
+    // Step 3: skip if already up-to-date
+    let behind = run_git(
+        &["rev-list", "--count", &format!("HEAD..{REMOTE_BRANCH}")],
+        store_path,
+    )
+    .unwrap_or_default();
+    if behind.trim() == "0" {
+        return Ok(report);
+    }
+
+    let before = run_git(&["rev-parse", "--verify", "HEAD"], store_path).ok();
+    if before.is_some() && git_is_shallow(store_path)? {
+        deepen_to_merge_base(store_path)?;
+    }
+    let both_changed: Vec<String> = match &before {
+        Some(head) => changed_on_both_sides(store_path, head)?
+            .into_iter()
+            .filter(|path| report::rule_key(path).is_some() || is_tombstone_file(path))
+            .collect(),
+        None => vec![],
+    };
+
+    // Step 4: merge, auto-resolving conflicts by preferring the local version
+    // for any conflicting hunks within a file.  New files from either side are
+    // always taken in full — only true per-line conflicts are affected by -X.
+    let merge_result = run_git(
+        &[
+            "merge",
+            "--no-edit",
+            "--no-commit",
+            "-X", "ours",
+            "--allow-unrelated-histories",
+            REMOTE_BRANCH,
+        ],
+        store_path,
+    );
+
+    // Step 5: surface conflicts that step 6 can't resolve clearly
+    let unmerged = run_git(&["diff", "--name-only", "--diff-filter=U"], store_path).unwrap_or_default();
+    let only_rules = !unmerged.is_empty() && unmerged.lines().all(|f| both_changed.iter().any(|b| b == f));
+    if let Err(e) = merge_result
+        && !only_rules
+    {
+        return Err(abort_merge(store_path, e));
+    }
+
+    // Step 6: merge rule files changed on both sides rule by rule, then commit
+    let before = before.as_deref().unwrap_or(EMPTY_TREE);
+    let merged = merge_rule_files(store_path, before, &both_changed, options).and_then(|conflicts| {
+        if run_git(&["rev-parse", "-q", "--verify", "MERGE_HEAD"], store_path).is_ok() {
+            run_git(&["commit", "--no-edit"], store_path)?;
+        }
+        Ok(conflicts)
+    });
+    report.conflicts = merged.map_err(|e| abort_merge(store_path, e))?;
+
+    let changes = run_git(&["diff", "--name-status", "--no-renames", before, "HEAD"], store_path)?;
+    for line in changes.lines() {
+        let Some((status, path)) = line.split_once('\t') else { continue };
+        let Some((project, name)) = report::rule_key(path) else { continue };
+        let list = match status {
+            "A" => &mut report.added,
+            "D" => &mut report.deleted,
+            _ => &mut report.updated,
+        };
+        list.push(format!("{project}/{name}"));
+    }
+    Ok(report)
+}
+
+/// Abort the merge in progress, leaving no partial state behind, and explain how
+/// to resolve it by hand.
+fn abort_merge(store_path: &Path, e: PolyrcError) -> PolyrcError {
+    let _ = run_git(&["merge", "--abort"], store_path);
+    PolyrcError::GitError {
+        msg: format!(
+            "could not auto-merge remote changes into the store.\n\
+             Run `git -C {} mergetool` to resolve manually, then retry sync-store.\n\
+             Details: {e}",
+            store_path.display()
+        ),
+    }
+}
+
+/// Merge the rule files at `paths`, changed both in `head` and on the remote,
+/// rule by rule with [`merge_rules`] and both sides' tombstones, and stage the
+/// result. A merged rule goes back to its local file, else its remote one; a
+/// remote rule whose file a local rule took gets a `-2`, `-3`, … suffix. A
+/// file that doesn't parse on one side keeps its local version whole. A
+/// tombstone file in `paths` gets the tombstones of both sides. Returns the
+/// rules whose versions differed.
+fn merge_rule_files(store_path: &Path, head: &str, paths: &[String], options: MergeOptions) -> Result<Vec<Conflict>> {
+    let mut by_project: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for path in paths {
+        if let Some((project, _)) = path.split_once('/') {
+            by_project.entry(project.to_string()).or_default().push(path);
+        }
+    }
+
+    let mut conflicts = vec![];
+    for (project, paths) in by_project {
+        let tombstone_path = format!("{project}/{TOMBSTONE_FILE}");
+        let tombstones_at = |rev: &str| match show_file(store_path, rev, &tombstone_path) {
+            Some(raw) => tombstones_from_yaml(&raw, Path::new(&tombstone_path)),
+            None => Ok(vec![]),
+        };
+        let tombstones = merge_tombstones(&tombstones_at(head)?, &tombstones_at(REMOTE_BRANCH)?);
+        if paths.contains(&tombstone_path.as_str()) {
+            write_tombstones(&store_path.join(&tombstone_path), &tombstones)?;
+        }
+
+        let (mut local, mut remote) = (vec![], vec![]);
+        for &path in paths.iter().filter(|p| report::rule_key(p).is_some()) {
+            let raw_local = show_file(store_path, head, path);
+            let raw_remote = show_file(store_path, REMOTE_BRANCH, path);
+            let parse = |raw: &Option<String>| raw.as_deref().map(|raw| crate::store::rule_from_yaml(raw, Path::new(path)));
+            match (parse(&raw_local), parse(&raw_remote)) {
+                (Some(Err(_)), _) | (_, Some(Err(_))) => {
+                    let raw = raw_local.or(raw_remote).unwrap_or_default();
+                    write_file(&store_path.join(path), &raw)?;
+                }
+                (ours, theirs) => {
+                    local.extend(ours.and_then(Result::ok).map(|rule| (path.to_string(), rule)));
+                    remote.extend(theirs.and_then(Result::ok).map(|rule| (path.to_string(), rule)));
+                }
+            }
+        }
+        let rules = |side: &[(String, Rule)]| side.iter().map(|(_, rule)| rule.clone()).collect::<Vec<_>>();
+        let merged = merge_rules(&rules(&local), &rules(&remote), &tombstones, options);
+
+        let mut written: Vec<String> = vec![];
+        for (path, _) in local.iter().chain(&remote) {
+            let file = store_path.join(path);
+            if file.exists() {
+                std::fs::remove_file(&file).map_err(|e| PolyrcError::Io { path: file, source: e })?;
+            }
+        }
+        for rule in &merged.rules {
+            let file_of = |side: &[(String, Rule)]| side.iter().find(|(_, r)| r.id == rule.id).map(|(p, _)| p.clone());
+            let path = file_of(&local)
+                .or_else(|| file_of(&remote))
+                .filter(|p| !written.contains(p))
+                .unwrap_or_else(|| free_rule_path(store_path, &project, rule, &written));
+            let file = store_path.join(&path);
+            write_file(&file, &crate::store::rule_to_yaml(rule, &file)?)?;
+            if let Some(resolution) = merged.resolutions.iter().find(|r| r.id == rule.id)
+                && let Some((project, name)) = report::rule_key(&path)
+            {
+                conflicts.push(Conflict {
+                    project,
+                    rule: name,
+                    winner: resolution.winner,
+                    local_updated_at: resolution.local_updated_at.clone(),
+                    remote_updated_at: resolution.remote_updated_at.clone(),
+                    deleted_at: resolution.deleted_at.clone(),
+                });
+            }
+            written.push(path);
+        }
+
+        let stage: Vec<&str> = paths.iter().copied().chain(written.iter().map(String::as_str)).collect();
+        let (present, gone): (Vec<&str>, Vec<&str>) = stage.into_iter().partition(|p| store_path.join(p).exists());
+        if !present.is_empty() {
+            run_git(&[&["add", "--"], present.as_slice()].concat(), store_path)?;
+        }
+        if !gone.is_empty() {
+            run_git(&[&["rm", "--cached", "--quiet", "--ignore-unmatch", "--"], gone.as_slice()].concat(), store_path)?;
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Whether the store-relative `path` is a project's tombstone file.
+fn is_tombstone_file(path: &str) -> bool {
+    path.split_once('/').is_some_and(|(_, file)| file == TOMBSTONE_FILE)
+}
+
+/// A store-relative `<project>/<stem>-N.yaml` for `rule` that is neither on
+/// disk nor in `taken`.
+fn free_rule_path(store_path: &Path, project: &str, rule: &Rule, taken: &[String]) -> String {
+    let stem = rule.filename_stem();
+    (2..)
+        .map(|n| format!("{project}/{stem}-{n}.yaml"))
+        .find(|p| !taken.contains(p) && !store_path.join(p).exists())
+        .expect("some suffix is free")
+}
+
+fn write_file(file: &Path, content: &str) -> Result<()> {
+    std::fs::write(file, content).map_err(|e| PolyrcError::Io { path: file.to_path_buf(), source: e })
+}
+
+/// Files changed both in `head` and on the remote since their merge base. Without
+/// a common history, files that exist on both sides with different content.
+fn changed_on_both_sides(store_path: &Path, head: &str) -> Result<Vec<String>> {
+    let names = |args: &[&str]| -> Result<Vec<String>> {
+        Ok(run_git(args, store_path)?.lines().map(str::to_string).collect())
+    };
+    let Ok(base) = run_git(&["merge-base", head, REMOTE_BRANCH], store_path) else {
+        return names(&["diff", "--name-only", "--diff-filter=M", head, REMOTE_BRANCH]);
+    };
+    let local = names(&["diff", "--name-only", &base, head])?;
+    let remote = names(&["diff", "--name-only", &base, REMOTE_BRANCH])?;
+    Ok(local.into_iter().filter(|f| remote.contains(f)).collect())
+}
+
+/// The file at `path` as of `rev`, if it exists there.
+fn show_file(store_path: &Path, rev: &str, path: &str) -> Option<String> {
+    let spec = format!("{rev}:{path}");
+    let args = ["show", spec.as_str()];
+    let output = Command::new("git").args(args).current_dir(store_path).output().ok()?;
+    log_git(&args, store_path, &output);
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,161 @@
+//! What the last `sync` pull did to the store, kept on disk so it can be
+//! audited after the terminal output has scrolled away (`polyrc sync --report`).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::error::{PolyrcError, Result};
+
+/// Report file name inside the polyrc directory.
+pub const REPORT_FILE: &str = "last-sync-report.json";
+
+/// Side of a sync whose version of a conflicting change was kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Local,
+    Remote,
+}
+
+/// A rule changed both locally and on the remote since they last agreed, with
+/// different content on each side, or deleted on one side and edited after on
+/// the other.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conflict {
+    pub project: String,
+    pub rule: String,
+    /// Whose version was kept: the one updated last, local on a tie.
+    pub winner: Side,
+    pub local_updated_at: Option<String>,
+    pub remote_updated_at: Option<String>,
+    /// When the side that lost deleted the rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<String>,
+}
+
+/// Rule changes brought in by one pull. Rules are `project/name`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub synced_at: String,
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Where the last report is kept: `~/polyrc/last-sync-report.json`.
+pub fn report_path() -> PathBuf {
+    crate::config::polyrc_dir().join(REPORT_FILE)
+}
+
+impl SyncReport {
+    /// An empty report stamped with the current time.
+    pub fn now() -> Self {
+        Self { synced_at: chrono::Utc::now().to_rfc3339(), ..Default::default() }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| PolyrcError::Io { path: parent.to_path_buf(), source: e })?;
+        }
+        let json = serde_json::to_string_pretty(self).expect("report serializes");
+        fs::write(path, json + "\n").map_err(|e| PolyrcError::Io { path: path.to_path_buf(), source: e })
+    }
+
+    /// The saved report, or `None` if no sync has written one yet.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read_to_string(path).map_err(|e| PolyrcError::Io { path: path.to_path_buf(), source: e })?;
+        serde_json::from_str(&raw).map(Some).map_err(|e| PolyrcError::ConfigError {
+            msg: format!("invalid sync report {}: {e}", path.display()),
+        })
+    }
+
+    /// One-line counts, e.g. `2 added, 1 updated, 0 deleted, 1 conflict(s)`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} updated, {} deleted, {} conflict(s)",
+            self.added.len(),
+            self.updated.len(),
+            self.deleted.len(),
+            self.conflicts.len()
+        )
+    }
+
+    /// Human-readable rendering for `sync --report`.
+    pub fn render(&self) -> String {
+        let mut out = format!("Last sync: {}\n  {}\n", self.synced_at, self.summary());
+        for (what, rules) in [("added", &self.added), ("updated", &self.updated), ("deleted", &self.deleted)] {
+            if !rules.is_empty() {
+                out.push_str(&format!("  {what}: {}\n", rules.join(", ")));
+            }
+        }
+        if !self.conflicts.is_empty() {
+            out.push_str("  conflicts:\n");
+            for c in &self.conflicts {
+                if let Some(deleted_at) = &c.deleted_at {
+                    out.push_str(&format!(
+                        "    {}/{} — kept the {} edit over a deletion at {deleted_at}\n",
+                        c.project,
+                        c.rule,
+                        format!("{:?}", c.winner).to_lowercase(),
+                    ));
+                    continue;
+                }
+                out.push_str(&format!(
+                    "    {}/{} — kept {} (local updated {}, remote updated {})\n",
+                    c.project,
+                    c.rule,
+                    format!("{:?}", c.winner).to_lowercase(),
+                    c.local_updated_at.as_deref().unwrap_or("?"),
+                    c.remote_updated_at.as_deref().unwrap_or("?"),
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// `(project, rule name)` for a store-relative rule file path, `None` for
+/// anything that isn't `<project>/<name>.yaml`.
+pub(crate) fn rule_key(path: &str) -> Option<(String, String)> {
+    let (project, file) = path.split_once('/')?;
+    let name = file.strip_suffix(".yaml")?;
+    (!name.contains('/')).then(|| (project.to_string(), name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(REPORT_FILE);
+        assert_eq!(SyncReport::load(&file).unwrap(), None);
+
+        let mut report = SyncReport::now();
+        report.added.push("app/style".into());
+        report.conflicts.push(Conflict {
+            project: "app".into(),
+            rule: "tests".into(),
+            winner: Side::Local,
+            local_updated_at: Some("2026-01-02T00:00:00Z".into()),
+            remote_updated_at: None,
+            deleted_at: None,
+        });
+        report.save(&file).unwrap();
+        let loaded = SyncReport::load(&file).unwrap().unwrap();
+        assert_eq!(loaded, report);
+        assert!(loaded.render().contains("app/tests — kept local (local updated 2026-01-02T00:00:00Z, remote updated ?)"));
+    }
+
+    #[test]
+    fn only_rule_files_have_keys() {
+        assert_eq!(rule_key("app/style.yaml"), Some(("app".into(), "style".into())));
+        assert_eq!(rule_key("app/project.toml"), None);
+        assert_eq!(rule_key(".gitignore"), None);
+    }
+}
@@ -0,0 +1,97 @@
+//! `polyrc undo` — revert the store's most recent polyrc commits.
+//!
+//! Commits are reverted with `git revert` rather than reset away, so history
+//! stays linear with the remote and the undo itself syncs like any other change.
+
+use std::collections::HashSet;
+use std::path::Path;
+use crate::error::Result;
+use super::{run_git, StoreCommit, REMOTE_BRANCH};
+
+/// Commits scanned for undo candidates.
+const LOG_DEPTH: &str = "200";
+
+/// A polyrc commit that can be undone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoCandidate {
+    pub sha: String,
+    pub subject: String,
+    /// Already on `origin/main`.
+    pub pushed: bool,
+}
+
+/// A file changed by a revert, as `git show --name-status` reports it:
+/// `A` (restored), `D` (removed) or `M` (rolled back).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevertedFile {
+    pub status: char,
+    pub path: String,
+}
+
+/// The newest `steps` polyrc commits, newest first. Merges, `git revert`
+/// commits and commits that were already reverted are skipped, so repeated
+/// undos walk further back instead of redoing. The walk stops at the first
+/// commit polyrc didn't make, and at the root commit, which holds the store's
+/// own files: reverting past them would undo changes polyrc can't account for.
+pub fn undo_candidates(store_path: &Path, steps: usize) -> Result<Vec<UndoCandidate>> {
+    let log = run_git(&["log", "--no-merges", "-n", LOG_DEPTH, "--format=%H%x1f%P%x1f%s%x1f%b%x1e"], store_path)?;
+    let mut reverted = HashSet::new();
+    let mut found = vec![];
+    for entry in log.split('\x1e').map(str::trim).filter(|e| !e.is_empty()) {
+        let mut fields = entry.splitn(4, '\x1f');
+        let (Some(sha), Some(parents), Some(subject)) = (fields.next(), fields.next(), fields.next()) else { continue };
+        let body = fields.next().unwrap_or_default();
+        if subject.starts_with("Revert \"") {
+            reverted.extend(reverted_shas(body));
+            continue;
+        }
+        if reverted.contains(sha) {
+            continue;
+        }
+        if parents.is_empty() || !StoreCommit::ALL.iter().any(|c| subject.starts_with(c.prefix())) {
+            break;
+        }
+        found.push(UndoCandidate { sha: sha.to_string(), subject: subject.to_string(), pushed: is_pushed(store_path, sha) });
+        if found.len() == steps {
+            break;
+        }
+    }
+    Ok(found)
+}
+
+/// Shas named by `This reverts commit <sha>.` lines in a revert's message.
+fn reverted_shas(body: &str) -> Vec<String> {
+    body.lines()
+        .filter_map(|l| l.trim().strip_prefix("This reverts commit "))
+        .map(|rest| rest.trim_end_matches('.').to_string())
+        .collect()
+}
+
+fn is_pushed(store_path: &Path, sha: &str) -> bool {
+    run_git(&["merge-base", "--is-ancestor", sha, REMOTE_BRANCH], store_path).is_ok()
+}
+
+/// Revert `shas` in order, one new commit each, and list the files each
+/// revert changed back. If any of them fails (say on a conflict) the whole
+/// sequence is aborted and the store is left as it was.
+pub fn git_revert(store_path: &Path, shas: &[&str]) -> Result<Vec<Vec<RevertedFile>>> {
+    let mut args = vec!["revert", "--no-edit"];
+    args.extend(shas);
+    if let Err(e) = run_git(&args, store_path) {
+        let _ = run_git(&["revert", "--abort"], store_path);
+        return Err(e);
+    }
+    (0..shas.len())
+        .rev()
+        .map(|back| {
+            let changed = run_git(&["show", "--name-status", "--format=", &format!("HEAD~{back}")], store_path)?;
+            Ok(changed
+                .lines()
+                .filter_map(|line| {
+                    let (status, path) = line.split_once('\t')?;
+                    Some(RevertedFile { status: status.chars().next()?, path: path.to_string() })
+                })
+                .collect())
+        })
+        .collect()
+}
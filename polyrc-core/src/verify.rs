@@ -0,0 +1,104 @@
+//! `polyrc verify` — does a format survive its own writer?
+//!
+//! The rules parsed from a directory are written with the same format's writer
+//! into a scratch directory and parsed again; optionally they pass through a
+//! scratch store first, as `push-format` + `pull-format` would take them. Both
+//! sides are compared field by field with [`crate::drift`]'s rules.
+
+use std::path::Path;
+use serde::Serialize;
+use crate::drift::{self, Drift};
+use crate::error::{PolyrcError, Result};
+use crate::formats::Format;
+use crate::ir::Rule;
+use crate::store::Store;
+
+/// Project key the scratch store keeps rules under.
+const SCRATCH_PROJECT: &str = "verify";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyReport {
+    pub format: &'static str,
+    /// Rules parsed from the input.
+    pub rules: usize,
+    pub via_store: bool,
+    /// Differences between the input and the round trip, by rule name.
+    /// `missing_locally` means the rule was lost, `missing_in_store` that it
+    /// only appeared after the round trip (usually under another name).
+    pub differences: Vec<Drift>,
+}
+
+impl VerifyReport {
+    pub fn faithful(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Round-trip what `fmt` parses from `input`. `None` when there is nothing to
+/// verify. Nothing under `input` is modified.
+pub fn verify(fmt: &Format, input: &Path, via_store: bool) -> Result<Option<VerifyReport>> {
+    let original = fmt.parser().parse(input)?;
+    if original.is_empty() {
+        return Ok(None);
+    }
+    let middle = if via_store { through_store(fmt, &original)? } else { original.clone() };
+    let round_trip = drift::render(fmt, &middle)?;
+    Ok(Some(VerifyReport {
+        format: fmt.name(),
+        rules: original.len(),
+        via_store,
+        differences: drift::diff_rules(&drift::keyed(original), &drift::keyed(round_trip)),
+    }))
+}
+
+/// Save `rules` to a scratch store and load them back.
+fn through_store(fmt: &Format, rules: &[Rule]) -> Result<Vec<Rule>> {
+    let scratch = std::env::temp_dir().join(format!("polyrc-verify-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&scratch).map_err(|e| PolyrcError::Io { path: scratch.clone(), source: e })?;
+    let store = Store::new(&scratch);
+    let result = store
+        .save_rules(Some(SCRATCH_PROJECT), rules, fmt.name())
+        .and_then(|_| store.load_rules(Some(SCRATCH_PROJECT)));
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::DriftKind;
+
+    #[test]
+    fn cursor_round_trips_through_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = dir.path().join(".cursor").join("rules");
+        std::fs::create_dir_all(&rules).unwrap();
+        std::fs::write(
+            rules.join("rust.mdc"),
+            "---\ndescription: Rust style\nglobs: \"*.rs\"\nalwaysApply: false\n---\n\nUse rustfmt.   \n\n",
+        )
+        .unwrap();
+        std::fs::write(rules.join("tests.mdc"), "---\nalwaysApply: true\n---\n\nWrite tests.\n").unwrap();
+
+        for via_store in [false, true] {
+            let report = verify(&Format::Cursor, dir.path(), via_store).unwrap().unwrap();
+            assert_eq!(report.rules, 2);
+            assert!(report.faithful(), "{:?}", report.differences);
+        }
+        assert!(verify(&Format::Windsurf, dir.path(), false).unwrap().is_none());
+    }
+
+    #[test]
+    fn lossy_round_trips_are_reported() {
+        // A lone rules/ file is written back as CLAUDE.md, under that name
+        let dir = tempfile::tempdir().unwrap();
+        let rules = dir.path().join(".claude").join("rules");
+        std::fs::create_dir_all(&rules).unwrap();
+        std::fs::write(rules.join("style.md"), "Use tabs.\n").unwrap();
+
+        let report = verify(&Format::Claude, dir.path(), false).unwrap().unwrap();
+        assert!(!report.faithful());
+        let kinds: Vec<_> = report.differences.iter().map(|d| (d.rule.as_str(), &d.kind)).collect();
+        assert_eq!(kinds, vec![("claude", &DriftKind::MissingInStore), ("style", &DriftKind::MissingLocally)]);
+    }
+}
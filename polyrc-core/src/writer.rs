@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use serde::{Deserialize, Serialize};
+use crate::error::{PolyrcError, Result};
+use crate::ir::{derive_description, Activation, Rule};
+
+/// Writes a list of Rules to the tool-specific configuration location.
+/// `target` is the project root directory to write into.
+pub trait Writer {
+    fn write(&self, rules: &[Rule], target: &Path) -> Result<()>;
+
+    /// [`write`](Self::write) with caller overrides. Only single-file writers
+    /// (and Copilot, for its main file) honor `output_file`; the rest reject it.
+    fn write_with(&self, rules: &[Rule], target: &Path, options: &WriteOptions) -> Result<()> {
+        if let Some(file) = &options.output_file {
+            return Err(PolyrcError::WriteFailure {
+                path: file.clone(),
+                reason: "--output-file only applies to single-file formats; this one writes several files".into(),
+            });
+        }
+        self.write(rules, target)
+    }
+}
+
+/// Overrides passed to [`Writer::write_with`].
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// Exact path of the main output file, instead of its default name under the target.
+    pub output_file: Option<PathBuf>,
+}
+
+/// What to do with rules whose activation the target format can't express
+/// (`[write] on_unsupported_activation`, `--unsupported-activation`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivationPolicy {
+    /// Write them anyway; the format loads them as always-on.
+    #[default]
+    Downgrade,
+    /// Leave them out.
+    Skip,
+    /// Refuse to write anything.
+    Error,
+}
+
+static ACTIVATION_POLICY: AtomicU8 = AtomicU8::new(0);
+
+/// Set the process-wide default policy (from `[write] on_unsupported_activation`).
+pub fn set_activation_policy(policy: ActivationPolicy) {
+    ACTIVATION_POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+pub fn activation_policy() -> ActivationPolicy {
+    match ACTIVATION_POLICY.load(Ordering::Relaxed) {
+        1 => ActivationPolicy::Skip,
+        2 => ActivationPolicy::Error,
+        _ => ActivationPolicy::Downgrade,
+    }
+}
+
+static DERIVE_DESCRIPTIONS: AtomicBool = AtomicBool::new(true);
+
+/// Enable or disable deriving missing descriptions (from `[write] derive_descriptions`).
+pub fn set_derive_descriptions(on: bool) {
+    DERIVE_DESCRIPTIONS.store(on, Ordering::Relaxed);
+}
+
+pub fn derive_descriptions() -> bool {
+    DERIVE_DESCRIPTIONS.load(Ordering::Relaxed)
+}
+
+/// Copy of `rules` where AI-decided rules without a description get one derived
+/// from their content, for targets that can't activate such rules otherwise.
+/// Raises a notice naming the affected rules. A no-op when disabled.
+pub(crate) fn with_derived_descriptions(rules: &[Rule], format: &str) -> Vec<Rule> {
+    let mut out = rules.to_vec();
+    if !derive_descriptions() {
+        return out;
+    }
+    let mut derived = vec![];
+    for rule in out.iter_mut().filter(|r| r.activation == Activation::AiDecides && r.description.is_none()) {
+        rule.description = derive_description(&rule.content);
+        if rule.description.is_some() {
+            derived.push(rule.name.clone().unwrap_or_else(|| rule.filename_stem()));
+        }
+    }
+    if !derived.is_empty() {
+        crate::notice::note(format!("{format}: derived description from content for {}", derived.join(", ")));
+    }
+    out
+}
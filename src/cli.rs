@@ -3,31 +3,27 @@ use clap::{Parser, Subcommand};
 
 // ── format enum ───────────────────────────────────────────────────────────────
 
-/// Canonical format names — drives tab-completion for all --format / --from / --to args.
-#[derive(Debug, Clone, clap::ValueEnum)]
-pub enum FormatArg {
-    Cursor,
-    Windsurf,
-    #[value(alias = "github-copilot", alias = "ghcopilot")]
-    Copilot,
-    #[value(alias = "claude-code")]
-    Claude,
-    #[value(alias = "gemini-cli")]
-    Gemini,
-    #[value(alias = "google-antigravity")]
-    Antigravity,
+/// A `--format` / `--from` / `--to` value. Names and aliases come from
+/// [`Format::name`](polyrc_core::formats::Format::name) and
+/// [`Format::aliases`](polyrc_core::formats::Format::aliases), which also drive
+/// tab-completion.
+#[derive(Debug, Clone)]
+pub struct FormatArg(pub polyrc_core::formats::Format);
+
+impl clap::ValueEnum for FormatArg {
+    fn value_variants<'a>() -> &'a [Self] {
+        static VARIANTS: std::sync::OnceLock<Vec<FormatArg>> = std::sync::OnceLock::new();
+        VARIANTS.get_or_init(|| polyrc_core::formats::Format::all().iter().cloned().map(FormatArg).collect())
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        Some(clap::builder::PossibleValue::new(self.0.name()).aliases(self.0.aliases().iter().copied()))
+    }
 }
 
 impl FormatArg {
     pub fn as_str(&self) -> &'static str {
-        match self {
-            Self::Cursor => "cursor",
-            Self::Windsurf => "windsurf",
-            Self::Copilot => "copilot",
-            Self::Claude => "claude",
-            Self::Gemini => "gemini",
-            Self::Antigravity => "antigravity",
-        }
+        self.0.name()
     }
 }
 
@@ -41,6 +37,30 @@ impl FormatArg {
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Skip input and store files larger than this (e.g. 512K, 10M). Default 4M.
+    #[arg(long, global = true, value_parser = polyrc_core::parser::parse_size)]
+    pub max_file_size: Option<u64>,
+
+    /// Write debug logs (git commands, files read and written) to
+    /// ~/polyrc/logs/polyrc.log, or $POLYRC_LOG_FILE / `[log] file` if set
+    #[arg(long, global = true)]
+    pub debug: bool,
+
+    /// Set a per-format option for this run, e.g. `copilot.split_always=true`.
+    /// Overrides `[formats.<name>]` in config.toml; repeatable
+    #[arg(long, global = true, value_name = "FORMAT.KEY=VALUE", value_parser = polyrc_core::formats::options::parse_assignment)]
+    pub format_option: Vec<polyrc_core::formats::options::Assignment>,
+
+    /// Keep `@path` import lines in CLAUDE.md as written instead of reading each
+    /// imported file as a rule; same as `--format-option claude.resolve_imports=false`
+    #[arg(long, global = true)]
+    pub no_resolve_imports: bool,
+
+    /// Replace `.claude/settings.json` with the stored settings instead of merging
+    /// them into it; same as `--format-option claude.overwrite_settings=true`
+    #[arg(long, global = true)]
+    pub overwrite_settings: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -50,7 +70,7 @@ pub enum Commands {
 
     /// List all supported formats
     #[command(name = "supported-formats", alias = "list-formats")]
-    SupportedFormats,
+    SupportedFormats(SupportedFormatsArgs),
 
     /// Initialize the local interlingua store (git repo)
     Init(InitArgs),
@@ -79,9 +99,69 @@ pub enum Commands {
     #[command(name = "pull-rule")]
     PullRule(PullRuleArgs),
 
+    /// Find stored rules by name and description, or content
+    Search(SearchArgs),
+
+    /// Open a stored rule in your editor and commit the change
+    #[command(name = "edit-rule")]
+    EditRule(EditRuleArgs),
+
+    /// Move a stored rule to another project or to user scope
+    #[command(name = "move-rule")]
+    MoveRule(MoveRuleArgs),
+
+    /// Copy a stored rule to another project or to user scope, with a new id
+    #[command(name = "copy-rule")]
+    CopyRule(MoveRuleArgs),
+
     /// Discover installed user-level configs for all (or one) format
     Discover(DiscoverArgs),
 
+    /// Check the store for unparseable rule files and index health
+    Doctor(DoctorArgs),
+
+    /// List or install starter rule packs
+    Pack(PackArgs),
+
+    /// Revert the store's most recent polyrc commits (push-format, push-rule, …)
+    Undo(UndoArgs),
+
+    /// Maintain the store itself (index, generated README)
+    Store(StoreArgs),
+
+    /// Install or remove a git pre-commit hook that runs check / push-format
+    Hook(HookArgs),
+
+    /// Compare local format files with the store without changing anything (for CI).
+    /// Exit codes: 0 clean, 6 drift, 7 error.
+    Check(CheckArgs),
+
+    /// Show what pull-format would change under --input, or with
+    /// --direction push what push-format would add to the store.
+    /// Exit codes: 0 no differences, 6 differences.
+    Diff(DiffArgs),
+
+    /// List files written by pull-format / convert (.polyrc.lock) that were
+    /// edited or deleted since; with --project or --user, whether each format's
+    /// rules are in sync with the store and the store with its remote
+    Status(StatusArgs),
+
+    /// Write a format's rules back out with its own writer, parse them again
+    /// and report what didn't survive. Exit codes: 0 faithful, 10 lossy.
+    Verify(VerifyArgs),
+
+    /// Measure how many characters and tokens each format's output would take,
+    /// against Windsurf's limits and `[formats.<name>] budget_chars`.
+    /// Exit codes: 0 within limits, 9 over a limit with --fail-over-limit.
+    Budget(BudgetArgs),
+
+    /// Print version, OS, config, store and environment details for a bug report
+    Env(EnvArgs),
+
+    /// Show each format's user config dir and where it comes from
+    /// (POLYRC_<FORMAT>_USER_DIR, [user_dirs] in config.toml, or the default)
+    Which(WhichArgs),
+
     /// Update polyrc to the latest release from GitHub
     SelfUpdate(SelfUpdateArgs),
 
@@ -99,6 +179,52 @@ pub enum Commands {
     },
 }
 
+impl Commands {
+    /// Whether the command was asked for JSON output, in which case errors are
+    /// printed as JSON on stdout too.
+    pub fn json(&self) -> bool {
+        match self {
+            Self::Convert(a) => a.json,
+            Self::PushFormat(a) => a.json,
+            Self::PullFormat(a) => a.json,
+            Self::ListProject(a) => a.json,
+            Self::Search(a) => a.json,
+            Self::Check(a) => a.json,
+            Self::Diff(a) => a.json,
+            Self::Status(a) => a.json,
+            Self::Verify(a) => a.json,
+            Self::Budget(a) => a.json,
+            Self::Env(a) => a.json,
+            _ => false,
+        }
+    }
+
+    /// Whether the command reads or writes the local store, and so should
+    /// remind about a stale sync (unless its output is [`json`](Self::json)).
+    /// `sync` itself, `init` and `check` (CI) don't.
+    pub fn uses_store(&self) -> bool {
+        matches!(
+            self,
+            Self::PushFormat(_)
+                | Self::PullFormat(_)
+                | Self::Project(_)
+                | Self::ListProject(_)
+                | Self::Search(_)
+                | Self::Diff(_)
+                | Self::PushRule(_)
+                | Self::PullRule(_)
+                | Self::EditRule(_)
+                | Self::MoveRule(_)
+                | Self::CopyRule(_)
+                | Self::Doctor(_)
+                | Self::Pack(_)
+                | Self::Store(_)
+                | Self::Budget(_)
+                | Self::Undo(_)
+        )
+    }
+}
+
 // ── convert ──────────────────────────────────────────────────────────────────
 
 #[derive(clap::Args, Debug)]
@@ -123,6 +249,11 @@ pub struct ConvertArgs {
     #[arg(long, default_value = ".")]
     pub output: PathBuf,
 
+    /// Write the target's main file here instead of its default name under
+    /// --output (gemini, copilot; multi-file formats are rejected)
+    #[arg(long)]
+    pub output_file: Option<PathBuf>,
+
     /// Filter by scope: user, project, or path
     #[arg(long)]
     pub scope: Option<String>,
@@ -130,6 +261,54 @@ pub struct ConvertArgs {
     /// Print what would be written without creating files or touching the store
     #[arg(long, default_value_t = false)]
     pub dry_run: bool,
+
+    /// With --dry-run, print the plan as JSON instead of a preview
+    #[arg(long, requires = "dry_run")]
+    pub json: bool,
+    /// What to do with rules the target format can't activate (e.g. on-demand
+    /// rules for Gemini). Overrides `[write] on_unsupported_activation`
+    #[arg(long, value_enum)]
+    pub unsupported_activation: Option<ActivationPolicyArg>,
+
+    /// Also read package configs in subdirectories of --input and write them
+    /// into the same subdirectories of --output
+    #[arg(long, conflicts_with = "output_file")]
+    pub recursive: bool,
+
+    /// How many directory levels below the root --recursive searches
+    #[arg(long, requires = "recursive", default_value_t = polyrc_core::monorepo::DEFAULT_DEPTH)]
+    pub depth: usize,
+
+    /// Merge glob rules that target the same files (after normalizing their
+    /// globs) into one rule, with each original under a `## <name>` header
+    #[arg(long)]
+    pub consolidate_globs: bool,
+
+    /// Write nothing (exit 10) if the target would lose any rule's activation,
+    /// globs or description, or go over a hard size limit; lists every loss.
+    /// Implies --unsupported-activation error
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ActivationPolicyArg {
+    /// Write them anyway (loaded as always-on) and say so
+    Downgrade,
+    /// Leave them out and list them
+    Skip,
+    /// Abort before writing anything
+    Error,
+}
+
+impl From<ActivationPolicyArg> for polyrc_core::writer::ActivationPolicy {
+    fn from(arg: ActivationPolicyArg) -> Self {
+        match arg {
+            ActivationPolicyArg::Downgrade => Self::Downgrade,
+            ActivationPolicyArg::Skip      => Self::Skip,
+            ActivationPolicyArg::Error     => Self::Error,
+        }
+    }
 }
 
 // ── init ──────────────────────────────────────────────────────────────────────
@@ -140,9 +319,38 @@ pub struct InitArgs {
     #[arg(long)]
     pub repo: Option<String>,
 
+    /// Clone only the latest commit (`--depth 1 --single-branch`); run
+    /// `polyrc store unshallow` later for the full history
+    #[arg(long, requires = "repo")]
+    pub shallow: bool,
+
     /// Path for the local store. Defaults to ~/.polyrc/store
     #[arg(long)]
     pub store: Option<PathBuf>,
+
+    /// After cloning, write the store's user rules into every format's user
+    /// config dir (like `pull-format --user --all`)
+    #[arg(long, requires = "repo")]
+    pub apply: bool,
+
+    /// With --apply, print what would be written without modifying local files
+    #[arg(long, requires = "apply")]
+    pub dry_run: bool,
+}
+
+// ── supported-formats ─────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct SupportedFormatsArgs {
+    /// Show which rule features each format supports (scopes, activations,
+    /// descriptions, layout, size limits)
+    #[arg(long)]
+    pub matrix: bool,
+
+    /// Print every format as JSON: name, aliases, description, user config
+    /// dir, project paths and capabilities
+    #[arg(long)]
+    pub json: bool,
 }
 
 // ── push-format ───────────────────────────────────────────────────────────────
@@ -172,6 +380,25 @@ pub struct PushFormatArgs {
     /// Print what would be written without touching the store
     #[arg(long, default_value_t = false)]
     pub dry_run: bool,
+
+    /// With --dry-run, print the plan as JSON instead of a preview
+    #[arg(long, requires = "dry_run")]
+    pub json: bool,
+
+    /// Also read package configs in subdirectories (packages/api/CLAUDE.md, …),
+    /// stored as rules scoped to `packages/api/**`. Skips hidden dirs,
+    /// node_modules and target
+    #[arg(long, conflicts_with = "user")]
+    pub recursive: bool,
+
+    /// How many directory levels below the root --recursive searches
+    #[arg(long, requires = "recursive", default_value_t = polyrc_core::monorepo::DEFAULT_DEPTH)]
+    pub depth: usize,
+
+    /// Replace every rule in the project, including ones pushed from other
+    /// formats (by default only this format's rules and same-named rules are replaced)
+    #[arg(long)]
+    pub replace: bool,
 }
 
 // ── pull-format ───────────────────────────────────────────────────────────────
@@ -201,6 +428,46 @@ pub struct PullFormatArgs {
     /// Print what would be written without modifying local files
     #[arg(long, default_value_t = false)]
     pub dry_run: bool,
+
+    /// With --dry-run, print the plan as JSON instead of a preview
+    #[arg(long, requires = "dry_run")]
+    pub json: bool,
+
+    /// Replace single-file user configs (Windsurf global_rules.md, ~/.gemini/GEMINI.md)
+    /// instead of merging into their `## ` sections
+    #[arg(long)]
+    pub overwrite: bool,
+
+    /// Overwrite files that were edited since the last pull (see `polyrc status`)
+    #[arg(long)]
+    pub force: bool,
+
+    /// Delete files written by an earlier pull that no store rule produces any more.
+    /// Only files listed in .polyrc.lock and unchanged since are removed
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Fail on the first unparseable rule file instead of warning and skipping it,
+    /// and write nothing (exit 10) if the format would lose any rule's activation,
+    /// globs or description, or go over a hard size limit. Implies
+    /// --unsupported-activation error
+    #[arg(long)]
+    pub strict: bool,
+
+    /// What to do with rules the target format can't activate (e.g. on-demand
+    /// rules for Gemini). Overrides `[write] on_unsupported_activation`
+    #[arg(long, value_enum)]
+    pub unsupported_activation: Option<ActivationPolicyArg>,
+
+    /// Write rules scoped to a plain `<dir>/**` glob into that directory's own
+    /// layout (the reverse of `push-format --recursive`)
+    #[arg(long, conflicts_with = "user")]
+    pub recursive: bool,
+
+    /// Merge glob rules that target the same files (after normalizing their
+    /// globs) into one rule, with each original under a `## <name>` header
+    #[arg(long)]
+    pub consolidate_globs: bool,
 }
 
 // ── sync ──────────────────────────────────────────────────────────────────────
@@ -214,6 +481,19 @@ pub struct SyncArgs {
     /// Only pull remote changes (skip push)
     #[arg(long, conflicts_with = "push_only")]
     pub pull_only: bool,
+
+    /// Print what the last sync pulled (added/updated/deleted rules and
+    /// resolved conflicts) instead of syncing
+    #[arg(long, conflicts_with_all = ["push_only", "pull_only"])]
+    pub report: bool,
+
+    /// Exit with code 8 when the remote is unreachable (default: work offline and exit 0)
+    #[arg(long)]
+    pub strict_network: bool,
+
+    /// Fail on the first unparseable rule file instead of warning and skipping it
+    #[arg(long)]
+    pub strict: bool,
 }
 
 // ── project ───────────────────────────────────────────────────────────────────
@@ -226,13 +506,53 @@ pub struct ProjectArgs {
 
 #[derive(Subcommand, Debug)]
 pub enum ProjectCommands {
+    /// List projects with rule counts, description and last update
+    List,
+
+    /// Create an empty project with metadata (project.toml) and commit
+    Init {
+        /// Project name (normalized to camelCase)
+        name: String,
+
+        /// One-line description of the project
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Formats the project's rules are meant for, comma-separated
+        #[arg(long, value_delimiter = ',')]
+        default_formats: Vec<String>,
+
+        /// Update the metadata of an existing project instead of failing
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show a project's metadata and a summary of its rules
+    Show {
+        /// Project name (or "user")
+        name: String,
+    },
+
     /// Rename a project in the store
-    RenameProject {
+    #[command(alias = "rename-project")]
+    Rename {
         /// Current project name
         old_name: String,
         /// New project name
         new_name: String,
     },
+
+    /// Move a project to .archive/ — excluded from list and pull, still versioned
+    Archive {
+        /// Project name
+        name: String,
+    },
+
+    /// Restore an archived project
+    Unarchive {
+        /// Project name
+        name: String,
+    },
 }
 
 // ── self-update ───────────────────────────────────────────────────────────────
@@ -270,18 +590,39 @@ pub struct ListProjectArgs {
     /// Show full rule content (when a name is given) or rule names per project (when listing all)
     #[arg(long)]
     pub verbose: bool,
+
+    /// Fail on the first unparseable rule file instead of warning and skipping it
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Also list projects that contain no rules
+    #[arg(long)]
+    pub include_empty: bool,
+
+    /// Print a machine-readable JSON listing
+    #[arg(long, conflicts_with = "verbose")]
+    pub json: bool,
 }
 
 // ── push-rule ─────────────────────────────────────────────────────────────────
 
 #[derive(clap::Args, Debug)]
 pub struct PushRuleArgs {
-    /// Name for the rule in the store (e.g. "rust-gitignore")
-    pub name: String,
+    /// Name for the rule in the store (e.g. "rust-gitignore"). Defaults to the
+    /// last path segment of --from-url. With several files, each rule is named
+    /// after its file stem and this becomes an optional prefix ("rust" →
+    /// "rust-errors", "rust-style").
+    pub name: Option<String>,
 
-    /// Read rule content from this file
-    #[arg(long)]
-    pub from_file: Option<std::path::PathBuf>,
+    /// Read rule content from this file; repeat it or pass a quoted glob
+    /// ('snippets/*.md') to push several rules in one commit. Without this or
+    /// --from-url, content is read from piped stdin, or written in your editor
+    #[arg(long, conflicts_with = "from_url")]
+    pub from_file: Vec<String>,
+
+    /// Download rule content from this http(s) URL (GitHub blob and gist pages are fetched raw)
+    #[arg(long, conflicts_with = "from_file")]
+    pub from_url: Option<String>,
 
     /// Store rule in user scope (store/user/)
     #[arg(long, conflicts_with = "project")]
@@ -294,6 +635,18 @@ pub struct PushRuleArgs {
     /// Activation mode of the rule
     #[arg(long, value_enum, default_value = "always")]
     pub activation: ActivationArg,
+
+    /// File pattern the rule applies to (repeatable; required with --activation glob)
+    #[arg(long = "glob", value_name = "PATTERN")]
+    pub globs: Vec<String>,
+
+    /// Short description; ai-decides rules are picked by it
+    #[arg(long)]
+    pub description: Option<String>,
+
+    /// Show which file becomes which rule without storing anything
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -304,6 +657,34 @@ pub enum ActivationArg {
     AiDecides,
 }
 
+impl From<&ActivationArg> for polyrc_core::ir::Activation {
+    fn from(arg: &ActivationArg) -> Self {
+        match arg {
+            ActivationArg::Always    => Self::Always,
+            ActivationArg::OnDemand  => Self::OnDemand,
+            ActivationArg::Glob      => Self::Glob,
+            ActivationArg::AiDecides => Self::AiDecides,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum ScopeArg {
+    User,
+    Project,
+    Path,
+}
+
+impl From<&ScopeArg> for polyrc_core::ir::Scope {
+    fn from(arg: &ScopeArg) -> Self {
+        match arg {
+            ScopeArg::User    => Self::User,
+            ScopeArg::Project => Self::Project,
+            ScopeArg::Path    => Self::Path,
+        }
+    }
+}
+
 // ── pull-rule ─────────────────────────────────────────────────────────────────
 
 #[derive(clap::Args, Debug)]
@@ -311,9 +692,9 @@ pub struct PullRuleArgs {
     /// Name of the rule to pull from the store (e.g. "rust-gitignore")
     pub name: String,
 
-    /// Target format to write the rule as
-    #[arg(long, value_enum, required = true)]
-    pub format: FormatArg,
+    /// Target format to write the rule as; repeat it, or pass "all"
+    #[arg(long, required = true, value_delimiter = ',')]
+    pub format: Vec<String>,
 
     /// Search in user scope (store/user/)
     #[arg(long, conflicts_with = "project")]
@@ -323,13 +704,432 @@ pub struct PullRuleArgs {
     #[arg(long, conflicts_with = "user")]
     pub project: Option<String>,
 
+    /// Read from someone else's store at this git URL (shallow temp clone) instead of yours
+    #[arg(long)]
+    pub from_repo: Option<String>,
+
+    /// With --from-repo: also copy the rule, with a fresh id, into the --project or --user of your store
+    #[arg(long, requires = "from_repo")]
+    pub save: bool,
+
     /// Directory to write the rule file into (default: current dir)
     #[arg(long)]
     pub output: Option<PathBuf>,
 
-    /// Overwrite existing file without asking
+    /// Replace existing files that differ from what the rule would write
     #[arg(long)]
     pub force: bool,
+
+    /// Add the rule as a `## <name>` section of an existing single-file config
+    /// (CLAUDE.md, GEMINI.md, copilot-instructions.md, global_rules.md) instead
+    /// of replacing the file
+    #[arg(long)]
+    pub append: bool,
+}
+
+// ── search ────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct SearchArgs {
+    /// Text to look for (case-insensitive), or a regular expression with --regex
+    pub query: String,
+
+    /// Only search this project ("user" for user scope)
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Also search rule content, showing the matching lines
+    #[arg(long)]
+    pub content: bool,
+
+    /// Treat the query as a regular expression, matched case-sensitively
+    #[arg(long)]
+    pub regex: bool,
+
+    /// Only rules last written by this format
+    #[arg(long, value_enum)]
+    pub format: Option<FormatArg>,
+
+    /// Only rules with this activation
+    #[arg(long, value_enum)]
+    pub activation: Option<ActivationArg>,
+
+    /// Only rules with this scope
+    #[arg(long, value_enum)]
+    pub scope: Option<ScopeArg>,
+
+    /// Print the matches as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+// ── edit-rule ─────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct EditRuleArgs {
+    /// Name of the rule to edit (e.g. "rust-gitignore")
+    pub name: String,
+
+    /// Search in user scope (store/user/)
+    #[arg(long, conflicts_with = "project")]
+    pub user: bool,
+
+    /// Project name to search in (e.g. "myApp")
+    #[arg(long, conflicts_with = "user")]
+    pub project: Option<String>,
+
+    /// Edit the whole stored YAML (activation, globs, …) instead of just the content
+    #[arg(long)]
+    pub raw: bool,
+}
+
+// ── move-rule / copy-rule ─────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct MoveRuleArgs {
+    /// Name of the rule to move or copy (e.g. "rust-gitignore")
+    pub name: String,
+
+    /// Project the rule is in, or "user" for user scope
+    #[arg(long)]
+    pub from: String,
+
+    /// Project to put it in, or "user" for user scope
+    #[arg(long)]
+    pub to: String,
+
+    /// Replace a rule of the same name already in the destination
+    #[arg(long)]
+    pub force: bool,
+}
+
+// ── pack ──────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct StoreArgs {
+    #[command(subcommand)]
+    pub command: StoreCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum StoreCommands {
+    /// Rebuild the rule index and regenerate the store README.md
+    Index,
+
+    /// Remove project directories that contain no rules (and commit)
+    Prune,
+
+    /// Rewrite rule files in canonical form (field order, trailing newline) and commit
+    Normalize,
+
+    /// Fetch the full history of a store cloned with `init --shallow`
+    Unshallow,
+
+    /// Save the store (history, untracked and uncommitted files) as one git bundle
+    Backup {
+        /// Bundle to write (default: ~/polyrc/backups/store-<timestamp>.bundle,
+        /// rotated per `[store] backup_retention`)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Move the store to a new directory and point config.toml at it
+    Move {
+        /// New store location (must not exist, or be empty)
+        path: PathBuf,
+
+        /// Copy the store and leave the original in place
+        #[arg(long)]
+        copy: bool,
+    },
+
+    /// Recreate a store from a backup bundle
+    Restore {
+        /// Bundle written by `polyrc store backup`
+        bundle: PathBuf,
+
+        /// Where to restore (default: the configured store path)
+        #[arg(long)]
+        store: Option<PathBuf>,
+
+        /// Replace an existing store at the target path (it is backed up first)
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+pub struct PackArgs {
+    #[command(subcommand)]
+    pub command: PackCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PackCommands {
+    /// List built-in packs
+    List,
+
+    /// Install a pack into the store (existing rules with the same name are replaced)
+    Install {
+        /// Built-in pack name, path to a pack JSON file or directory, or http(s) URL
+        source: String,
+
+        /// Install into user scope (store/user/)
+        #[arg(long, conflicts_with = "project")]
+        user: bool,
+
+        /// Project to install into
+        #[arg(long, conflicts_with = "user")]
+        project: Option<String>,
+
+        /// Install without asking for confirmation
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+}
+
+// ── hook ──────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct HookArgs {
+    #[command(subcommand)]
+    pub command: HookCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HookCommands {
+    /// Write .git/hooks/pre-commit (or a .pre-commit-config.yaml entry). Safe to rerun.
+    Install {
+        /// Store project the repo's rules belong to
+        #[arg(long)]
+        project: String,
+
+        /// Block commits whose rule files drifted from the store (default)
+        #[arg(long, conflicts_with = "push")]
+        check: bool,
+
+        /// Push rule files to the store on commit
+        #[arg(long, conflicts_with = "check")]
+        push: bool,
+
+        /// Format to cover, or "all"
+        #[arg(long, default_value = "all")]
+        format: String,
+
+        /// Add the hook to .pre-commit-config.yaml instead of .git/hooks
+        #[arg(long)]
+        pre_commit_config: bool,
+
+        /// Replace an existing pre-commit hook that polyrc did not write
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Remove the polyrc pre-commit hook
+    Uninstall {
+        /// Remove the entry from .pre-commit-config.yaml instead of .git/hooks
+        #[arg(long)]
+        pre_commit_config: bool,
+    },
+}
+
+// ── check ─────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct CheckArgs {
+    /// Format to compare (mutually exclusive with --all)
+    #[arg(long, value_enum, required_unless_present = "all", conflicts_with = "all")]
+    pub format: Option<FormatArg>,
+
+    /// Compare every format that has files under --input
+    #[arg(long, conflicts_with = "format")]
+    pub all: bool,
+
+    /// Compare against user-scope rules (store/user/)
+    #[arg(long, conflicts_with = "project")]
+    pub user: bool,
+
+    /// Project in the store to compare against
+    #[arg(long, conflicts_with = "user", required_unless_present = "user")]
+    pub project: Option<String>,
+
+    /// Project root holding the format files
+    #[arg(long, default_value = ".")]
+    pub input: PathBuf,
+
+    /// Store checkout to read (skips config and store discovery)
+    #[arg(long)]
+    pub store: Option<PathBuf>,
+
+    /// Print a machine-readable JSON report
+    #[arg(long)]
+    pub json: bool,
+}
+
+// ── diff ──────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct DiffArgs {
+    /// Format to compare (mutually exclusive with --all)
+    #[arg(long, value_enum, required_unless_present = "all", conflicts_with = "all")]
+    pub format: Option<FormatArg>,
+
+    /// Compare every supported format
+    #[arg(long, conflicts_with = "format")]
+    pub all: bool,
+
+    /// Compare against user-scope rules (store/user/)
+    #[arg(long, conflicts_with = "project")]
+    pub user: bool,
+
+    /// Project in the store to compare against
+    #[arg(long, conflicts_with = "user", required_unless_present = "user")]
+    pub project: Option<String>,
+
+    /// Project root holding the format files
+    #[arg(long, default_value = ".")]
+    pub input: PathBuf,
+
+    /// Which way the changes would flow
+    #[arg(long, value_enum, default_value = "pull")]
+    pub direction: DiffDirection,
+
+    /// Print a machine-readable JSON report
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffDirection {
+    /// From the store to the local files
+    Pull,
+    /// From the local files to the store
+    Push,
+}
+
+// ── verify ────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct VerifyArgs {
+    /// Format to round-trip (mutually exclusive with --all)
+    #[arg(long, value_enum, required_unless_present = "all", conflicts_with = "all")]
+    pub format: Option<FormatArg>,
+
+    /// Round-trip every format that has files under --input
+    #[arg(long, conflicts_with = "format")]
+    pub all: bool,
+
+    /// Project root holding the format files
+    #[arg(long, default_value = ".")]
+    pub input: PathBuf,
+
+    /// Also save and reload the rules through a scratch store, as
+    /// push-format and pull-format would
+    #[arg(long)]
+    pub via_store: bool,
+
+    /// Print a machine-readable JSON report
+    #[arg(long)]
+    pub json: bool,
+}
+
+// ── status ────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct StatusArgs {
+    /// Output root holding .polyrc.lock and the format files
+    #[arg(long, default_value = ".")]
+    pub input: PathBuf,
+
+    /// Instead, show how each format's rules compare with user-scope rules (store/user/)
+    #[arg(long, conflicts_with = "project")]
+    pub user: bool,
+
+    /// Instead, show how each format's rules compare with this store project
+    #[arg(long, conflicts_with = "user")]
+    pub project: Option<String>,
+
+    /// Print a machine-readable JSON report
+    #[arg(long)]
+    pub json: bool,
+}
+
+// ── undo ──────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct UndoArgs {
+    /// Number of polyrc commits to revert, newest first
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub steps: u32,
+
+    /// Revert without asking for confirmation
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+
+    /// Also revert commits already pushed to the remote (run `polyrc sync` afterwards)
+    #[arg(long)]
+    pub allow_pushed: bool,
+}
+
+// ── budget ────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct BudgetArgs {
+    /// Format to measure (default: all formats)
+    #[arg(long, value_enum, conflicts_with = "all")]
+    pub format: Option<FormatArg>,
+
+    /// Measure every format
+    #[arg(long, conflicts_with = "format")]
+    pub all: bool,
+
+    /// Measure user-scope rules (store/user/)
+    #[arg(long, conflicts_with = "project")]
+    pub user: bool,
+
+    /// Project in the store to measure
+    #[arg(long, conflicts_with = "user", required_unless_present = "user")]
+    pub project: Option<String>,
+
+    /// Print a machine-readable JSON report
+    #[arg(long)]
+    pub json: bool,
+
+    /// Exit with code 9 when any format is over a limit
+    #[arg(long)]
+    pub fail_over_limit: bool,
+}
+
+// ── env ───────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct EnvArgs {
+    /// Print a machine-readable JSON report
+    #[arg(long)]
+    pub json: bool,
+
+    /// Show the home directory in paths instead of `~` (credentials stay hidden)
+    #[arg(long)]
+    pub no_redact: bool,
+}
+
+// ── which ─────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct WhichArgs {
+    /// Only this format (default: every format)
+    #[arg(long, value_enum)]
+    pub format: Option<FormatArg>,
+}
+
+// ── doctor ────────────────────────────────────────────────────────────────────
+
+#[derive(clap::Args, Debug)]
+pub struct DoctorArgs {
+    /// Discard and rebuild the store index (.polyrc-index.json)
+    #[arg(long)]
+    pub reindex: bool,
 }
 
 // ── discover ──────────────────────────────────────────────────────────────────
@@ -1,10 +1,16 @@
+use std::path::PathBuf;
+use std::str::FromStr;
 use anyhow::Context;
+use polyrc_core::config::Config;
+use polyrc_core::formats::Format;
+use polyrc_core::ir::Scope;
+use polyrc_core::ops::{self, ConvertOptions};
+use polyrc_core::plan::FormatPlan;
+use polyrc_core::store::Store;
+use polyrc_core::sync;
+use polyrc_core::writer::{ActivationPolicy, WriteOptions};
 use crate::cli::ConvertArgs;
-use crate::config::Config;
-use crate::formats::Format;
-use crate::ir::Scope;
-use crate::store::Store;
-use crate::sync;
+use crate::preview::{print_plan, print_rules_preview, print_unsupported_activations};
 
 pub fn run(args: ConvertArgs) -> anyhow::Result<()> {
     // When --project is specified, route through the store (push-format + pull-format)
@@ -21,29 +27,32 @@ pub fn run(args: ConvertArgs) -> anyhow::Result<()> {
     let to_format = Format::from_str(to_name)
         .with_context(|| format!("invalid --to format '{}'", to_name))?;
 
-    let parser = from_format.parser();
-    let mut rules = parser
-        .parse(&args.input)
-        .with_context(|| format!("failed to parse {} config at {:?}", from_name, args.input))?;
-
-    if let Some(scope_str) = &args.scope {
-        let target_scope = parse_scope(scope_str)?;
-        rules.retain(|r| r.scope == target_scope);
-    }
-
-    if rules.is_empty() {
-        eprintln!("warning: no rules found after parsing");
+    let options = ConvertOptions {
+        scope: args.scope.as_deref().map(parse_scope).transpose()?,
+        dry_run: args.dry_run,
+        output_file: args.output_file.clone(),
+        activation_policy: args.unsupported_activation.map(Into::into),
+        recursive: args.recursive.then_some(args.depth),
+        consolidate_globs: args.consolidate_globs,
+        strict: args.strict,
+    };
+    let report = polyrc_core::convert(&from_format, &to_format, &args.input, &args.output, &options)
+        .with_context(|| format!("failed to convert {} config at {:?} to {}", from_name, args.input, to_name))?;
+
+    print_unsupported_activations(to_name, options.activation_policy, &report.unsupported);
+    if args.json {
+        print_plan("convert", write_plans(&to_format, report.rules, &args)?);
         return Ok(());
     }
-
-    if args.dry_run {
-        println!("Dry run: {} rule(s) from {} → {}", rules.len(), from_name, to_name);
-        print_rules_preview(&rules);
+    if report.rules.is_empty() {
+        if report.unsupported.is_empty() {
+            eprintln!("warning: no rules found after parsing");
+        }
+    } else if report.written {
+        println!("Converted {} rule(s) from {} to {}", report.rules.len(), from_name, to_name);
     } else {
-        let writer = to_format.writer();
-        writer.write(&rules, &args.output)
-            .with_context(|| format!("failed to write {} config to {:?}", to_name, args.output))?;
-        println!("Converted {} rule(s) from {} to {}", rules.len(), from_name, to_name);
+        println!("Dry run: {} rule(s) from {} → {}", report.rules.len(), from_name, to_name);
+        print_rules_preview(&report.rules, 300);
     }
     Ok(())
 }
@@ -51,9 +60,9 @@ pub fn run(args: ConvertArgs) -> anyhow::Result<()> {
 /// Convert via store: push-format source → pull-format target.
 fn run_via_store(args: ConvertArgs, project: String) -> anyhow::Result<()> {
     let config = Config::load()?;
-    let store_path = config.store_path();
-    let store = Store::open(&store_path)
+    let store = Store::open_resolved(&config)
         .context("store not initialized — run `polyrc init` first")?;
+    let store_path = store.path.clone();
 
     let from_name = args.from.as_str();
     let to_name = args.to.as_str();
@@ -64,9 +73,11 @@ fn run_via_store(args: ConvertArgs, project: String) -> anyhow::Result<()> {
         .with_context(|| format!("invalid --to format '{}'", to_name))?;
 
     // Parse source format
-    let parser = from_format.parser();
-    let mut rules = parser.parse(&args.input)
-        .with_context(|| format!("failed to parse {} at {:?}", from_name, args.input))?;
+    let mut rules = match args.recursive {
+        true => polyrc_core::monorepo::parse(&from_format, &args.input, args.depth),
+        false => from_format.parser().parse(&args.input),
+    }
+    .with_context(|| format!("failed to parse {} at {:?}", from_name, args.input))?;
 
     if let Some(scope_str) = &args.scope {
         let s = parse_scope(scope_str)?;
@@ -74,27 +85,49 @@ fn run_via_store(args: ConvertArgs, project: String) -> anyhow::Result<()> {
     }
 
     if rules.is_empty() {
-        eprintln!("warning: no rules found after parsing");
+        if args.json {
+            print_plan("convert", vec![FormatPlan::skipped(&to_format, "no rules found")]);
+        } else {
+            eprintln!("warning: no rules found after parsing");
+        }
         return Ok(());
     }
 
+    // --strict and an `error` policy must fail before the store is touched
+    if args.strict {
+        let mut target_rules = rules.clone();
+        if args.consolidate_globs {
+            target_rules = polyrc_core::globs::consolidate(target_rules);
+        }
+        polyrc_core::loss::ensure_lossless(&to_format, &target_rules)?;
+    }
+    let policy = args.unsupported_activation.map(Into::into).unwrap_or_else(polyrc_core::writer::activation_policy);
+    if policy == ActivationPolicy::Error {
+        ops::apply_activation_policy(&to_format, &mut rules.clone(), policy)?;
+    }
+
+    if args.json {
+        let mut target_rules = rules.clone();
+        if args.consolidate_globs {
+            target_rules = polyrc_core::globs::consolidate(target_rules);
+        }
+        ops::apply_activation_policy(&to_format, &mut target_rules, policy)?;
+        print_plan("convert", write_plans(&to_format, target_rules, &args)?);
+        return Ok(());
+    }
     if args.dry_run {
         println!(
             "Dry run: {} rule(s) from {} → store/{} → {}",
             rules.len(), from_name, project, to_name
         );
-        print_rules_preview(&rules);
+        print_rules_preview(&rules, 300);
         return Ok(());
     }
 
     // Push to store
     let stored = store.save_rules(Some(&project), &rules, from_name)?;
-    let msg = format!(
-        "convert from {} ({})",
-        from_name,
-        chrono::Utc::now().format("%Y-%m-%d")
-    );
-    sync::git_commit(&store_path, &msg).context("git commit failed")?;
+    let detail = format!("{} ({})", from_name, chrono::Utc::now().format("%Y-%m-%d"));
+    sync::git_commit(&store_path, sync::StoreCommit::Convert, &detail).context("git commit failed")?;
 
     // Pull from store as target format
     let mut stored_rules = stored;
@@ -102,9 +135,16 @@ fn run_via_store(args: ConvertArgs, project: String) -> anyhow::Result<()> {
         stored_rules.retain(|r| r.scope == parse_scope(scope_str).unwrap_or(Scope::Project));
     }
 
-    let writer = to_format.writer();
-    writer.write(&stored_rules, &args.output)
-        .with_context(|| format!("failed to write {} to {:?}", to_name, args.output))?;
+    if args.consolidate_globs {
+        stored_rules = polyrc_core::globs::consolidate(stored_rules);
+    }
+    let unsupported = ops::apply_activation_policy(&to_format, &mut stored_rules, policy)?;
+    print_unsupported_activations(to_name, Some(policy), &unsupported);
+    let write_options = WriteOptions { output_file: args.output_file.clone() };
+    for (output, rules) in targets(stored_rules.clone(), &args) {
+        to_format.writer().write_with(&rules, &output, &write_options)
+            .with_context(|| format!("failed to write {} to {:?}", to_name, output))?;
+    }
 
     println!(
         "Converted {} rule(s): {} → store/{} → {}",
@@ -113,6 +153,36 @@ fn run_via_store(args: ConvertArgs, project: String) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// `convert --dry-run --json` entries for the target format: one per output
+/// directory, which with --recursive includes each package.
+fn write_plans(to_format: &Format, rules: Vec<polyrc_core::ir::Rule>, args: &ConvertArgs) -> anyhow::Result<Vec<FormatPlan>> {
+    if rules.is_empty() {
+        return Ok(vec![FormatPlan::skipped(to_format, "no rules found")]);
+    }
+    let write_options = WriteOptions { output_file: args.output_file.clone() };
+    targets(rules, args)
+        .into_iter()
+        .map(|(output, rules)| {
+            Ok(FormatPlan {
+                files: Some(polyrc_core::plan::plan_writes(to_format, &rules, &output, &write_options)?),
+                output: Some(output.display().to_string()),
+                ..FormatPlan::new(to_format)
+            })
+        })
+        .collect()
+}
+
+/// Where to write `rules`: all of them to --output, or with --recursive,
+/// package rules into their package under it.
+fn targets(rules: Vec<polyrc_core::ir::Rule>, args: &ConvertArgs) -> Vec<(PathBuf, Vec<polyrc_core::ir::Rule>)> {
+    if !args.recursive {
+        return vec![(args.output.clone(), rules)];
+    }
+    let (root, packages) = polyrc_core::monorepo::split(rules);
+    let root = (!root.is_empty()).then(|| (args.output.clone(), root));
+    root.into_iter().chain(packages.into_iter().map(|(package, rules)| (args.output.join(package), rules))).collect()
+}
+
 fn parse_scope(s: &str) -> anyhow::Result<Scope> {
     match s.to_lowercase().as_str() {
         "user" => Ok(Scope::User),
@@ -121,14 +191,3 @@ fn parse_scope(s: &str) -> anyhow::Result<Scope> {
         other => anyhow::bail!("unknown scope '{}': expected user, project, or path", other),
     }
 }
-
-fn print_rules_preview(rules: &[crate::ir::Rule]) {
-    for (i, rule) in rules.iter().enumerate() {
-        println!("\n--- Rule {} ({:?}/{:?}) ---", i + 1, rule.scope, rule.activation);
-        if let Some(n) = &rule.name { println!("name: {}", n); }
-        if let Some(d) = &rule.description { println!("description: {}", d); }
-        let preview = rule.content.len().min(300);
-        println!("{}", &rule.content[..preview]);
-        if rule.content.len() > 300 { println!("... ({} chars total)", rule.content.len()); }
-    }
-}
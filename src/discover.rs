@@ -1,9 +1,10 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use anyhow::Result;
 
 use crate::cli::DiscoverArgs;
-use crate::formats::Format;
+use polyrc_core::formats::Format;
 
 // ── types ─────────────────────────────────────────────────────────────────────
 
@@ -26,15 +27,18 @@ pub enum UserLocation {
 // ── per-format user locations ─────────────────────────────────────────────────
 
 /// Returns the canonical user-level config locations for `fmt` on the current OS.
+/// The resolved user config dir of a format that has one.
+fn user_dir(fmt: &Format) -> PathBuf {
+    fmt.user_input_dir().expect("format has a user config dir")
+}
+
 pub fn user_locations(fmt: &Format) -> Vec<UserLocation> {
-    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("~"));
+    let home = polyrc_core::config::home_dir();
 
     match fmt {
         Format::Claude => {
-            // The config dir can be overridden via CLAUDE_CONFIG_DIR; fall back to ~/.claude
-            let claude_dir = std::env::var("CLAUDE_CONFIG_DIR")
-                .map(PathBuf::from)
-                .unwrap_or_else(|_| home.join(".claude"));
+            // POLYRC_CLAUDE_USER_DIR, [user_dirs], CLAUDE_CONFIG_DIR, then ~/.claude
+            let claude_dir = user_dir(fmt);
 
             // Managed/system-level settings path varies by OS
             #[cfg(target_os = "macos")]
@@ -88,25 +92,25 @@ pub fn user_locations(fmt: &Format) -> Vec<UserLocation> {
         }
 
         Format::Gemini => vec![UserLocation::File {
-            path: home.join(".gemini/GEMINI.md"),
+            path: user_dir(fmt).join("GEMINI.md"),
             note: None,
         }],
 
         Format::Antigravity => vec![UserLocation::Dir {
-            path: home.join(".gemini/antigravity/rules"),
+            path: user_dir(fmt).join("rules"),
             extension: "md",
         }],
 
         Format::Windsurf => vec![UserLocation::File {
-            path: home.join(".codeium/windsurf/memories/global_rules.md"),
+            path: user_dir(fmt).join(polyrc_core::formats::windsurf::GLOBAL_RULES_FILE),
             note: None,
         }],
 
         Format::Cursor => {
             // User rules live inside the VS Code–style settings JSON, not a standalone file.
             let settings = dirs::config_dir()
-                .unwrap_or_else(|| home.join("Library/Application Support"))
-                .join("Cursor/User/settings.json");
+                .unwrap_or_else(|| home.join("Library").join("Application Support"))
+                .join("Cursor").join("User").join("settings.json");
             vec![UserLocation::File {
                 path: settings,
                 note: Some("user rules embedded in JSON — edit via Cursor Settings UI"),
@@ -116,6 +120,30 @@ pub fn user_locations(fmt: &Format) -> Vec<UserLocation> {
         Format::Copilot => vec![UserLocation::WebUi {
             hint: "github.com → Settings → Copilot → Personal instructions",
         }],
+
+        Format::Aider => vec![UserLocation::File {
+            path: home.join(".aider.conf.yml"),
+            note: Some("aider settings — `read:` lists the conventions files it loads"),
+        }],
+
+        Format::AgentsMd => vec![UserLocation::File {
+            path: home.join(".codex").join("AGENTS.md"),
+            note: Some("Codex user instructions — AGENTS.md itself is per project"),
+        }],
+
+        Format::AmazonQ | Format::Zed | Format::Warp | Format::OpenHands | Format::Kiro | Format::Qodo => vec![],
+
+        // The layout from formats.toml, under its user_dir
+        Format::Custom(c) if c.user_dir.is_some() => {
+            let dir = user_dir(fmt);
+            let files = fmt.single_files().iter().map(|(file, _)| UserLocation::File { path: dir.join(file), note: None });
+            let dirs = fmt.rule_dirs().iter().map(|d| UserLocation::Dir {
+                path: dir.join(d.path),
+                extension: d.suffix.rsplit('.').next().unwrap_or(d.suffix),
+            });
+            files.chain(dirs).collect()
+        }
+        Format::Custom(_) => vec![],
     }
 }
 
@@ -184,7 +212,7 @@ fn print_location(loc: &UserLocation) {
         }
 
         UserLocation::Dir { path, extension } => {
-            let display = format!("{}/", tilde(path));
+            let display = format!("{}{}", tilde(path), std::path::MAIN_SEPARATOR);
             if path.exists() {
                 match dir_files(path, extension) {
                     Ok(files) if files.is_empty() => {
@@ -212,7 +240,7 @@ fn print_location(loc: &UserLocation) {
         }
 
         UserLocation::SkillDir { path } => {
-            let display = format!("{}/", tilde(path));
+            let display = format!("{}{}", tilde(path), std::path::MAIN_SEPARATOR);
             if path.exists() {
                 match skill_subdirs(path) {
                     Ok(skills) if skills.is_empty() => {
@@ -243,19 +271,18 @@ fn print_location(loc: &UserLocation) {
 }
 
 /// Replace the home directory prefix with `~`.
-fn tilde(path: &PathBuf) -> String {
-    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("~"));
-    match path.strip_prefix(&home) {
-        Ok(rel) => format!("~/{}", rel.display()),
+fn tilde(path: &Path) -> String {
+    match path.strip_prefix(polyrc_core::config::home_dir()) {
+        Ok(rel) => Path::new("~").join(rel).display().to_string(),
         Err(_) => path.display().to_string(),
     }
 }
 
-fn line_count(path: &PathBuf) -> Result<usize> {
+fn line_count(path: &Path) -> Result<usize> {
     Ok(std::fs::read_to_string(path)?.lines().count())
 }
 
-fn dir_files(dir: &PathBuf, ext: &str) -> Result<Vec<PathBuf>> {
+fn dir_files(dir: &Path, ext: &str) -> Result<Vec<PathBuf>> {
     let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
         .filter_map(|e| e.ok())
         .map(|e| e.path())
@@ -266,7 +293,7 @@ fn dir_files(dir: &PathBuf, ext: &str) -> Result<Vec<PathBuf>> {
 }
 
 /// Returns names of subdirectories that contain a SKILL.md file.
-fn skill_subdirs(dir: &PathBuf) -> Result<Vec<String>> {
+fn skill_subdirs(dir: &Path) -> Result<Vec<String>> {
     let mut names: Vec<String> = std::fs::read_dir(dir)?
         .filter_map(|e| e.ok())
         .filter(|e| e.path().is_dir())
@@ -1,21 +1,114 @@
 use anyhow::Context;
 use clap::Parser as ClapParser;
 
+use polyrc_core::{config, formats, parser, writer};
+
 mod cli;
-mod config;
 mod convert;
 mod discover;
-mod error;
+mod pager;
 mod self_update;
-mod formats;
-mod ir;
-mod parser;
-mod store;
-mod sync;
-mod writer;
-
-fn main() -> anyhow::Result<()> {
+mod preview;
+
+/// Remind on stderr to sync when the store's last sync is older than
+/// `[sync] stale_after_days`.
+fn print_stale_notice(config: Option<&config::Config>) {
+    let default = config::Config::default();
+    let config = config.unwrap_or(&default);
+    let env = std::env::var(polyrc_core::store::STORE_ENV).ok();
+    let cwd = std::env::current_dir().unwrap_or_else(|_| ".".into());
+    let store = polyrc_core::store::resolve_store_path(env.as_deref(), config, &cwd);
+    if store.is_dir()
+        && let Some(notice) = polyrc_core::sync::stale_notice(&store, config.sync_stale_after_days())
+    {
+        eprintln!("note: {notice}");
+    }
+}
+
+fn main() {
+    // The library raises its warnings and notes; show them as they come
+    polyrc_core::notice::set_handler(Some(|notice| eprintln!("{notice}")));
+    // Before parsing, so `--format` accepts the custom names
+    if let Err(e) = formats::custom::load_and_register() {
+        eprintln!("warning: custom formats not loaded: {e}");
+    }
     let args = cli::Cli::parse();
+    let json = args.command.json();
+    if let Err(e) = run(args) {
+        polyrc_core::debug_log!("error: {e:#}");
+        eprintln!("Error: {e:?}");
+        let code = exit_code(&e);
+        if json {
+            println!("{}", serde_json::to_string_pretty(&error_json(&e, code)).expect("error serializes"));
+        }
+        std::process::exit(code);
+    }
+}
+
+/// The [`polyrc_core::PolyrcError`] behind `e`, if any.
+fn polyrc_error(e: &anyhow::Error) -> Option<&polyrc_core::PolyrcError> {
+    e.chain().find_map(|cause| cause.downcast_ref::<polyrc_core::PolyrcError>())
+}
+
+fn exit_code(e: &anyhow::Error) -> i32 {
+    polyrc_error(e).map_or(polyrc_core::error::EXIT_ERROR, polyrc_core::PolyrcError::exit_code)
+}
+
+/// `{"error": {"kind", "message", "exit_code"}}` for `--json` output. `kind` is
+/// `error` when the failure didn't come from polyrc itself.
+fn error_json(e: &anyhow::Error, exit_code: i32) -> serde_json::Value {
+    let kind = polyrc_error(e).map_or("error", polyrc_core::PolyrcError::kind);
+    serde_json::json!({ "error": { "kind": kind, "message": format!("{e:#}"), "exit_code": exit_code } })
+}
+
+fn run(args: cli::Cli) -> anyhow::Result<()> {
+    let config = config::Config::load().ok();
+    let log_env = std::env::var(polyrc_core::logfile::LOG_FILE_ENV).ok();
+    if let Some(file) = config.as_ref().unwrap_or(&config::Config::default()).log_file(args.debug, log_env.as_deref()) {
+        polyrc_core::logfile::init(file);
+        let argv: Vec<String> = std::env::args().collect();
+        polyrc_core::debug_log!("polyrc {} ({}): {}", env!("CARGO_PKG_VERSION"), polyrc_core::diagnostics::GIT_SHA, argv.join(" "));
+    }
+    let max_file_size = args
+        .max_file_size
+        .or_else(|| config.as_ref().and_then(|c| c.max_file_size))
+        .unwrap_or(parser::DEFAULT_MAX_FILE_SIZE);
+    parser::set_max_file_size(max_file_size);
+    if let Some(policy) = config.as_ref().and_then(|c| c.invalid_utf8) {
+        parser::set_invalid_utf8_policy(policy);
+    }
+    if let Some(derive) = config.as_ref().and_then(|c| c.write.derive_descriptions) {
+        writer::set_derive_descriptions(derive);
+    }
+    if let Some(policy) = config.as_ref().and_then(|c| c.write.on_unsupported_activation) {
+        writer::set_activation_policy(policy);
+    }
+    let mut format_options = match &config {
+        Some(c) => c.format_options().context("invalid [formats] option in config.toml")?,
+        None => Default::default(),
+    };
+    for option in &args.format_option {
+        format_options.entry(option.format.clone()).or_default().set(option.key.clone(), option.value.clone());
+    }
+    if args.no_resolve_imports {
+        format_options
+            .entry(formats::Format::Claude.name().to_string())
+            .or_default()
+            .set(formats::claude::RESOLVE_IMPORTS, false.into());
+    }
+    if args.overwrite_settings {
+        format_options
+            .entry(formats::Format::Claude.name().to_string())
+            .or_default()
+            .set(formats::claude::OVERWRITE_SETTINGS, true.into());
+    }
+    formats::options::set_configured(format_options);
+    if let Some(c) = &config {
+        formats::set_user_dirs(c.user_dirs().context("invalid [user_dirs] in config.toml")?);
+    }
+    if args.command.uses_store() && !args.command.json() {
+        print_stale_notice(config.as_ref());
+    }
     match args.command {
         cli::Commands::Convert(a) => convert::run(a).context("conversion failed")?,
         cli::Commands::Discover(a) => discover::run(a).context("discover failed")?,
@@ -23,19 +116,82 @@ fn main() -> anyhow::Result<()> {
             self_update::run(a.check_only, a.skip_checksum).context("self-update failed")?
         }
         cli::Commands::SetEditor(a) => commands::set_editor(a)?,
-        cli::Commands::SupportedFormats => {
-            for fmt in formats::Format::all() {
-                println!("{:<15} {}", fmt.name(), fmt.description());
+        cli::Commands::SupportedFormats(a) => {
+            use polyrc_core::formats::capabilities;
+            if a.json {
+                let info: Vec<formats::FormatInfo> = formats::Format::all().iter().map(Into::into).collect();
+                println!("{}", serde_json::to_string_pretty(&info).expect("formats serialize"));
+            } else if a.matrix {
+                print!("{}", capabilities::render_matrix(&capabilities::matrix()));
+            } else {
+                for fmt in formats::Format::all() {
+                    let custom = if matches!(fmt, formats::Format::Custom(_)) { " (custom)" } else { "" };
+                    println!("{:<15} {}{custom}", fmt.name(), fmt.description());
+                }
             }
         }
         cli::Commands::Init(a) => commands::init(a)?,
         cli::Commands::PushFormat(a) => commands::push_format(a)?,
         cli::Commands::PullFormat(a) => commands::pull_format(a)?,
-        cli::Commands::Sync(a) => commands::sync(a)?,
+        cli::Commands::Sync(a) => {
+            let code = commands::sync(a)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
         cli::Commands::ListProject(a) => commands::list_project(a)?,
         cli::Commands::PushRule(a) => commands::push_rule(a)?,
         cli::Commands::PullRule(a) => commands::pull_rule(a)?,
+        cli::Commands::Search(a) => commands::search(a)?,
+        cli::Commands::EditRule(a) => commands::edit_rule(a)?,
+        cli::Commands::MoveRule(a) => commands::move_rule(a, false)?,
+        cli::Commands::CopyRule(a) => commands::move_rule(a, true)?,
         cli::Commands::Project(a) => commands::project(a)?,
+        cli::Commands::Doctor(a) => commands::doctor(a)?,
+        cli::Commands::Pack(a) => commands::pack(a)?,
+        cli::Commands::Store(a) => commands::store(a)?,
+        cli::Commands::Undo(a) => commands::undo(a)?,
+        cli::Commands::Hook(a) => commands::hook(a)?,
+        cli::Commands::Check(a) => std::process::exit(commands::check(a)),
+        cli::Commands::Diff(a) => {
+            let code = commands::diff(a)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        cli::Commands::Status(a) => commands::status(a)?,
+        cli::Commands::Verify(a) => {
+            let code = commands::verify(a)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        cli::Commands::Env(a) => {
+            let report = polyrc_core::diagnostics::collect(!a.no_redact);
+            if a.json {
+                println!("{}", serde_json::to_string_pretty(&report).expect("report serializes"));
+            } else {
+                print!("{}", report.render());
+            }
+        }
+        cli::Commands::Which(a) => {
+            let selected = match &a.format {
+                Some(f) => vec![f.0.clone()],
+                None => formats::Format::all().to_vec(),
+            };
+            for fmt in selected {
+                match fmt.resolve_user_dir() {
+                    Some((dir, source)) => println!("{:<12} {}  ({source})", fmt.name(), dir.display()),
+                    None => println!("{:<12} -  (no local user config)", fmt.name()),
+                }
+            }
+        }
+        cli::Commands::Budget(a) => {
+            let code = commands::budget(a)?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
         cli::Commands::Completion { shell, install } => {
             run_completion(&shell, install)
                 .with_context(|| format!("failed to generate completion for '{shell}'"))?;
@@ -78,48 +234,48 @@ fn run_completion(shell_str: &str, install: bool) -> anyhow::Result<()> {
 }
 
 fn completion_install_path(shell: clap_complete::Shell) -> anyhow::Result<(std::path::PathBuf, Option<String>)> {
-    use clap_complete::Shell;
-    use std::path::PathBuf;
+    let home = config::home_dir();
+    let documents = dirs::document_dir().unwrap_or_else(|| home.join("Documents"));
+    completion_install_path_for(shell, &home, &documents, cfg!(windows))
+}
 
-    let home = std::env::var("HOME").unwrap_or_else(|_| "~".to_string());
+/// Pure path logic behind `completion_install_path`, parameterised so both the
+/// Unix and Windows layouts can be tested on any host.
+fn completion_install_path_for(
+    shell: clap_complete::Shell,
+    home: &std::path::Path,
+    documents: &std::path::Path,
+    windows: bool,
+) -> anyhow::Result<(std::path::PathBuf, Option<String>)> {
+    use clap_complete::Shell;
 
-    let (path, msg): (PathBuf, Option<String>) = match shell {
+    let (path, msg) = match shell {
+        // Git Bash on Windows ships without bash-completion; source the file directly.
+        Shell::Bash if windows => (
+            home.join(".bash_completion.d").join("polyrc"),
+            Some("Add to your ~/.bashrc:\n  source ~/.bash_completion.d/polyrc".to_string()),
+        ),
         Shell::Bash => (
-            PathBuf::from(format!(
-                "{}/.local/share/bash-completion/completions/polyrc",
-                home
-            )),
+            home.join(".local").join("share").join("bash-completion").join("completions").join("polyrc"),
             Some(
                 "Ensure bash-completion is installed and sourced in your ~/.bashrc".to_string(),
             ),
         ),
         Shell::Zsh => (
-            PathBuf::from(format!("{}/.zsh/completions/_polyrc", home)),
+            home.join(".zsh").join("completions").join("_polyrc"),
             Some(
                 "Ensure ~/.zsh/completions is in your fpath — add to ~/.zshrc:\n  fpath=(~/.zsh/completions $fpath)\n  autoload -Uz compinit && compinit"
                     .to_string(),
             ),
         ),
         Shell::Fish => (
-            PathBuf::from(format!(
-                "{}/.config/fish/completions/polyrc.fish",
-                home
-            )),
+            home.join(".config").join("fish").join("completions").join("polyrc.fish"),
             None,
         ),
         Shell::PowerShell => {
-            let userprofile =
-                std::env::var("USERPROFILE").unwrap_or_else(|_| home.clone());
-            (
-                PathBuf::from(format!(
-                    r"{}\Documents\PowerShell\Completions\polyrc.ps1",
-                    userprofile
-                )),
-                Some(
-                    "Add to your $PROFILE:\n  . \"$env:USERPROFILE\\Documents\\PowerShell\\Completions\\polyrc.ps1\""
-                        .to_string(),
-                ),
-            )
+            let path = documents.join("PowerShell").join("Completions").join("polyrc.ps1");
+            let msg = format!("Add to your $PROFILE:\n  . \"{}\"", path.display());
+            (path, Some(msg))
         }
         _ => anyhow::bail!("Unsupported shell: {:?}", shell),
     };
@@ -127,20 +283,64 @@ fn completion_install_path(shell: clap_complete::Shell) -> anyhow::Result<(std::
     Ok((path, msg))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap_complete::Shell;
+    use std::path::Path;
+
+    #[test]
+    fn completion_paths_unix() {
+        let home = Path::new("/home/me");
+        let docs = home.join("Documents");
+        let (bash, _) = completion_install_path_for(Shell::Bash, home, &docs, false).unwrap();
+        assert!(bash.ends_with(Path::new(".local/share/bash-completion/completions/polyrc")));
+        let (zsh, _) = completion_install_path_for(Shell::Zsh, home, &docs, false).unwrap();
+        assert_eq!(zsh, home.join(".zsh").join("completions").join("_polyrc"));
+    }
+
+    #[test]
+    fn completion_paths_windows_layout() {
+        let home = Path::new("/users/me");
+        let docs = Path::new("/users/me/OneDrive/Documents");
+        let (bash, msg) = completion_install_path_for(Shell::Bash, home, docs, true).unwrap();
+        assert_eq!(bash, home.join(".bash_completion.d").join("polyrc"));
+        assert!(msg.unwrap().contains("source"));
+        let (ps, msg) = completion_install_path_for(Shell::PowerShell, home, docs, true).unwrap();
+        assert_eq!(ps, docs.join("PowerShell").join("Completions").join("polyrc.ps1"));
+        assert!(msg.unwrap().contains(&ps.display().to_string()));
+    }
+}
+
 mod commands {
+    use std::str::FromStr;
     use anyhow::Context;
-    use crate::cli::{ActivationArg, InitArgs, ListProjectArgs, ProjectArgs, ProjectCommands, PullFormatArgs, PullRuleArgs, PushFormatArgs, PushRuleArgs, SetEditorArgs, SyncArgs};
-    use crate::config::Config;
-    use crate::formats::Format;
-    use crate::ir::Scope;
-    use crate::store::{self, Store};
-    use crate::sync;
+    use polyrc_core::config::Config;
+    use polyrc_core::error::{EXIT_CHECK_ERROR, EXIT_DRIFT, EXIT_OFFLINE, EXIT_OVER_BUDGET};
+    use polyrc_core::formats::Format;
+    use polyrc_core::ir::{Rule, Scope};
+    use polyrc_core::ops::{self, PullOutcome, PushOptions, PushOutcome};
+    use polyrc_core::store::{self, SaveMode, Store};
+    use polyrc_core::suggest;
+    use polyrc_core::sync::{self, StoreCommit};
+    use crate::cli::{ActivationArg, BudgetArgs, CheckArgs, DiffArgs, DoctorArgs, EditRuleArgs, MoveRuleArgs, HookArgs, HookCommands, PackArgs, PackCommands, StoreArgs, StoreCommands, InitArgs, ListProjectArgs, ProjectArgs, ProjectCommands, PullFormatArgs, PullRuleArgs, PushFormatArgs, PushRuleArgs, SearchArgs, SetEditorArgs, StatusArgs, SyncArgs, UndoArgs, VerifyArgs};
+    use crate::preview::{absolute_time, print_plan, print_rules_preview, print_unsupported_activations, relative_time};
+    use polyrc_core::plan::FormatPlan;
 
     /// Normalize a project name to camelCase, stripping invalid characters.
-    /// Rejects empty results and the reserved name "user".
+    /// Rejects non-ASCII input, empty results and the reserved name "user".
     fn normalize_project_name(input: &str) -> anyhow::Result<String> {
+        // Non-ASCII names become directory names that some filesystems mangle
+        // (NFC vs NFD, case folding), so reject rather than guess a transliteration.
+        if let Some(c) = input.chars().find(|c| !c.is_ascii()) {
+            anyhow::bail!(
+                "project name '{}' contains non-ASCII character '{}'; use ASCII letters, digits, '-' or '_'",
+                input, c
+            );
+        }
+
         let segments: Vec<&str> = input
-            .split(|c: char| matches!(c, ' ' | '\t' | '_' | '-' | '/' | '\\' | '.'))
+            .split([' ', '\t', '_', '-', '/', '\\', '.'])
             .filter(|s| !s.is_empty())
             .collect();
 
@@ -181,50 +381,122 @@ mod commands {
 
     pub fn init(args: InitArgs) -> anyhow::Result<()> {
         let mut config = Config::load()?;
-        let store_path = args.store.unwrap_or_else(crate::config::default_store_path);
+        let store_path = args.store.unwrap_or_else(polyrc_core::config::default_store_path);
 
         if let Some(url) = &args.repo {
             println!("Cloning {} → {}", url, store_path.display());
-            sync::git_clone(url, &store_path)
+            sync::git_clone(url, &store_path, args.shallow)
                 .with_context(|| format!("failed to clone {url}"))?;
             store::init_git(&store_path)?;
+            sync::record_sync(&store_path)?;
             config.init_store_config(Some(url));
         } else {
             println!("Initializing local store at {}", store_path.display());
+            let fresh = !store_path.join(".git").exists();
             store::init_git(&store_path)?;
+            // The store's own files get a commit of their own, so undoing the
+            // first push can't take them with it
+            if fresh {
+                sync::git_commit(&store_path, StoreCommit::InitStore, "").context("git commit failed")?;
+            }
             config.init_store_config(None);
         }
 
         config.store.path = Some(store_path.to_string_lossy().to_string());
         config.save().context("failed to save config")?;
         println!("Store ready at {}", store_path.display());
+        if args.apply {
+            apply_user_rules(&config, args.dry_run)?;
+        }
+        Ok(())
+    }
+
+    /// `init --apply`: pull the user rules into every format, as
+    /// `pull-format --user --all` would. The store stays initialized if a
+    /// format fails; the failures are reported and turned into an error.
+    fn apply_user_rules(config: &Config, dry_run: bool) -> anyhow::Result<()> {
+        let store = Store::open_resolved(config).context("store not initialized — run `polyrc init` first")?;
+        let rules = load_project_rules(&store, store::USER_PROJECT, false)?;
+        if rules.is_empty() {
+            println!("No user rules in the store — nothing to apply.");
+            return Ok(());
+        }
+        println!("Applying user rules:");
+        let args = PullFormatArgs {
+            format: None,
+            all: true,
+            user: true,
+            project: None,
+            output: std::path::PathBuf::from("."),
+            dry_run,
+            json: false,
+            overwrite: false,
+            force: false,
+            prune: false,
+            strict: false,
+            unsupported_activation: None,
+            recursive: false,
+            consolidate_globs: false,
+        };
+        let mut failed = vec![];
+        for fmt in Format::all() {
+            if let Err(e) = pull_one(&rules, fmt, &args, true) {
+                eprintln!("  {} — error: {:#}", fmt.name(), e);
+                failed.push(fmt.name());
+            }
+        }
+        if !failed.is_empty() {
+            anyhow::bail!("store initialized, but applying user rules failed for {}", failed.join(", "));
+        }
         Ok(())
     }
 
     pub fn push_format(args: PushFormatArgs) -> anyhow::Result<()> {
         let config = Config::load()?;
-        let store_path = config.store_path();
-        let store = Store::open(&store_path).context("store not initialized — run `polyrc init` first")?;
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+        let store_path = store.path.clone();
 
         // Determine routing
         let (user_mode, project_key) = resolve_routing(args.user, args.project.as_deref())?;
+        let project_key = match args.project.as_deref() {
+            Some(raw) if !user_mode => check_project_collision(&store, raw, project_key)?,
+            _ => project_key,
+        };
+        let options = PushOptions {
+            user: user_mode,
+            dry_run: args.dry_run,
+            recursive: args.recursive.then_some(args.depth),
+            mode: if args.replace { SaveMode::Replace } else { SaveMode::Merge },
+        };
+
+        if args.json {
+            let plans = selected_formats(args.format.as_ref(), args.all)?
+                .iter()
+                .map(|fmt| {
+                    push_plan(&store, fmt, &args.input, &project_key, &options)
+                        .unwrap_or_else(|e| FormatPlan::error(fmt, format!("{e:#}")))
+                })
+                .collect();
+            print_plan("push-format", plans);
+            return Ok(());
+        }
 
         if args.all {
             let mut pushed_names: Vec<&str> = vec![];
             for fmt in Format::all() {
-                match push_one(&store, &fmt, &args.input, user_mode, args.dry_run, &project_key) {
+                match push_one(&store, fmt, &args.input, &project_key, &options) {
                     Ok(0) => {} // push_one already printed the reason
                     Ok(_) => pushed_names.push(fmt.name()),
                     Err(e) => eprintln!("  {} — error: {:#}", fmt.name(), e),
                 }
             }
             if !args.dry_run && !pushed_names.is_empty() {
-                let msg = format!(
-                    "push-format --all ({}) ({})",
+                let detail = format!(
+                    "--all ({}) ({})",
                     pushed_names.join(", "),
                     chrono::Utc::now().format("%Y-%m-%d")
                 );
-                sync::git_commit(&store_path, &msg).context("git commit failed")?;
+                let msg = sync::git_commit(&store_path, StoreCommit::PushFormat, &detail).context("git commit failed")?;
                 println!("Committed: {}", msg);
             }
         } else {
@@ -232,161 +504,283 @@ mod commands {
             let fmt_name = fmt_arg.as_str();
             let fmt = Format::from_str(fmt_name)
                 .with_context(|| format!("unknown format '{}'", fmt_name))?;
-            let n = push_one(&store, &fmt, &args.input, user_mode, args.dry_run, &project_key)?;
+            let n = push_one(&store, &fmt, &args.input, &project_key, &options)?;
             if n > 0 && !args.dry_run {
-                let msg = format!(
-                    "push-format from {} ({})",
+                let detail = format!(
+                    "from {} ({})",
                     fmt_name,
                     chrono::Utc::now().format("%Y-%m-%d")
                 );
-                sync::git_commit(&store_path, &msg).context("git commit failed")?;
+                let msg = sync::git_commit(&store_path, StoreCommit::PushFormat, &detail).context("git commit failed")?;
                 println!("Committed: {}", msg);
             }
         }
         Ok(())
     }
 
-    /// Push one format into the store. Returns the number of rules stored (0 = nothing to push).
-    fn push_one(
-        store: &Store,
-        fmt: &Format,
-        input: &std::path::Path,
-        user: bool,
-        dry_run: bool,
-        project_key: &str,
-    ) -> anyhow::Result<usize> {
-        let fmt_name = fmt.name();
-
-        // Auto-detect user input dir when --user and --input is the default "."
-        let user_dir;
-        let effective_input: &std::path::Path = if user && input == std::path::Path::new(".") {
-            match fmt.user_input_dir() {
-                Some(dir) => { user_dir = dir; &user_dir }
-                None => {
-                    println!("  {} — skipped (no local user-level config; use --input to specify)", fmt_name);
-                    return Ok(0);
-                }
-            }
-        } else {
-            input
-        };
+    /// The formats named by `--format`, or all of them with `--all`.
+    fn selected_formats(format: Option<&crate::cli::FormatArg>, all: bool) -> anyhow::Result<Vec<Format>> {
+        if all {
+            return Ok(Format::all().to_vec());
+        }
+        let name = format.expect("--format is required without --all").as_str();
+        Ok(vec![Format::from_str(name).with_context(|| format!("unknown format '{}'", name))?])
+    }
 
-        let parser = fmt.parser();
-        let mut rules = parser.parse(effective_input)
-            .with_context(|| format!("failed to parse {} at {}", fmt_name, effective_input.display()))?;
+    /// `push-format --dry-run --json` entry for one format.
+    fn push_plan(store: &Store, fmt: &Format, input: &std::path::Path, project_key: &str, options: &PushOptions) -> anyhow::Result<FormatPlan> {
+        let options = PushOptions { dry_run: true, ..*options };
+        Ok(match ops::push_format(store, fmt, input, project_key, &options)? {
+            PushOutcome::NoUserConfig => FormatPlan::skipped(fmt, "no local user-level config"),
+            PushOutcome::NoRules => FormatPlan::skipped(fmt, "no rules found"),
+            PushOutcome::DryRun(rules) | PushOutcome::Stored(rules) => FormatPlan {
+                rules: Some(polyrc_core::plan::plan_push(store, project_key, &rules, fmt.name(), options.mode)?),
+                ..FormatPlan::new(fmt)
+            },
+        })
+    }
 
-        // When using --user, filter to user-scope rules only
-        if user {
-            rules.retain(|r| r.scope == Scope::User);
+    /// Push one format into the store and report the outcome. Returns the number
+    /// of rules stored (0 = nothing to push).
+    fn push_one(store: &Store, fmt: &Format, input: &std::path::Path, project_key: &str, options: &PushOptions) -> anyhow::Result<usize> {
+        let fmt_name = fmt.name();
+        let outcome = ops::push_format(store, fmt, input, project_key, options)
+            .with_context(|| format!("failed to push {} from {}", fmt_name, input.display()))?;
+        match &outcome {
+            PushOutcome::NoUserConfig => {
+                println!("  {} — skipped (no local user-level config; use --input to specify)", fmt_name);
+            }
+            PushOutcome::NoRules => println!("  {} — skipped (no rules found)", fmt_name),
+            PushOutcome::DryRun(rules) => {
+                println!("  {} — dry run: {} rule(s) → store/{}", fmt_name, rules.len(), project_key);
+                print_rules_preview(rules, 200);
+            }
+            PushOutcome::Stored(rules) => {
+                println!("  {} — stored {} rule(s) → store/{}", fmt_name, rules.len(), project_key);
+            }
         }
-
-        if rules.is_empty() {
-            println!("  {} — skipped (no rules found)", fmt_name);
-            return Ok(0);
+        if *fmt == Format::Windsurf
+            && let PushOutcome::DryRun(rules) | PushOutcome::Stored(rules) = &outcome
+        {
+            print_windsurf_budget(rules);
         }
+        Ok(outcome.count())
+    }
 
-        if dry_run {
-            println!("  {} — dry run: {} rule(s) → store/{}", fmt_name, rules.len(), project_key);
-            print_rules_preview(&rules);
-            return Ok(rules.len());
+    /// Per-rule character counts against Windsurf's 6k/12k limits.
+    fn print_windsurf_budget(rules: &[polyrc_core::ir::Rule]) {
+        use polyrc_core::formats::windsurf::{CharBudget, FILE_CHAR_LIMIT, TOTAL_CHAR_LIMIT};
+        let budget = CharBudget::of(rules);
+        for (name, chars) in &budget.rules {
+            let flag = if *chars > FILE_CHAR_LIMIT { "  ← over per-file limit" } else { "" };
+            println!("      {:<28}  {:>6} chars{}", name, chars, flag);
         }
-
-        let stored = store.save_rules(Some(project_key), &rules, fmt_name)?;
-        println!("  {} — stored {} rule(s) → store/{}", fmt_name, stored.len(), project_key);
-        Ok(stored.len())
+        let flag = if budget.over_total() { "  ← over total limit" } else { "" };
+        println!("      {:<28}  {:>6} / {} chars{}", "total", budget.total, TOTAL_CHAR_LIMIT, flag);
     }
 
     pub fn pull_format(args: PullFormatArgs) -> anyhow::Result<()> {
         let config = Config::load()?;
-        let store_path = config.store_path();
-        let store = Store::open(&store_path).context("store not initialized — run `polyrc init` first")?;
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
 
         let (user_mode, project_key) = resolve_routing(args.user, args.project.as_deref())?;
+        if !user_mode {
+            ensure_project_exists(&store, &project_key)?;
+        }
+
+        // Loaded once and shared by every format, however many are selected
+        let rules = load_project_rules(&store, &project_key, args.strict)?;
+        if args.json {
+            let plans = selected_formats(args.format.as_ref(), args.all)?
+                .iter()
+                .flat_map(|fmt| {
+                    pull_plans(&rules, fmt, &args, user_mode)
+                        .unwrap_or_else(|e| vec![FormatPlan::error(fmt, format!("{e:#}"))])
+                })
+                .collect();
+            print_plan("pull-format", plans);
+            return Ok(());
+        }
+        if args.strict {
+            check_lossless(&rules, &selected_formats(args.format.as_ref(), args.all)?, &args, user_mode)?;
+        }
 
         if args.all {
             for fmt in Format::all() {
-                match pull_one(&store, &fmt, &args.output, user_mode, args.dry_run, &project_key) {
+                match pull_one(&rules, fmt, &args, user_mode) {
                     Ok(_) => {} // pull_one prints its own per-format status
                     Err(e) => eprintln!("  {} — error: {:#}", fmt.name(), e),
                 }
             }
         } else {
-            let fmt_arg = args.format.expect("--format is required without --all");
+            let fmt_arg = args.format.as_ref().expect("--format is required without --all");
             let fmt_name = fmt_arg.as_str();
             let fmt = Format::from_str(fmt_name)
                 .with_context(|| format!("unknown format '{}'", fmt_name))?;
-            pull_one(&store, &fmt, &args.output, user_mode, args.dry_run, &project_key)?;
+            pull_one(&rules, &fmt, &args, user_mode)?;
         }
         Ok(())
     }
 
-    /// Pull rules from the store and write them as one format. Returns the number of rules written.
-    fn pull_one(
-        store: &Store,
-        fmt: &Format,
-        output: &std::path::Path,
-        user: bool,
-        dry_run: bool,
-        project_key: &str,
-    ) -> anyhow::Result<usize> {
-        let fmt_name = fmt.name();
-        let mut rules = store.load_rules(Some(project_key))?;
-
-        // When using --user, filter to user-scope rules only
-        if user {
-            rules.retain(|r| r.scope == Scope::User);
+    /// `pull-format --strict`: fail before any format is written if one of them
+    /// would lose something, listing the losses of all of them.
+    fn check_lossless(rules: &[Rule], formats: &[Format], args: &PullFormatArgs, user: bool) -> anyhow::Result<()> {
+        let options = ops::PullOptions { dry_run: true, strict: true, consolidate_globs: args.consolidate_globs, ..Default::default() };
+        let (mut lossy, mut losses) = (vec![], vec![]);
+        for fmt in formats {
+            for (output, rules) in pull_targets(rules.to_vec(), &args.output, args.recursive) {
+                match ops::pull_format(fmt, rules, &output, user, &options) {
+                    Err(polyrc_core::PolyrcError::LossyConversion { losses: found, .. }) => {
+                        lossy.push(fmt.name());
+                        losses.extend(found);
+                    }
+                    Err(e) => return Err(e.into()),
+                    Ok(_) => {}
+                }
+            }
+        }
+        if lossy.is_empty() {
+            return Ok(());
         }
+        lossy.dedup();
+        Err(polyrc_core::PolyrcError::LossyConversion { format: lossy.join(", "), losses }.into())
+    }
 
-        if rules.is_empty() {
-            println!("  {} — skipped (no rules in store)", fmt_name);
-            return Ok(0);
+    /// `pull-format --dry-run --json` entries for one format: one per output
+    /// directory, which with --recursive includes each package.
+    fn pull_plans(rules: &[Rule], fmt: &Format, args: &PullFormatArgs, user: bool) -> anyhow::Result<Vec<FormatPlan>> {
+        pull_targets(rules.to_vec(), &args.output, args.recursive)
+            .into_iter()
+            .map(|(output, rules)| pull_plan(fmt, rules, &output, args, user))
+            .collect()
+    }
+
+    /// Where to write `rules`: all of them to `output`, or with `recursive`,
+    /// package rules to their package under it (see `polyrc_core::monorepo`).
+    fn pull_targets(rules: Vec<Rule>, output: &std::path::Path, recursive: bool) -> Vec<(std::path::PathBuf, Vec<Rule>)> {
+        if !recursive {
+            return vec![(output.to_path_buf(), rules)];
         }
+        let (root, packages) = polyrc_core::monorepo::split(rules);
+        let root = (!root.is_empty() || packages.is_empty()).then(|| (output.to_path_buf(), root));
+        root.into_iter().chain(packages.into_iter().map(|(package, rules)| (output.join(package), rules))).collect()
+    }
 
-        // Auto-detect user output dir when --user and output is the default "."
-        let user_dir;
-        let effective_output: &std::path::Path = if user && output == std::path::Path::new(".") {
-            match fmt.user_input_dir() {
-                Some(dir) => { user_dir = dir; &user_dir }
-                None => {
-                    println!("  {} — skipped (no local user-level config; use --output to specify)", fmt_name);
-                    return Ok(0);
-                }
-            }
-        } else {
-            output
+    fn pull_plan(fmt: &Format, rules: Vec<Rule>, output: &std::path::Path, args: &PullFormatArgs, user: bool) -> anyhow::Result<FormatPlan> {
+        let options = ops::PullOptions {
+            dry_run: true,
+            activation_policy: args.unsupported_activation.map(Into::into),
+            consolidate_globs: args.consolidate_globs,
+            strict: args.strict,
+            ..Default::default()
         };
+        Ok(match ops::pull_format(fmt, rules, output, user, &options)? {
+            PullOutcome::NoRules => FormatPlan::skipped(fmt, "no rules in store"),
+            PullOutcome::NoUserConfig => FormatPlan::skipped(fmt, "no local user-level config"),
+            PullOutcome::DryRun { .. } if fmt.is_plugin() => FormatPlan::skipped(fmt, "plugin formats can't be previewed"),
+            PullOutcome::DryRun { rules, output } => FormatPlan {
+                files: Some(polyrc_core::plan::plan_writes(fmt, &rules, &output, &Default::default())?),
+                output: Some(output.display().to_string()),
+                ..FormatPlan::new(fmt)
+            },
+            PullOutcome::LocalEdits { .. } | PullOutcome::Written { .. } => unreachable!("dry run writes nothing"),
+        })
+    }
 
-        if dry_run {
-            println!("  {} — dry run: {} rule(s) from store → {}", fmt_name, rules.len(), effective_output.display());
-            print_rules_preview(&rules);
-            return Ok(rules.len());
+    /// Write rules loaded from the store as one format. Returns the number of rules written.
+    fn pull_one(rules: &[Rule], fmt: &Format, args: &PullFormatArgs, user: bool) -> anyhow::Result<usize> {
+        let options = ops::PullOptions {
+            dry_run: args.dry_run,
+            overwrite: args.overwrite,
+            force: args.force,
+            prune: args.prune,
+            activation_policy: args.unsupported_activation.map(Into::into),
+            consolidate_globs: args.consolidate_globs,
+            strict: args.strict,
+        };
+        let mut written = 0;
+        for (output, rules) in pull_targets(rules.to_vec(), &args.output, args.recursive) {
+            written += pull_into(fmt, rules, &output, user, &options)?;
         }
+        Ok(written)
+    }
 
-        let writer = fmt.writer();
-        writer.write(&rules, effective_output)
-            .with_context(|| format!("failed to write {} to {}", fmt_name, effective_output.display()))?;
-        println!("  {} — wrote {} rule(s) to {}", fmt_name, rules.len(), effective_output.display());
-        Ok(rules.len())
+    /// Write `rules` as one format into `output` and report the outcome.
+    fn pull_into(fmt: &Format, rules: Vec<Rule>, output: &std::path::Path, user: bool, options: &ops::PullOptions) -> anyhow::Result<usize> {
+        let fmt_name = fmt.name();
+        let outcome = ops::pull_format(fmt, rules, output, user, options)
+            .with_context(|| format!("failed to write {}", fmt_name))?;
+        match outcome {
+            PullOutcome::NoRules => {
+                println!("  {} — skipped (no rules in store)", fmt_name);
+                Ok(0)
+            }
+            PullOutcome::NoUserConfig => {
+                println!("  {} — skipped (no local user-level config; use --output to specify)", fmt_name);
+                Ok(0)
+            }
+            PullOutcome::DryRun { rules, output } => {
+                println!("  {} — dry run: {} rule(s) from store → {}", fmt_name, rules.len(), output.display());
+                print_rules_preview(&rules, 200);
+                Ok(rules.len())
+            }
+            PullOutcome::LocalEdits { files, output } => {
+                eprintln!(
+                    "  {} — skipped: edited since the last pull in {}: {} (use --force to overwrite)",
+                    fmt_name,
+                    output.display(),
+                    files.join(", ")
+                );
+                Ok(0)
+            }
+            PullOutcome::Written { count, output, pruned, kept, unsupported } => {
+                print_unsupported_activations(fmt_name, options.activation_policy, &unsupported);
+                println!("  {} — wrote {} rule(s) to {}", fmt_name, count, output.display());
+                if !pruned.is_empty() {
+                    println!("    pruned: {}", pruned.join(", "));
+                }
+                if !kept.is_empty() {
+                    eprintln!("    not pruned (edited since the last pull): {}", kept.join(", "));
+                }
+                Ok(count)
+            }
+        }
     }
 
-    pub fn sync(args: SyncArgs) -> anyhow::Result<()> {
+    /// Run `polyrc sync` and return its process exit code.
+    pub fn sync(args: SyncArgs) -> anyhow::Result<i32> {
+        let report_path = sync::report::report_path();
+        if args.report {
+            match sync::report::SyncReport::load(&report_path)? {
+                Some(report) => print!("{}", report.render()),
+                None => println!("No sync report yet — run `polyrc sync` first."),
+            }
+            return Ok(0);
+        }
+
         let config = Config::load()?;
-        let store_path = config.store_path();
-        let store = Store::open(&store_path).context("store not initialized")?;
+        let store = Store::open_resolved(&config).context("store not initialized")?;
+        let store_path = store.path.clone();
+
+        if let sync::RemoteStatus::Unreachable(reason) = sync::probe_remote(&store_path, sync::PROBE_TIMEOUT) {
+            println!("Remote unreachable ({reason}) — working offline.");
+            if !args.push_only {
+                normalize_projects(&store, args.strict, config.sync_tombstone_retention_days())?;
+            }
+            let strict = args.strict_network || !config.sync_offline_ok();
+            return Ok(if strict { EXIT_OFFLINE } else { 0 });
+        }
 
         if !args.push_only {
             // Pull phase
             println!("Pulling from remote...");
-            sync::git_pull(&store_path).context("git pull failed")?;
-
-            // Re-save all projects after pull to normalise IDs and metadata
-            for project in store.list_projects()? {
-                let rules = store.load_rules(Some(&project))?;
-                if !rules.is_empty() {
-                    let _ = store.save_rules(Some(&project), &rules, "sync");
-                }
+            let report = sync::git_pull(&store_path, config.sync_merge_options()).context("git pull failed")?;
+            report.save(&report_path)?;
+            normalize_projects(&store, args.strict, config.sync_tombstone_retention_days())?;
+            println!("Pull complete: {}.", report.summary());
+            if !report.conflicts.is_empty() {
+                println!("  Rules edited on both sides kept the version updated last — see `polyrc sync --report`.");
             }
-            println!("Pull complete.");
         }
 
         if !args.pull_only {
@@ -399,202 +793,1514 @@ mod commands {
         if !args.push_only && !args.pull_only {
             println!("Sync complete.");
         }
+        sync::record_sync(&store_path)?;
+        Ok(0)
+    }
+
+    /// Re-save every project to normalise IDs and metadata (after a pull, or in
+    /// place of one when offline), and expire its old tombstones.
+    fn normalize_projects(store: &Store, strict: bool, tombstone_retention_days: u64) -> anyhow::Result<()> {
+        for project in store.list_projects()? {
+            store.expire_tombstones(&project, tombstone_retention_days)?;
+            let rules = load_project_rules(store, &project, strict)?;
+            if !rules.is_empty() {
+                let _ = store.save_rules_for(Some(&project), &rules, "sync", store::SaveReason::Normalize);
+            }
+        }
         Ok(())
     }
 
     pub fn project(args: ProjectArgs) -> anyhow::Result<()> {
         let config = Config::load()?;
-        let store_path = config.store_path();
-        let store = Store::open(&store_path).context("store not initialized")?;
+        let store = Store::open_resolved(&config).context("store not initialized")?;
+        let store_path = store.path.clone();
 
         match args.command {
-            ProjectCommands::RenameProject { old_name, new_name } => {
+            ProjectCommands::List => {
+                let projects = store.list_projects()?;
+                if projects.is_empty() {
+                    println!("No projects in store.");
+                    return Ok(());
+                }
+                for p in &projects {
+                    let info = store.project_info(p)?;
+                    let updated = relative_time(info.last_updated.as_deref(), chrono::Utc::now());
+                    println!(
+                        "  {:<24}  {:>3} rule(s)  {:<10}  {}",
+                        info.name,
+                        info.rule_count,
+                        updated,
+                        info.meta.description.as_deref().unwrap_or("")
+                    );
+                }
+                let archived = store.list_archived_projects()?;
+                if !archived.is_empty() {
+                    println!("\nArchived: {}", archived.join(", "));
+                }
+            }
+            ProjectCommands::Init { name, description, default_formats, force } => {
+                let key = normalize_project_name(&name).with_context(|| format!("invalid project name '{}'", name))?;
+                if key != name {
+                    println!("Normalized project name '{}' → '{}'.", name, key);
+                }
+                let formats = default_formats
+                    .iter()
+                    .map(|f| Format::from_str(f).map(|f| f.name().to_string()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let exists = store_path.join(&key).is_dir();
+                if exists && !force {
+                    anyhow::bail!("project '{}' already exists; pass --force to update its metadata", key);
+                }
+                let mut meta = if exists { store.project_meta(&key)? } else { store::ProjectMeta::default() };
+                if meta.created_at.is_none() {
+                    meta.created_at = Some(chrono::Utc::now().to_rfc3339());
+                }
+                if description.is_some() {
+                    meta.description = description;
+                }
+                if !formats.is_empty() {
+                    meta.default_formats = Some(formats);
+                }
+                store.write_project_meta(&key, &meta)?;
+                let kind = if exists { StoreCommit::UpdateProject } else { StoreCommit::InitProject };
+                sync::git_commit(&store_path, kind, &key)?;
+                let done = if exists { "Updated" } else { "Created" };
+                println!("{done} project '{}' at {} and committed.", key, store_path.join(&key).display());
+            }
+            ProjectCommands::Show { name } => {
+                let key = project_key_arg(&name)?;
+                let info = store.project_info(&key)?;
+                println!("PROJECT: {}", info.name);
+                if let Some(d) = &info.meta.description {
+                    println!("  description:  {}", d);
+                }
+                if let Some(c) = &info.meta.created_at {
+                    println!("  created:      {}", c);
+                }
+                if let Some(formats) = &info.meta.default_formats {
+                    println!("  formats:      {}", formats.join(", "));
+                }
+                println!("  last updated: {}", info.last_updated.as_deref().unwrap_or("-"));
+                println!("  rules:        {}", info.rule_count);
+                for rule in load_project_rules(&store, &key, false)? {
+                    println!(
+                        "    - {:<28}  {:<10}  {}",
+                        rule.name.as_deref().unwrap_or("<unnamed>"),
+                        format!("{:?}", rule.activation).to_lowercase(),
+                        rule.source_format.as_deref().unwrap_or("?")
+                    );
+                }
+            }
+            ProjectCommands::Rename { old_name, new_name } => {
                 let old_norm = normalize_project_name(&old_name)
                     .with_context(|| format!("invalid old project name '{}'", old_name))?;
                 let new_norm = normalize_project_name(&new_name)
                     .with_context(|| format!("invalid new project name '{}'", new_name))?;
                 store.rename_project(&old_norm, &new_norm)?;
-                let msg = format!("rename project {} → {}", old_norm, new_norm);
-                sync::git_commit(&store_path, &msg)?;
+                sync::git_commit(&store_path, StoreCommit::RenameProject, &format!("{} → {}", old_norm, new_norm))?;
                 println!("Renamed '{}' → '{}' and committed.", old_norm, new_norm);
             }
+            ProjectCommands::Archive { name } => {
+                let key = normalize_project_name(&name)
+                    .with_context(|| format!("invalid project name '{}'", name))?;
+                store.archive_project(&key)?;
+                sync::git_commit(&store_path, StoreCommit::ArchiveProject, &key)?;
+                println!("Archived '{}' and committed.", key);
+            }
+            ProjectCommands::Unarchive { name } => {
+                let key = normalize_project_name(&name)
+                    .with_context(|| format!("invalid project name '{}'", name))?;
+                store.unarchive_project(&key)?;
+                sync::git_commit(&store_path, StoreCommit::UnarchiveProject, &key)?;
+                println!("Unarchived '{}' and committed.", key);
+            }
         }
         Ok(())
     }
 
-    pub fn list_project(args: ListProjectArgs) -> anyhow::Result<()> {
-        let config = Config::load()?;
-        let store_path = config.store_path();
-        let store = Store::open(&store_path).context("store not initialized — run `polyrc init` first")?;
+    pub fn pack(args: PackArgs) -> anyhow::Result<()> {
+        use polyrc_core::pack::{self, Change};
 
-        if let Some(ref name) = args.name {
-            // Show rules for a specific project (name can be "user")
-            let rules = store.load_rules(Some(name))?;
-            if rules.is_empty() {
-                println!("No rules in project '{}'.", name);
-                return Ok(());
+        match args.command {
+            PackCommands::List => {
+                for p in pack::builtin_packs() {
+                    println!("{:<22} {} ({} rule(s))", p.name, p.description, p.rules.len());
+                }
             }
-
-            const W_NAME: usize = 28;
-            const W_SCOPE: usize = 7;
-            const W_FMT: usize = 10;
-            const W_ACT: usize = 10;
-            const W_DATE: usize = 10;
-
-            let header = format!(
-                "  {:<W_NAME$}  {:<W_SCOPE$}  {:<W_FMT$}  {:<W_ACT$}  {:<W_DATE$}  {}",
-                "NAME", "SCOPE", "FORMAT", "ACTIVATION", "UPDATED", "PATH"
-            );
-            let divider = "─".repeat(header.len());
-
-            println!("PROJECT: {} ({} rule(s))", name, rules.len());
-            println!("{}", divider);
-            println!("{}", header);
-            println!("{}", divider);
-
-            for rule in &rules {
-                let rule_name = rule.name.as_deref().unwrap_or("<unnamed>");
-                let fmt_tag   = rule.source_format.as_deref().unwrap_or("?");
-                let scope_tag = format!("{:?}", rule.scope).to_lowercase();
-                let act_tag   = format!("{:?}", rule.activation).to_lowercase();
-                let updated   = rule.updated_at.as_deref().unwrap_or("?");
-                let date      = updated.get(..10).unwrap_or(updated);
-                let path      = format!("{}/{}.yaml", name, rule.filename_stem());
-
-                println!(
-                    "  {:<W_NAME$}  {:<W_SCOPE$}  {:<W_FMT$}  {:<W_ACT$}  {:<W_DATE$}  {}",
-                    rule_name, scope_tag, fmt_tag, act_tag, date, path
-                );
-
-                if args.verbose {
-                    // Print full content
-                    for line in rule.content.lines() {
-                        println!("      {}", line);
+            PackCommands::Install { source, user, project, yes } => {
+                let pack = if let Some(p) = pack::builtin(&source) {
+                    p
+                } else if source.starts_with("https://") || source.starts_with("http://") {
+                    pack::load_url(&source)?
+                } else if std::path::Path::new(&source).exists() {
+                    pack::load_path(std::path::Path::new(&source))?
+                } else {
+                    let names: Vec<String> = pack::builtin_packs().into_iter().map(|p| p.name).collect();
+                    anyhow::bail!(
+                        "no built-in pack, file or URL named '{}'.{} Built-in packs: {}",
+                        source,
+                        suggest::did_you_mean(&suggest::suggest(&source, &names)),
+                        names.join(", ")
+                    );
+                };
+
+                let config = Config::load()?;
+                let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+                let (user_mode, namespace) = resolve_routing(user, project.as_deref())?;
+                let namespace = match project.as_deref() {
+                    Some(raw) if !user_mode => check_project_collision(&store, raw, namespace)?,
+                    _ => namespace,
+                };
+
+                let planned = pack::plan(&store, &pack, &namespace)?;
+                println!("Pack '{}' → store/{}:", pack.name, namespace);
+                for (change, rule) in &planned {
+                    let tag = match change {
+                        Change::Add => "add",
+                        Change::Replace => "replace",
+                    };
+                    println!("  {:<8} {}", tag, rule.name.as_deref().unwrap_or("<unnamed>"));
+                }
+                if !yes {
+                    if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+                        anyhow::bail!("refusing to install without confirmation; pass --yes");
+                    }
+                    if !confirm("Install these rules?")? {
+                        println!("Aborted.");
+                        return Ok(());
                     }
-                    println!();
                 }
-            }
 
-            println!("{}", divider);
-            println!("  {} rule(s)", rules.len());
-        } else {
-            // List all projects
-            let all_projects = store.list_projects()?;
-            if all_projects.is_empty() {
-                println!("No projects in store.");
-                return Ok(());
+                let stored = pack::install(&store, &namespace, &planned)?;
+                let msg = sync::git_commit(&store.path, StoreCommit::PackInstall, &format!("{} → {}", pack.name, namespace))
+                    .context("git commit failed")?;
+                println!("Installed {} rule(s). Committed: {}", stored.len(), msg);
             }
+        }
+        Ok(())
+    }
 
-            // user first, then alphabetical
-            let mut ordered = all_projects.clone();
-            if let Some(pos) = ordered.iter().position(|n| n == store::USER_PROJECT) {
-                ordered.remove(pos);
-                ordered.insert(0, store::USER_PROJECT.to_string());
+    pub fn hook(args: HookArgs) -> anyhow::Result<()> {
+        use polyrc_core::hook::{self, HookFormats, HookMode};
+
+        let cwd = std::env::current_dir()?;
+        match args.command {
+            HookCommands::Install { project, check: _, push, format, pre_commit_config, force } => {
+                let mode = if push { HookMode::Push } else { HookMode::Check };
+                let formats = if format.eq_ignore_ascii_case("all") {
+                    HookFormats::All
+                } else {
+                    HookFormats::One(Format::from_str(&format)?)
+                };
+                if pre_commit_config {
+                    let config = sync::git_toplevel(&cwd)?.join(hook::PRE_COMMIT_CONFIG);
+                    hook::install_pre_commit(&config, mode, &project, &formats)?;
+                    println!("Added polyrc {} hook to {}", mode.name(), config.display());
+                } else {
+                    let script = hook::render_script(mode, &project, &formats);
+                    let file = hook::install_script(&sync::git_hooks_dir(&cwd)?, &script, force)?;
+                    println!("Installed polyrc {} hook at {}", mode.name(), file.display());
+                }
             }
+            HookCommands::Uninstall { pre_commit_config } => {
+                let removed = if pre_commit_config {
+                    hook::uninstall_pre_commit(&sync::git_toplevel(&cwd)?.join(hook::PRE_COMMIT_CONFIG))?
+                } else {
+                    hook::uninstall_script(&sync::git_hooks_dir(&cwd)?)?
+                };
+                println!("{}", if removed { "Removed polyrc pre-commit hook." } else { "No polyrc hook installed." });
+            }
+        }
+        Ok(())
+    }
 
-            println!("Projects in store:");
-            for p in &ordered {
-                let rules = store.load_rules(Some(p)).unwrap_or_default();
-                if args.verbose {
-                    println!("  {} ({} rule(s)):", p, rules.len());
-                    for r in &rules {
-                        println!("    - {}", r.name.as_deref().unwrap_or("<unnamed>"));
-                    }
+    /// Run `polyrc check` and return its process exit code.
+    pub fn check(args: CheckArgs) -> i32 {
+        match check_drift(&args) {
+            Ok(report) => {
+                if args.json {
+                    println!("{}", serde_json::to_string_pretty(&report).expect("report serializes"));
                 } else {
-                    println!("  {} ({} rule(s))", p, rules.len());
+                    print_check_report(&report);
                 }
+                if report.clean { 0 } else { EXIT_DRIFT }
             }
-            println!("\nTotal: {} project(s)", ordered.len());
+            Err(e) => {
+                if args.json {
+                    let err = crate::error_json(&e, EXIT_CHECK_ERROR);
+                    println!("{}", serde_json::to_string_pretty(&err).expect("error serializes"));
+                }
+                eprintln!("error: {e:#}");
+                EXIT_CHECK_ERROR
+            }
+        }
+    }
+
+    pub fn status(args: StatusArgs) -> anyhow::Result<()> {
+        if args.user || args.project.is_some() {
+            return store_status(&args);
+        }
+        let lock = polyrc_core::lock::Lockfile::load(&args.input)?;
+        let files = lock.status(&args.input);
+        let unmanaged = lock.unmanaged(&args.input);
+        if args.json {
+            let entries: Vec<_> = files
+                .iter()
+                .map(|(format, path, state)| serde_json::json!({ "format": format, "path": path, "state": state }))
+                .chain(unmanaged.iter().map(|(format, path)| {
+                    serde_json::json!({ "format": format, "path": path, "state": "unmanaged" })
+                }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries).expect("status serializes"));
+            return Ok(());
+        }
+        if files.is_empty() {
+            println!("No files recorded in {} — nothing pulled here yet.", args.input.join(polyrc_core::lock::LOCK_FILE).display());
+        }
+        let changed: Vec<_> = files.iter().filter(|(_, _, state)| *state != polyrc_core::lock::FileState::Clean).collect();
+        if !files.is_empty() && changed.is_empty() {
+            println!("All {} file(s) written by polyrc are unchanged.", files.len());
+        }
+        for (format, path, state) in changed {
+            println!("  {:<10} {:<10} {}", format!("{state:?}").to_lowercase(), format, path);
+        }
+        for (format, path) in &unmanaged {
+            println!("  {:<10} {:<10} {}", "unmanaged", format, path);
+        }
+        if !unmanaged.is_empty() {
+            println!("Unmanaged files are neither read nor written by polyrc; delete them or rename them so the format picks them up.");
         }
         Ok(())
     }
 
-    pub fn push_rule(args: PushRuleArgs) -> anyhow::Result<()> {
-        use crate::ir::{Activation, Rule};
-        let config = Config::load()?;
-        let store_path = config.store_path();
-        let store = Store::open(&store_path).context("store not initialized — run `polyrc init` first")?;
+    #[derive(serde::Serialize)]
+    struct StoreStatus {
+        project: String,
+        store: std::path::PathBuf,
+        /// Commits ahead of and behind origin/main as of the last fetch; absent without a remote branch.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        remote: Option<RemoteCounts>,
+        formats: Vec<FormatStatus>,
+    }
 
-        // Determine destination namespace
-        let namespace_owned;
-        let namespace: &str = if args.user {
-            store::USER_PROJECT
-        } else if let Some(ref p) = args.project {
-            namespace_owned = normalize_project_name(p)
-                .with_context(|| format!("invalid project name '{}'", p))?;
-            &namespace_owned
-        } else {
-            anyhow::bail!("specify --user or --project <name> to choose where to store this rule");
-        };
+    #[derive(serde::Serialize)]
+    struct RemoteCounts {
+        ahead: usize,
+        behind: usize,
+    }
 
-        let scope = if args.user {
-            Scope::User
-        } else {
-            Scope::Project
-        };
+    #[derive(serde::Serialize)]
+    struct FormatStatus {
+        format: &'static str,
+        state: polyrc_core::drift::FormatState,
+        rules: Vec<polyrc_core::drift::RuleState>,
+    }
 
-        let content = if let Some(ref file) = args.from_file {
-            std::fs::read_to_string(file)
-                .with_context(|| format!("failed to read {}", file.display()))?
+    /// `polyrc status --project/--user`: each format with files under --input
+    /// against the store, and the store against its remote.
+    fn store_status(args: &StatusArgs) -> anyhow::Result<()> {
+        use polyrc_core::drift::{self, FormatState, SyncState};
+        let store = Store::open_resolved(&Config::load()?).context("store not initialized — run `polyrc init` first")?;
+        let (user_mode, project_key) = resolve_routing(args.user, args.project.as_deref())?;
+        let mut rules = if user_mode || store.path.join(&project_key).is_dir() {
+            load_project_rules(&store, &project_key, false)?
         } else {
-            anyhow::bail!("--from-file is required (interactive input not yet supported)");
+            vec![] // a project nobody pushed yet: every format is "not in store"
         };
+        if user_mode {
+            rules.retain(|r| r.scope == Scope::User);
+        }
 
-        let activation = match args.activation {
-            ActivationArg::Always    => Activation::Always,
-            ActivationArg::OnDemand  => Activation::OnDemand,
-            ActivationArg::Glob      => Activation::Glob,
-            ActivationArg::AiDecides => Activation::AiDecides,
+        let mut report = StoreStatus {
+            remote: sync::git_ahead_behind(&store.path)?.map(|(ahead, behind)| RemoteCounts { ahead, behind }),
+            project: project_key,
+            store: store.path.clone(),
+            formats: vec![],
         };
+        for fmt in Format::all() {
+            if fmt.parser().parse(&args.input)?.is_empty() {
+                continue;
+            }
+            let rules = drift::sync_states(fmt, &rules, &args.input)
+                .with_context(|| format!("failed to compare {}", fmt.name()))?;
+            report.formats.push(FormatStatus { format: fmt.name(), state: FormatState::of(&rules), rules });
+        }
 
-        let rule = Rule {
-            name: Some(args.name.clone()),
-            scope,
-            activation,
-            content: content.trim_end().to_string(),
-            ..Default::default()
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&report).expect("status serializes"));
+            return Ok(());
+        }
+        let remote = match &report.remote {
+            None => "no remote branch".to_string(),
+            Some(RemoteCounts { ahead: 0, behind: 0 }) => "up to date with origin/main".to_string(),
+            Some(RemoteCounts { ahead, behind }) => format!("{ahead} ahead, {behind} behind origin/main — run `polyrc sync`"),
         };
+        println!("Store: {} ({})", report.store.display(), remote);
+        if report.formats.is_empty() {
+            println!("No format config files found under {}.", args.input.display());
+            return Ok(());
+        }
 
-        let stored = store.save_rule_to_namespace(namespace, &args.name, &rule)?;
-        println!(
-            "Pushed '{}' → {}/{}/{}.yaml",
-            args.name, store_path.display(), namespace, args.name
+        let header = format!(
+            "  {:<10}  {:<12}  {}",
+            "FORMAT",
+            "STATE",
+            SyncState::ALL.map(|s| format!("{:>11}", s.label().to_uppercase())).join("  ")
         );
+        let divider = "─".repeat(header.chars().count());
+        println!("{divider}\n{header}\n{divider}");
+        for f in &report.formats {
+            let counts = SyncState::ALL.map(|s| format!("{:>11}", f.rules.iter().filter(|r| r.state == s).count()));
+            println!("  {:<10}  {:<12}  {}", f.format, f.state.label(), counts.join("  "));
+        }
+        for f in report.formats.iter().filter(|f| f.state != FormatState::InSync) {
+            println!("\n  {}:", f.format);
+            for r in f.rules.iter().filter(|r| r.state != SyncState::InSync) {
+                println!("    {:<12} {}", r.state.label(), r.rule);
+            }
+        }
+        Ok(())
+    }
 
-        sync::git_commit(&store_path, &format!("push-rule: {}", args.name))
-            .context("git commit failed")?;
+    pub fn verify(args: VerifyArgs) -> anyhow::Result<i32> {
+        let formats = match &args.format {
+            Some(f) => vec![Format::from_str(f.as_str())?],
+            None => Format::all().to_vec(),
+        };
+        let mut reports = vec![];
+        for fmt in &formats {
+            match polyrc_core::verify::verify(fmt, &args.input, args.via_store)
+                .with_context(|| format!("failed to verify {}", fmt.name()))?
+            {
+                Some(report) => reports.push(report),
+                None if args.format.is_some() => {
+                    anyhow::bail!("no {} config files found under {}", fmt.name(), args.input.display())
+                }
+                None => {}
+            }
+        }
+        if reports.is_empty() {
+            anyhow::bail!("no format config files found under {}", args.input.display());
+        }
+
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&reports).expect("verify report serializes"));
+        } else {
+            print_verify_reports(&reports);
+        }
+        Ok(if reports.iter().all(|r| r.faithful()) { 0 } else { polyrc_core::error::EXIT_LOSSY })
+    }
+
+    fn print_verify_reports(reports: &[polyrc_core::verify::VerifyReport]) {
+        use polyrc_core::drift::DriftKind;
+        for r in reports {
+            let via = if r.via_store { " via the store" } else { "" };
+            if r.faithful() {
+                println!("  {} — {} rule(s) unchanged after the round trip{}", r.format, r.rules, via);
+                continue;
+            }
+            println!("  {} — {} of {} rule(s) changed in the round trip{}:", r.format, r.differences.len(), r.rules, via);
+            for d in &r.differences {
+                match &d.kind {
+                    DriftKind::MissingLocally => println!("    {}: lost", d.rule),
+                    DriftKind::MissingInStore => println!("    {}: appeared (a rule came back under another name)", d.rule),
+                    DriftKind::Modified { fields, first_difference } => {
+                        println!("    {}: {} changed", d.rule, fields.join(", "));
+                        if let Some(diff) = first_difference {
+                            println!("      line {}: {:?} → {:?}", diff.line, diff.store.as_deref().unwrap_or(""), diff.local.as_deref().unwrap_or(""));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn budget(args: BudgetArgs) -> anyhow::Result<i32> {
+        let config = Config::load()?;
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+        let (user_mode, project_key) = resolve_routing(args.user, args.project.as_deref())?;
+        if !user_mode {
+            ensure_project_exists(&store, &project_key)?;
+        }
+        let mut rules = load_project_rules(&store, &project_key, false)?;
+        if user_mode {
+            rules.retain(|r| r.scope == Scope::User);
+        }
+
+        let formats = match &args.format {
+            Some(_) => selected_formats(args.format.as_ref(), false)?,
+            None => Format::all().iter().filter(|f| !f.is_plugin()).cloned().collect(),
+        };
+        let mut budgets = vec![];
+        for fmt in &formats {
+            let options = polyrc_core::formats::options::configured(fmt);
+            budgets.push(
+                polyrc_core::budget::measure(fmt, &rules, &options).with_context(|| format!("failed to measure {}", fmt.name()))?,
+            );
+        }
+
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&budgets).expect("budget serializes"));
+        } else {
+            print_budget(&project_key, &budgets);
+        }
+        let over = budgets.iter().any(|b| b.over_limit);
+        Ok(if over && args.fail_over_limit { EXIT_OVER_BUDGET } else { 0 })
+    }
+
+    fn print_budget(project: &str, budgets: &[polyrc_core::budget::FormatBudget]) {
+        println!("Budget for store/{project}:");
+        println!("  {:<12} {:>6} {:>9} {:>9} {:>9}", "FORMAT", "FILES", "CHARS", "~TOKENS", "LIMIT");
+        for b in budgets {
+            let limit = b.limit_chars.map_or("-".to_string(), |l| l.to_string());
+            let flag = if b.over_limit { "  OVER" } else { "" };
+            println!("  {:<12} {:>6} {:>9} {:>9} {:>9}{flag}", b.format, b.files.len(), b.chars, b.tokens, limit);
+            for f in b.files.iter().filter(|f| f.over_limit) {
+                println!(
+                    "    {} is {} chars, over the per-file limit of {}",
+                    f.path,
+                    f.chars,
+                    b.file_limit_chars.unwrap_or_default()
+                );
+            }
+        }
+        if let Some(top) = budgets.first().map(|b| &b.top_rules).filter(|t| !t.is_empty()) {
+            let list: Vec<String> = top.iter().map(|r| format!("{} ({} chars, ~{} tokens)", r.rule, r.chars, r.tokens)).collect();
+            println!("Largest rules: {}", list.join(", "));
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct CheckReport {
+        project: String,
+        store: std::path::PathBuf,
+        clean: bool,
+        formats: Vec<FormatDrift>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct FormatDrift {
+        format: &'static str,
+        drift: Vec<polyrc_core::drift::Drift>,
+    }
+
+    fn check_drift(args: &CheckArgs) -> anyhow::Result<CheckReport> {
+        let store = match &args.store {
+            Some(path) => Store::open_read_only(path)
+                .with_context(|| format!("no store at {}", path.display()))?,
+            None => Store::open_resolved(&Config::load()?)
+                .context("store not initialized — run `polyrc init` or pass --store")?,
+        };
+        let (user_mode, project_key) = resolve_routing(args.user, args.project.as_deref())?;
+        if !store.path.join(&project_key).is_dir() {
+            return Err(store.project_not_found(&project_key).into());
+        }
+        let mut rules = store.load_rules(Some(&project_key))?;
+        if user_mode {
+            rules.retain(|r| r.scope == Scope::User);
+        }
+
+        let formats: Vec<Format> = match &args.format {
+            Some(f) => vec![Format::from_str(f.as_str())?],
+            None => {
+                let mut present = vec![];
+                for fmt in Format::all() {
+                    if !fmt.parser().parse(&args.input)?.is_empty() {
+                        present.push(fmt.clone());
+                    }
+                }
+                if present.is_empty() {
+                    anyhow::bail!("no format config files found under {}", args.input.display());
+                }
+                present
+            }
+        };
+
+        let mut report = CheckReport { project: project_key, store: store.path.clone(), clean: true, formats: vec![] };
+        for fmt in &formats {
+            let drift = polyrc_core::drift::diff_format(fmt, &rules, &args.input)
+                .with_context(|| format!("failed to compare {}", fmt.name()))?;
+            report.clean &= drift.is_empty();
+            report.formats.push(FormatDrift { format: fmt.name(), drift });
+        }
+        Ok(report)
+    }
+
+    fn print_check_report(report: &CheckReport) {
+        use polyrc_core::drift::DriftKind;
+        for f in &report.formats {
+            if f.drift.is_empty() {
+                println!("  {} — in sync with store/{}", f.format, report.project);
+                continue;
+            }
+            println!("  {} — {} rule(s) drifted from store/{}:", f.format, f.drift.len(), report.project);
+            for d in &f.drift {
+                match &d.kind {
+                    DriftKind::MissingLocally => println!(
+                        "    {}: in store but not on disk (run `polyrc pull-format --format {} --project {}`)",
+                        d.rule, f.format, report.project
+                    ),
+                    DriftKind::MissingInStore => println!(
+                        "    {}: on disk but not in store (run `polyrc push-format --format {} --project {}`)",
+                        d.rule, f.format, report.project
+                    ),
+                    DriftKind::Modified { fields, first_difference } => {
+                        println!("    {}: differs in {}", d.rule, fields.join(", "));
+                        if let Some(diff) = first_difference {
+                            println!("      line {}:", diff.line);
+                            println!("        store: {}", diff.store.as_deref().unwrap_or("<end of content>"));
+                            println!("        local: {}", diff.local.as_deref().unwrap_or("<end of content>"));
+                        }
+                    }
+                }
+            }
+        }
+        if report.clean {
+            println!("Clean.");
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct DiffReport {
+        project: String,
+        direction: crate::cli::DiffDirection,
+        clean: bool,
+        formats: Vec<FormatDiff>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct FormatDiff {
+        format: &'static str,
+        /// Rules the receiving side would gain.
+        added: Vec<String>,
+        /// Rules only the receiving side has; the transfer keeps them.
+        removed: Vec<String>,
+        changed: Vec<RuleDiff>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct RuleDiff {
+        rule: String,
+        fields: Vec<String>,
+        /// Unified diff of the content, when it differs.
+        #[serde(skip_serializing_if = "String::is_empty")]
+        diff: String,
+    }
+
+    /// Run `polyrc diff` and return its process exit code.
+    pub fn diff(args: DiffArgs) -> anyhow::Result<i32> {
+        use polyrc_core::drift::{self, DriftKind};
+        let store = Store::open_resolved(&Config::load()?).context("store not initialized — run `polyrc init` first")?;
+        let (user_mode, project_key) = resolve_routing(args.user, args.project.as_deref())?;
+        if !user_mode {
+            ensure_project_exists(&store, &project_key)?;
+        }
+        let mut rules = load_project_rules(&store, &project_key, false)?;
+        if user_mode {
+            rules.retain(|r| r.scope == Scope::User);
+        }
+
+        let push = args.direction == crate::cli::DiffDirection::Push;
+        let mut report = DiffReport { project: project_key, direction: args.direction, clean: true, formats: vec![] };
+        for fmt in selected_formats(args.format.as_ref(), args.all)? {
+            let (stored, local) = drift::sides(&fmt, &rules, &args.input)
+                .with_context(|| format!("failed to compare {}", fmt.name()))?;
+            // push-format --all skips formats without local files, so they change nothing
+            if push && args.all && local.is_empty() {
+                continue;
+            }
+            let mut entry = FormatDiff { format: fmt.name(), added: vec![], removed: vec![], changed: vec![] };
+            for d in drift::diff_rules(&stored, &local) {
+                match d.kind {
+                    DriftKind::MissingLocally if push => entry.removed.push(d.rule),
+                    DriftKind::MissingLocally => entry.added.push(d.rule),
+                    DriftKind::MissingInStore if push => entry.added.push(d.rule),
+                    DriftKind::MissingInStore => entry.removed.push(d.rule),
+                    DriftKind::Modified { fields, .. } => {
+                        let (store_side, local_side) = (&stored[&d.rule], &local[&d.rule]);
+                        let store_label = format!("store/{}/{}", report.project, d.rule);
+                        let local_label = format!("local/{}", d.rule);
+                        let diff = if !fields.iter().any(|f| f == "content") {
+                            String::new()
+                        } else if push {
+                            drift::unified_diff(&store_side.content, &local_side.content, &store_label, &local_label)
+                        } else {
+                            drift::unified_diff(&local_side.content, &store_side.content, &local_label, &store_label)
+                        };
+                        entry.changed.push(RuleDiff { rule: d.rule, fields, diff });
+                    }
+                }
+            }
+            report.clean &= entry.added.is_empty() && entry.removed.is_empty() && entry.changed.is_empty();
+            report.formats.push(entry);
+        }
+
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&report).expect("diff report serializes"));
+        } else {
+            print_diff_report(&report, &args.input);
+        }
+        Ok(if report.clean { 0 } else { EXIT_DRIFT })
+    }
+
+    fn print_diff_report(report: &DiffReport, input: &std::path::Path) {
+        let push = report.direction == crate::cli::DiffDirection::Push;
+        let (target, keep) = if push { ("the store", "push-format --replace") } else { ("local files", "pull-format --prune") };
+        for f in &report.formats {
+            if f.added.is_empty() && f.removed.is_empty() && f.changed.is_empty() {
+                println!("  {} — no differences with store/{}", f.format, report.project);
+                continue;
+            }
+            let flow = if push {
+                format!("pushing {} into store/{}", input.display(), report.project)
+            } else {
+                format!("pulling store/{} into {}", report.project, input.display())
+            };
+            println!(
+                "  {} — {} added, {} removed, {} changed by {}:",
+                f.format,
+                f.added.len(),
+                f.removed.len(),
+                f.changed.len(),
+                flow
+            );
+            for name in &f.added {
+                println!("    + {name}");
+            }
+            for name in &f.removed {
+                println!("    - {name}: only in {target} (kept unless you run {keep})");
+            }
+            for c in &f.changed {
+                println!("    ~ {}: differs in {}", c.rule, c.fields.join(", "));
+                for line in c.diff.lines() {
+                    println!("      {line}");
+                }
+            }
+        }
+        if report.clean {
+            println!("No differences.");
+        }
+    }
+
+    pub fn doctor(args: DoctorArgs) -> anyhow::Result<()> {
+        let config = Config::load()?;
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+        println!("Store: {}", store.path.display());
+
+        let index = if args.reindex {
+            let index = store.rebuild_index()?;
+            println!("Rebuilt {}.", store::INDEX_FILE);
+            index
+        } else {
+            store.index()?
+        };
+
+        let rule_count: usize = index.projects.values().map(|p| p.rules.len()).sum();
+        println!("{} project(s), {} rule(s)", index.projects.len(), rule_count);
 
-        println!("Stored: {} ({})", stored.name.as_deref().unwrap_or(&args.name), namespace);
+        let unparsed: Vec<_> = index.projects.values().flat_map(|p| &p.unparsed).collect();
+        if unparsed.is_empty() {
+            println!("All rule files parse.");
+        } else {
+            println!("{} rule file(s) failed to parse:", unparsed.len());
+            for stamp in unparsed {
+                println!("  {}", stamp.path);
+            }
+        }
+
+        let orphans: Vec<_> = index
+            .projects
+            .keys()
+            .map(|p| store.orphan_files(p))
+            .collect::<polyrc_core::Result<Vec<_>>>()?
+            .concat();
+        if !orphans.is_empty() {
+            println!("{} non-rule file(s) in project directories (ignored by polyrc):", orphans.len());
+            for f in &orphans {
+                println!("  {}", f.display());
+            }
+        }
+        let empty = store.empty_projects()?;
+        if !empty.is_empty() {
+            println!("{} empty project(s): {} — remove with `polyrc store prune`", empty.len(), empty.join(", "));
+        }
+
+        let tracked = sync::git_tracked_ignored(&store.path).unwrap_or_default();
+        if tracked.is_empty() {
+            return Ok(());
+        }
+        println!("{} tracked file(s) match .gitignore:", tracked.len());
+        for file in &tracked {
+            println!("  {}", file);
+        }
+        if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+            println!("Untrack them with `git rm --cached` in {}.", store.path.display());
+            return Ok(());
+        }
+        if confirm("Untrack these files (they stay on disk)?")? {
+            sync::git_untrack(&store.path, &tracked)?;
+            sync::git_commit(&store.path, StoreCommit::Untrack, "").context("git commit failed")?;
+            println!("Untracked {} file(s).", tracked.len());
+        }
         Ok(())
     }
 
-    pub fn pull_rule(args: PullRuleArgs) -> anyhow::Result<()> {
+    pub fn undo(args: UndoArgs) -> anyhow::Result<()> {
         let config = Config::load()?;
-        let store_path = config.store_path();
-        let store = Store::open(&store_path).context("store not initialized — run `polyrc init` first")?;
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+        let candidates = sync::undo::undo_candidates(&store.path, args.steps as usize)?;
+        if candidates.is_empty() {
+            println!("Nothing to undo: no polyrc commits in {}.", store.path.display());
+            return Ok(());
+        }
+        if candidates.len() < args.steps as usize {
+            println!("Only {} polyrc commit(s) to undo.", candidates.len());
+        }
 
-        // Determine which namespace to search
-        let search_ns: Option<String> = if args.user {
-            Some(store::USER_PROJECT.to_string())
+        println!("Commits to revert (newest first):");
+        for c in &candidates {
+            println!("  {} {}{}", short_sha(&c.sha), c.subject, if c.pushed { "  (pushed)" } else { "" });
+        }
+        let pushed = candidates.iter().filter(|c| c.pushed).count();
+        if pushed > 0 && !args.allow_pushed {
+            anyhow::bail!(
+                "{} of these commit(s) are already on the remote; pass --allow-pushed to revert them anyway and `polyrc sync` afterwards",
+                pushed
+            );
+        }
+        if !args.yes {
+            if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+                anyhow::bail!("not reverting without confirmation; pass --yes");
+            }
+            if !confirm("Revert these commits?")? {
+                println!("Nothing changed.");
+                return Ok(());
+            }
+        }
+
+        let shas: Vec<&str> = candidates.iter().map(|c| c.sha.as_str()).collect();
+        let reverted = sync::undo::git_revert(&store.path, &shas)
+            .context("could not revert; the store was left unchanged")?;
+        for (c, files) in candidates.iter().zip(&reverted) {
+            println!("Reverted {} {}", short_sha(&c.sha), c.subject);
+            for f in files {
+                let what = match f.status {
+                    'A' => "restored",
+                    'D' => "removed",
+                    _ => "rolled back",
+                };
+                println!("    {what} {}", f.path);
+            }
+        }
+        if pushed > 0 {
+            println!("Run `polyrc sync` to push the revert(s).");
+        }
+        Ok(())
+    }
+
+    fn short_sha(sha: &str) -> &str {
+        sha.get(..7).unwrap_or(sha)
+    }
+
+    pub fn store(args: StoreArgs) -> anyhow::Result<()> {
+        let config = Config::load()?;
+        // Restoring must work when the live store is missing or broken
+        if let StoreCommands::Restore { bundle, store: target, force } = &args.command {
+            return restore_store(&config, bundle, target.as_deref(), *force);
+        }
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+        match args.command {
+            StoreCommands::Index => {
+                let index = store.rebuild_index()?;
+                let rule_count: usize = index.projects.values().map(|p| p.rules.len()).sum();
+                println!("Indexed {} project(s), {} rule(s).", index.projects.len(), rule_count);
+                if store.write_readme(&index)? {
+                    sync::git_commit(&store.path, StoreCommit::Regenerate, store::README_FILE).context("git commit failed")?;
+                    println!("Updated {}.", store.path.join(store::README_FILE).display());
+                } else {
+                    println!("{} is up to date.", store::README_FILE);
+                }
+            }
+            StoreCommands::Prune => {
+                let pruned = store.prune_empty_projects()?;
+                if pruned.is_empty() {
+                    println!("No empty projects.");
+                    return Ok(());
+                }
+                for p in &pruned {
+                    println!("Removed empty project '{}'.", p);
+                }
+                let detail = format!("{} empty project(s)", pruned.len());
+                sync::git_commit(&store.path, StoreCommit::Prune, &detail).context("git commit failed")?;
+            }
+            StoreCommands::Normalize => {
+                let rewritten = store.normalize()?;
+                if rewritten.is_empty() {
+                    println!("All rule files are already canonical.");
+                    return Ok(());
+                }
+                for p in &rewritten {
+                    println!("Normalized {}", p.display());
+                }
+                let detail = format!("{} rule file(s)", rewritten.len());
+                sync::git_commit(&store.path, StoreCommit::Normalize, &detail).context("git commit failed")?;
+            }
+            StoreCommands::Backup { output } => {
+                let file = output.clone().unwrap_or_else(|| store::timestamped_backup_path(&store::backups_dir()));
+                store.backup(&file).context("backup failed")?;
+                println!("Backed up {} → {}", store.path.display(), file.display());
+                if output.is_none() {
+                    for old in store::rotate_backups(&store::backups_dir(), config.backup_retention())? {
+                        println!("Removed old backup {}", old.display());
+                    }
+                }
+            }
+            StoreCommands::Move { path, copy } => move_store(&store, &path, copy)?,
+            StoreCommands::Restore { .. } => unreachable!("handled before opening the store"),
+            StoreCommands::Unshallow => {
+                if !sync::git_is_shallow(&store.path)? {
+                    println!("Store already has its full history.");
+                    return Ok(());
+                }
+                sync::git_unshallow(&store.path).context("git fetch --unshallow failed")?;
+                println!("Fetched the full history into {}.", store.path.display());
+            }
+        }
+        Ok(())
+    }
+
+    /// `polyrc store move`: relocate the store, check git still works there,
+    /// then set `[store] path`. Any failure puts the store and config.toml
+    /// back as they were.
+    fn move_store(store: &Store, dest: &std::path::Path, copy: bool) -> anyhow::Result<()> {
+        let dest = std::path::absolute(dest).with_context(|| format!("invalid path {}", dest.display()))?;
+        let config_path = polyrc_core::config::config_file_path();
+        let original_config = std::fs::read(&config_path).ok();
+        let method = store::relocate(&store.path, &dest, copy).context("could not move the store")?;
+
+        let updated = sync::git_check_repo(&dest).and_then(|()| {
+            let mut config = Config::load()?;
+            config.store.path = Some(dest.display().to_string());
+            config.save()
+        });
+        if let Err(e) = updated {
+            let _ = match &original_config {
+                Some(raw) => std::fs::write(&config_path, raw),
+                None => std::fs::remove_file(&config_path),
+            };
+            if copy {
+                let _ = std::fs::remove_dir_all(&dest);
+            } else {
+                let _ = store::relocate(&dest, &store.path, false);
+            }
+            return Err(e).context("moved store failed its check; the move was undone");
+        }
+
+        let how = match (copy, method) {
+            (true, _) => "Copied",
+            (false, store::MoveMethod::Renamed) => "Moved",
+            (false, store::MoveMethod::Copied) => "Moved (copied across filesystems)",
+        };
+        println!("{how} {} → {}", store.path.display(), dest.display());
+        println!("Set [store] path in {}.", config_path.display());
+        if std::env::var_os(store::STORE_ENV).is_some() {
+            eprintln!("warning: ${} is set and still takes precedence over config.toml", store::STORE_ENV);
+        }
+        Ok(())
+    }
+
+    /// `polyrc store restore`: clone `bundle` into `target` (default: the
+    /// configured store). An existing store there is only replaced with `force`,
+    /// after backing it up.
+    fn restore_store(config: &Config, bundle: &std::path::Path, target: Option<&std::path::Path>, force: bool) -> anyhow::Result<()> {
+        let bundle = std::fs::canonicalize(bundle).with_context(|| format!("cannot read {}", bundle.display()))?;
+        let dest = match target {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let env = std::env::var(store::STORE_ENV).ok();
+                let cwd = std::env::current_dir().unwrap_or_else(|_| ".".into());
+                store::resolve_store_path(env.as_deref(), config, &cwd)
+            }
+        };
+        let occupied = std::fs::read_dir(&dest).is_ok_and(|mut entries| entries.next().is_some());
+        if occupied {
+            if !force {
+                anyhow::bail!(
+                    "{} is not empty — restore somewhere else with --store <path>, or replace it with --force",
+                    dest.display()
+                );
+            }
+            if dest.join(".git").exists() {
+                let file = store::timestamped_backup_path(&store::backups_dir());
+                Store::new(&dest).backup(&file).context("could not back up the store being replaced")?;
+                println!("Backed up the current store to {}", file.display());
+            }
+            std::fs::remove_dir_all(&dest).with_context(|| format!("failed to remove {}", dest.display()))?;
+        }
+        sync::git_bundle_restore(&bundle, &dest, config.store.remote_url.as_deref()).context("restore failed")?;
+        println!("Restored {} → {}", bundle.display(), dest.display());
+        Ok(())
+    }
+
+    pub fn list_project(args: ListProjectArgs) -> anyhow::Result<()> {
+        let config = Config::load()?;
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+
+        if let Some(ref name) = args.name {
+            // Show rules for a specific project (name can be "user")
+            if name != store::USER_PROJECT {
+                ensure_project_exists(&store, name)?;
+            }
+            let rules = load_project_rules(&store, name, args.strict)?;
+            let stems = polyrc_core::ir::stored_filename_stems(&rules);
+            if args.json {
+                let entries: Vec<_> = rules
+                    .iter()
+                    .zip(&stems)
+                    .map(|(r, stem)| {
+                        serde_json::json!({
+                            "name": r.name.clone().unwrap_or_else(|| r.filename_stem()),
+                            "scope": r.scope,
+                            "format": r.source_format,
+                            "activation": r.activation,
+                            "updated_at": r.updated_at,
+                            "path": format!("{}/{}.yaml", name, stem),
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&entries).expect("rules serialize"));
+                return Ok(());
+            }
+            if rules.is_empty() {
+                println!("No rules in project '{}'.", name);
+                if args.verbose {
+                    print!("{}", orphans_text(&store, name)?);
+                }
+                return Ok(());
+            }
+
+            const W_CHARS: usize = 6;
+
+            // Character counts only matter for Windsurf's 6k/12k budgets
+            let windsurf = Format::Windsurf.name();
+            let show_chars = rules.iter().any(|r| r.source_format.as_deref() == Some(windsurf));
+            let chars_col = |value: &str| if show_chars { format!("{:>W_CHARS$}  ", value) } else { String::new() };
+
+            let header = rule_table_line(RULE_TABLE_HEADER, &chars_col("CHARS"), "PATH");
+            let divider = "─".repeat(header.len());
+
+            // Buffered so long verbose listings can go through the pager
+            use std::fmt::Write as _;
+            let color = crate::pager::use_color(
+                std::io::IsTerminal::is_terminal(&std::io::stdout()),
+                std::env::var("NO_COLOR").ok().as_deref(),
+            );
+            let now = chrono::Utc::now();
+            let mut out = String::new();
+            writeln!(out, "PROJECT: {} ({} rule(s))", name, rules.len())?;
+            writeln!(out, "{}", divider)?;
+            writeln!(out, "{}", header)?;
+            writeln!(out, "{}", divider)?;
+
+            for (rule, stem) in rules.iter().zip(&stems) {
+                let path = std::path::Path::new(name).join(format!("{}.yaml", stem)).display().to_string();
+                let chars = if rule.source_format.as_deref() == Some(windsurf) {
+                    polyrc_core::formats::windsurf::rule_chars(rule).to_string()
+                } else {
+                    "-".to_string()
+                };
+                writeln!(out, "{}", rule_table_row(rule, now, &chars_col(&chars), &path))?;
+
+                if args.verbose {
+                    writeln!(out, "      updated: {}", absolute_time(rule.updated_at.as_deref()))?;
+                    // Print full content
+                    for line in crate::pager::render_markdown(&rule.content, color).lines() {
+                        writeln!(out, "      {}", line)?;
+                    }
+                    writeln!(out)?;
+                }
+            }
+
+            writeln!(out, "{}", divider)?;
+            writeln!(out, "  {} rule(s)", rules.len())?;
+            if args.verbose {
+                out.push_str(&orphans_text(&store, name)?);
+                crate::pager::show(&out);
+            } else {
+                print!("{out}");
+            }
+        } else {
+            // List all projects
+            let all_projects = store.list_projects()?;
+            if all_projects.is_empty() {
+                println!("No projects in store.");
+                return Ok(());
+            }
+
+            // user first, then alphabetical
+            let mut ordered = all_projects.clone();
+            if let Some(pos) = ordered.iter().position(|n| n == store::USER_PROJECT) {
+                ordered.remove(pos);
+                ordered.insert(0, store::USER_PROJECT.to_string());
+            }
+
+            // --strict has to parse every file to surface errors; otherwise the index suffices
+            if args.strict {
+                for p in &ordered {
+                    load_project_rules(&store, p, true)?;
+                }
+            }
+            let index = store.index()?;
+            let mut hidden = 0;
+            if !args.include_empty {
+                let before = ordered.len();
+                ordered.retain(|p| index.projects.get(p).is_some_and(|pi| !pi.rules.is_empty() || !pi.unparsed.is_empty()));
+                hidden = before - ordered.len();
+            }
+
+            if args.json {
+                let entries: Vec<_> = ordered
+                    .iter()
+                    .map(|p| {
+                        let rules = index.projects.get(p).map(|pi| pi.rules.as_slice()).unwrap_or_default();
+                        let names: Vec<_> = rules.iter().map(|r| r.name.as_deref().unwrap_or("<unnamed>")).collect();
+                        serde_json::json!({ "name": p, "rules": names })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&entries).expect("projects serialize"));
+                return Ok(());
+            }
+
+            println!("Projects in store:");
+            for p in &ordered {
+                let rules = index.projects.get(p).map(|pi| pi.rules.as_slice()).unwrap_or_default();
+                if args.verbose {
+                    println!("  {} ({} rule(s)):", p, rules.len());
+                    for r in rules {
+                        println!("    - {}", r.name.as_deref().unwrap_or("<unnamed>"));
+                    }
+                } else {
+                    println!("  {} ({} rule(s))", p, rules.len());
+                }
+            }
+            println!("\nTotal: {} project(s)", ordered.len());
+            if hidden > 0 {
+                println!("{} project(s) without rules hidden; use --include-empty to show them.", hidden);
+            }
+        }
+        Ok(())
+    }
+
+    const RULE_TABLE_HEADER: [&str; 5] = ["NAME", "SCOPE", "FORMAT", "ACTIVATION", "UPDATED"];
+
+    /// A line of the `list-project` rule table: the five [`RULE_TABLE_HEADER`]
+    /// columns, then `extra` (say a CHARS column) and the path.
+    fn rule_table_line(columns: [&str; 5], extra: &str, path: &str) -> String {
+        const W_NAME: usize = 28;
+        const W_SCOPE: usize = 7;
+        const W_FMT: usize = 10;
+        const W_ACT: usize = 10;
+        const W_DATE: usize = 10;
+        let [name, scope, format, activation, updated] = columns;
+        format!("  {name:<W_NAME$}  {scope:<W_SCOPE$}  {format:<W_FMT$}  {activation:<W_ACT$}  {updated:<W_DATE$}  {extra}{path}")
+    }
+
+    /// `rule`'s row in the `list-project` rule table.
+    fn rule_table_row(rule: &Rule, now: chrono::DateTime<chrono::Utc>, extra: &str, path: &str) -> String {
+        let name = rule.name.as_deref().unwrap_or("<unnamed>");
+        let format = rule.source_format.as_deref().unwrap_or("?");
+        let scope = format!("{:?}", rule.scope).to_lowercase();
+        let activation = format!("{:?}", rule.activation).to_lowercase();
+        let updated = relative_time(rule.updated_at.as_deref(), now);
+        rule_table_line([name, &scope, format, &activation, &updated], extra, path)
+    }
+
+    pub fn search(args: SearchArgs) -> anyhow::Result<()> {
+        use polyrc_core::ir::{Activation, Scope};
+        let config = Config::load()?;
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+        let query = polyrc_core::search::Query::new(&args.query, args.regex, args.content)?;
+        let projects = match &args.project {
+            Some(p) if p == store::USER_PROJECT => vec![p.clone()],
+            Some(p) => {
+                ensure_project_exists(&store, p)?;
+                vec![p.clone()]
+            }
+            None => {
+                // user first, then alphabetical
+                let mut all = store.list_projects()?;
+                if let Some(pos) = all.iter().position(|n| n == store::USER_PROJECT) {
+                    let user = all.remove(pos);
+                    all.insert(0, user);
+                }
+                all
+            }
+        };
+        let format = args.format.as_ref().map(crate::cli::FormatArg::as_str);
+        let activation = args.activation.as_ref().map(Activation::from);
+        let scope = args.scope.as_ref().map(Scope::from);
+
+        let mut hits = vec![];
+        for project in &projects {
+            let rules = load_project_rules(&store, project, false)?;
+            let stems = polyrc_core::ir::stored_filename_stems(&rules);
+            for (rule, stem) in rules.into_iter().zip(stems) {
+                if format.is_some_and(|f| rule.source_format.as_deref() != Some(f))
+                    || activation.as_ref().is_some_and(|a| rule.activation != *a)
+                    || scope.as_ref().is_some_and(|s| rule.scope != *s)
+                {
+                    continue;
+                }
+                if let Some(hit) = query.find(&rule) {
+                    hits.push((format!("{project}/{stem}.yaml"), project.clone(), rule, hit));
+                }
+            }
+        }
+
+        if args.json {
+            let entries: Vec<_> = hits
+                .iter()
+                .map(|(path, project, r, hit)| {
+                    serde_json::json!({
+                        "project": project,
+                        "name": r.name.clone().unwrap_or_else(|| r.filename_stem()),
+                        "scope": r.scope,
+                        "format": r.source_format,
+                        "activation": r.activation,
+                        "updated_at": r.updated_at,
+                        "path": path,
+                        "matched": hit.fields,
+                        "lines": hit.lines,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries).expect("matches serialize"));
+            return Ok(());
+        }
+        if hits.is_empty() {
+            println!("No rules match '{}'.", args.query);
+            return Ok(());
+        }
+
+        let color = crate::pager::use_color(
+            std::io::IsTerminal::is_terminal(&std::io::stdout()),
+            std::env::var("NO_COLOR").ok().as_deref(),
+        );
+        let now = chrono::Utc::now();
+        let header = rule_table_line(RULE_TABLE_HEADER, "", "PATH");
+        let divider = "─".repeat(header.chars().count());
+        println!("{divider}\n{header}\n{divider}");
+        for (path, _, rule, hit) in &hits {
+            println!("{}", rule_table_row(rule, now, "", path));
+            for line in &hit.lines {
+                println!("      {:>4}: {}", line.line, crate::pager::highlight(&line.text, &line.ranges, color));
+            }
+        }
+        println!("{divider}\n  {} rule(s)", hits.len());
+        Ok(())
+    }
+
+    /// Lines listing files in a project directory that no command reads.
+    fn orphans_text(store: &Store, project: &str) -> anyhow::Result<String> {
+        let orphans = store.orphan_files(project)?;
+        let mut out = String::new();
+        if !orphans.is_empty() {
+            out.push_str(&format!("  {} non-rule file(s), ignored by polyrc:\n", orphans.len()));
+            for f in &orphans {
+                out.push_str(&format!("    {}\n", f.display()));
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn push_rule(args: PushRuleArgs) -> anyhow::Result<()> {
+        use polyrc_core::ir::{Activation, Rule};
+        let stdin_requested = args.from_file.iter().any(|f| f == STDIN_ARG);
+        if stdin_requested && args.from_file.len() > 1 {
+            anyhow::bail!("--from-file - reads one rule from stdin and can't be combined with other files");
+        }
+        let files = if stdin_requested { vec![] } else { expand_from_files(&args.from_file)? };
+        let stdin_is_tty = std::io::IsTerminal::is_terminal(&std::io::stdin());
+        let sources: Vec<(String, RuleSource)> = match (files.as_slice(), &args.from_url) {
+            ([], Some(url)) => {
+                let name = match &args.name {
+                    Some(n) => n.clone(),
+                    None => polyrc_core::fetch::name_from_url(url)
+                        .with_context(|| format!("cannot derive a rule name from {url}; pass one explicitly"))?,
+                };
+                vec![(name, RuleSource::Url(url.clone()))]
+            }
+            ([], None) if stdin_requested || !stdin_is_tty => {
+                let name = args.name.clone().context("a rule name is required when reading from stdin")?;
+                vec![(name, RuleSource::Stdin)]
+            }
+            ([], None) => {
+                let name = args.name.clone().context("a rule name is required when writing the rule in an editor")?;
+                vec![(name, RuleSource::Editor)]
+            }
+            ([file], _) => {
+                let name = match &args.name {
+                    Some(n) => n.clone(),
+                    None => file_stem_name(file)?,
+                };
+                vec![(name, RuleSource::File(file.clone()))]
+            }
+            (files, _) => files
+                .iter()
+                .map(|file| {
+                    let stem = file_stem_name(file)?;
+                    let name = args.name.as_ref().map_or(stem.clone(), |prefix| format!("{prefix}-{stem}"));
+                    Ok((name, RuleSource::File(file.clone())))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        };
+        check_push_rule_activation(&args)?;
+        for (i, (name, source)) in sources.iter().enumerate() {
+            polyrc_core::ir::validate_rule_name(name)?;
+            if let Some((_, other)) = sources[..i].iter().find(|(n, _)| n == name) {
+                anyhow::bail!("{} and {} would both be stored as '{}'", other, source, name);
+            }
+        }
+        let config = Config::load()?;
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+        let store_path = store.path.clone();
+
+        // Determine destination namespace
+        let namespace_owned;
+        let namespace: &str = if args.user {
+            store::USER_PROJECT
         } else if let Some(ref p) = args.project {
             let norm = normalize_project_name(p)
                 .with_context(|| format!("invalid project name '{}'", p))?;
-            Some(norm)
+            namespace_owned = check_project_collision(&store, p, norm)?;
+            &namespace_owned
+        } else {
+            anyhow::bail!("specify --user or --project <name> to choose where to store this rule");
+        };
+
+        let scope = if args.user {
+            Scope::User
         } else {
-            None // search all
+            Scope::Project
         };
+        let activation = Activation::from(&args.activation);
+
+        let exists = |name: &str| store.load_rule_by_name(name, Some(namespace)).map(|r| r.is_some());
+        if args.dry_run {
+            println!("Dry run: {} rule(s) → {}", sources.len(), namespace);
+            for (name, source) in &sources {
+                let action = if exists(name)? { "update" } else { "add" };
+                println!("  {:<6} {:<28} ← {}", action, name, source);
+            }
+            return Ok(());
+        }
+
+        let mut names = vec![];
+        for (name, source) in &sources {
+            let (content, source_path) = match source {
+                RuleSource::File(file) => {
+                    polyrc_core::parser::check_file_size(file)?;
+                    let content = std::fs::read_to_string(file)
+                        .with_context(|| format!("failed to read {}", file.display()))?;
+                    let source = std::path::absolute(file).unwrap_or_else(|_| file.clone());
+                    (content, Some(source.display().to_string()))
+                }
+                RuleSource::Url(url) => {
+                    let fetched = polyrc_core::fetch::fetch_text(url, polyrc_core::parser::max_file_size())?;
+                    (fetched.content, Some(fetched.url))
+                }
+                RuleSource::Stdin => (read_rule_from_stdin(stdin_is_tty)?, None),
+                RuleSource::Editor => (write_rule_in_editor(&config, name)?, None),
+            };
+            let rule = Rule {
+                name: Some(name.clone()),
+                scope: scope.clone(),
+                activation: activation.clone(),
+                globs: (!args.globs.is_empty()).then(|| args.globs.clone()),
+                description: args.description.clone(),
+                content: content.trim_end().to_string(),
+                source_path,
+                ..Default::default()
+            };
+
+            let verb = if exists(name)? { "Updated" } else { "Pushed" };
+            let stored = store.save_rule_to_namespace(namespace, name, &rule)?;
+            println!(
+                "{} '{}' → {}",
+                verb,
+                name,
+                store_path.join(namespace).join(format!("{}.yaml", name)).display()
+            );
+            names.push(stored.name.unwrap_or_else(|| name.clone()));
+        }
+
+        sync::git_commit(&store_path, StoreCommit::PushRule, &names.join(", "))
+            .context("git commit failed")?;
+
+        println!("Stored: {} ({})", names.join(", "), namespace);
+        println!("  activation:   {}", activation.as_str());
+        if !args.globs.is_empty() {
+            println!("  globs:        {}", args.globs.join(", "));
+        }
+        if let Some(d) = &args.description {
+            println!("  description:  {}", d);
+        }
+        Ok(())
+    }
+
+    /// `--from-file` value that reads the rule from stdin.
+    const STDIN_ARG: &str = "-";
+
+    /// Where `push-rule` reads a rule's content.
+    enum RuleSource {
+        File(std::path::PathBuf),
+        Url(String),
+        Stdin,
+        Editor,
+    }
+
+    impl std::fmt::Display for RuleSource {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::File(path) => write!(f, "{}", path.display()),
+                Self::Url(url) => f.write_str(url),
+                Self::Stdin => f.write_str("stdin"),
+                Self::Editor => f.write_str("editor"),
+            }
+        }
+    }
+
+    /// Read a rule from stdin to EOF, up to the file size limit. Empty (or
+    /// whitespace-only) input is an error.
+    fn read_rule_from_stdin(is_tty: bool) -> anyhow::Result<String> {
+        use std::io::Read;
+        if is_tty {
+            eprintln!("Type or paste the rule, then press Ctrl-D on an empty line:");
+        }
+        let limit = polyrc_core::parser::max_file_size();
+        let mut content = String::new();
+        std::io::stdin()
+            .take(limit + 1)
+            .read_to_string(&mut content)
+            .context("failed to read the rule from stdin")?;
+        if content.len() as u64 > limit {
+            anyhow::bail!("rule on stdin is over the {limit}-byte limit; raise it with --max-file-size");
+        }
+        let content = content.trim_end().to_string();
+        if content.trim().is_empty() {
+            anyhow::bail!("no rule content on stdin");
+        }
+        Ok(content)
+    }
 
-        let (namespace, rule) = store.load_rule_by_name(&args.name, search_ns.as_deref())?
-            .with_context(|| {
-                let location = search_ns.as_deref()
-                    .map(|ns| format!("in project '{}'", ns))
-                    .unwrap_or_else(|| "in any project".to_string());
-                format!("rule '{}' not found {}", args.name, location)
-            })?;
+    /// Open an empty temp file in the configured editor and return the rule
+    /// written there. An empty file or a failed editor saves nothing.
+    fn write_rule_in_editor(config: &Config, name: &str) -> anyhow::Result<String> {
+        // `name` already passed validate_rule_name, so it is a file name
+        let temp = std::env::temp_dir().join(format!("polyrc-new-{}-{name}.md", std::process::id()));
+        std::fs::write(&temp, "").with_context(|| format!("failed to write {}", temp.display()))?;
+        let written = run_editor(config, &temp);
+        let _ = std::fs::remove_file(&temp);
+        let content = written?.trim_end().to_string();
+        if content.trim().is_empty() {
+            anyhow::bail!("the editor left the rule empty; nothing saved");
+        }
+        Ok(content)
+    }
 
-        let fmt = crate::formats::Format::from_str(args.format.as_str())
-            .with_context(|| format!("unknown format '{}'", args.format.as_str()))?;
-        let writer = fmt.writer();
+    /// `--activation glob` needs a `--glob`; globs on another activation and an
+    /// ai-decides rule without a description are allowed but warned about.
+    fn check_push_rule_activation(args: &PushRuleArgs) -> anyhow::Result<()> {
+        match args.activation {
+            ActivationArg::Glob if args.globs.is_empty() => {
+                anyhow::bail!("--activation glob needs at least one --glob <pattern>")
+            }
+            ActivationArg::Glob => {}
+            _ if !args.globs.is_empty() => {
+                eprintln!("warning: --glob is ignored by writers unless --activation glob is set");
+            }
+            _ => {}
+        }
+        if matches!(args.activation, ActivationArg::AiDecides) && args.description.is_none() {
+            eprintln!(
+                "warning: ai-decides rules are picked by their description and most formats need one; pass --description"
+            );
+        }
+        Ok(())
+    }
+
+    /// `--from-file` values as files: globs are expanded (sorted), plain paths
+    /// kept as given. A glob that matches nothing is an error.
+    fn expand_from_files(patterns: &[String]) -> anyhow::Result<Vec<std::path::PathBuf>> {
+        let mut files = vec![];
+        for pattern in patterns {
+            if !pattern.contains(['*', '?', '[']) {
+                files.push(std::path::PathBuf::from(pattern));
+                continue;
+            }
+            let mut matched: Vec<_> = glob::glob(pattern)
+                .with_context(|| format!("invalid glob '{pattern}'"))?
+                .filter_map(Result::ok)
+                .filter(|p| p.is_file())
+                .collect();
+            if matched.is_empty() {
+                anyhow::bail!("no files match '{pattern}'");
+            }
+            matched.sort();
+            files.extend(matched);
+        }
+        files.dedup();
+        Ok(files)
+    }
+
+    fn file_stem_name(file: &std::path::Path) -> anyhow::Result<String> {
+        file.file_stem()
+            .and_then(|s| s.to_str())
+            .map(str::to_string)
+            .with_context(|| format!("cannot derive a rule name from {}; pass one explicitly", file.display()))
+    }
+
+    pub fn pull_rule(args: PullRuleArgs) -> anyhow::Result<()> {
+        let config = Config::load()?;
+        // With --from-repo, read from a temp clone of the other store; dropped (deleted) on return
+        let remote = match &args.from_repo {
+            Some(url) => Some(sync::git_clone_temp(url).with_context(|| format!("failed to fetch {url}"))?),
+            None => None,
+        };
+        let store = match &remote {
+            Some(clone) => Store::open_read_only(&clone.path)?,
+            None => Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?,
+        };
+
+        // --save copies into the --project or --user of my own store; check it before writing anything
+        let save_to = match &args.from_repo {
+            Some(_) if args.save => {
+                let mine = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+                let Some(ns) = rule_namespace(&mine, args.user, args.project.as_deref())? else {
+                    anyhow::bail!("--save needs --project <name> or --user to choose where in your store to save the rule");
+                };
+                Some((mine, ns))
+            }
+            _ => None,
+        };
+
+        let search_ns = rule_namespace(&store, args.user, args.project.as_deref())?;
+        let Some((namespace, rule)) = store.load_rule_by_name(&args.name, search_ns.as_deref())? else {
+            return Err(polyrc_core::error::PolyrcError::RuleNotFound {
+                name: args.name.clone(),
+                namespace: search_ns.clone(),
+                available: store.rule_names(search_ns.as_deref())?,
+            }
+            .into());
+        };
+
+        let formats = if args.format.iter().any(|f| f.eq_ignore_ascii_case("all")) {
+            Format::all().to_vec()
+        } else {
+            let mut formats = args.format.iter().map(|f| Format::from_str(f)).collect::<Result<Vec<_>, _>>()?;
+            formats.dedup();
+            formats
+        };
 
         let target = if let Some(ref out) = args.output {
             out.clone()
@@ -602,13 +2308,145 @@ mod commands {
             std::env::current_dir().context("failed to get current directory")?
         };
 
-        writer.write(std::slice::from_ref(&rule), &target)
-            .with_context(|| format!("failed to write rule as {}", fmt.name()))?;
+        let origin = match &args.from_repo {
+            Some(url) => format!("{url} ({namespace})"),
+            None => namespace.clone(),
+        };
+        println!("Pulling '{}' from {} into {}", args.name, origin, target.display());
+        let mut failed = vec![];
+        for fmt in &formats {
+            match pull_rule_as(fmt, &rule, &target, args.force, args.append) {
+                Ok(Ok(files)) => println!("  {} — wrote {}", fmt.name(), files.join(", ")),
+                Ok(Err(reason)) => eprintln!("  {} — skipped: {}", fmt.name(), reason),
+                Err(e) => {
+                    eprintln!("  {} — failed: {:#}", fmt.name(), e);
+                    failed.push(fmt.name());
+                }
+            }
+        }
 
-        println!(
-            "Pulled '{}' from {} → {} format in {}",
-            args.name, namespace, fmt.name(), target.display()
-        );
+        if let Some((mine, save_ns)) = save_to
+            && let Some(url) = &args.from_repo
+        {
+            let copy = polyrc_core::ir::Rule {
+                id: String::new(),
+                project: None,
+                created_at: None,
+                updated_at: None,
+                source_path: Some(format!("{url}#{namespace}/{}", args.name)),
+                ..rule
+            };
+            // Named like the store names its files: a nested name like `backend/api` isn't a file name
+            let stored = mine.save_rule_to_namespace(&save_ns, &copy.filename_stem(), &copy)?;
+            sync::git_commit(&mine.path, StoreCommit::PullRule, &format!("{} from {}", args.name, url))
+                .context("git commit failed")?;
+            println!("Saved '{}' to your store ({}) as {}", args.name, save_ns, stored.id);
+        }
+        if !failed.is_empty() {
+            anyhow::bail!("could not write '{}' as {}", args.name, failed.join(", "));
+        }
+        Ok(())
+    }
+
+    /// Write one rule as `fmt` into `target`. Returns the files written, or
+    /// why the format was skipped: it has nothing to write for this rule, or
+    /// (without `force`) it would replace files that hold something else.
+    /// With `append`, an existing single-file config gets the rule as a section.
+    fn pull_rule_as(
+        fmt: &Format,
+        rule: &polyrc_core::ir::Rule,
+        target: &std::path::Path,
+        force: bool,
+        append: bool,
+    ) -> anyhow::Result<Result<Vec<String>, String>> {
+        let mut rules = vec![rule.clone()];
+        let unsupported = ops::apply_activation_policy(fmt, &mut rules, polyrc_core::writer::activation_policy())?;
+        if rules.is_empty() {
+            return Ok(Err(format!("can't express {}", unsupported.join(", "))));
+        }
+        if fmt.is_plugin() {
+            fmt.writer().write(&rules, target)?;
+            return Ok(Ok(vec![target.display().to_string()]));
+        }
+        let planned = polyrc_core::lock::plan(fmt, &rules, target, &Default::default())?;
+        if planned.is_empty() {
+            return Ok(Err("nothing to write for this rule".to_string()));
+        }
+        let single = fmt.single_files().iter().find(|(key, _)| planned.contains_key(*key) && target.join(key).is_file());
+        if append && let Some((key, whole_file)) = single {
+            let file = target.join(key);
+            let existing = std::fs::read_to_string(&file).with_context(|| format!("failed to read {}", file.display()))?;
+            let (merged, report) = polyrc_core::formats::sections::merge(&existing, &rules, whole_file);
+            std::fs::write(&file, merged).with_context(|| format!("failed to write {}", file.display()))?;
+            let how = if report.replaced.is_empty() { "added" } else { "replaced" };
+            let name = rule.name.as_deref().unwrap_or("Rule");
+            return Ok(Ok(vec![format!("{key} ({how} section '{name}')")]));
+        }
+        let merges = rule.scope == Scope::User && fmt.merges_user_rules();
+        let clobbered: Vec<&String> = planned
+            .iter()
+            .filter(|(key, file)| std::fs::read(target.join(key)).is_ok_and(|existing| existing != file.content))
+            .map(|(key, _)| key)
+            .collect();
+        if !clobbered.is_empty() && !force && !merges {
+            let files: Vec<&str> = clobbered.iter().map(|k| k.as_str()).collect();
+            return Ok(Err(format!("would replace {} (pass --force)", files.join(", "))));
+        }
+        fmt.writer().write(&rules, target)?;
+        Ok(Ok(planned.into_keys().collect()))
+    }
+
+    pub fn edit_rule(args: EditRuleArgs) -> anyhow::Result<()> {
+        let config = Config::load()?;
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+        let search_ns = rule_namespace(&store, args.user, args.project.as_deref())?;
+        let Some((namespace, file, rule)) = store.locate_rule(&args.name, search_ns.as_deref())? else {
+            return Err(polyrc_core::error::PolyrcError::RuleNotFound {
+                name: args.name.clone(),
+                namespace: search_ns.clone(),
+                available: store.rule_names(search_ns.as_deref())?,
+            }
+            .into());
+        };
+
+        // The editor gets a temp copy, so an aborted edit never touches the store
+        let (original, suffix) = if args.raw {
+            (std::fs::read_to_string(&file).with_context(|| format!("failed to read {}", file.display()))?, "yaml")
+        } else {
+            (rule.content.trim_end().to_string() + "\n", "md")
+        };
+        let temp = std::env::temp_dir().join(format!("polyrc-edit-{}-{}.{suffix}", std::process::id(), rule.filename_stem()));
+        std::fs::write(&temp, &original).with_context(|| format!("failed to write {}", temp.display()))?;
+        let edited = run_editor(&config, &temp);
+        let _ = std::fs::remove_file(&temp);
+        let edited = edited?;
+        if edited.trim_end() == original.trim_end() {
+            println!("No changes to '{}' in {}.", args.name, namespace);
+            return Ok(());
+        }
+
+        let updated = if args.raw {
+            store::rule_from_yaml(&edited, &file)
+                .with_context(|| format!("edited YAML for '{}' is not a valid rule; nothing saved", args.name))?
+        } else {
+            Rule { content: edited.trim_end().to_string(), ..rule }
+        };
+        store.update_rule_file(&file, &updated)?;
+        sync::git_commit(&store.path, StoreCommit::EditRule, &format!("{} ({namespace})", args.name)).context("git commit failed")?;
+        println!("Saved '{}' in {}.", args.name, namespace);
+        Ok(())
+    }
+
+    /// `move-rule`, or `copy-rule` with `copy`.
+    pub fn move_rule(args: MoveRuleArgs, copy: bool) -> anyhow::Result<()> {
+        let config = Config::load()?;
+        let store = Store::open_resolved(&config).context("store not initialized — run `polyrc init` first")?;
+        let from = rule_namespace(&store, args.from == store::USER_PROJECT, Some(&args.from))?.unwrap_or_default();
+        let to = rule_namespace(&store, args.to == store::USER_PROJECT, Some(&args.to))?.unwrap_or_default();
+        store.transfer_rule(&args.name, &from, &to, copy, args.force)?;
+        let kind = if copy { StoreCommit::CopyRule } else { StoreCommit::MoveRule };
+        sync::git_commit(&store.path, kind, &format!("{} from {from} to {to}", args.name)).context("git commit failed")?;
+        println!("{} '{}' from {} to {}.", if copy { "Copied" } else { "Moved" }, args.name, from, to);
         Ok(())
     }
 
@@ -633,6 +2471,139 @@ mod commands {
 
     // ── helpers ──────────────────────────────────────────────────────────────
 
+    /// The namespace `--user` / `--project` select for a rule lookup, checked to
+    /// exist; `None` searches every project.
+    fn rule_namespace(store: &Store, user: bool, project: Option<&str>) -> anyhow::Result<Option<String>> {
+        let search_ns = if user {
+            Some(store::USER_PROJECT.to_string())
+        } else if let Some(p) = project {
+            Some(normalize_project_name(p).with_context(|| format!("invalid project name '{}'", p))?)
+        } else {
+            None
+        };
+        if let Some(ns) = search_ns.as_deref().filter(|ns| *ns != store::USER_PROJECT) {
+            ensure_project_exists(store, ns)?;
+        }
+        Ok(search_ns)
+    }
+
+    /// Open `file` in the configured editor and return what it holds once the
+    /// editor exits. A non-zero exit is an error, so nothing gets saved.
+    fn run_editor(config: &Config, file: &std::path::Path) -> anyhow::Result<String> {
+        let editor = config.editor(std::env::var("EDITOR").ok().as_deref());
+        // `code --wait` and the like: the first word is the program
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or_default();
+        let status = std::process::Command::new(program)
+            .args(words)
+            .arg(file)
+            .status()
+            .with_context(|| format!("failed to run editor `{editor}`"))?;
+        if !status.success() {
+            anyhow::bail!("editor `{editor}` exited with {status}; nothing saved");
+        }
+        std::fs::read_to_string(file).with_context(|| format!("failed to read {}", file.display()))
+    }
+
+    /// Guard against surprising project names before a push writes to the store.
+    ///
+    /// - If `raw` normalizes to something different, tell the user what it became.
+    /// - If `norm` doesn't exist yet but an existing project differs from it only by
+    ///   case or punctuation, ask whether they meant that one instead of silently
+    ///   creating a sibling. Non-interactive sessions get an error instead of a prompt.
+    ///
+    /// Returns the project key to use.
+    fn check_project_collision(store: &Store, raw: &str, norm: String) -> anyhow::Result<String> {
+        let existing = store.list_projects()?;
+
+        if existing.contains(&norm) {
+            if raw != norm {
+                println!("Project '{}' normalized to existing project '{}'.", raw, norm);
+            }
+            return Ok(norm);
+        }
+
+        let folded = fold_project_name(&norm);
+        if let Some(similar) = existing
+            .iter()
+            .find(|p| p.as_str() != store::USER_PROJECT && fold_project_name(p) == folded)
+        {
+            if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+                anyhow::bail!(
+                    "project '{}' does not exist, but '{}' differs only by case or punctuation; \
+                     refusing to create a near-duplicate without confirmation",
+                    norm, similar
+                );
+            }
+            let question = format!(
+                "Project '{}' does not exist, but '{}' does. Use '{}' instead?",
+                norm, similar, similar
+            );
+            if confirm(&question)? {
+                return Ok(similar.clone());
+            }
+        }
+
+        if raw != norm {
+            println!("Project '{}' normalized to '{}'.", raw, norm);
+        }
+        let close = suggest::suggest(&norm, &existing);
+        if !close.is_empty() {
+            println!("Creating new project '{}'.{}", norm, suggest::did_you_mean(&close));
+        }
+        Ok(norm)
+    }
+
+    /// Case- and punctuation-insensitive key used to detect near-duplicate project names.
+    fn fold_project_name(name: &str) -> String {
+        name.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    /// Ask a yes/no question on stdin. Defaults to yes on empty input.
+    fn confirm(question: &str) -> anyhow::Result<bool> {
+        use std::io::Write;
+        print!("{} [Y/n] ", question);
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        let answer = answer.trim().to_lowercase();
+        Ok(answer.is_empty() || answer == "y" || answer == "yes")
+    }
+
+    /// Error with a did-you-mean hint when `project` is not a directory in the store.
+    fn ensure_project_exists(store: &Store, project: &str) -> anyhow::Result<()> {
+        let projects = store.list_projects()?;
+        if projects.iter().any(|p| p == project) {
+            return Ok(());
+        }
+        Err(polyrc_core::error::PolyrcError::ProjectNotFound { name: project.to_string(), available: projects }.into())
+    }
+
+    /// Load a project's rules. Unparseable files are reported as warnings and
+    /// skipped, unless `strict` is set, in which case the first one is an error.
+    fn load_project_rules(store: &Store, project: &str, strict: bool) -> anyhow::Result<Vec<polyrc_core::ir::Rule>> {
+        if strict {
+            return Ok(store.load_rules(Some(project))?);
+        }
+        let loaded = store.load_rules_lossy(Some(project))?;
+        for (path, err) in &loaded.skipped {
+            eprintln!("warning: skipping unreadable rule file {}: {}", path.display(), err);
+        }
+        Ok(loaded.rules)
+    }
+
+    /// Map a project argument to its store key: "user" passes through, anything
+    /// else is normalized.
+    fn project_key_arg(name: &str) -> anyhow::Result<String> {
+        if name == store::USER_PROJECT {
+            return Ok(name.to_string());
+        }
+        normalize_project_name(name).with_context(|| format!("invalid project name '{}'", name))
+    }
+
     /// Resolve (user_mode, project_key) from --user / --project flags.
     /// Errors if neither is given.
     fn resolve_routing(user: bool, project: Option<&str>) -> anyhow::Result<(bool, String)> {
@@ -646,14 +2617,82 @@ mod commands {
         }
     }
 
-    fn print_rules_preview(rules: &[crate::ir::Rule]) {
-        for (i, rule) in rules.iter().enumerate() {
-            println!("\n--- Rule {} ({:?}/{:?}) ---", i + 1, rule.scope, rule.activation);
-            if let Some(n) = &rule.name { println!("name: {}", n); }
-            if let Some(d) = &rule.description { println!("description: {}", d); }
-            let preview = rule.content.len().min(200);
-            println!("{}", &rule.content[..preview]);
-            if rule.content.len() > 200 { println!("... ({} chars total)", rule.content.len()); }
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn normalize_camel_cases_separators() {
+            assert_eq!(normalize_project_name("my-app").unwrap(), "myApp");
+            assert_eq!(normalize_project_name("My App").unwrap(), "myApp");
+            assert_eq!(normalize_project_name("my_app").unwrap(), "myApp");
+        }
+
+        #[test]
+        fn normalize_rejects_cyrillic() {
+            let err = normalize_project_name("данные").unwrap_err();
+            assert!(err.to_string().contains("non-ASCII"));
+        }
+
+        #[test]
+        fn normalize_rejects_emoji() {
+            assert!(normalize_project_name("my🦀app").is_err());
+        }
+
+        #[test]
+        fn normalize_accepts_digits_only() {
+            assert_eq!(normalize_project_name("2024").unwrap(), "2024");
+        }
+
+        #[test]
+        fn normalize_rejects_reserved_user() {
+            assert!(normalize_project_name("User").is_err());
+        }
+
+        fn store_with(projects: &[(&str, &[&str])]) -> (tempfile::TempDir, Store) {
+            let dir = tempfile::tempdir().unwrap();
+            let store = Store::new(dir.path());
+            for (project, rules) in projects {
+                std::fs::create_dir_all(dir.path().join(project)).unwrap();
+                let rules: Vec<polyrc_core::ir::Rule> = rules
+                    .iter()
+                    .map(|n| polyrc_core::ir::Rule { name: Some(n.to_string()), ..Default::default() })
+                    .collect();
+                store.save_rules(Some(project), &rules, "cursor").unwrap();
+            }
+            (dir, store)
+        }
+
+        #[test]
+        fn missing_project_suggests_close_name() {
+            let (_dir, store) = store_with(&[("myApp", &[]), ("other", &[])]);
+            assert!(ensure_project_exists(&store, "myApp").is_ok());
+            let msg = ensure_project_exists(&store, "myAp").unwrap_err().to_string();
+            assert!(msg.contains("Did you mean 'myApp'?"), "{msg}");
+            assert!(msg.contains("Available projects: myApp, other."), "{msg}");
+        }
+
+        #[test]
+        fn missing_project_without_suggestion() {
+            let (_dir, store) = store_with(&[("myApp", &[])]);
+            let msg = ensure_project_exists(&store, "zzzzzz").unwrap_err().to_string();
+            assert!(!msg.contains("Did you mean"), "{msg}");
+        }
+
+        #[test]
+        fn rule_names_feed_suggestions() {
+            let (_dir, store) = store_with(&[("myApp", &["rust-style", "ts-style"]), ("user", &["tone"])]);
+            let names = store.rule_names(None).unwrap();
+            assert_eq!(suggest::suggest("rust-stye", &names), vec!["rust-style"]);
+            assert!(suggest::suggest("unrelated", &names).is_empty());
+            assert_eq!(store.rule_names(Some("user")).unwrap(), vec!["tone"]);
+        }
+
+        #[test]
+        fn fold_ignores_case_and_punctuation() {
+            assert_eq!(fold_project_name("myApp"), fold_project_name("my_app"));
+            assert_eq!(fold_project_name("MyApp"), "myapp");
+            assert_ne!(fold_project_name("myApp"), fold_project_name("myApp2"));
         }
     }
 }
@@ -0,0 +1,150 @@
+//! Terminal output for long listings: minimal markdown styling and paging
+//! through `$PAGER` (default `less -R`). Both are off when stdout isn't a
+//! terminal, and styling is off when `NO_COLOR` is set. JSON output must never
+//! go through here.
+
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+/// Terminal height assumed when `$LINES` is unset.
+const DEFAULT_HEIGHT: usize = 24;
+
+/// Whether `lines` of output should go through the pager: only on a terminal,
+/// and only when it wouldn't fit.
+pub fn should_page(is_tty: bool, lines: usize, height: usize) -> bool {
+    is_tty && lines > height
+}
+
+/// Whether to emit ANSI styling: on a terminal, unless `NO_COLOR` is set.
+pub fn use_color(is_tty: bool, no_color: Option<&str>) -> bool {
+    is_tty && no_color.is_none_or(str::is_empty)
+}
+
+/// `text` with the byte `ranges` (sorted, non-overlapping) in bold, such as
+/// search matches. Returns `text` unchanged when `color` is off.
+pub fn highlight(text: &str, ranges: &[std::ops::Range<usize>], color: bool) -> String {
+    if !color {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut at = 0;
+    for range in ranges {
+        out.push_str(&text[at..range.start]);
+        out.push_str(&format!("{BOLD}{}{RESET}", &text[range.clone()]));
+        at = range.end;
+    }
+    out.push_str(&text[at..]);
+    out
+}
+
+/// Style markdown for the terminal: bold headings, dimmed code fences and
+/// code, colored bullet markers. Returns `text` unchanged when `color` is off.
+pub fn render_markdown(text: &str, color: bool) -> String {
+    if !color {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut in_code = false;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            out.push_str(&format!("{DIM}{line}{RESET}"));
+        } else if in_code {
+            out.push_str(&format!("{DIM}{line}{RESET}"));
+        } else if trimmed.starts_with('#') {
+            out.push_str(&format!("{indent}{BOLD}{trimmed}{RESET}"));
+        } else if let Some(marker) = bullet_marker(trimmed) {
+            let (marker, rest) = trimmed.split_at(marker);
+            out.push_str(&format!("{indent}{CYAN}{marker}{RESET}{rest}"));
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    if !text.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+/// Byte length of a list marker (`- `, `* `, `+ `, `1. `) at the start of `line`.
+fn bullet_marker(line: &str) -> Option<usize> {
+    if ["- ", "* ", "+ "].iter().any(|m| line.starts_with(m)) {
+        return Some(2);
+    }
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    (digits > 0 && line[digits..].starts_with(". ")).then_some(digits + 2)
+}
+
+/// Write `text` to stdout, through the pager when it's longer than the
+/// terminal. Falls back to printing directly when the pager can't be started.
+pub fn show(text: &str) {
+    let height = std::env::var("LINES").ok().and_then(|l| l.parse().ok()).unwrap_or(DEFAULT_HEIGHT);
+    if should_page(std::io::stdout().is_terminal(), text.lines().count(), height) && page(text) {
+        return;
+    }
+    print!("{text}");
+}
+
+/// Pipe `text` into `$PAGER` or `less -R`. `false` if no pager could be run.
+fn page(text: &str) -> bool {
+    let pager = std::env::var("PAGER").ok().filter(|p| !p.trim().is_empty()).unwrap_or_else(|| "less -R".into());
+    let mut parts = pager.split_whitespace();
+    let Some(program) = parts.next() else { return false };
+    let Ok(mut child) = Command::new(program).args(parts).stdin(Stdio::piped()).spawn() else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let _ = child.wait();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_only_long_output_on_a_terminal() {
+        assert!(should_page(true, 25, 24));
+        assert!(!should_page(true, 24, 24));
+        assert!(!should_page(false, 500, 24));
+    }
+
+    #[test]
+    fn no_color_or_a_pipe_means_plain_text() {
+        assert!(use_color(true, None));
+        assert!(use_color(true, Some("")));
+        assert!(!use_color(true, Some("1")));
+        assert!(!use_color(false, None));
+
+        let md = "# Title\n- item\n```\ncode\n```\n";
+        assert_eq!(render_markdown(md, false), md);
+    }
+
+    #[test]
+    fn highlights_match_ranges() {
+        let ranges = [4..10, 14..16];
+        assert_eq!(highlight("Run Clippy in CI", &ranges, true), "Run \x1b[1mClippy\x1b[0m in \x1b[1mCI\x1b[0m");
+        assert_eq!(highlight("Run Clippy in CI", &ranges, false), "Run Clippy in CI");
+    }
+
+    #[test]
+    fn styles_headings_fences_and_bullets() {
+        let styled = render_markdown("## Style\n  1. first\n```rust\nlet x;\n```\nplain", true);
+        let lines: Vec<&str> = styled.lines().collect();
+        assert_eq!(lines[0], "\x1b[1m## Style\x1b[0m");
+        assert_eq!(lines[1], "  \x1b[36m1. \x1b[0mfirst");
+        assert_eq!(lines[3], "\x1b[2mlet x;\x1b[0m");
+        assert_eq!(lines[5], "plain");
+        assert!(!styled.ends_with('\n'));
+    }
+}
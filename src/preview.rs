@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use polyrc_core::ir::Rule;
+use polyrc_core::plan::{DryRunPlan, FormatPlan};
+use polyrc_core::writer::ActivationPolicy;
+
+/// Truncate `s` to at most `max_chars` characters, appending `…` when cut.
+///
+/// Always splits on a char boundary, so multi-byte content (CJK, emoji) is safe.
+pub fn truncate_chars(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((byte_idx, _)) => format!("{}…", &s[..byte_idx]),
+        None => s.to_string(),
+    }
+}
+
+/// Shown for a missing or unparseable timestamp.
+pub const UNKNOWN_TIME: &str = "unknown";
+
+fn parse_time(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp?.trim()).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// How long before `now` an RFC 3339 `timestamp` was, for table columns:
+/// "today", "yesterday", "3d ago", "5w ago", "4mo ago", "2y ago". At most
+/// 9 characters.
+pub fn relative_time(timestamp: Option<&str>, now: DateTime<Utc>) -> String {
+    let Some(time) = parse_time(timestamp) else { return UNKNOWN_TIME.to_string() };
+    let days = (now - time).num_days();
+    match days {
+        ..0 => "future".to_string(),
+        0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        2..14 => format!("{days}d ago"),
+        14..60 => format!("{}w ago", days / 7),
+        60..365 => format!("{}mo ago", days / 30),
+        _ => format!("{}y ago", days / 365),
+    }
+}
+
+/// `timestamp` normalized to RFC 3339 in UTC, or [`UNKNOWN_TIME`].
+pub fn absolute_time(timestamp: Option<&str>) -> String {
+    parse_time(timestamp).map_or_else(|| UNKNOWN_TIME.to_string(), |t| t.to_rfc3339())
+}
+
+/// Print a short human-readable preview of each rule (used by `--dry-run`).
+pub fn print_rules_preview(rules: &[Rule], max_chars: usize) {
+    for (i, rule) in rules.iter().enumerate() {
+        println!("\n--- Rule {} ({:?}/{:?}) ---", i + 1, rule.scope, rule.activation);
+        if let Some(n) = &rule.name { println!("name: {}", n); }
+        if let Some(d) = &rule.description { println!("description: {}", d); }
+        println!("{}", truncate_chars(&rule.content, max_chars));
+        let total = rule.content.chars().count();
+        if total > max_chars { println!("... ({} chars total)", total); }
+    }
+}
+
+/// Print a `--dry-run --json` plan for `command`.
+pub fn print_plan(command: &str, formats: Vec<FormatPlan>) {
+    let plan = DryRunPlan { command: command.to_string(), formats };
+    println!("{}", serde_json::to_string_pretty(&plan).expect("plan serializes"));
+}
+
+/// Note on stderr which rules `format` can't activate and what `policy` did with them.
+pub fn print_unsupported_activations(format: &str, policy: Option<ActivationPolicy>, rules: &[String]) {
+    if rules.is_empty() {
+        return;
+    }
+    match policy.unwrap_or_else(polyrc_core::writer::activation_policy) {
+        ActivationPolicy::Skip => eprintln!("note: {format}: skipped {} rule(s) it can't activate: {}", rules.len(), rules.join(", ")),
+        _ => eprintln!("note: {format}: can't activate {}; written as always-on", rules.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_short_is_unchanged() {
+        assert_eq!(truncate_chars("hello", 10), "hello");
+        assert_eq!(truncate_chars("hello", 5), "hello");
+    }
+
+    #[test]
+    fn truncate_ascii_appends_ellipsis() {
+        assert_eq!(truncate_chars("hello world", 5), "hello…");
+    }
+
+    #[test]
+    fn truncate_multibyte_at_boundary() {
+        // Each char is 3 bytes — a byte-offset slice at 200 would land mid-char.
+        let jp = "日本語のスタイルガイド".repeat(30);
+        let out = truncate_chars(&jp, 200);
+        assert_eq!(out.chars().count(), 201);
+        assert!(out.ends_with('…'));
+        // Emoji are 4 bytes
+        assert_eq!(truncate_chars("🦀🚀 ship it", 2), "🦀🚀…");
+    }
+
+    #[test]
+    fn relative_time_buckets() {
+        let now = DateTime::parse_from_rfc3339("2026-03-15T12:00:00Z").unwrap().with_timezone(&Utc);
+        let ago = |ts: &str| relative_time(Some(ts), now);
+        assert_eq!(ago("2026-03-15T01:00:00Z"), "today");
+        assert_eq!(ago("2026-03-14T11:00:00+00:00"), "yesterday");
+        assert_eq!(ago("2026-03-12T12:00:00Z"), "3d ago");
+        assert_eq!(ago("2026-02-08T12:00:00Z"), "5w ago");
+        assert_eq!(ago("2025-11-01T12:00:00Z"), "4mo ago");
+        assert_eq!(ago("2024-01-01T00:00:00Z"), "2y ago");
+        assert_eq!(ago("2026-04-01T00:00:00Z"), "future");
+        // Offsets are converted, not cut off
+        assert_eq!(ago("2026-03-14T23:00:00-05:00"), "today");
+    }
+
+    #[test]
+    fn malformed_timestamps_are_unknown() {
+        let now = Utc::now();
+        for bad in ["", "?", "2025-11", "yesterday", "2025-13-40T00:00:00Z", "日本語のスタイル"] {
+            assert_eq!(relative_time(Some(bad), now), UNKNOWN_TIME, "{bad}");
+            assert_eq!(absolute_time(Some(bad)), UNKNOWN_TIME, "{bad}");
+        }
+        assert_eq!(relative_time(None, now), UNKNOWN_TIME);
+        assert_eq!(absolute_time(Some("2025-11-02T08:00:00+02:00")), "2025-11-02T06:00:00+00:00");
+    }
+
+    #[test]
+    fn truncate_emoji() {
+        assert_eq!(truncate_chars("a🦀b🦀c", 2), "a🦀…");
+    }
+}
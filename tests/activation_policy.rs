@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::Path;
+use assert_cmd::Command;
+
+/// A Claude project with one always-on rule (`CLAUDE.md`) and one on-demand
+/// slash command, which Gemini can't express.
+fn fixture(config: &str) -> tempfile::TempDir {
+    let root = tempfile::tempdir().unwrap();
+    fs::create_dir_all(root.path().join("polyrc")).unwrap();
+    fs::write(root.path().join("polyrc").join("config.toml"), config).unwrap();
+    let input = root.path().join("in");
+    fs::create_dir_all(input.join(".claude").join("commands")).unwrap();
+    fs::write(input.join("CLAUDE.md"), "Always be terse.\n").unwrap();
+    fs::write(input.join(".claude").join("commands").join("review.md"), "Review the diff.\n").unwrap();
+    fs::create_dir_all(root.path().join("out")).unwrap();
+    root
+}
+
+fn convert(home: &Path, extra: &[&str]) -> (bool, String) {
+    let out = Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", home)
+        .args(["convert", "--from", "claude", "--to", "gemini", "--input"])
+        .arg(home.join("in"))
+        .arg("--output")
+        .arg(home.join("out"))
+        .args(extra)
+        .output()
+        .unwrap();
+    (out.status.success(), String::from_utf8(out.stderr).unwrap())
+}
+
+fn gemini(home: &Path) -> Option<String> {
+    fs::read_to_string(home.join("out").join("GEMINI.md")).ok()
+}
+
+#[test]
+fn downgrade_writes_the_rule_and_says_so() {
+    let root = fixture("");
+    let (ok, stderr) = convert(root.path(), &[]);
+    assert!(ok, "{stderr}");
+    assert!(stderr.contains("gemini: can't activate review (on_demand); written as always-on"), "{stderr}");
+    assert!(gemini(root.path()).unwrap().contains("Review the diff."));
+}
+
+#[test]
+fn skip_leaves_the_rule_out() {
+    let root = fixture("");
+    let (ok, stderr) = convert(root.path(), &["--unsupported-activation", "skip"]);
+    assert!(ok, "{stderr}");
+    assert!(stderr.contains("gemini: skipped 1 rule(s) it can't activate: review (on_demand)"), "{stderr}");
+    let written = gemini(root.path()).unwrap();
+    assert!(written.contains("Always be terse.") && !written.contains("Review the diff."), "{written}");
+}
+
+#[test]
+fn error_writes_nothing() {
+    let root = fixture("");
+    let (ok, stderr) = convert(root.path(), &["--unsupported-activation", "error"]);
+    assert!(!ok);
+    assert!(stderr.contains("gemini can't express the activation of review (on_demand); nothing written"), "{stderr}");
+    assert!(gemini(root.path()).is_none());
+}
+
+#[test]
+fn config_sets_the_default_and_the_flag_overrides_it() {
+    let root = fixture("[write]\non_unsupported_activation = \"error\"\n");
+    assert!(!convert(root.path(), &[]).0);
+    assert!(gemini(root.path()).is_none());
+
+    let (ok, stderr) = convert(root.path(), &["--unsupported-activation", "downgrade"]);
+    assert!(ok, "{stderr}");
+    assert!(gemini(root.path()).unwrap().contains("Review the diff."));
+}
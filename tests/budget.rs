@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use assert_cmd::Command;
+use polyrc_core::ir::Rule;
+use polyrc_core::store::Store;
+use serde_json::Value;
+
+fn rule(name: &str, chars: usize) -> Rule {
+    Rule { name: Some(name.into()), content: "x".repeat(chars), ..Default::default() }
+}
+
+/// A store whose `app` project holds a 7,000-char rule (over Windsurf's
+/// per-file limit) and a 1,000-char one.
+fn fixture(config: &str) -> (tempfile::TempDir, PathBuf) {
+    let root = tempfile::tempdir().unwrap();
+    fs::create_dir_all(root.path().join("polyrc")).unwrap();
+    fs::write(root.path().join("polyrc").join("config.toml"), config).unwrap();
+    let store_dir = root.path().join("store");
+    fs::create_dir_all(&store_dir).unwrap();
+    fs::write(store_dir.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    Store::open_read_only(&store_dir)
+        .unwrap()
+        .save_rules(Some("app"), &[rule("huge", 7_000), rule("small", 1_000)], "claude")
+        .unwrap();
+    (root, store_dir)
+}
+
+fn budget(home: &Path, store: &Path, args: &[&str]) -> Output {
+    Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", home)
+        .env("POLYRC_STORE", store)
+        .args(["budget", "--project", "app"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn reports_chars_tokens_and_the_largest_rules() {
+    let (root, store) = fixture("");
+    let out = budget(root.path(), &store, &["--format", "windsurf", "--json"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let report: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let windsurf = &report[0];
+    assert_eq!(windsurf["format"], "windsurf");
+    assert_eq!(windsurf["chars"], 8_002);
+    assert_eq!(windsurf["tokens"], 2_001);
+    assert_eq!(windsurf["limit_chars"], 12_000);
+    assert_eq!(windsurf["over_limit"], true);
+    assert_eq!(windsurf["top_rules"][0]["rule"], "huge");
+    assert_eq!(windsurf["top_rules"][0]["tokens"], 1_750);
+}
+
+#[test]
+fn fail_over_limit_sets_the_exit_code() {
+    let (root, store) = fixture("");
+    let out = budget(root.path(), &store, &["--format", "windsurf", "--fail-over-limit"]);
+    assert_eq!(out.status.code(), Some(9));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains(".windsurf/rules/huge.md is 7001 chars, over the per-file limit of 6000"), "{stdout}");
+    assert!(stdout.contains("Largest rules: huge (7000 chars, ~1750 tokens), small (1000 chars, ~250 tokens)"), "{stdout}");
+
+    // Formats with no limit configured never fail
+    assert!(budget(root.path(), &store, &["--format", "gemini", "--fail-over-limit"]).status.success());
+}
+
+#[test]
+fn budget_chars_in_config_is_a_soft_limit() {
+    let (root, store) = fixture("[formats.gemini]\nbudget_chars = 5000\n");
+    let out = budget(root.path(), &store, &["--fail-over-limit"]);
+    assert_eq!(out.status.code(), Some(9));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let gemini = stdout.lines().find(|l| l.trim_start().starts_with("gemini")).unwrap();
+    assert!(gemini.contains("5000") && gemini.ends_with("OVER"), "{gemini}");
+    let cursor = stdout.lines().find(|l| l.trim_start().starts_with("cursor")).unwrap();
+    assert!(!cursor.contains("OVER"), "{cursor}");
+
+    let raised = budget(root.path(), &store, &["--format", "gemini", "--fail-over-limit", "--format-option", "gemini.budget_chars=20000"]);
+    assert!(raised.status.success());
+}
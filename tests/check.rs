@@ -0,0 +1,85 @@
+use std::fs;
+use std::path::Path;
+use assert_cmd::Command;
+use polyrc_core::formats::Format;
+use polyrc_core::ir::Rule;
+use polyrc_core::store::Store;
+
+/// A store checkout with project `app` and a project dir whose Cursor rules match it.
+fn fixture() -> (tempfile::TempDir, std::path::PathBuf, std::path::PathBuf) {
+    let root = tempfile::tempdir().unwrap();
+    let store_dir = root.path().join("store");
+    let repo = root.path().join("repo");
+    fs::create_dir_all(&store_dir).unwrap();
+    fs::create_dir_all(&repo).unwrap();
+
+    let rules = vec![
+        Rule { name: Some("style".into()), content: "Use tabs.\nWrap at 100.".into(), ..Default::default() },
+        Rule { name: Some("tests".into()), content: "Write tests.".into(), ..Default::default() },
+    ];
+    let store = Store::open_read_only(&store_dir).unwrap();
+    store.save_rules(Some("app"), &rules, "cursor").unwrap();
+    Format::Cursor.writer().write(&rules, &repo).unwrap();
+    (root, store_dir, repo)
+}
+
+fn check(home: &Path, store: &Path, repo: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("polyrc").unwrap();
+    cmd.env("HOME", home)
+        .env_remove("POLYRC_STORE")
+        .args(["check", "--project", "app", "--format", "cursor", "--store"])
+        .arg(store)
+        .arg("--input")
+        .arg(repo);
+    cmd
+}
+
+#[test]
+fn clean_checkout_exits_zero() {
+    let (root, store, repo) = fixture();
+    check(root.path(), &store, &repo).assert().code(0);
+}
+
+#[test]
+fn edited_rule_exits_six_with_json_report() {
+    let (root, store, repo) = fixture();
+    let file = repo.join(".cursor").join("rules").join("style.mdc");
+    let edited = fs::read_to_string(&file).unwrap().replace("Wrap at 100.", "Wrap at 80.");
+    fs::write(&file, edited).unwrap();
+    fs::remove_file(repo.join(".cursor").join("rules").join("tests.mdc")).unwrap();
+
+    let out = check(root.path(), &store, &repo).arg("--json").assert().code(6).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(report["clean"], false);
+    let drift = report["formats"][0]["drift"].as_array().unwrap();
+    assert_eq!(drift.len(), 2);
+    assert_eq!(drift[0]["rule"], "style");
+    assert_eq!(drift[0]["kind"], "modified");
+    assert_eq!(drift[0]["first_difference"]["line"], 2);
+    assert_eq!(drift[0]["first_difference"]["local"], "Wrap at 80.");
+    assert_eq!(drift[1]["rule"], "tests");
+    assert_eq!(drift[1]["kind"], "missing_locally");
+}
+
+#[test]
+fn check_does_not_mutate_store() {
+    let (root, store, repo) = fixture();
+    fs::remove_file(store.join(polyrc_core::store::INDEX_FILE)).unwrap();
+    check(root.path(), &store, &repo).assert().code(0);
+    assert!(!store.join(polyrc_core::store::INDEX_FILE).exists());
+}
+
+#[test]
+fn missing_store_or_project_exits_seven() {
+    let (root, store, repo) = fixture();
+    check(root.path(), &root.path().join("nope"), &repo).assert().code(7);
+    Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", root.path())
+        .args(["check", "--project", "other", "--format", "cursor", "--store"])
+        .arg(&store)
+        .arg("--input")
+        .arg(&repo)
+        .assert()
+        .code(7);
+}
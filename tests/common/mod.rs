@@ -0,0 +1,42 @@
+//! Helpers shared by the integration tests: `mod common;` in each test file.
+//! Every test crate compiles this module but uses only some of it.
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::process::Output;
+use assert_cmd::Command;
+
+/// The polyrc binary with `HOME` set to `home`, no `POLYRC_STORE` from the
+/// environment, and a git identity for the store commits it makes.
+pub fn polyrc_cmd(home: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("polyrc").unwrap();
+    cmd.env("HOME", home)
+        .env_remove("POLYRC_STORE")
+        .env("GIT_AUTHOR_NAME", "test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com");
+    cmd
+}
+
+/// Run polyrc with `args` from `home` (see [`polyrc_cmd`]).
+pub fn polyrc(home: &Path, args: &[&str]) -> Output {
+    polyrc_cmd(home).current_dir(home).args(args).output().unwrap()
+}
+
+/// Run git in `dir` as user `test`, failing the test if it fails. Returns stdout, trimmed.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let out = std::process::Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "git {args:?} failed: {}", String::from_utf8_lossy(&out.stderr));
+    String::from_utf8(out.stdout).unwrap().trim().to_string()
+}
+
+/// Subject of the last commit in the store at `store`.
+pub fn last_commit(store: &Path) -> String {
+    git(store, &["log", "-1", "--format=%s"])
+}
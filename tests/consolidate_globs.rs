@@ -0,0 +1,44 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::polyrc;
+
+/// Three Copilot instruction files scoped to the same TypeScript files, spelled
+/// slightly differently, plus one for Rust.
+fn copilot_repo(root: &Path) {
+    let dir = root.join(".github").join("instructions");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, apply_to) in [("api", "**/*.ts"), ("naming", "./**/*.ts"), ("tests", "**/*.ts"), ("rust", "**/*.rs")] {
+        fs::write(dir.join(format!("{name}.instructions.md")), format!("---\napplyTo: \"{apply_to}\"\n---\n{name} rules\n")).unwrap();
+    }
+}
+
+#[test]
+fn consolidation_is_off_by_default() {
+    let home = tempfile::tempdir().unwrap();
+    copilot_repo(home.path());
+    let out_dir = home.path().join("out");
+    let out = polyrc(home.path(), &["convert", "--from", "copilot", "--to", "cursor", "--input", home.path().to_str().unwrap(), "--output", out_dir.to_str().unwrap()]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(fs::read_dir(out_dir.join(".cursor").join("rules")).unwrap().count(), 4);
+}
+
+#[test]
+fn rules_with_the_same_globs_become_one() {
+    let home = tempfile::tempdir().unwrap();
+    copilot_repo(home.path());
+    let out_dir = home.path().join("out");
+    let out = polyrc(
+        home.path(),
+        &["convert", "--from", "copilot", "--to", "cursor", "--consolidate-globs", "--input", home.path().to_str().unwrap(), "--output", out_dir.to_str().unwrap()],
+    );
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let rules = out_dir.join(".cursor").join("rules");
+    let mut files: Vec<String> = fs::read_dir(&rules).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().into_owned()).collect();
+    files.sort();
+    assert_eq!(files, ["api-naming-tests.mdc", "rust.mdc"]);
+    let merged = fs::read_to_string(rules.join("api-naming-tests.mdc")).unwrap();
+    assert!(merged.contains("globs: **/*.ts\n"), "{merged}");
+    assert!(merged.contains("## api\n\napi rules\n\n## naming\n\nnaming rules\n\n## tests\n\ntests rules"), "{merged}");
+}
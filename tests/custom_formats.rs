@@ -0,0 +1,74 @@
+use std::fs;
+
+mod common;
+use common::polyrc;
+
+#[test]
+fn formats_toml_adds_usable_formats() {
+    let home = tempfile::tempdir().unwrap();
+    fs::create_dir_all(home.path().join("polyrc")).unwrap();
+    fs::write(
+        home.path().join("polyrc/formats.toml"),
+        "[formats.mytool]\nrules_glob = \".mytool/rules/*.md\"\nfrontmatter = \"cursor\"\n\n[formats.notes]\nsingle_file = \"NOTES.md\"\n",
+    )
+    .unwrap();
+
+    let out = polyrc(home.path(), &["supported-formats"]);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("mytool          mytool (.mytool/rules/*.md) (custom)"), "{stdout}");
+    assert!(stdout.contains("notes           notes (NOTES.md) (custom)"), "{stdout}");
+
+    let project = tempfile::tempdir().unwrap();
+    let rules = project.path().join(".cursor/rules");
+    fs::create_dir_all(&rules).unwrap();
+    fs::write(rules.join("ts.mdc"), "---\ndescription: TypeScript\nglobs: *.ts\n---\n\nPrefer interfaces.\n").unwrap();
+    let dir = project.path().to_str().unwrap();
+    let out = polyrc(home.path(), &["convert", "--from", "cursor", "--to", "mytool", "--input", dir, "--output", dir]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let written = fs::read_to_string(project.path().join(".mytool/rules/ts.md")).unwrap();
+    assert!(written.starts_with("---\ndescription: TypeScript\nglobs: *.ts\n---\n"), "{written}");
+
+    let out = polyrc(home.path(), &["convert", "--from", "mytool", "--to", "notes", "--input", dir, "--output", dir]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(fs::read_to_string(project.path().join("NOTES.md")).unwrap(), "Prefer interfaces.\n");
+}
+
+#[test]
+fn invalid_formats_toml_is_a_warning() {
+    let home = tempfile::tempdir().unwrap();
+    fs::create_dir_all(home.path().join("polyrc")).unwrap();
+    fs::write(home.path().join("polyrc/formats.toml"), "[formats.claude]\nsingle_file = \"X.md\"\n").unwrap();
+    let out = polyrc(home.path(), &["supported-formats"]);
+    assert!(out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("warning: custom formats not loaded") && stderr.contains("built-in"), "{stderr}");
+}
+
+#[cfg(unix)]
+#[test]
+fn plugins_from_config_toml_convert_through_the_command() {
+    use std::os::unix::fs::PermissionsExt;
+    let home = tempfile::tempdir().unwrap();
+    let bin = home.path().join("db-polyrc");
+    // Keeps the rules JSON in rules.json, standing in for a database
+    fs::write(&bin, "#!/bin/sh\ncase \"$1\" in parse) cat \"$2/rules.json\" ;; write) cat > \"$2/rules.json\" ;; esac\n").unwrap();
+    fs::set_permissions(&bin, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::create_dir_all(home.path().join("polyrc")).unwrap();
+    fs::write(home.path().join("polyrc/config.toml"), format!("[plugins.db]\ncommand = \"{}\"\n", bin.display())).unwrap();
+
+    let out = polyrc(home.path(), &["supported-formats"]);
+    assert!(String::from_utf8(out.stdout).unwrap().contains("db              db (plugin: "));
+
+    let project = tempfile::tempdir().unwrap();
+    fs::write(project.path().join("CLAUDE.md"), "Use tabs.\n").unwrap();
+    let dir = project.path().to_str().unwrap();
+    let out = polyrc(home.path(), &["convert", "--from", "claude", "--to", "db", "--input", dir, "--output", dir]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let rules: serde_json::Value = serde_json::from_str(&fs::read_to_string(project.path().join("rules.json")).unwrap()).unwrap();
+    assert_eq!(rules[0]["content"], "Use tabs.");
+
+    fs::write(project.path().join("rules.json"), "not json").unwrap();
+    let out = polyrc(home.path(), &["convert", "--from", "db", "--to", "gemini", "--input", dir, "--output", dir]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Plugin 'db' failed"));
+}
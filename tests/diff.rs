@@ -0,0 +1,41 @@
+use std::fs;
+
+mod common;
+use common::polyrc;
+
+#[test]
+fn shows_what_pull_and_push_would_change() {
+    let home = tempfile::tempdir().unwrap();
+    let rules = home.path().join(".cursor").join("rules");
+    fs::create_dir_all(&rules).unwrap();
+    fs::write(rules.join("style.mdc"), "---\nalwaysApply: true\n---\n\nUse rustfmt.\nWrap at 100.\n").unwrap();
+    fs::write(rules.join("tests.mdc"), "---\nalwaysApply: true\n---\n\nTest it.\n").unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    let out = polyrc(home.path(), &["push-format", "--format", "cursor", "--project", "app"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+
+    let out = polyrc(home.path(), &["diff", "--format", "cursor", "--project", "app"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("No differences."));
+
+    fs::write(rules.join("style.mdc"), "---\nalwaysApply: true\n---\n\nUse rustfmt.\nWrap at 80.\n").unwrap();
+    fs::remove_file(rules.join("tests.mdc")).unwrap();
+    fs::write(rules.join("docs.mdc"), "---\nalwaysApply: true\n---\n\nDocument it.\n").unwrap();
+
+    let out = polyrc(home.path(), &["diff", "--format", "cursor", "--project", "app"]);
+    assert_eq!(out.status.code(), Some(6));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("1 added, 1 removed, 1 changed by pulling store/app into ."), "{stdout}");
+    assert!(stdout.contains("    + tests\n") && stdout.contains("    - docs: only in local files"), "{stdout}");
+    assert!(stdout.contains("      --- local/style\n      +++ store/app/style\n"), "{stdout}");
+    assert!(stdout.contains("      -Wrap at 80.\n      +Wrap at 100.\n"), "{stdout}");
+
+    let out = polyrc(home.path(), &["diff", "--all", "--project", "app", "--direction", "push", "--json"]);
+    assert_eq!(out.status.code(), Some(6));
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let formats = json["formats"].as_array().unwrap();
+    assert_eq!(formats.len(), 1, "{json}");
+    assert_eq!((formats[0]["added"][0].as_str(), formats[0]["removed"][0].as_str()), (Some("docs"), Some("tests")));
+    let diff = formats[0]["changed"][0]["diff"].as_str().unwrap();
+    assert!(diff.starts_with("--- store/app/style\n+++ local/style\n") && diff.contains("+Wrap at 80."), "{diff}");
+}
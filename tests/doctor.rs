@@ -0,0 +1,58 @@
+use std::fs;
+use assert_cmd::Command;
+
+mod common;
+use common::git;
+
+#[test]
+fn flags_tracked_files_that_are_now_ignored() {
+    let root = tempfile::tempdir().unwrap();
+    let store = root.path().join("store");
+    fs::create_dir_all(store.join("user")).unwrap();
+    fs::write(store.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    fs::write(store.join(".DS_Store"), "junk").unwrap();
+    fs::write(store.join("user").join("style.yaml.swp"), "junk").unwrap();
+    git(&store, &["init", "-q"]);
+    git(&store, &["add", "-A"]);
+    git(&store, &["commit", "-q", "-m", "before .gitignore"]);
+
+    let out = Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", root.path())
+        .env("POLYRC_STORE", &store)
+        .arg("doctor")
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("2 tracked file(s) match .gitignore"), "{stdout}");
+    assert!(stdout.contains(".DS_Store") && stdout.contains("user/style.yaml.swp"), "{stdout}");
+
+    // Opening the store migrated its .gitignore, but nothing was untracked without confirmation
+    let gitignore = fs::read_to_string(store.join(".gitignore")).unwrap();
+    assert!(gitignore.lines().any(|l| l == "*.swp"));
+    assert!(git(&store, &["ls-files"]).contains(".DS_Store"));
+}
+
+#[test]
+fn reports_orphan_files_and_empty_projects() {
+    let root = tempfile::tempdir().unwrap();
+    let store = root.path().join("store");
+    fs::create_dir_all(store.join("app")).unwrap();
+    fs::create_dir_all(store.join("stale")).unwrap();
+    fs::write(store.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    fs::write(store.join("app").join("notes.txt"), "todo").unwrap();
+    git(&store, &["init", "-q"]);
+
+    let out = Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", root.path())
+        .env("POLYRC_STORE", &store)
+        .arg("doctor")
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("app/notes.txt"), "{stdout}");
+    assert!(stdout.contains("1 empty project(s): stale"), "{stdout}");
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use assert_cmd::Command;
+use polyrc_core::ir::Rule;
+use polyrc_core::store::Store;
+use serde_json::Value;
+
+fn rule(name: &str, content: &str) -> Rule {
+    Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+}
+
+/// A store whose `app` project holds `style` and `old`, and a Cursor project
+/// with `style` (changed) and `tests`.
+fn fixture() -> (tempfile::TempDir, PathBuf) {
+    let root = tempfile::tempdir().unwrap();
+    let store_dir = root.path().join("store");
+    fs::create_dir_all(&store_dir).unwrap();
+    fs::write(store_dir.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    Store::open_read_only(&store_dir)
+        .unwrap()
+        .save_rules(Some("app"), &[rule("style", "Use tabs."), rule("old", "Gone soon.")], "cursor")
+        .unwrap();
+    let rules = root.path().join("in").join(".cursor").join("rules");
+    fs::create_dir_all(&rules).unwrap();
+    fs::write(rules.join("style.mdc"), "---\nalwaysApply: true\n---\n\nUse spaces.\n").unwrap();
+    fs::write(rules.join("tests.mdc"), "---\nalwaysApply: true\n---\n\nWrite tests.\n").unwrap();
+    (root, store_dir)
+}
+
+fn json(home: &Path, store: &Path, args: &[&str]) -> Value {
+    let out = Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", home)
+        .env("POLYRC_STORE", store)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    serde_json::from_slice(&out.stdout).expect("stdout is JSON")
+}
+
+#[test]
+fn convert_plans_files() {
+    let (root, store) = fixture();
+    let input = root.path().join("in");
+    let output = root.path().join("out");
+    fs::create_dir_all(output.join(".windsurf").join("rules")).unwrap();
+    fs::write(output.join(".windsurf").join("rules").join("style.md"), "hand-made").unwrap();
+
+    let plan = json(
+        root.path(),
+        &store,
+        &["convert", "--from", "cursor", "--to", "windsurf", "--dry-run", "--json", "--input", input.to_str().unwrap(), "--output", output.to_str().unwrap()],
+    );
+    assert_eq!(plan["command"], "convert");
+    let format = &plan["formats"][0];
+    assert_eq!(format["format"], "windsurf");
+    let files = format["files"].as_array().unwrap();
+    assert_eq!(files[0]["path"], ".windsurf/rules/style.md");
+    assert_eq!(files[0]["action"], "overwrite");
+    assert_eq!(files[0]["rule_names"], serde_json::json!(["style"]));
+    assert!(files[0]["bytes"].as_u64().unwrap() >= "Use spaces.\n".len() as u64);
+    assert_eq!(files[1]["action"], "create");
+    assert_eq!(fs::read_to_string(output.join(".windsurf").join("rules").join("style.md")).unwrap(), "hand-made");
+    assert!(!output.join(".windsurf").join("rules").join("tests.md").exists());
+}
+
+#[test]
+fn push_plans_rule_changes_without_touching_the_store() {
+    let (root, store) = fixture();
+    let input = root.path().join("in");
+    let plan = json(
+        root.path(),
+        &store,
+        &["push-format", "--format", "cursor", "--project", "app", "--dry-run", "--json", "--input", input.to_str().unwrap()],
+    );
+    assert_eq!(plan["command"], "push-format");
+    assert_eq!(
+        plan["formats"][0]["rules"],
+        serde_json::json!([
+            {"rule": "old", "action": "remove", "project": "app"},
+            {"rule": "style", "action": "update", "project": "app"},
+            {"rule": "tests", "action": "add", "project": "app"},
+        ])
+    );
+    assert_eq!(Store::open_read_only(&store).unwrap().load_rules(Some("app")).unwrap().len(), 2);
+}
+
+#[test]
+fn pull_all_lists_every_format() {
+    let (root, store) = fixture();
+    let output = root.path().join("out");
+    fs::create_dir_all(&output).unwrap();
+    let plan = json(
+        root.path(),
+        &store,
+        &["pull-format", "--all", "--project", "app", "--dry-run", "--json", "--output", output.to_str().unwrap()],
+    );
+    let formats = plan["formats"].as_array().unwrap();
+    assert_eq!(formats.len(), polyrc_core::formats::Format::all().len());
+    let cursor = formats.iter().find(|f| f["format"] == "cursor").unwrap();
+    let paths: Vec<&str> = cursor["files"].as_array().unwrap().iter().map(|f| f["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, vec![".cursor/rules/old.mdc", ".cursor/rules/style.mdc"]);
+    assert!(fs::read_dir(&output).unwrap().next().is_none());
+}
+
+#[test]
+fn json_requires_dry_run() {
+    let (root, store) = fixture();
+    let out = Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", root.path())
+        .env("POLYRC_STORE", &store)
+        .args(["pull-format", "--format", "cursor", "--project", "app", "--json"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+}
@@ -0,0 +1,61 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Output;
+
+mod common;
+use common::{last_commit, polyrc_cmd};
+
+/// Run polyrc with `$EDITOR` set to a script running `editor` on the file (`$1`).
+fn polyrc(home: &Path, editor: &str, args: &[&str]) -> Output {
+    let script = home.join("editor.sh");
+    fs::write(&script, format!("#!/bin/sh\n{editor}\n")).unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    polyrc_cmd(home).env("EDITOR", &script).current_dir(home).args(args).output().unwrap()
+}
+
+#[test]
+fn edits_are_saved_and_committed() {
+    let home = tempfile::tempdir().unwrap();
+    let store = home.path().join("polyrc").join("store");
+    assert!(polyrc(home.path(), "true", &["init"]).status.success());
+    fs::write(home.path().join("style.md"), "Use rustfmt.\n").unwrap();
+    let out = polyrc(home.path(), "true", &["push-rule", "style", "--user", "--from-file", "style.md"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let file = store.join("user").join("style.yaml");
+    let before = fs::read_to_string(&file).unwrap();
+
+    // Closing the editor without changes, or with an error, saves nothing
+    let out = polyrc(home.path(), "true", &["edit-rule", "style", "--user"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("No changes to 'style'"));
+    let out = polyrc(home.path(), "echo 'Use tabs.' > \"$1\"; exit 1", &["edit-rule", "style", "--user"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("nothing saved"));
+    assert_eq!(fs::read_to_string(&file).unwrap(), before);
+
+    let out = polyrc(home.path(), "sed -i 's/rustfmt/clippy/' \"$1\"", &["edit-rule", "style", "--user"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let after = fs::read_to_string(&file).unwrap();
+    assert!(after.contains("content: Use clippy.") && after.contains("updated_at:"), "{after}");
+    assert_eq!(last_commit(&store), "edit-rule: style (user)");
+
+    // --raw edits the YAML itself
+    let out = polyrc(home.path(), "sed -i 's/activation: always/activation: on_demand/' \"$1\"", &["edit-rule", "style", "--user", "--raw"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let after = fs::read_to_string(&file).unwrap();
+    assert!(after.contains("activation: on_demand") && after.contains("content: Use clippy."), "{after}");
+
+    let out = polyrc(home.path(), "echo 'activation: [' > \"$1\"", &["edit-rule", "style", "--user", "--raw"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("is not a valid rule"));
+    assert_eq!(fs::read_to_string(&file).unwrap(), after);
+
+    // Edits are polyrc commits that undo takes back
+    let out = polyrc(home.path(), "true", &["undo", "--yes"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(last_commit(&store), "Revert \"edit-rule: style (user)\"");
+    assert!(fs::read_to_string(&file).unwrap().contains("content: Use clippy.\n"));
+}
@@ -0,0 +1,75 @@
+use std::fs;
+use std::path::Path;
+use assert_cmd::Command;
+
+/// Home dir with `config` as `~/polyrc/config.toml`, and a project with two
+/// always-on Cursor rules.
+fn fixture(config: &str) -> tempfile::TempDir {
+    let root = tempfile::tempdir().unwrap();
+    fs::create_dir_all(root.path().join("polyrc")).unwrap();
+    fs::write(root.path().join("polyrc").join("config.toml"), config).unwrap();
+    let rules = root.path().join("in").join(".cursor").join("rules");
+    fs::create_dir_all(&rules).unwrap();
+    for name in ["style", "tests"] {
+        fs::write(rules.join(format!("{name}.mdc")), format!("---\nalwaysApply: true\n---\n\n{name} rule\n")).unwrap();
+    }
+    root
+}
+
+fn convert(home: &Path, extra: &[&str]) -> std::process::Output {
+    Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", home)
+        .args(["convert", "--from", "cursor", "--to", "copilot", "--input"])
+        .arg(home.join("in"))
+        .arg("--output")
+        .arg(home.join("out"))
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+fn split(home: &Path) -> bool {
+    home.join("out").join(".github").join("instructions").join("style.instructions.md").exists()
+}
+
+#[test]
+fn config_table_sets_the_option() {
+    let root = fixture("[formats.copilot]\nsplit_always = true\n");
+    assert!(convert(root.path(), &[]).status.success());
+    assert!(split(root.path()));
+}
+
+#[test]
+fn cli_flag_overrides_config() {
+    let root = fixture("[formats.copilot]\nsplit_always = true\n");
+    assert!(convert(root.path(), &["--format-option", "copilot.split_always=false"]).status.success());
+    assert!(!split(root.path()));
+    let main = fs::read_to_string(root.path().join("out").join(".github").join("copilot-instructions.md")).unwrap();
+    assert!(main.contains("## style") && main.contains("## tests"), "{main}");
+}
+
+#[test]
+fn formats_table_overrides_legacy_section() {
+    let root = fixture("[copilot]\nsplit_always = true\n\n[formats.copilot]\nsplit_always = false\n");
+    assert!(convert(root.path(), &[]).status.success());
+    assert!(!split(root.path()));
+
+    let root = fixture("[copilot]\nsplit_always = true\n");
+    assert!(convert(root.path(), &[]).status.success());
+    assert!(split(root.path()));
+}
+
+#[test]
+fn unknown_options_are_errors() {
+    let root = fixture("[formats.copilot]\nsplit = true\n");
+    let out = convert(root.path(), &[]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("unknown option 'split' for format 'copilot' (known: split_always, budget_chars)"), "{stderr}");
+
+    let root = fixture("");
+    let out = convert(root.path(), &["--format-option", "cursor.legacy=true"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8(out.stderr).unwrap().contains("unknown option 'legacy' for format 'cursor'"));
+}
@@ -0,0 +1,91 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+use assert_cmd::Command;
+use polyrc_core::ir::{Rule, Scope};
+use polyrc_core::store::Store;
+
+mod common;
+use common::git;
+
+/// A bare remote whose store holds one user rule pushed from Claude and one
+/// from Gemini. Returns its `file://` URL.
+fn remote_with_user_rules(root: &Path) -> String {
+    let remote = root.join("remote.git");
+    let seed = root.join("seed");
+    git(root, &["init", "-q", "--bare", "-b", "main", remote.to_str().unwrap()]);
+    fs::create_dir_all(&seed).unwrap();
+    git(&seed, &["init", "-q", "-b", "main"]);
+    polyrc_core::store::init_git(&seed).unwrap();
+    let store = Store::open(&seed).unwrap();
+    for (name, content, format) in [("tone", "Be terse.", "claude"), ("commits", "Use conventional commits.", "gemini")] {
+        let rule = Rule {
+            name: Some(name.into()),
+            content: content.into(),
+            scope: Scope::User,
+            source_format: Some(format.into()),
+            ..Default::default()
+        };
+        store.save_rule_to_namespace(polyrc_core::store::USER_PROJECT, name, &rule).unwrap();
+    }
+    git(&seed, &["add", "-A"]);
+    git(&seed, &["commit", "-q", "-m", "user rules"]);
+    git(&seed, &["remote", "add", "origin", remote.to_str().unwrap()]);
+    git(&seed, &["push", "-q", "origin", "main"]);
+    format!("file://{}", remote.display())
+}
+
+fn init(home: &Path, url: &str, extra: &[&str]) -> Output {
+    Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", home)
+        .env_remove("POLYRC_STORE")
+        .env_remove("CLAUDE_CONFIG_DIR")
+        .args(["init", "--repo", url, "--apply"])
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn apply_writes_user_rules_after_cloning() {
+    let root = tempfile::tempdir().unwrap();
+    let url = remote_with_user_rules(root.path());
+    let home = root.path().join("home");
+    fs::create_dir_all(&home).unwrap();
+
+    let out = init(&home, &url, &["--dry-run"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("gemini — dry run: 2 rule(s)"), "{stdout}");
+    assert!(!home.join(".gemini").exists() && !home.join(".claude").exists());
+
+    fs::remove_dir_all(home.join("polyrc")).unwrap();
+    let out = init(&home, &url, &[]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("claude — wrote 2 rule(s)") && stdout.contains("gemini — wrote 2 rule(s)"), "{stdout}");
+    assert!(stdout.contains("cursor — skipped (no local user-level config"), "{stdout}");
+    let gemini = fs::read_to_string(home.join(".gemini").join("GEMINI.md")).unwrap();
+    assert!(gemini.contains("Be terse.") && gemini.contains("Use conventional commits."), "{gemini}");
+}
+
+#[test]
+fn apply_failure_keeps_the_store() {
+    let root = tempfile::tempdir().unwrap();
+    let url = remote_with_user_rules(root.path());
+    let home = root.path().join("home");
+    fs::create_dir_all(&home).unwrap();
+    // ~/.gemini is a file, so Gemini can't be written
+    fs::write(home.join(".gemini"), "").unwrap();
+
+    let out = init(&home, &url, &[]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("gemini — error"), "{stderr}");
+    assert!(stderr.contains("store initialized, but applying user rules failed for gemini"), "{stderr}");
+    assert!(String::from_utf8(out.stdout).unwrap().contains("claude — wrote"));
+    assert!(home.join("polyrc").join("store").join("user").join("tone.yaml").exists());
+    let config = fs::read_to_string(home.join("polyrc").join("config.toml")).unwrap();
+    assert!(config.contains(&url), "{config}");
+}
@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+use serde_json::Value;
+
+mod common;
+use common::polyrc_cmd;
+
+fn polyrc(home: &Path, store: &Path, args: &[&str]) -> Output {
+    polyrc_cmd(home).env("POLYRC_STORE", store).args(args).output().unwrap()
+}
+
+/// The JSON error on stdout, after checking the exit code matches it and the
+/// human message is still on stderr.
+fn json_error(out: &Output) -> Value {
+    let stdout: Value = serde_json::from_slice(&out.stdout).expect("stdout is JSON");
+    let error = stdout["error"].clone();
+    assert_eq!(out.status.code(), error["exit_code"].as_i64().map(|c| c as i32));
+    assert!(String::from_utf8_lossy(&out.stderr).starts_with("Error: "));
+    error
+}
+
+#[test]
+fn missing_store_is_reported_as_json() {
+    let root = tempfile::tempdir().unwrap();
+    let out = polyrc(root.path(), &root.path().join("nowhere"), &["list-project", "--json"]);
+    let error = json_error(&out);
+    assert_eq!(error["kind"], "store_not_found");
+    assert_eq!(error["exit_code"], 3);
+    assert!(error["message"].as_str().unwrap().contains("polyrc init"), "{error}");
+}
+
+#[test]
+fn missing_project_is_reported_as_json() {
+    let root = tempfile::tempdir().unwrap();
+    let store = root.path().join("store");
+    fs::create_dir_all(store.join("app")).unwrap();
+    fs::write(store.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+
+    let out = polyrc(root.path(), &store, &["list-project", "ap", "--json"]);
+    let error = json_error(&out);
+    assert_eq!(error["kind"], "project_not_found");
+    assert_eq!(error["exit_code"], 4);
+    assert!(error["message"].as_str().unwrap().contains("Did you mean 'app'?"), "{error}");
+
+    // Without --json stdout stays empty
+    let out = polyrc(root.path(), &store, &["list-project", "ap"]);
+    assert_eq!(out.status.code(), Some(4));
+    assert!(out.stdout.is_empty());
+}
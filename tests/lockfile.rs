@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use assert_cmd::Command;
+use polyrc_core::ir::Rule;
+use polyrc_core::store::Store;
+
+mod common;
+use common::polyrc_cmd;
+
+fn rule(name: &str, content: &str) -> Rule {
+    Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+}
+
+/// A store whose `app` project holds `rules`, and an empty output directory.
+fn fixture(rules: &[Rule]) -> (tempfile::TempDir, PathBuf, PathBuf) {
+    let root = tempfile::tempdir().unwrap();
+    let store_dir = root.path().join("store");
+    fs::create_dir_all(&store_dir).unwrap();
+    fs::write(store_dir.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    Store::open_read_only(&store_dir).unwrap().save_rules(Some("app"), rules, "claude").unwrap();
+    let out = root.path().join("out");
+    fs::create_dir_all(&out).unwrap();
+    (root, store_dir, out)
+}
+
+fn polyrc(home: &Path, store: &Path) -> Command {
+    let mut cmd = polyrc_cmd(home);
+    cmd.env("POLYRC_STORE", store);
+    cmd
+}
+
+fn pull(home: &Path, store: &Path, out: &Path, extra: &[&str]) -> std::process::Output {
+    polyrc(home, store)
+        .args(["pull-format", "--project", "app", "--format", "cursor", "--output"])
+        .arg(out)
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+fn cursor_file(out: &Path, name: &str) -> PathBuf {
+    out.join(".cursor").join("rules").join(format!("{name}.mdc"))
+}
+
+#[test]
+fn prune_only_deletes_unchanged_files_from_the_lock() {
+    let (root, store, out) = fixture(&[rule("style", "Use tabs."), rule("tests", "Write tests."), rule("docs", "Document.")]);
+    assert!(pull(root.path(), &store, &out, &[]).status.success());
+    let lock = fs::read_to_string(out.join(polyrc_core::lock::LOCK_FILE)).unwrap();
+    assert!(lock.contains("\".cursor/rules/style.mdc\""), "{lock}");
+    fs::write(cursor_file(&out, "mine"), "hand-made").unwrap();
+    fs::write(cursor_file(&out, "docs"), "edited locally").unwrap();
+
+    // `tests` and `docs` leave the store
+    Store::open_read_only(&store).unwrap().save_rules(Some("app"), &[rule("style", "Use tabs.")], "claude").unwrap();
+    let output = pull(root.path(), &store, &out, &["--prune"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stdout.contains("pruned: .cursor/rules/tests.mdc"), "{stdout}");
+    assert!(stderr.contains("not pruned (edited since the last pull): .cursor/rules/docs.mdc"), "{stderr}");
+
+    assert!(!cursor_file(&out, "tests").exists());
+    assert!(cursor_file(&out, "style").exists());
+    assert_eq!(fs::read_to_string(cursor_file(&out, "mine")).unwrap(), "hand-made");
+    assert_eq!(fs::read_to_string(cursor_file(&out, "docs")).unwrap(), "edited locally");
+    let lock = fs::read_to_string(out.join(polyrc_core::lock::LOCK_FILE)).unwrap();
+    assert!(!lock.contains("tests.mdc") && !lock.contains("docs.mdc"), "{lock}");
+}
+
+#[test]
+fn stale_files_stay_without_prune() {
+    let (root, store, out) = fixture(&[rule("style", "Use tabs."), rule("tests", "Write tests.")]);
+    assert!(pull(root.path(), &store, &out, &[]).status.success());
+    Store::open_read_only(&store).unwrap().save_rules(Some("app"), &[rule("style", "Use tabs.")], "claude").unwrap();
+
+    assert!(pull(root.path(), &store, &out, &[]).status.success());
+    assert!(cursor_file(&out, "tests").exists());
+    // Still tracked, so a later --prune removes it
+    assert!(pull(root.path(), &store, &out, &["--prune"]).status.success());
+    assert!(!cursor_file(&out, "tests").exists());
+}
+
+#[test]
+fn edited_files_are_reported_and_not_overwritten_without_force() {
+    let (root, store, out) = fixture(&[rule("style", "Use tabs.")]);
+    assert!(pull(root.path(), &store, &out, &[]).status.success());
+    let style = cursor_file(&out, "style");
+    fs::write(&style, "my local tweak").unwrap();
+
+    let status = polyrc(root.path(), &store).args(["status", "--input"]).arg(&out).output().unwrap();
+    let stdout = String::from_utf8(status.stdout).unwrap();
+    assert!(stdout.contains("modified") && stdout.contains(".cursor/rules/style.mdc"), "{stdout}");
+
+    let output = pull(root.path(), &store, &out, &[]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("edited since the last pull") && stderr.contains("--force"), "{stderr}");
+    assert_eq!(fs::read_to_string(&style).unwrap(), "my local tweak");
+
+    assert!(pull(root.path(), &store, &out, &["--force"]).status.success());
+    assert!(fs::read_to_string(&style).unwrap().contains("Use tabs."));
+    let status = polyrc(root.path(), &store).args(["status", "--input"]).arg(&out).output().unwrap();
+    assert!(String::from_utf8(status.stdout).unwrap().contains("are unchanged"));
+}
+
+#[test]
+fn status_reports_unmanaged_files_in_format_dirs() {
+    let (root, store, out) = fixture(&[rule("style", "Use tabs.")]);
+    assert!(pull(root.path(), &store, &out, &[]).status.success());
+    let rules_dir = out.join(".cursor").join("rules");
+    fs::write(rules_dir.join("scratch.md"), "todo").unwrap();
+    fs::write(rules_dir.join("deprecated.mdc.bak"), "old").unwrap();
+    fs::create_dir_all(out.join(".github")).unwrap();
+    fs::write(out.join(".github").join("README.md"), "not copilot's").unwrap();
+
+    let status = polyrc(root.path(), &store).args(["status", "--input"]).arg(&out).output().unwrap();
+    let stdout = String::from_utf8(status.stdout).unwrap();
+    assert!(stdout.contains("are unchanged"), "{stdout}");
+    assert!(stdout.contains("unmanaged  cursor     .cursor/rules/scratch.md"), "{stdout}");
+    assert!(stdout.contains(".cursor/rules/deprecated.mdc.bak"), "{stdout}");
+    assert!(!stdout.contains("style.mdc") && !stdout.contains("README.md"), "{stdout}");
+
+    let status = polyrc(root.path(), &store).args(["status", "--json", "--input"]).arg(&out).output().unwrap();
+    let entries: serde_json::Value = serde_json::from_slice(&status.stdout).unwrap();
+    let unmanaged = entries.as_array().unwrap().iter().filter(|e| e["state"] == "unmanaged").count();
+    assert_eq!(unmanaged, 2);
+}
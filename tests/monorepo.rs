@@ -0,0 +1,73 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::polyrc;
+
+/// A repo with a root CLAUDE.md, `packages/api/CLAUDE.md`, a slash command in
+/// `packages/web`, and a CLAUDE.md under node_modules that must be ignored.
+fn monorepo(root: &Path) {
+    let write = |rel: &str, content: &str| {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+    write("CLAUDE.md", "Use pnpm.\n");
+    write("packages/api/CLAUDE.md", "Return JSON errors.\n");
+    write("packages/web/.claude/commands/review.md", "Review the diff.\n");
+    write("node_modules/dep/CLAUDE.md", "Vendored.\n");
+}
+
+#[test]
+fn push_and_pull_recursive_round_trip() {
+    let home = tempfile::tempdir().unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    let repo = home.path().join("repo");
+    monorepo(&repo);
+
+    let out = polyrc(home.path(), &["push-format", "--format", "claude", "--project", "mono", "--input", "repo", "--recursive"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let project = home.path().join("polyrc").join("store").join("mono");
+    let api = fs::read_to_string(project.join("packages-api-claude.yaml")).unwrap();
+    assert!(api.contains("scope: path") && api.contains("activation: glob") && api.contains("- packages/api/**"), "{api}");
+    assert!(project.join("packages-web-review.yaml").exists());
+    assert_eq!(fs::read_dir(&project).unwrap().filter(|e| e.as_ref().unwrap().path().extension().is_some_and(|x| x == "yaml")).count(), 3);
+
+    // Back into each package's own layout
+    let out = polyrc(home.path(), &["pull-format", "--format", "claude", "--project", "mono", "--output", "checkout", "--recursive"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let checkout = home.path().join("checkout");
+    assert_eq!(fs::read_to_string(checkout.join("CLAUDE.md")).unwrap(), "Use pnpm.\n");
+    assert_eq!(fs::read_to_string(checkout.join("packages/api/CLAUDE.md")).unwrap(), "Return JSON errors.\n");
+    assert!(fs::read_to_string(checkout.join("packages/web/.claude/commands/review.md")).unwrap().contains("Review the diff."));
+
+    // Without --recursive the package rules stay at the root, scoped by glob
+    let out = polyrc(home.path(), &["pull-format", "--format", "cursor", "--project", "mono", "--output", "flat"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let mdc = fs::read_to_string(home.path().join("flat/.cursor/rules/packages-api-claude.mdc")).unwrap();
+    assert!(mdc.contains("globs: packages/api/**"), "{mdc}");
+    assert!(!home.path().join("flat/packages").exists());
+}
+
+#[test]
+fn convert_recursive_writes_into_each_package() {
+    let home = tempfile::tempdir().unwrap();
+    let repo = home.path().join("repo");
+    monorepo(&repo);
+
+    let out = polyrc(home.path(), &["convert", "--from", "claude", "--to", "cursor", "--input", "repo", "--output", "repo", "--recursive"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let rules = repo.join(".cursor").join("rules");
+    assert!(rules.join("claude.mdc").exists());
+    assert!(!rules.join("packages-api-claude.mdc").exists());
+    let api = fs::read_to_string(repo.join("packages/api/.cursor/rules/claude.mdc")).unwrap();
+    assert!(api.contains("alwaysApply: true") && api.contains("Return JSON errors."), "{api}");
+    assert!(repo.join("packages/web/.cursor/rules/review.mdc").exists());
+    assert!(!repo.join("node_modules/dep/.cursor").exists());
+
+    // --depth 1 stops at packages/, which has no config of its own
+    let out = polyrc(home.path(), &["convert", "--from", "claude", "--to", "gemini", "--input", "repo", "--output", "gem", "--recursive", "--depth", "1"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(home.path().join("gem/GEMINI.md").exists());
+    assert!(!home.path().join("gem/packages").exists());
+}
@@ -0,0 +1,65 @@
+use std::fs;
+
+mod common;
+use common::{last_commit, polyrc};
+
+fn field(yaml: &str, key: &str) -> String {
+    let prefix = format!("{key}: ");
+    yaml.lines().find_map(|l| l.strip_prefix(&prefix)).unwrap_or_default().to_string()
+}
+
+#[test]
+fn rules_move_and_copy_between_projects() {
+    let home = tempfile::tempdir().unwrap();
+    let store = home.path().join("polyrc").join("store");
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    fs::write(home.path().join("style.md"), "Use rustfmt.\n").unwrap();
+    fs::write(home.path().join("tests.md"), "Test it.\n").unwrap();
+    for (project, file) in [("app", "style.md"), ("web", "tests.md")] {
+        let out = polyrc(home.path(), &["push-rule", "--project", project, "--from-file", file]);
+        assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    }
+    let original = fs::read_to_string(store.join("app/style.yaml")).unwrap();
+
+    let out = polyrc(home.path(), &["copy-rule", "style", "--from", "app", "--to", "web"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(last_commit(&store), "copy-rule: style from app to web");
+    let copy = fs::read_to_string(store.join("web/style.yaml")).unwrap();
+    assert_eq!(field(&copy, "project"), "web");
+    assert_ne!(field(&copy, "id"), field(&original, "id"));
+    assert_eq!(fs::read_to_string(store.join("app/style.yaml")).unwrap(), original);
+
+    // The destination already has it now
+    let out = polyrc(home.path(), &["copy-rule", "style", "--from", "app", "--to", "web"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("web already has a rule 'style' (use --force to replace it)"));
+    let out = polyrc(home.path(), &["copy-rule", "style", "--from", "app", "--to", "web", "--force"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+
+    let out = polyrc(home.path(), &["move-rule", "style", "--from", "app", "--to", "user"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(last_commit(&store), "move-rule: style from app to user");
+    assert!(!store.join("app/style.yaml").exists());
+    let moved = fs::read_to_string(store.join("user/style.yaml")).unwrap();
+    assert_eq!((field(&moved, "id"), field(&moved, "scope")), (field(&original, "id"), "user".to_string()));
+    assert_eq!(field(&moved, "project"), "user");
+
+    let out = polyrc(home.path(), &["move-rule", "style", "--from", "app", "--to", "web"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Rule 'style' not found in project 'app'"));
+}
+
+#[test]
+fn undo_reverts_a_move() {
+    let home = tempfile::tempdir().unwrap();
+    let store = home.path().join("polyrc").join("store");
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    fs::write(home.path().join("style.md"), "Use rustfmt.\n").unwrap();
+    assert!(polyrc(home.path(), &["push-rule", "--project", "app", "--from-file", "style.md"]).status.success());
+    assert!(polyrc(home.path(), &["move-rule", "style", "--from", "app", "--to", "user"]).status.success());
+
+    let out = polyrc(home.path(), &["undo", "--yes"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(last_commit(&store), "Revert \"move-rule: style from app to user\"");
+    assert!(store.join("app/style.yaml").exists() && !store.join("user/style.yaml").exists());
+}
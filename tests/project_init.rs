@@ -0,0 +1,54 @@
+use std::fs;
+
+mod common;
+use common::{last_commit, polyrc};
+
+#[test]
+fn init_normalizes_the_name_and_writes_metadata() {
+    let home = tempfile::tempdir().unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    let store = home.path().join("polyrc").join("store");
+
+    let out = polyrc(
+        home.path(),
+        &["project", "init", "team-backend", "--description", "Backend services", "--default-formats", "Cursor,claude-code"],
+    );
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("Normalized project name 'team-backend' → 'teamBackend'."), "{stdout}");
+    assert!(stdout.contains(&store.join("teamBackend").display().to_string()), "{stdout}");
+
+    let meta = fs::read_to_string(store.join("teamBackend").join("project.toml")).unwrap();
+    assert!(meta.contains("description = \"Backend services\""), "{meta}");
+    assert!(meta.contains("default_formats = [\"cursor\", \"claude\"]"), "{meta}");
+    assert_eq!(last_commit(&store), "init project teamBackend");
+
+    // An already-normalized name is taken as is
+    let out = polyrc(home.path(), &["project", "init", "docs"]);
+    assert!(!String::from_utf8(out.stdout).unwrap().contains("Normalized"));
+}
+
+#[test]
+fn init_refuses_an_existing_project_unless_forced() {
+    let home = tempfile::tempdir().unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    let store = home.path().join("polyrc").join("store");
+    let meta_file = store.join("app").join("project.toml");
+    assert!(polyrc(home.path(), &["project", "init", "app", "--description", "First"]).status.success());
+    let created = fs::read_to_string(&meta_file).unwrap();
+
+    let out = polyrc(home.path(), &["project", "init", "app", "--description", "Second"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("already exists; pass --force"));
+
+    let out = polyrc(home.path(), &["project", "init", "app", "--description", "Second", "--force"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let updated = fs::read_to_string(&meta_file).unwrap();
+    assert!(updated.contains("Second"));
+    let created_at = |meta: &str| meta.lines().find(|l| l.starts_with("created_at")).map(str::to_string);
+    assert_eq!(created_at(&updated), created_at(&created));
+
+    let out = polyrc(home.path(), &["project", "init", "web", "--default-formats", "cursr"]);
+    assert!(!out.status.success());
+    assert!(!store.join("web").exists());
+}
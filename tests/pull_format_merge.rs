@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use assert_cmd::Command;
+use polyrc_core::ir::{Rule, Scope};
+use polyrc_core::store::Store;
+
+const LOCAL: &str = "## tone\n\nBe verbose.\n\n## local-only\n\nNever pushed.\n";
+
+/// A store with user rules `tone` and `style`, and a home dir whose Windsurf and
+/// Gemini user files already hold a `tone` section and a local-only section.
+fn fixture() -> (tempfile::TempDir, PathBuf) {
+    let root = tempfile::tempdir().unwrap();
+    let store_dir = root.path().join("store");
+    fs::create_dir_all(&store_dir).unwrap();
+    fs::write(store_dir.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    let user_rule = |name: &str, content: &str| Rule {
+        name: Some(name.into()),
+        content: content.into(),
+        scope: Scope::User,
+        ..Default::default()
+    };
+    let store = Store::open_read_only(&store_dir).unwrap();
+    store
+        .save_rules(Some("user"), &[user_rule("tone", "Be terse."), user_rule("style", "Use tabs.")], "claude")
+        .unwrap();
+
+    for file in [windsurf_file(root.path()), gemini_file(root.path())] {
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, LOCAL).unwrap();
+    }
+    (root, store_dir)
+}
+
+fn windsurf_file(home: &Path) -> PathBuf {
+    home.join(".codeium").join("windsurf").join("memories").join("global_rules.md")
+}
+
+fn gemini_file(home: &Path) -> PathBuf {
+    home.join(".gemini").join("GEMINI.md")
+}
+
+fn pull(home: &Path, store: &Path, format: &str) -> Command {
+    let mut cmd = Command::cargo_bin("polyrc").unwrap();
+    cmd.env("HOME", home)
+        .env("POLYRC_STORE", store)
+        .args(["pull-format", "--user", "--format", format]);
+    cmd
+}
+
+#[test]
+fn merges_into_existing_user_files() {
+    let (root, store) = fixture();
+    for (format, file) in [("windsurf", windsurf_file(root.path())), ("gemini", gemini_file(root.path()))] {
+        let out = pull(root.path(), &store, format).output().unwrap();
+        assert!(out.status.success());
+        let stderr = String::from_utf8(out.stderr).unwrap();
+        assert!(stderr.contains("replaced: tone"), "{stderr}");
+        assert!(stderr.contains("added: style"), "{stderr}");
+        assert!(stderr.contains("kept: local-only"), "{stderr}");
+        assert_eq!(
+            fs::read_to_string(file).unwrap(),
+            "## tone\n\nBe terse.\n\n## local-only\n\nNever pushed.\n\n## style\n\nUse tabs.\n"
+        );
+    }
+}
+
+#[test]
+fn overwrite_replaces_the_whole_file() {
+    let (root, store) = fixture();
+    pull(root.path(), &store, "windsurf").arg("--overwrite").assert().success();
+    let written = fs::read_to_string(windsurf_file(root.path())).unwrap();
+    assert!(!written.contains("local-only"), "{written}");
+    assert!(written.contains("Be terse.") && written.contains("Use tabs."));
+}
@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+use assert_cmd::Command;
+use polyrc_core::ir::{Activation, Rule};
+use polyrc_core::store::Store;
+
+mod common;
+use common::polyrc_cmd;
+
+fn polyrc(home: &Path, args: &[&str]) -> Output {
+    polyrc_cmd(home).env("POLYRC_STORE", home.join("store")).args(args).output().unwrap()
+}
+
+fn store_with(home: &Path, activation: Activation) {
+    let store = home.join("store");
+    fs::create_dir_all(&store).unwrap();
+    fs::write(store.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    let rule = Rule { name: Some("style".into()), content: "Use tabs.".into(), activation, ..Default::default() };
+    Store::open_read_only(&store).unwrap().save_rules(Some("app"), &[rule], "cursor").unwrap();
+}
+
+#[test]
+fn all_formats_from_one_command() {
+    let home = tempfile::tempdir().unwrap();
+    store_with(home.path(), Activation::Always);
+    let out_dir = home.path().join("repo");
+    fs::create_dir_all(&out_dir).unwrap();
+    fs::write(out_dir.join("GEMINI.md"), "Hand-written notes.\n").unwrap();
+
+    let out = polyrc(home.path(), &["pull-rule", "style", "--format", "all", "--output", out_dir.to_str().unwrap()]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    for file in [".cursor/rules/style.mdc", ".windsurf/rules/style.md", ".github/copilot-instructions.md", "CLAUDE.md", ".agent/rules/style.md", "CONVENTIONS.md", "AGENTS.md", ".amazonq/rules/style.md", ".rules", "WARP.md", ".openhands/microagents/repo.md", ".kiro/steering/style.md", "best_practices.md"] {
+        let written = fs::read_to_string(out_dir.join(file)).unwrap_or_else(|_| panic!("{file} missing"));
+        assert!(written.contains("Use tabs."), "{file}: {written}");
+    }
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(stdout.matches(" — wrote ").count(), 13, "{stdout}");
+
+    // The existing GEMINI.md is left alone until --force
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("gemini — skipped: would replace GEMINI.md (pass --force)"), "{stderr}");
+    assert_eq!(fs::read_to_string(out_dir.join("GEMINI.md")).unwrap(), "Hand-written notes.\n");
+    let out = polyrc(home.path(), &["pull-rule", "style", "--format", "gemini", "--force", "--output", out_dir.to_str().unwrap()]);
+    assert!(out.status.success());
+    assert_eq!(fs::read_to_string(out_dir.join("GEMINI.md")).unwrap(), "Use tabs.\n");
+}
+
+#[test]
+fn one_failing_format_does_not_stop_the_others() {
+    let home = tempfile::tempdir().unwrap();
+    store_with(home.path(), Activation::OnDemand);
+    fs::create_dir_all(home.path().join("polyrc")).unwrap();
+    fs::write(home.path().join("polyrc").join("config.toml"), "[write]\non_unsupported_activation = \"error\"\n").unwrap();
+    let out_dir = home.path().join("repo");
+
+    let out = polyrc(
+        home.path(),
+        &["pull-rule", "style", "--format", "gemini", "--format", "cursor,claude", "--output", out_dir.to_str().unwrap()],
+    );
+    assert!(!out.status.success());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("gemini — failed"), "{stderr}");
+    assert!(stderr.contains("could not write 'style' as gemini"), "{stderr}");
+    assert!(out_dir.join(".cursor").join("rules").join("style.mdc").exists());
+    assert!(out_dir.join(".claude").join("commands").join("style.md").exists());
+    assert!(!out_dir.join("GEMINI.md").exists());
+}
+
+#[test]
+fn append_merges_into_an_existing_single_file() {
+    let home = tempfile::tempdir().unwrap();
+    store_with(home.path(), Activation::Always);
+    let out_dir = home.path().join("repo");
+    fs::create_dir_all(&out_dir).unwrap();
+    let claude_md = out_dir.join("CLAUDE.md");
+    fs::write(&claude_md, "# Project\n\nHand-written notes.\n\n## testing\n\nRun cargo test.\n").unwrap();
+    let pull = |extra: &[&str]| {
+        let mut args = vec!["pull-rule", "style", "--format", "claude", "--output", out_dir.to_str().unwrap()];
+        args.extend_from_slice(extra);
+        polyrc(home.path(), &args)
+    };
+
+    // Default behaviour is unchanged: the file is not replaced without --force
+    let out = pull(&[]);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("would replace CLAUDE.md (pass --force)"));
+
+    let out = pull(&["--append"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8(out.stdout).unwrap().contains("CLAUDE.md (added section 'style')"));
+    let merged = fs::read_to_string(&claude_md).unwrap();
+    assert!(merged.starts_with("# Project\n\nHand-written notes.\n\n## testing\n\nRun cargo test.\n"), "{merged}");
+    assert!(merged.ends_with("## style\n\nUse tabs.\n"), "{merged}");
+
+    // Pulling again replaces the section instead of adding a second one
+    fs::write(&claude_md, merged.replace("Use tabs.", "Use spaces.")).unwrap();
+    let out = pull(&["--append"]);
+    assert!(String::from_utf8(out.stdout).unwrap().contains("(replaced section 'style')"));
+    let merged = fs::read_to_string(&claude_md).unwrap();
+    assert_eq!(merged.matches("## style").count(), 1, "{merged}");
+    assert!(merged.contains("Use tabs.") && merged.contains("Run cargo test."), "{merged}");
+
+    // Multi-file formats ignore --append
+    let out = polyrc(home.path(), &["pull-rule", "style", "--format", "cursor", "--append", "--output", out_dir.to_str().unwrap()]);
+    assert!(out.status.success());
+    assert!(out_dir.join(".cursor").join("rules").join("style.mdc").exists());
+}
+
+#[test]
+fn nested_cursor_rules_can_be_pulled_edited_and_moved() {
+    let home = tempfile::tempdir().unwrap();
+    let repo = home.path().join("repo");
+    fs::create_dir_all(repo.join(".cursor/rules/backend")).unwrap();
+    fs::write(repo.join(".cursor/rules/backend/api.mdc"), "---\nalwaysApply: true\n---\n\nReturn JSON errors.\n").unwrap();
+    let run = |args: &[&str]| {
+        Command::cargo_bin("polyrc")
+            .unwrap()
+            .env("HOME", home.path())
+            .env_remove("POLYRC_STORE")
+            .env("GIT_AUTHOR_NAME", "test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .env("EDITOR", "true")
+            .current_dir(&repo)
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    assert!(run(&["init"]).status.success());
+    let out = run(&["push-format", "--format", "cursor", "--project", "app"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+
+    let out_dir = home.path().join("out");
+    let out = run(&["pull-rule", "backend/api", "--project", "app", "--format", "claude", "--output", out_dir.to_str().unwrap()]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(fs::read_to_string(out_dir.join("CLAUDE.md")).unwrap().contains("Return JSON errors."));
+
+    let out = run(&["edit-rule", "backend/api", "--project", "app"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let out = run(&["copy-rule", "backend/api", "--from", "app", "--to", "user"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+}
@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use assert_cmd::Command;
+use polyrc_core::ir::Rule;
+use polyrc_core::store::Store;
+
+mod common;
+use common::{git, polyrc_cmd};
+
+/// A committed store repo with rule `style` in project `team`; returns its file:// URL.
+fn teammate_store(root: &Path) -> String {
+    let dir = root.join("theirs");
+    fs::create_dir_all(&dir).unwrap();
+    let rule = Rule { name: Some("style".into()), content: "Use tabs.".into(), ..Default::default() };
+    Store::open_read_only(&dir).unwrap().save_rules(Some("team"), &[rule], "cursor").unwrap();
+    git(&dir, &["init", "-q"]);
+    git(&dir, &["add", "-A"]);
+    git(&dir, &["commit", "-q", "-m", "init"]);
+    format!("file://{}", dir.display())
+}
+
+/// An empty store of my own, found via POLYRC_STORE.
+fn my_store(root: &Path) -> PathBuf {
+    let dir = root.join("mine");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    git(&dir, &["init", "-q"]);
+    git(&dir, &["config", "user.name", "test"]);
+    git(&dir, &["config", "user.email", "test@example.com"]);
+    dir
+}
+
+fn polyrc(root: &Path) -> Command {
+    let mut cmd = polyrc_cmd(root);
+    cmd.env("POLYRC_STORE", root.join("mine"));
+    cmd
+}
+
+#[test]
+fn pulls_rule_from_other_store() {
+    let root = tempfile::tempdir().unwrap();
+    let url = teammate_store(root.path());
+    let out = root.path().join("out");
+    fs::create_dir_all(&out).unwrap();
+
+    polyrc(root.path())
+        .args(["pull-rule", "style", "--format", "cursor", "--project", "team", "--from-repo", &url, "--output"])
+        .arg(&out)
+        .assert()
+        .success();
+    let written = fs::read_to_string(out.join(".cursor").join("rules").join("style.mdc")).unwrap();
+    assert!(written.contains("Use tabs."));
+}
+
+#[test]
+fn save_copies_rule_into_my_store_with_fresh_id() {
+    let root = tempfile::tempdir().unwrap();
+    let url = teammate_store(root.path());
+    let mine = my_store(root.path());
+    fs::create_dir_all(mine.join("team")).unwrap();
+    let out = root.path().join("out");
+    fs::create_dir_all(&out).unwrap();
+
+    polyrc(root.path())
+        .args(["pull-rule", "style", "--format", "cursor", "--project", "team", "--from-repo", &url, "--save", "--output"])
+        .arg(&out)
+        .assert()
+        .success();
+
+    let theirs = Store::open_read_only(&root.path().join("theirs")).unwrap().load_rules(Some("team")).unwrap();
+    let saved = Store::open_read_only(&mine).unwrap().load_rules(Some("team")).unwrap();
+    assert_eq!(saved.len(), 1);
+    assert_ne!(saved[0].id, theirs[0].id);
+    assert_eq!(saved[0].source_path.as_deref(), Some(format!("{url}#team/style").as_str()));
+}
+
+#[test]
+fn save_needs_an_existing_project_of_mine() {
+    let root = tempfile::tempdir().unwrap();
+    let url = teammate_store(root.path());
+    let mine = my_store(root.path());
+    let out = root.path().join("out");
+    fs::create_dir_all(&out).unwrap();
+    let pull = |extra: &[&str]| {
+        polyrc(root.path())
+            .args(["pull-rule", "style", "--format", "cursor", "--from-repo", &url, "--save", "--force", "--output"])
+            .arg(&out)
+            .args(extra)
+            .assert()
+    };
+
+    // Nothing is written until the destination in my store is known to exist
+    let stderr = String::from_utf8_lossy(&pull(&[]).failure().get_output().stderr).into_owned();
+    assert!(stderr.contains("--save needs --project <name> or --user"), "{stderr}");
+    pull(&["--project", "team"]).failure();
+    assert!(!mine.join("team").exists());
+    assert!(!out.join(".cursor").exists());
+
+    fs::create_dir_all(mine.join("team")).unwrap();
+    pull(&["--project", "team"]).success();
+    let saved = Store::open_read_only(&mine).unwrap().load_rules(Some("team")).unwrap();
+    assert_eq!(saved[0].project.as_deref(), Some("team"));
+}
+
+#[test]
+fn unknown_repo_fails_with_hint() {
+    let root = tempfile::tempdir().unwrap();
+    let missing = format!("file://{}", root.path().join("nowhere").display());
+    let out = polyrc(root.path())
+        .args(["pull-rule", "style", "--format", "cursor", "--from-repo", &missing])
+        .assert()
+        .failure();
+    let stderr = String::from_utf8_lossy(&out.get_output().stderr).into_owned();
+    assert!(stderr.contains("not found, or you have no access"), "{stderr}");
+}
@@ -0,0 +1,34 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::polyrc;
+
+fn stored(home: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(home.join("polyrc").join("store").join("app"))
+        .unwrap()
+        .filter_map(|e| e.unwrap().file_name().into_string().ok())
+        .filter(|n| n.ends_with(".yaml"))
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn second_format_keeps_the_first_formats_rules() {
+    let home = tempfile::tempdir().unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    let rules = home.path().join(".cursor").join("rules");
+    fs::create_dir_all(&rules).unwrap();
+    fs::write(home.path().join("CLAUDE.md"), "Use tabs.\n").unwrap();
+    fs::write(rules.join("tests.mdc"), "---\nalwaysApply: true\n---\nWrite tests first.\n").unwrap();
+
+    for format in ["claude", "cursor"] {
+        assert!(polyrc(home.path(), &["push-format", "--project", "app", "--format", format]).status.success());
+    }
+    assert_eq!(stored(home.path()), vec!["claude.yaml", "tests.yaml"]);
+
+    let out = polyrc(home.path(), &["push-format", "--project", "app", "--format", "cursor", "--replace"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(stored(home.path()), vec!["tests.yaml"]);
+}
@@ -0,0 +1,170 @@
+use std::fs;
+use assert_cmd::Command;
+
+mod common;
+use common::{last_commit, polyrc};
+
+/// An initialized store plus `snippets/{errors,style}.md` and `notes.txt`.
+fn fixture() -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    let snippets = home.path().join("snippets");
+    fs::create_dir_all(&snippets).unwrap();
+    fs::write(snippets.join("style.md"), "Use rustfmt.\n").unwrap();
+    fs::write(snippets.join("errors.md"), "Use anyhow.\n").unwrap();
+    fs::write(snippets.join("notes.txt"), "Not a rule.\n").unwrap();
+    home
+}
+
+#[test]
+fn glob_pushes_each_file_with_a_prefix_in_one_commit() {
+    let home = fixture();
+    let store = home.path().join("polyrc").join("store");
+
+    let out = polyrc(home.path(), &["push-rule", "rust", "--project", "app", "--from-file", "snippets/*.md", "--dry-run"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("rust-errors") && stdout.contains("snippets/errors.md"), "{stdout}");
+    assert!(!store.join("app").exists());
+
+    let out = polyrc(home.path(), &["push-rule", "rust", "--project", "app", "--from-file", "snippets/*.md", "--activation", "on-demand"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(last_commit(&store), "push-rule: rust-errors, rust-style");
+    let errors = fs::read_to_string(store.join("app").join("rust-errors.yaml")).unwrap();
+    assert!(errors.contains("content: Use anyhow.") && errors.contains("activation: on_demand"), "{errors}");
+    assert!(store.join("app").join("rust-style.yaml").exists());
+    assert!(!store.join("app").join("rust-notes.yaml").exists());
+
+    // Without a prefix the stems are the names; existing rules are reported as updates
+    let out = polyrc(
+        home.path(),
+        &["push-rule", "--project", "app", "--from-file", "snippets/style.md", "--from-file", "snippets/notes.txt"],
+    );
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(last_commit(&store), "push-rule: style, notes");
+    let out = polyrc(home.path(), &["push-rule", "rust", "--project", "app", "--from-file", "snippets/*.md"]);
+    assert!(String::from_utf8(out.stdout).unwrap().contains("Updated 'rust-style'"));
+}
+
+#[test]
+fn single_file_keeps_the_given_name() {
+    let home = fixture();
+    let store = home.path().join("polyrc").join("store");
+
+    let out = polyrc(home.path(), &["push-rule", "formatting", "--user", "--from-file", "snippets/style.md"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8(out.stdout).unwrap().contains("Pushed 'formatting'"));
+    assert!(store.join("user").join("formatting.yaml").exists());
+    assert_eq!(last_commit(&store), "push-rule: formatting");
+
+    let out = polyrc(home.path(), &["push-rule", "x", "--user", "--from-file", "snippets/*.rs"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("no files match 'snippets/*.rs'"));
+}
+
+#[test]
+fn glob_and_description_flags_are_validated_and_stored() {
+    let home = fixture();
+    let store = home.path().join("polyrc").join("store");
+    let push = |extra: &[&str]| {
+        let mut args = vec!["push-rule", "style", "--project", "app", "--from-file", "snippets/style.md"];
+        args.extend_from_slice(extra);
+        polyrc(home.path(), &args)
+    };
+
+    let out = push(&["--activation", "glob"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--activation glob needs at least one --glob"));
+    assert!(!store.join("app").exists());
+
+    let out = push(&["--activation", "glob", "--glob", "src/**/*.rs", "--glob", "*.toml"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8(out.stdout).unwrap().contains("globs:        src/**/*.rs, *.toml"));
+    let stored = fs::read_to_string(store.join("app").join("style.yaml")).unwrap();
+    assert!(stored.contains("activation: glob\nglobs:\n- src/**/*.rs\n- '*.toml'\n"), "{stored}");
+
+    let out = push(&["--glob", "*.rs"]);
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--glob is ignored by writers unless --activation glob"));
+
+    let out = push(&["--activation", "ai-decides"]);
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("pass --description"));
+
+    let out = push(&["--activation", "ai-decides", "--description", "Rust formatting conventions"]);
+    assert!(out.status.success());
+    assert!(!String::from_utf8_lossy(&out.stderr).contains("warning"));
+    let stored = fs::read_to_string(store.join("app").join("style.yaml")).unwrap();
+    assert!(stored.contains("description: Rust formatting conventions"), "{stored}");
+}
+
+#[test]
+fn piped_content_is_read_from_stdin() {
+    let home = fixture();
+    let store = home.path().join("polyrc").join("store");
+    let pipe = |args: &[&str], input: &str| {
+        Command::cargo_bin("polyrc")
+            .unwrap()
+            .env("HOME", home.path())
+            .env_remove("POLYRC_STORE")
+            .env("GIT_AUTHOR_NAME", "test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .args(args)
+            .write_stdin(input)
+            .output()
+            .unwrap()
+    };
+
+    let out = pipe(&["push-rule", "api-style", "--user"], "Return JSON errors.\n\n  \n");
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stored = fs::read_to_string(store.join("user").join("api-style.yaml")).unwrap();
+    assert!(stored.contains("content: Return JSON errors.\n"), "{stored}");
+    assert!(!stored.contains("source_path"), "{stored}");
+    assert_eq!(last_commit(&store), "push-rule: api-style");
+
+    let out = pipe(&["push-rule", "tone", "--user", "--from-file", "-"], "Be terse.");
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(store.join("user").join("tone.yaml").exists());
+
+    let out = pipe(&["push-rule", "empty", "--user"], " \n\t\n");
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("no rule content on stdin"));
+    assert!(!store.join("user").join("empty.yaml").exists());
+
+    let out = pipe(&["push-rule", "--user"], "Nameless.");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("a rule name is required when reading from stdin"));
+}
+
+/// On a terminal with no source given, the rule is written in `$EDITOR`.
+/// `script` gives polyrc a terminal for stdin.
+#[cfg(target_os = "linux")]
+#[test]
+fn terminal_without_a_source_opens_the_editor() {
+    use std::os::unix::fs::PermissionsExt;
+    let home = fixture();
+    let editor = home.path().join("editor.sh");
+    fs::write(&editor, "#!/bin/sh\nprintf 'Use tabs.\\n' > \"$1\"\n").unwrap();
+    fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
+    let command = format!("{} push-rule tabs --user", assert_cmd::cargo::cargo_bin("polyrc").display());
+    let out = std::process::Command::new("script")
+        .args(["-qec", &command, "/dev/null"])
+        .env("HOME", home.path())
+        .env("EDITOR", &editor)
+        .env_remove("POLYRC_STORE")
+        .env("GIT_AUTHOR_NAME", "test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .current_dir(home.path())
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stdout));
+
+    let store = home.path().join("polyrc").join("store");
+    let saved = fs::read_to_string(store.join("user").join("tabs.yaml")).unwrap();
+    assert!(saved.contains("Use tabs."), "{saved}");
+    assert_eq!(last_commit(&store), "push-rule: tabs");
+}
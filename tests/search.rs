@@ -0,0 +1,36 @@
+use std::fs;
+
+mod common;
+use common::polyrc;
+
+#[test]
+fn finds_rules_across_projects() {
+    let home = tempfile::tempdir().unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    fs::write(home.path().join("rust-style.md"), "Use rustfmt.\nRun clippy in CI.\n").unwrap();
+    fs::write(home.path().join("commits.md"), "Keep subjects short.\n").unwrap();
+    for args in [
+        &["push-rule", "--project", "app", "--from-file", "rust-style.md"][..],
+        &["push-rule", "--user", "--from-file", "commits.md", "--activation", "on-demand"],
+    ] {
+        let out = polyrc(home.path(), args);
+        assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    }
+
+    let out = polyrc(home.path(), &["search", "clippy"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("No rules match 'clippy'."));
+
+    let out = polyrc(home.path(), &["search", "CLIPPY", "--content"]);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("rust-style") && stdout.contains("app/rust-style.yaml"), "{stdout}");
+    assert!(stdout.contains("   2: Run clippy in CI.") && stdout.contains("1 rule(s)"), "{stdout}");
+
+    let out = polyrc(home.path(), &["search", "^(rust|commits)", "--regex", "--activation", "on-demand", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1, "{json}");
+    assert_eq!((json[0]["project"].as_str(), json[0]["matched"][0].as_str()), (Some("user"), Some("name")));
+
+    let out = polyrc(home.path(), &["search", "rust(", "--regex"]);
+    assert_eq!(out.status.code(), Some(2));
+}
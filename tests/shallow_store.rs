@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::Path;
+use assert_cmd::Command;
+use polyrc_core::ir::Rule;
+use polyrc_core::store::Store;
+
+mod common;
+use common::{git, polyrc_cmd};
+
+const HISTORY: usize = 12;
+
+fn commit_rule(dir: &Path, name: &str, content: &str) {
+    let rule = Rule { name: Some(name.into()), content: content.into(), ..Default::default() };
+    Store::open(dir).unwrap().save_rule_to_namespace("app", name, &rule).unwrap();
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "-q", "-m", &format!("update {name}")]);
+}
+
+fn polyrc(home: &Path) -> Command {
+    polyrc_cmd(home)
+}
+
+#[test]
+fn shallow_store_clones_syncs_and_unshallows() {
+    let root = tempfile::tempdir().unwrap();
+    let remote = root.path().join("remote.git");
+    let seed = root.path().join("seed");
+    let store = root.path().join("store");
+    git(root.path(), &["init", "-q", "--bare", "-b", "main", remote.to_str().unwrap()]);
+
+    // A shared store with a long history
+    fs::create_dir_all(&seed).unwrap();
+    git(&seed, &["init", "-q", "-b", "main"]);
+    polyrc_core::store::init_git(&seed).unwrap();
+    for i in 0..HISTORY {
+        commit_rule(&seed, "style", &format!("Revision {i}."));
+    }
+    git(&seed, &["remote", "add", "origin", remote.to_str().unwrap()]);
+    git(&seed, &["push", "-q", "origin", "main"]);
+
+    let url = format!("file://{}", remote.display());
+    polyrc(root.path())
+        .args(["init", "--shallow", "--repo", &url, "--store"])
+        .arg(&store)
+        .assert()
+        .success();
+    assert_eq!(git(&store, &["rev-list", "--count", "HEAD"]), "1");
+    assert_eq!(git(&store, &["rev-parse", "--is-shallow-repository"]), "true");
+    git(&store, &["config", "user.name", "test"]);
+    git(&store, &["config", "user.email", "test@example.com"]);
+
+    // Both sides move on; sync merges and pushes from the shallow clone
+    commit_rule(&seed, "tests", "Write tests.");
+    git(&seed, &["push", "-q", "origin", "main"]);
+    commit_rule(&store, "mine", "Local rule.");
+    polyrc(root.path()).arg("sync").assert().success();
+    assert!(store.join("app").join("tests.yaml").exists());
+    let remote_files = git(root.path(), &["--git-dir", remote.to_str().unwrap(), "ls-tree", "-r", "--name-only", "main"]);
+    assert!(remote_files.lines().any(|f| f == "app/mine.yaml"), "{remote_files}");
+
+    polyrc(root.path()).args(["store", "unshallow"]).assert().success();
+    assert_eq!(git(&store, &["rev-parse", "--is-shallow-repository"]), "false");
+    let commits: usize = git(&store, &["rev-list", "--count", "HEAD"]).parse().unwrap();
+    assert!(commits > HISTORY, "{commits}");
+}
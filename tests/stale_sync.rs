@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use assert_cmd::Command;
+
+mod common;
+use common::git;
+
+const NOTICE: &str = "store last synced";
+
+/// A store whose last sync was `days_ago` days ago, with or without a remote.
+fn store(root: &Path, days_ago: i64, remote: bool) -> PathBuf {
+    let store = root.join("store");
+    polyrc_core::store::init_git(&store).unwrap();
+    if remote {
+        git(&store, &["remote", "add", "origin", "https://example.com/rules.git"]);
+    }
+    let synced = chrono::Utc::now() - chrono::Duration::days(days_ago);
+    fs::write(store.join(".git").join("polyrc-last-sync"), synced.to_rfc3339()).unwrap();
+    store
+}
+
+fn list_project(root: &Path, store: &Path, args: &[&str]) -> std::process::Output {
+    let out = Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", root)
+        .env("POLYRC_STORE", store)
+        .arg("list-project")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    out
+}
+
+fn list_stderr(root: &Path, store: &Path) -> String {
+    String::from_utf8(list_project(root, store, &[]).stderr).unwrap()
+}
+
+#[test]
+fn nudges_once_when_stale() {
+    let root = tempfile::tempdir().unwrap();
+    let store = store(root.path(), 12, true);
+    let stderr = list_stderr(root.path(), &store);
+    assert!(stderr.contains("store last synced 12 days ago — run `polyrc sync`"), "{stderr}");
+    assert_eq!(stderr.matches(NOTICE).count(), 1);
+}
+
+#[test]
+fn quiet_when_recent_local_only_or_disabled() {
+    let root = tempfile::tempdir().unwrap();
+    let recent = store(root.path(), 2, true);
+    assert!(!list_stderr(root.path(), &recent).contains(NOTICE));
+
+    let root = tempfile::tempdir().unwrap();
+    let local_only = store(root.path(), 30, false);
+    assert!(!list_stderr(root.path(), &local_only).contains(NOTICE));
+
+    let root = tempfile::tempdir().unwrap();
+    let stale = store(root.path(), 30, true);
+    fs::create_dir_all(root.path().join("polyrc")).unwrap();
+    fs::write(root.path().join("polyrc").join("config.toml"), "[sync]\nstale_after_days = 0\n").unwrap();
+    assert!(!list_stderr(root.path(), &stale).contains(NOTICE));
+}
+
+#[test]
+fn json_output_carries_no_notice() {
+    let root = tempfile::tempdir().unwrap();
+    let store = store(root.path(), 12, true);
+    let out = list_project(root.path(), &store, &["--json"]);
+    serde_json::from_slice::<serde_json::Value>(&out.stdout).expect("stdout is JSON");
+    assert!(!String::from_utf8(out.stderr).unwrap().contains(NOTICE));
+}
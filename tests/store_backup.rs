@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use assert_cmd::Command;
+
+mod common;
+use common::{git, polyrc_cmd};
+
+/// A store with one committed rule, one untracked rule and an uncommitted edit.
+fn store(root: &Path) -> PathBuf {
+    let store = root.join("store");
+    polyrc_core::store::init_git(&store).unwrap();
+    fs::create_dir_all(store.join("app")).unwrap();
+    fs::write(store.join("app").join("style.yaml"), "content: committed\n").unwrap();
+    git(&store, &["add", "-A"]);
+    git(&store, &["commit", "-q", "-m", "initial"]);
+    fs::write(store.join("app").join("style.yaml"), "content: edited\n").unwrap();
+    fs::write(store.join("app").join("draft.yaml"), "content: untracked\n").unwrap();
+    store
+}
+
+fn polyrc(root: &Path, store: &Path) -> Command {
+    let mut cmd = polyrc_cmd(root);
+    cmd.env("POLYRC_STORE", store);
+    cmd
+}
+
+#[test]
+fn backup_restores_history_and_working_tree() {
+    let root = tempfile::tempdir().unwrap();
+    let store = store(root.path());
+    let bundle = root.path().join("snap.bundle");
+    polyrc(root.path(), &store).args(["store", "backup", "--output"]).arg(&bundle).assert().success();
+    // The snapshot ref doesn't linger in the live store
+    assert_eq!(git(&store, &["for-each-ref", "refs/polyrc"]), "");
+
+    let restored = root.path().join("restored");
+    polyrc(root.path(), &store).args(["store", "restore"]).arg(&bundle).arg("--store").arg(&restored).assert().success();
+    assert_eq!(git(&restored, &["log", "--format=%s"]), "initial");
+    assert_eq!(fs::read_to_string(restored.join("app").join("style.yaml")).unwrap(), "content: edited\n");
+    assert_eq!(fs::read_to_string(restored.join("app").join("draft.yaml")).unwrap(), "content: untracked\n");
+    let status = git(&restored, &["status", "--porcelain"]);
+    assert!(status.contains("M app/style.yaml") && status.contains("?? app/draft.yaml"), "{status}");
+}
+
+#[test]
+fn restore_over_a_store_needs_force_and_backs_it_up() {
+    let root = tempfile::tempdir().unwrap();
+    let store = store(root.path());
+    let bundle = root.path().join("snap.bundle");
+    polyrc(root.path(), &store).args(["store", "backup", "--output"]).arg(&bundle).assert().success();
+    fs::write(store.join("app").join("later.yaml"), "content: after backup\n").unwrap();
+
+    polyrc(root.path(), &store).args(["store", "restore"]).arg(&bundle).assert().failure();
+    assert!(store.join("app").join("later.yaml").exists());
+
+    polyrc(root.path(), &store).args(["store", "restore", "--force"]).arg(&bundle).assert().success();
+    assert!(!store.join("app").join("later.yaml").exists());
+    assert_eq!(polyrc_core::store::list_backups(&root.path().join("polyrc").join("backups")).unwrap().len(), 1);
+}
+
+#[test]
+fn default_backups_rotate_per_retention() {
+    let root = tempfile::tempdir().unwrap();
+    let store = store(root.path());
+    fs::create_dir_all(root.path().join("polyrc")).unwrap();
+    fs::write(root.path().join("polyrc").join("config.toml"), "[store]\nbackup_retention = 2\n").unwrap();
+    for _ in 0..3 {
+        polyrc(root.path(), &store).args(["store", "backup"]).assert().success();
+    }
+    assert_eq!(polyrc_core::store::list_backups(&root.path().join("polyrc").join("backups")).unwrap().len(), 2);
+}
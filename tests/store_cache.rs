@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+use polyrc_core::ir::Rule;
+use polyrc_core::store::Store;
+
+const RULES: usize = 500;
+
+/// Fastest of a few runs, to keep scheduler noise out of the comparison.
+fn fastest(runs: usize, mut f: impl FnMut()) -> Duration {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+/// Parsing 500 rule files once per load versus once per invocation. Run with
+/// `--nocapture` to see the timings.
+#[test]
+fn repeated_loads_of_a_large_project_are_cached() {
+    let dir = tempfile::tempdir().unwrap();
+    let rules: Vec<Rule> = (0..RULES)
+        .map(|i| Rule {
+            name: Some(format!("rule-{i:03}")),
+            description: Some(format!("Generated rule {i}")),
+            content: format!("# Rule {i}\n\n{}\n", "Keep functions short and names descriptive. ".repeat(20)),
+            ..Default::default()
+        })
+        .collect();
+    Store::new(dir.path()).save_rules(Some("big"), &rules, "cursor").unwrap();
+
+    let uncached = fastest(5, || {
+        let fresh = Store::new(dir.path());
+        assert_eq!(fresh.load_rules(Some("big")).unwrap().len(), RULES);
+    });
+    let store = Store::new(dir.path());
+    store.load_rules(Some("big")).unwrap();
+    let cached = fastest(5, || assert_eq!(store.load_rules(Some("big")).unwrap().len(), RULES));
+
+    println!("{RULES} rules: {uncached:?} parsed, {cached:?} cached");
+    assert!(cached < uncached, "cached load ({cached:?}) not faster than parsing ({uncached:?})");
+}
@@ -0,0 +1,69 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::polyrc;
+
+/// An initialized default store (`~/polyrc/store`) holding `app/style`.
+fn fixture() -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    let file = home.path().join("style.md");
+    fs::write(&file, "Use tabs.\n").unwrap();
+    let out = polyrc(home.path(), &["push-rule", "style", "--project", "app", "--from-file", file.to_str().unwrap()]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    home
+}
+
+fn config(home: &Path) -> String {
+    fs::read_to_string(home.join("polyrc").join("config.toml")).unwrap_or_default()
+}
+
+#[test]
+fn move_renames_the_store_and_updates_config() {
+    let home = fixture();
+    let old = home.path().join("polyrc").join("store");
+    let new = home.path().join("rules").join("store");
+
+    let out = polyrc(home.path(), &["store", "move", new.to_str().unwrap()]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8(out.stdout).unwrap().contains("Moved "));
+    assert!(!old.exists());
+    assert!(new.join(".git").is_dir() && new.join("app").join("style.yaml").exists());
+    assert!(config(home.path()).contains(new.to_str().unwrap()));
+
+    // The next command finds the store at its new place
+    let out = polyrc(home.path(), &["list-project", "app"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8(out.stdout).unwrap().contains("style"));
+}
+
+#[test]
+fn move_rejects_a_non_empty_destination() {
+    let home = fixture();
+    let old = home.path().join("polyrc").join("store");
+    let taken = home.path().join("taken");
+    fs::create_dir_all(&taken).unwrap();
+    fs::write(taken.join("notes.md"), "mine").unwrap();
+    let before = config(home.path());
+
+    let out = polyrc(home.path(), &["store", "move", taken.to_str().unwrap()]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("destination is not empty"));
+    assert!(old.join("app").join("style.yaml").exists());
+    assert_eq!(fs::read_to_string(taken.join("notes.md")).unwrap(), "mine");
+    assert_eq!(config(home.path()), before);
+}
+
+#[test]
+fn copy_leaves_the_original() {
+    let home = fixture();
+    let old = home.path().join("polyrc").join("store");
+    let new = home.path().join("copy");
+
+    let out = polyrc(home.path(), &["store", "move", "--copy", new.to_str().unwrap()]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(old.join("app").join("style.yaml").exists());
+    assert!(new.join("app").join("style.yaml").exists());
+    assert!(config(home.path()).contains(new.to_str().unwrap()));
+}
@@ -0,0 +1,52 @@
+use std::fs;
+
+mod common;
+use common::{git, polyrc};
+
+#[test]
+fn reports_each_format_against_the_store_and_its_remote() {
+    let home = tempfile::tempdir().unwrap();
+    let store = home.path().join("polyrc").join("store");
+    let rules = home.path().join(".cursor").join("rules");
+    fs::create_dir_all(&rules).unwrap();
+    fs::write(rules.join("style.mdc"), "---\nalwaysApply: true\n---\n\nWrap at 100.\n").unwrap();
+    fs::write(rules.join("tests.mdc"), "---\nalwaysApply: true\n---\n\nTest it.\n").unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+
+    let out = polyrc(home.path(), &["status", "--project", "myApp"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("(no remote branch)") && stdout.contains("cursor      not in store"), "{stdout}");
+
+    let out = polyrc(home.path(), &["push-format", "--format", "cursor", "--project", "myApp"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let remote = home.path().join("remote.git");
+    git(home.path(), &["init", "-q", "--bare", remote.to_str().unwrap()]);
+    git(&store, &["remote", "add", "origin", remote.to_str().unwrap()]);
+    git(&store, &["push", "-q", "origin", "HEAD:main"]);
+    git(&store, &["fetch", "-q", "origin"]);
+
+    let out = polyrc(home.path(), &["status", "--project", "myApp"]);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("(up to date with origin/main)") && stdout.contains("cursor      in sync "), "{stdout}");
+
+    // Edited and added locally, and a rule pushed to the store from elsewhere
+    fs::write(rules.join("style.mdc"), "---\nalwaysApply: true\n---\n\nWrap at 80.\n").unwrap();
+    fs::write(rules.join("docs.mdc"), "---\nalwaysApply: true\n---\n\nDocument it.\n").unwrap();
+    fs::write(home.path().join("commits.md"), "Keep subjects short.\n").unwrap();
+    let out = polyrc(home.path(), &["push-rule", "--project", "myApp", "--from-file", "commits.md"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+
+    let out = polyrc(home.path(), &["status", "--project", "myApp"]);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("(1 ahead, 0 behind origin/main"), "{stdout}");
+    assert!(stdout.contains("    local newer  style\n") && stdout.contains("    only store   commits\n"), "{stdout}");
+
+    let out = polyrc(home.path(), &["status", "--project", "myApp", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!((json["remote"]["ahead"].as_u64(), json["remote"]["behind"].as_u64()), (Some(1), Some(0)));
+    let cursor = &json["formats"][0];
+    assert_eq!((cursor["format"].as_str(), cursor["state"].as_str()), (Some("cursor"), Some("diverged")));
+    let states: Vec<_> = cursor["rules"].as_array().unwrap().iter().map(|r| r["state"].as_str().unwrap()).collect();
+    assert_eq!(states, ["only_store", "only_local", "local_newer", "in_sync"]);
+}
@@ -0,0 +1,91 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+mod common;
+use common::polyrc;
+
+/// A `repo/.cursor/rules/` holding the given `.mdc` files.
+fn cursor_repo(home: &Path, rules: &[(&str, &str)]) {
+    let dir = home.join("repo").join(".cursor").join("rules");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, content) in rules {
+        fs::write(dir.join(format!("{name}.mdc")), content).unwrap();
+    }
+}
+
+fn convert_strict(home: &Path, to: &str) -> Output {
+    polyrc(home, &["convert", "--from", "cursor", "--to", to, "--input", "repo", "--output", "out", "--strict"])
+}
+
+#[test]
+fn each_kind_of_loss_aborts_before_writing() {
+    let home = tempfile::tempdir().unwrap();
+    cursor_repo(
+        home.path(),
+        &[
+            ("review", "Review the diff.\n"),
+            ("ts", "---\nglobs: src/**/*.ts\n---\nUse strict mode.\n"),
+            ("tone", "---\ndescription: Tone of voice\nalwaysApply: true\n---\nBe terse.\n"),
+        ],
+    );
+    // The config asks for downgrades; --strict overrides it
+    fs::create_dir_all(home.path().join("polyrc")).unwrap();
+    fs::write(home.path().join("polyrc").join("config.toml"), "[write]\non_unsupported_activation = \"downgrade\"\n").unwrap();
+
+    let out = convert_strict(home.path(), "gemini");
+    assert_eq!(out.status.code(), Some(10));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    for loss in [
+        "review: gemini can't express on_demand activation",
+        "ts: gemini can't express glob activation",
+        "ts: gemini drops globs (src/**/*.ts)",
+        "tone: gemini drops descriptions",
+    ] {
+        assert!(stderr.contains(loss), "{loss}: {stderr}");
+    }
+    assert!(!home.path().join("out").exists());
+
+    // Without --strict the same conversion degrades and succeeds
+    let out = polyrc(home.path(), &["convert", "--from", "cursor", "--to", "gemini", "--input", "repo", "--output", "out"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+}
+
+#[test]
+fn hard_size_limits_abort_and_clean_conversions_pass() {
+    let home = tempfile::tempdir().unwrap();
+    let big = format!("---\nalwaysApply: true\n---\n{}\n", "x".repeat(7000));
+    cursor_repo(home.path(), &[("big", &big), ("small", "---\nalwaysApply: true\n---\nUse tabs.\n")]);
+
+    let out = convert_strict(home.path(), "windsurf");
+    assert_eq!(out.status.code(), Some(10));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("over windsurf's 6000-char file limit"));
+    assert!(!home.path().join("out").exists());
+
+    let out = convert_strict(home.path(), "claude");
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(home.path().join("out").join(".claude").join("rules").join("small.md").exists());
+}
+
+#[test]
+fn pull_format_strict_checks_every_format_first() {
+    let home = tempfile::tempdir().unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    cursor_repo(home.path(), &[("review", "Review the diff.\n"), ("tone", "---\nalwaysApply: true\n---\nBe terse.\n")]);
+    let out = polyrc(home.path(), &["push-format", "--format", "cursor", "--project", "app", "--input", "repo"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+
+    // Dry-run plans report the losses per format
+    let out = polyrc(home.path(), &["pull-format", "--all", "--project", "app", "--output", "out", "--strict", "--dry-run", "--json"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8(out.stdout).unwrap().contains("review: gemini can't express on_demand activation"));
+    let out = polyrc(home.path(), &["pull-format", "--all", "--project", "app", "--output", "out", "--strict"]);
+    assert_eq!(out.status.code(), Some(10));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("review: aider can't express on_demand activation"), "{stderr}");
+    assert!(stderr.contains("review: gemini can't express on_demand activation"), "{stderr}");
+    assert!(!home.path().join("out").exists(), "cursor must not be written either");
+
+    let out = polyrc(home.path(), &["pull-format", "--format", "cursor", "--project", "app", "--output", "out", "--strict"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+}
@@ -0,0 +1,54 @@
+use std::str::FromStr;
+use polyrc_core::formats::Format;
+
+mod common;
+use common::polyrc;
+
+#[test]
+fn json_lists_every_format_with_aliases_and_paths() {
+    let home = tempfile::tempdir().unwrap();
+    let out = polyrc(home.path(), &["supported-formats", "--json"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let formats: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let formats = formats.as_array().unwrap();
+    assert_eq!(formats.len(), Format::all().len());
+
+    for entry in formats {
+        let name = entry["name"].as_str().unwrap();
+        let fmt = Format::from_str(name).unwrap();
+        assert_eq!(entry["description"], fmt.description());
+        assert_eq!(entry["project_paths"], serde_json::json!(fmt.project_paths()));
+        assert_eq!(entry["user_input_dir"].is_null(), fmt.user_input_dir().is_none(), "{name}");
+        assert!(entry["capabilities"]["project_scope"].as_bool().unwrap());
+
+        // Every alias is accepted by from_str and by the CLI
+        let aliases: Vec<&str> = entry["aliases"].as_array().unwrap().iter().map(|a| a.as_str().unwrap()).collect();
+        assert_eq!(aliases, fmt.aliases());
+        for alias in aliases {
+            assert_eq!(Format::from_str(alias).unwrap(), fmt);
+            let out = polyrc(home.path(), &["pull-format", "--format", alias, "--project", "app", "--dry-run"]);
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            assert!(!stderr.contains("invalid value"), "{alias}: {stderr}");
+        }
+    }
+    let gemini = formats.iter().find(|f| f["name"] == "gemini").unwrap();
+    assert_eq!(gemini["user_input_dir"], home.path().join(".gemini").display().to_string());
+    assert_eq!(gemini["aliases"], serde_json::json!(["gemini-cli"]));
+}
+
+#[test]
+fn plain_and_matrix_output() {
+    let home = tempfile::tempdir().unwrap();
+    let out = polyrc(home.path(), &["supported-formats"]);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), Format::all().len());
+    assert!(stdout.starts_with("cursor          Cursor (.cursor/rules/*.mdc"), "{stdout}");
+
+    let out = polyrc(home.path(), &["supported-formats", "--matrix"]);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.starts_with("FORMAT  ") && stdout.contains("AI-DECIDES"), "{stdout}");
+    assert_eq!(stdout.lines().count(), Format::all().len() + 1);
+
+    let out = polyrc(home.path(), &["pull-format", "--format", "vscode", "--project", "app"]);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("invalid value 'vscode'"));
+}
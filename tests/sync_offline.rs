@@ -0,0 +1,47 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use assert_cmd::Command;
+
+mod common;
+use common::{git, polyrc_cmd};
+
+/// A store whose `origin` can never be reached (`.invalid` never resolves).
+fn offline_store(root: &Path) -> std::path::PathBuf {
+    let store = root.join("store");
+    polyrc_core::store::init_git(&store).unwrap();
+    git(&store, &["remote", "add", "origin", "https://polyrc-test.invalid/rules.git"]);
+    store
+}
+
+fn sync(root: &Path, store: &Path) -> Command {
+    let mut cmd = polyrc_cmd(root);
+    cmd.env("POLYRC_STORE", store).arg("sync");
+    cmd
+}
+
+#[test]
+fn unreachable_remote_works_offline_and_exits_zero() {
+    let root = tempfile::tempdir().unwrap();
+    let store = offline_store(root.path());
+
+    let started = Instant::now();
+    let out = sync(root.path(), &store).output().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert_eq!(out.status.code(), Some(0), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("working offline"), "{stdout}");
+    assert!(!stdout.contains("Pushing"), "{stdout}");
+}
+
+#[test]
+fn strict_network_exits_eight() {
+    let root = tempfile::tempdir().unwrap();
+    let store = offline_store(root.path());
+    sync(root.path(), &store).arg("--strict-network").assert().code(8);
+
+    // Same via config
+    fs::create_dir_all(root.path().join("polyrc")).unwrap();
+    fs::write(root.path().join("polyrc").join("config.toml"), "[sync]\noffline_ok = false\n").unwrap();
+    sync(root.path(), &store).assert().code(8);
+}
@@ -0,0 +1,171 @@
+use std::fs;
+use std::path::Path;
+use assert_cmd::Command;
+use polyrc_core::ir::Rule;
+use polyrc_core::store::Store;
+
+mod common;
+use common::git;
+
+fn rule(name: &str, content: &str) -> Rule {
+    Rule { name: Some(name.into()), content: content.into(), ..Default::default() }
+}
+
+/// Save `rules` as project `app` in the store checkout at `dir` and commit.
+fn save(dir: &Path, rules: &[Rule], message: &str) {
+    Store::open(dir).unwrap().save_rules(Some("app"), rules, "cursor").unwrap();
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "-q", "-m", message]);
+}
+
+#[test]
+fn sync_writes_a_report_of_pulled_changes_and_conflicts() {
+    let root = tempfile::tempdir().unwrap();
+    let remote = root.path().join("remote.git");
+    let (a, b) = (root.path().join("a"), root.path().join("b"));
+    git(root.path(), &["init", "-q", "--bare", "-b", "main", remote.to_str().unwrap()]);
+
+    // Machine A creates the store and shares it
+    fs::create_dir_all(&a).unwrap();
+    git(&a, &["init", "-q", "-b", "main"]);
+    fs::write(a.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    save(&a, &[rule("style", "Use tabs."), rule("old", "Remove me.")], "initial");
+    git(&a, &["remote", "add", "origin", remote.to_str().unwrap()]);
+    git(&a, &["push", "-q", "origin", "main"]);
+    git(root.path(), &["clone", "-q", remote.to_str().unwrap(), b.to_str().unwrap()]);
+    // polyrc itself commits the merge in B
+    git(&b, &["config", "user.name", "test"]);
+    git(&b, &["config", "user.email", "test@example.com"]);
+
+    // Both machines edit `style`; A also adds `tests` and deletes `old`
+    save(&a, &[rule("style", "Use spaces."), rule("tests", "Write tests.")], "a edits");
+    git(&a, &["push", "-q", "origin", "main"]);
+    Store::open(&b).unwrap().save_rule_to_namespace("app", "style", &rule("style", "Use two tabs.")).unwrap();
+    git(&b, &["commit", "-q", "-am", "b edits"]);
+
+    let out = Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", root.path())
+        .env("POLYRC_STORE", &b)
+        .args(["sync", "--pull-only"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("1 added, 0 updated, 1 deleted, 1 conflict(s)"), "{stdout}");
+
+    let raw = fs::read_to_string(root.path().join("polyrc").join(polyrc_core::sync::report::REPORT_FILE)).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&raw).unwrap();
+    assert_eq!(report["added"], serde_json::json!(["app/tests"]));
+    assert_eq!(report["deleted"], serde_json::json!(["app/old"]));
+    let conflicts = report["conflicts"].as_array().unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0]["project"], "app");
+    assert_eq!(conflicts[0]["rule"], "style");
+    assert_eq!(conflicts[0]["winner"], "local");
+    assert!(conflicts[0]["local_updated_at"].is_string() && conflicts[0]["remote_updated_at"].is_string());
+
+    let out = Command::cargo_bin("polyrc").unwrap().env("HOME", root.path()).args(["sync", "--report"]).output().unwrap();
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("app/style — kept local"), "{stdout}");
+}
+
+#[test]
+fn a_rule_created_on_both_machines_is_merged_into_one() {
+    let root = tempfile::tempdir().unwrap();
+    let remote = root.path().join("remote.git");
+    let (a, b) = (root.path().join("a"), root.path().join("b"));
+    git(root.path(), &["init", "-q", "--bare", "-b", "main", remote.to_str().unwrap()]);
+    fs::create_dir_all(&a).unwrap();
+    git(&a, &["init", "-q", "-b", "main"]);
+    fs::write(a.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    save(&a, &[rule("style", "Use tabs.")], "initial");
+    git(&a, &["remote", "add", "origin", remote.to_str().unwrap()]);
+    git(&a, &["push", "-q", "origin", "main"]);
+    git(root.path(), &["clone", "-q", remote.to_str().unwrap(), b.to_str().unwrap()]);
+    git(&b, &["config", "user.name", "test"]);
+    git(&b, &["config", "user.email", "test@example.com"]);
+
+    // Both machines push their own `rust-style` before syncing; B's comes later
+    let rust_style = |content: &str| rule("rust-style", &format!("# Rust\n\n{content}\n\nWrap at 100.\n"));
+    save(&a, &[rust_style("Run rustfmt before committing.")], "a adds rust-style");
+    git(&a, &["push", "-q", "origin", "main"]);
+    save(&b, &[rust_style("Prefer clippy's pedantic lints.")], "b adds rust-style");
+    let local_id = Store::open(&b).unwrap().load_rule_by_name("rust-style", Some("app")).unwrap().unwrap().1.id;
+
+    let out = Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", root.path())
+        .env("POLYRC_STORE", &b)
+        .args(["sync", "--pull-only"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("app/rust-style: the local and remote rules have different ids but the same name"), "{stderr}");
+
+    // One rule, B's version whole under B's id, and nothing left to commit
+    let rules = Store::open(&b).unwrap().load_rules(Some("app")).unwrap();
+    let merged: Vec<&Rule> = rules.iter().filter(|r| r.name.as_deref() == Some("rust-style")).collect();
+    assert_eq!(merged.len(), 1, "{rules:?}");
+    assert_eq!(merged[0].id, local_id);
+    assert!(merged[0].content.contains("Prefer clippy's pedantic lints."));
+    assert!(!merged[0].content.contains("rustfmt"));
+    assert_eq!(git(&b, &["status", "--porcelain", "--untracked-files=no"]), "");
+    assert_eq!(git(&b, &["rev-list", "--count", "--merges", "HEAD"]), "1");
+}
+
+#[test]
+fn deletions_reach_the_other_machine_unless_edited_there_after() {
+    let root = tempfile::tempdir().unwrap();
+    let remote = root.path().join("remote.git");
+    let (a, b) = (root.path().join("a"), root.path().join("b"));
+    git(root.path(), &["init", "-q", "--bare", "-b", "main", remote.to_str().unwrap()]);
+    fs::create_dir_all(&a).unwrap();
+    git(&a, &["init", "-q", "-b", "main"]);
+    fs::write(a.join(polyrc_core::store::STORE_MARKER), "store_version = \"1\"\n").unwrap();
+    save(&a, &[rule("style", "Use tabs."), rule("old", "Remove me."), rule("draft", "Maybe.")], "initial");
+    git(&a, &["remote", "add", "origin", remote.to_str().unwrap()]);
+    git(&a, &["push", "-q", "origin", "main"]);
+    git(root.path(), &["clone", "-q", remote.to_str().unwrap(), b.to_str().unwrap()]);
+    git(&b, &["config", "user.name", "test"]);
+    git(&b, &["config", "user.email", "test@example.com"]);
+
+    // B edits `old`, then A deletes `old` and `draft`, then B edits `draft`
+    let b_store = Store::open(&b).unwrap();
+    b_store.save_rule_to_namespace("app", "old", &rule("old", "Edited before the deletion.")).unwrap();
+    git(&b, &["commit", "-q", "-am", "b edits old"]);
+    save(&a, &[rule("style", "Use tabs.")], "a deletes old and draft");
+    git(&a, &["push", "-q", "origin", "main"]);
+    b_store.save_rule_to_namespace("app", "draft", &rule("draft", "Edited after the deletion.")).unwrap();
+    git(&b, &["commit", "-q", "-am", "b edits draft"]);
+
+    let out = Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", root.path())
+        .env("POLYRC_STORE", &b)
+        .args(["sync", "--pull-only"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("app/draft: deleted on the remote side at"), "{stderr}");
+    assert!(stderr.contains("but edited on the local side after; keeping it"), "{stderr}");
+
+    assert!(!b.join("app").join("old.yaml").exists());
+    assert!(fs::read_to_string(b.join("app").join("draft.yaml")).unwrap().contains("Edited after the deletion."));
+    let report = fs::read_to_string(root.path().join("polyrc").join(polyrc_core::sync::report::REPORT_FILE)).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+    assert_eq!(report["deleted"], serde_json::json!(["app/old"]));
+    assert_eq!(report["conflicts"][0]["rule"], "draft");
+    assert_eq!(report["conflicts"][0]["winner"], "local");
+    assert!(report["conflicts"][0]["deleted_at"].is_string());
+    let out = Command::cargo_bin("polyrc").unwrap().env("HOME", root.path()).args(["sync", "--report"]).output().unwrap();
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("app/draft — kept the local edit over a deletion at"), "{stdout}");
+    // `draft` is back, so only `old` stays deleted when B pushes
+    let tombstones = Store::open(&b).unwrap().tombstones(Some("app")).unwrap();
+    let names: Vec<_> = tombstones.iter().map(|t| t.name.as_deref().unwrap()).collect();
+    assert_eq!(names, vec!["old"]);
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::{git, polyrc};
+
+fn git_log(store: &Path) -> String {
+    git(store, &["log", "--format=%s"])
+}
+
+/// An initialized store with `style` then `tests` pushed into project `app`.
+fn fixture() -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    for name in ["style", "tests"] {
+        let file = home.path().join(format!("{name}.md"));
+        fs::write(&file, format!("Rule {name}.\n")).unwrap();
+        let out = polyrc(home.path(), &["push-rule", name, "--project", "app", "--from-file", file.to_str().unwrap()]);
+        assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    }
+    home
+}
+
+#[test]
+fn undo_reverts_the_last_push_and_keeps_history() {
+    let home = fixture();
+    let store = home.path().join("polyrc").join("store");
+
+    let out = polyrc(home.path(), &["undo", "--yes"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("push-rule: tests"), "{stdout}");
+    assert!(stdout.contains("removed app/tests.yaml"), "{stdout}");
+
+    assert!(!store.join("app").join("tests.yaml").exists());
+    assert!(store.join("app").join("style.yaml").exists());
+    let log = git_log(&store);
+    assert!(log.starts_with("Revert \"push-rule: tests\"\npush-rule: tests\n"), "{log}");
+
+    // A second undo walks further back instead of redoing the first
+    let out = polyrc(home.path(), &["undo", "--yes"]);
+    assert!(String::from_utf8(out.stdout).unwrap().contains("push-rule: style"));
+    assert!(!store.join("app").join("style.yaml").exists());
+    assert!(!store.join("app").join("tests.yaml").exists());
+}
+
+#[test]
+fn undo_needs_confirmation_and_refuses_pushed_commits() {
+    let home = fixture();
+    let store = home.path().join("polyrc").join("store");
+
+    // stdin isn't a terminal here, so there's no one to confirm
+    let out = polyrc(home.path(), &["undo"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("pass --yes"));
+
+    let remote = home.path().join("remote.git");
+    git(home.path(), &["init", "-q", "--bare", "-b", "main", remote.to_str().unwrap()]);
+    git(&store, &["remote", "add", "origin", remote.to_str().unwrap()]);
+    git(&store, &["push", "-q", "origin", "HEAD:main"]);
+    git(&store, &["fetch", "-q", "origin"]);
+
+    let out = polyrc(home.path(), &["undo", "--steps", "2", "--yes"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("2 of these commit(s) are already on the remote"));
+    assert!(store.join("app").join("tests.yaml").exists());
+
+    let out = polyrc(home.path(), &["undo", "--yes", "--allow-pushed"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(String::from_utf8(out.stdout).unwrap().contains("Run `polyrc sync` to push the revert(s)."));
+    assert!(!store.join("app").join("tests.yaml").exists());
+}
+
+#[test]
+fn undoing_the_first_push_keeps_the_store_files() {
+    let home = tempfile::tempdir().unwrap();
+    let store = home.path().join("polyrc").join("store");
+    assert!(polyrc(home.path(), &["init"]).status.success());
+    let file = home.path().join("style.md");
+    fs::write(&file, "Use tabs.\n").unwrap();
+    assert!(polyrc(home.path(), &["push-rule", "--project", "app", "--from-file", file.to_str().unwrap()]).status.success());
+
+    let out = polyrc(home.path(), &["undo", "--yes"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(!store.join("app").join("style.yaml").exists());
+    assert!(store.join(polyrc_core::store::STORE_MARKER).exists() && store.join(".gitignore").exists());
+
+    let out = polyrc(home.path(), &["undo", "--yes"]);
+    assert!(String::from_utf8(out.stdout).unwrap().contains("Nothing to undo"));
+}
+
+#[test]
+fn undo_stops_at_other_commits_and_leaves_the_store_alone_on_failure() {
+    let home = fixture();
+    let store = home.path().join("polyrc").join("store");
+
+    // A local edit in the way: the revert fails and nothing is half-done
+    fs::write(store.join("app").join("tests.yaml"), "edited by hand\n").unwrap();
+    let out = polyrc(home.path(), &["undo", "--yes"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("the store was left unchanged"));
+    assert!(!store.join(".git").join("REVERT_HEAD").exists() && !store.join(".git").join("sequencer").exists());
+    assert!(git_log(&store).starts_with("push-rule: tests\n"));
+
+    // A commit polyrc didn't make ends the walk instead of being stepped over
+    git(&store, &["commit", "-q", "-am", "hand edit"]);
+    let out = polyrc(home.path(), &["undo", "--yes"]);
+    assert!(String::from_utf8(out.stdout).unwrap().contains("Nothing to undo"));
+    assert!(store.join("app").join("style.yaml").exists());
+}
@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+use assert_cmd::Command;
+
+fn verify(input: &Path, extra: &[&str]) -> Output {
+    Command::cargo_bin("polyrc")
+        .unwrap()
+        .env("HOME", input)
+        .env_remove("POLYRC_STORE")
+        .arg("verify")
+        .arg("--input")
+        .arg(input)
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+fn put(root: &Path, rel: &str, content: &str) {
+    let file = root.join(rel);
+    fs::create_dir_all(file.parent().unwrap()).unwrap();
+    fs::write(file, content).unwrap();
+}
+
+/// A project with a typical config for every format.
+fn fixture() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    put(root, ".cursor/rules/rust.mdc", "---\ndescription: Rust style\nglobs: \"*.rs\"\nalwaysApply: false\n---\n\nUse rustfmt.\n");
+    put(root, ".cursor/rules/tests.mdc", "---\nalwaysApply: true\n---\n\nWrite tests.\n");
+    put(root, ".windsurf/rules/01-style.md", "Keep functions short.\n");
+    put(root, ".github/copilot-instructions.md", "Be concise.\n");
+    put(root, ".github/instructions/ts.instructions.md", "---\napplyTo: \"**/*.ts\"\n---\n\nPrefer interfaces.\n");
+    put(root, "CLAUDE.md", "# Project\n\nUse tabs.\n");
+    put(root, ".claude/rules/errors.md", "Errors with anyhow.\n");
+    put(root, ".claude/commands/pr.md", "Open a PR.\n");
+    put(root, "GEMINI.md", "Answer briefly.\n");
+    put(root, ".agent/rules/tone.md", "No emojis.\n");
+    dir
+}
+
+#[test]
+fn every_format_round_trips_its_own_files() {
+    let dir = fixture();
+    for extra in [&[][..], &["--via-store"]] {
+        let out = verify(dir.path(), &[&["--all"], extra].concat());
+        let stdout = String::from_utf8(out.stdout).unwrap();
+        assert!(out.status.success(), "{stdout}{}", String::from_utf8_lossy(&out.stderr));
+        assert_eq!(stdout.matches("unchanged after the round trip").count(), 6, "{stdout}");
+    }
+}
+
+#[test]
+fn lossy_round_trip_exits_nonzero() {
+    let dir = fixture();
+    // Written back as skills/review-tool/
+    put(dir.path(), ".claude/skills/Review Tool/SKILL.md", "Review the diff.\n");
+
+    let out = verify(dir.path(), &["--format", "claude"]);
+    assert_eq!(out.status.code(), Some(10));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("claude — 2 of 4 rule(s) changed") && stdout.contains("Review Tool: lost"), "{stdout}");
+
+    let out = verify(dir.path(), &["--format", "claude", "--json"]);
+    let reports: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(reports[0]["differences"][0]["kind"], "missing_locally");
+
+    let empty = tempfile::tempdir().unwrap();
+    let out = verify(empty.path(), &["--format", "cursor"]);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("no cursor config files found"));
+}
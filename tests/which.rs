@@ -0,0 +1,53 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+mod common;
+use common::polyrc_cmd;
+
+fn polyrc(home: &Path, env: &[(&str, &str)], args: &[&str]) -> Output {
+    let mut cmd = polyrc_cmd(home);
+    cmd.env_remove("CLAUDE_CONFIG_DIR");
+    for fmt in ["claude", "gemini", "antigravity", "windsurf"] {
+        cmd.env_remove(format!("POLYRC_{}_USER_DIR", fmt.to_uppercase()));
+    }
+    cmd.envs(env.iter().copied()).args(args).output().unwrap()
+}
+
+#[test]
+fn which_reports_each_dir_and_its_source() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join("polyrc");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(config_dir.join("config.toml"), "[user_dirs]\nwindsurf = \"~/flatpak/memories\"\n").unwrap();
+
+    let out = polyrc(home.path(), &[("POLYRC_GEMINI_USER_DIR", "/opt/gemini")], &["which"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let line = |fmt: &str| stdout.lines().find(|l| l.starts_with(fmt)).unwrap_or_else(|| panic!("{fmt}: {stdout}")).to_string();
+    assert!(line("gemini").ends_with("/opt/gemini  ($POLYRC_GEMINI_USER_DIR)"), "{stdout}");
+    let windsurf = home.path().join("flatpak").join("memories");
+    assert!(line("windsurf").contains(&format!("{}  ([user_dirs] in config.toml)", windsurf.display())), "{stdout}");
+    assert!(line("claude").ends_with("(built-in default)"), "{stdout}");
+    assert!(line("cursor").contains("no local user config"), "{stdout}");
+
+    let out = polyrc(home.path(), &[("CLAUDE_CONFIG_DIR", "/etc/claude")], &["which", "--format", "claude-code"]);
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "claude       /etc/claude  ($CLAUDE_CONFIG_DIR)");
+
+    // Discovery follows the override
+    let out = polyrc(home.path(), &[], &["discover", "--user", "--format", "windsurf"]);
+    assert!(String::from_utf8(out.stdout).unwrap().contains("~/flatpak/memories/global_rules.md"));
+}
+
+#[test]
+fn invalid_user_dirs_are_rejected() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join("polyrc");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(config_dir.join("config.toml"), "[user_dirs]\ncopilot = \"/x\"\n").unwrap();
+
+    let out = polyrc(home.path(), &[], &["which"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("invalid [user_dirs] in config.toml") && stderr.contains("copilot has no local user config dir"), "{stderr}");
+}
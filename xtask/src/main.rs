@@ -47,16 +47,20 @@ fn install() -> Result<()> {
         anyhow::bail!("Cargo build failed");
     }
 
-    let home = env::var("HOME").context("HOME environment variable not set")?;
+    let home = env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .context("neither HOME nor USERPROFILE is set")?;
     let install_dir = std::path::Path::new(&home).join(".local").join("bin");
 
     std::fs::create_dir_all(&install_dir)
         .with_context(|| format!("Failed to create directory: {}", install_dir.display()))?;
 
-    let install_path = install_dir.join("polyrc");
+    let bin_name = format!("polyrc{}", env::consts::EXE_SUFFIX);
+    let built = std::path::Path::new("target").join("release").join(&bin_name);
+    let install_path = install_dir.join(&bin_name);
 
     println!("Installing to {}...", install_dir.display());
-    std::fs::copy("target/release/polyrc", &install_path)
+    std::fs::copy(&built, &install_path)
         .with_context(|| format!("Failed to copy binary to {}", install_path.display()))?;
 
     #[cfg(unix)]